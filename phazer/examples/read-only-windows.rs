@@ -57,6 +57,10 @@ mod inner {
             Err((e, p)) => {
                 // If the error is anything except PermissionDenied then return it
                 if e.kind() != ErrorKind::PermissionDenied {
+                    // Remove the working file and report if that also failed
+                    if let Err(de) = p.discard() {
+                        eprintln!("Discard failed: {}", de);
+                    }
                     return Err(e.into());
                 }
                 // Clear the read-only attribute
@@ -0,0 +1,74 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// A commit strategy that publishes the working file to object storage instead of renaming it.  A
+// `HashMap` stands in for the storage service; a real strategy would call PutObject (or complete a
+// multipart upload) instead.
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::Mutex;
+
+use phazer::{CommitDetails, CommitStrategy, PhazerBuilder};
+
+#[derive(Default)]
+struct RemoteUploadStrategy {
+    bucket: Mutex<HashMap<String, Vec<u8>>>,
+}
+
+impl CommitStrategy for RemoteUploadStrategy {
+    fn commit(&self, phazer: &dyn CommitDetails) -> std::io::Result<()> {
+        // The target path is the object's key
+        let key = phazer.get_target_path().to_string_lossy().into_owned();
+        let mut body = Vec::new();
+        std::fs::File::open(phazer.get_working_path())?.read_to_end(&mut body)?;
+        // The "atomic publish"; the object appears with all of its contents or not at all
+        self.bucket
+            .lock()
+            .map_err(|_| std::io::Error::other("the bucket is poisoned"))?
+            .insert(key, body);
+        // The working file is removed when the Phazer is dropped
+        Ok(())
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let strategy = RemoteUploadStrategy::default();
+
+    let p = PhazerBuilder::with_target("summary.txt")
+        .commit_strategy(&strategy)
+        .build();
+
+    #[cfg(feature = "simple")]
+    {
+        use std::io::Write;
+
+        let mut w = p.simple_writer()?;
+        w.write_all("uploaded".as_bytes())?;
+        drop(w);
+    }
+
+    p.commit()?;
+
+    for (key, body) in strategy
+        .bucket
+        .lock()
+        .map_err(|_| "the bucket is poisoned")?
+        .iter()
+    {
+        println!("{}: {} bytes", key, body.len());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,256 @@
+#![cfg(feature = "tar")]
+//
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Atomically replace one member of an archive.
+//!
+//! An archive cannot be safely edited in place.  Instead, the archive is rebuilt in the working
+//! file with the one member replaced then the working file is committed over the archive.  There is
+//! never a window where the archive is corrupt.
+//!
+//! This module is available when the `tar` feature is enabled.
+//!
+use std::fs::OpenOptions;
+use std::io::{ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tar::{Archive, Builder, Entry, EntryType, Header};
+
+use crate::Phazer;
+
+impl<'cs> Phazer<'cs> {
+    /// Returns a synchronous file-like thing that collects the new content for one member of the
+    /// tar archive that is the target.
+    ///
+    /// [`TarMemberWriter::finish`] copies every other member of the existing archive (the target)
+    /// to the working file and writes the new content in place of `member_name`.  If the archive
+    /// does not have the member, it is added to the end.  If the archive does not exist, the new
+    /// archive has just the one member.  Until [`TarMemberWriter::finish`] succeeds
+    /// [`Phazer::commit`] fails with an [`InvalidData`][id] error.
+    ///
+    /// The other members are copied with their headers unchanged.  PAX extended headers and GNU
+    /// long name / long link entries are copied with the member they describe so long names and
+    /// extended metadata survive.  GNU sparse members cannot be copied; an archive with one is
+    /// refused with an [`Unsupported`][eu] error.
+    ///
+    /// The new content is held in memory until [`TarMemberWriter::finish`] is called.
+    ///
+    /// This method is available when the `tar` feature is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `member_name` - Path of the member within the archive.
+    ///
+    /// [eu]: std::io::ErrorKind::Unsupported
+    /// [id]: std::io::ErrorKind::InvalidData
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # #[cfg(feature = "tar")]
+    /// # {
+    /// use std::io::Write;
+    ///
+    /// use phazer::Phazer;
+    ///
+    /// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let phazer = Phazer::new("bundle.tar");
+    ///     let mut writer = phazer.tar_member_writer("config/settings.toml");
+    ///     writer.write_all(b"volume = 11\n")?;
+    ///     // Rebuilds the archive in the working file
+    ///     writer.finish()?;
+    ///     phazer.commit()?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    ///
+    pub fn tar_member_writer<'a, P>(&'a self, member_name: P) -> TarMemberWriter<'a, 'cs>
+    where
+        P: Into<PathBuf>,
+    {
+        self.unfinished_writer.store(true, Ordering::Relaxed);
+        TarMemberWriter {
+            member_name: member_name.into(),
+            content: Vec::new(),
+            parent: self,
+        }
+    }
+}
+
+/// TarMemberWriter is a synchronous file-like thing that collects the new content for one member
+/// of a tar archive.
+///
+/// It maintains a reference to the [`Phazer`] used to construct it, ensuring [`Phazer::commit`]
+/// cannot be called if there are any writers.
+///
+/// This struct is available when the `tar` feature is enabled.
+pub struct TarMemberWriter<'a, 'cs> {
+    member_name: PathBuf,
+    content: Vec<u8>,
+    parent: &'a Phazer<'cs>,
+}
+
+impl<'a, 'cs> TarMemberWriter<'a, 'cs> {
+    /// Rebuilds the archive, with the new member content, in the working file.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the existing archive cannot be read or the working file
+    /// cannot be written.  In that case [`Phazer::commit`] continues to fail.
+    ///
+    /// [ioe]: std::io::Error
+    ///
+    pub fn finish(self) -> std::io::Result<()> {
+        let parent = self.parent;
+        let mut builder = Builder::new(parent.simple_writer()?);
+        let mut replaced = false;
+        match parent
+            .file_system
+            .open(&parent.target_path, OpenOptions::new().read(true))
+        {
+            Ok(file) => {
+                let mut archive = Archive::new(file);
+                for entry in archive.entries()? {
+                    let mut entry = entry?;
+                    if entry.path()? == self.member_name {
+                        let mode = entry.header().mode().unwrap_or(0o644);
+                        append_member(&mut builder, &self.member_name, &self.content, mode)?;
+                        replaced = true;
+                    } else {
+                        copy_member(&mut builder, &mut entry)?;
+                    }
+                }
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+        if !replaced {
+            append_member(&mut builder, &self.member_name, &self.content, 0o644)?;
+        }
+        let mut writer = builder.into_inner()?;
+        writer.flush()?;
+        drop(writer);
+        parent.unfinished_writer.store(false, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+impl<'a, 'cs> Write for TarMemberWriter<'a, 'cs> {
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.content.write(buf)
+    }
+}
+
+// Copy a member, and the PAX / GNU extension entries that describe it, without changing its header.
+fn copy_member<W: Write, R: Read>(
+    builder: &mut Builder<W>,
+    entry: &mut Entry<'_, R>,
+) -> std::io::Result<()> {
+    let header = entry.header().clone();
+    if header.entry_type().is_gnu_sparse() {
+        return Err(std::io::Error::new(
+            ErrorKind::Unsupported,
+            "GNU sparse archive members cannot be copied",
+        ));
+    }
+    let mut pax = Vec::new();
+    let mut pax_path = false;
+    let mut pax_link = false;
+    if let Some(extensions) = entry.pax_extensions()? {
+        for extension in extensions {
+            let extension = extension?;
+            pax_path |= extension.key_bytes() == b"path";
+            pax_link |= extension.key_bytes() == b"linkpath";
+            pax.extend(pax_record(extension.key_bytes(), extension.value_bytes()));
+        }
+    }
+    if !pax.is_empty() {
+        append_extension(builder, EntryType::XHeader, &pax)?;
+    }
+    let path = entry.path_bytes();
+    if !pax_path && path != header.path_bytes() {
+        append_extension(builder, EntryType::GNULongName, &[&path[..], &[0]].concat())?;
+    }
+    if let Some(link) = entry.link_name_bytes() {
+        if !pax_link && Some(&link) != header.link_name_bytes().as_ref() {
+            append_extension(builder, EntryType::GNULongLink, &[&link[..], &[0]].concat())?;
+        }
+    }
+    builder.append(&header, entry)
+}
+
+// Append an entry holding extension data (PAX records or a GNU long name / link) for the next
+// member.
+fn append_extension<W: Write>(
+    builder: &mut Builder<W>,
+    entry_type: EntryType,
+    data: &[u8],
+) -> std::io::Result<()> {
+    let mut header = match entry_type {
+        EntryType::XHeader => Header::new_ustar(),
+        _ => Header::new_gnu(),
+    };
+    let name = match entry_type {
+        EntryType::XHeader => "././@PaxHeader",
+        _ => "././@LongLink",
+    };
+    header.set_path(name)?;
+    header.set_entry_type(entry_type);
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(0);
+    header.set_cksum();
+    builder.append(&header, data)
+}
+
+// Encode one PAX record: "<length> <key>=<value>\n" where the length includes itself.
+fn pax_record(key: &[u8], value: &[u8]) -> Vec<u8> {
+    let rest = key.len() + value.len() + 3;
+    let mut length = rest + 1;
+    while length != rest + length.to_string().len() {
+        length = rest + length.to_string().len();
+    }
+    let mut record = format!("{} ", length).into_bytes();
+    record.extend_from_slice(key);
+    record.push(b'=');
+    record.extend_from_slice(value);
+    record.push(b'\n');
+    record
+}
+
+// Append a regular file member with the current time as its modification time.
+fn append_member<W: Write>(
+    builder: &mut Builder<W>,
+    member_name: &Path,
+    content: &[u8],
+    mode: u32,
+) -> std::io::Result<()> {
+    let mtime = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let mut header = Header::new_gnu();
+    header.set_entry_type(EntryType::Regular);
+    header.set_size(content.len() as u64);
+    header.set_mode(mode);
+    header.set_mtime(mtime);
+    builder.append_data(&mut header, member_name, content)
+}
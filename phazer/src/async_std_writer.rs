@@ -0,0 +1,249 @@
+#![cfg(feature = "async_std")]
+//
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A file-like thing used to build a working file using async-std.
+//!
+//! This module is available when the `async_std` feature is enabled.
+//!
+use crate::Phazer;
+
+use std::io::SeekFrom;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::task::{ready, Context, Poll};
+
+use async_std::fs::File;
+use async_std::io::{Read, Seek, Write};
+use async_std::task::spawn_blocking;
+
+use crate::size_budget::SizeBudget;
+use crate::write_buffering::WriteBuffer;
+
+impl<'cs> Phazer<'cs> {
+    /// Returns an asynchronous file-like thing, for async-std (or smol), that's used to build the
+    /// working file.
+    ///
+    /// This is the async-std analogue of [`Phazer::tokio_writer`][ptw].  If a working file has not
+    /// yet been created this method creates the working file.  If a working file exists this
+    /// method opens the existing file for read / write access.
+    ///
+    /// The working file cannot be open when [`Phazer::commit`][pc] is called.  This is enforced by
+    /// a lifetime connecting each [`AsyncStdPhazerWriter`] to the [`Phazer`] that created it.  The
+    /// commit is the same for every writer.
+    ///
+    /// This method is available when the `async_std` feature is enabled.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the working file cannot be created or opened for read
+    /// / write access.  Otherwise a new [`AsyncStdPhazerWriter`] is returned that provides access
+    /// to the working file.
+    ///
+    /// [ptw]: crate::Phazer::tokio_writer
+    /// [pc]: crate::Phazer::commit
+    /// [ioe]: std::io::Error
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "async_std")]
+    /// # {
+    /// use async_std::io::WriteExt;
+    ///
+    /// use phazer::Phazer;
+    ///
+    /// async fn save() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let phazer = Phazer::new("config.toml");
+    ///     // Write some stuff.  Drop the writer to ensure the file is not open.
+    ///     let mut writer = phazer.async_std_writer().await?;
+    ///     writer.write_all("[Serial Port]\nbaud = 250000\n".as_bytes()).await?;
+    ///     writer.flush().await?;
+    ///     drop(writer);
+    ///     // Rename the working file to the target file ("save" the changes)
+    ///     phazer.commit()?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    ///
+    pub async fn async_std_writer<'a>(&'a self) -> std::io::Result<AsyncStdPhazerWriter<'a, 'cs>> {
+        let options = self.working_file_options()?;
+        // Open the file on async-std's blocking pool, as async_std::fs::OpenOptions does
+        let working_path = self.working_path.clone();
+        let phase1 = File::from(spawn_blocking(move || options.open(working_path)).await?);
+        Ok(AsyncStdPhazerWriter {
+            phase1,
+            cancelled: &self.cancelled,
+            size_budget: self.size_budget.as_ref(),
+            buffer: WriteBuffer::new(self.write_buffering),
+            unfinished_writer: &self.unfinished_writer,
+            deferred_error: None,
+            _parent: PhantomData::<&'a Self>,
+        })
+    }
+}
+
+/// AsyncStdPhazerWriter is an asynchronous file-like thing that's used to build the working file
+/// with async-std (or smol).
+///
+/// It maintains a reference the the [`Phazer`] used to construct it, ensuring [`Phazer::commit`]
+/// cannot be called if there are any potential writers.  Buffered bytes (see
+/// [`write_buffering`][wb]) cannot be written while the writer is dropped so the writer must be
+/// flushed (or closed) first; otherwise the commit fails.
+///
+/// This struct is available when the `async_std` feature is enabled.
+///
+/// [wb]: crate::PhazerBuilderWithTarget::write_buffering
+pub struct AsyncStdPhazerWriter<'a, 'cs> {
+    phase1: File,
+    cancelled: &'a AtomicBool,
+    size_budget: Option<&'a SizeBudget>,
+    buffer: WriteBuffer,
+    unfinished_writer: &'a AtomicBool,
+    deferred_error: Option<std::io::Error>,
+    _parent: PhantomData<&'a Phazer<'cs>>,
+}
+
+// Write to `file` retrying if a signal interrupts the write.
+fn poll_write_retrying(
+    file: &mut File,
+    cx: &mut Context<'_>,
+    buf: &[u8],
+) -> Poll<std::io::Result<usize>> {
+    loop {
+        match Pin::new(&mut *file).poll_write(cx, buf) {
+            Poll::Ready(Err(e)) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            rv => return rv,
+        }
+    }
+}
+
+impl<'a, 'cs> AsyncStdPhazerWriter<'a, 'cs> {
+    // Write the buffered bytes to the working file.
+    fn poll_drain(&mut self, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        if let Some(e) = self.deferred_error.take() {
+            return Poll::Ready(Err(e));
+        }
+        let rv = ready!(self.poll_write_buffer(cx));
+        if rv.is_err() {
+            // The file may have taken some of the buffered bytes before it failed so the working
+            // file is incomplete; the commit must fail
+            self.unfinished_writer.store(true, Ordering::Relaxed);
+        }
+        Poll::Ready(rv)
+    }
+    // Write everything in the write buffer to the working file.
+    fn poll_write_buffer(&mut self, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        while !self.buffer.is_empty() {
+            let n = ready!(poll_write_retrying(
+                &mut self.phase1,
+                cx,
+                self.buffer.pending()
+            ))?;
+            if n == 0 {
+                return Poll::Ready(Err(std::io::ErrorKind::WriteZero.into()));
+            }
+            self.buffer.consume(n);
+        }
+        Poll::Ready(Ok(()))
+    }
+    // Collect `buf` in the write buffer.
+    fn poll_buffered(&mut self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<std::io::Result<usize>> {
+        // Bytes left over from a drain are written before anything new is accepted
+        if self.deferred_error.is_some() || self.buffer.drain_needed() {
+            ready!(self.poll_drain(cx))?;
+        }
+        if let Some(size_budget) = self.size_budget {
+            size_budget.reserve(buf.len())?;
+        }
+        self.buffer.accept(buf);
+        // buf has been accepted so a failure cannot be returned now.  It is returned by the next
+        // write, flush, read, or seek and the commit fails.
+        if self.buffer.drain_needed() {
+            if let Poll::Ready(Err(e)) = self.poll_drain(cx) {
+                self.deferred_error = Some(e);
+            }
+        }
+        Poll::Ready(Ok(buf.len()))
+    }
+}
+
+impl<'a, 'cs> Read for AsyncStdPhazerWriter<'a, 'cs> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        ready!(self.poll_drain(cx))?;
+        Pin::new(&mut self.phase1).poll_read(cx, buf)
+    }
+}
+
+impl<'a, 'cs> Seek for AsyncStdPhazerWriter<'a, 'cs> {
+    fn poll_seek(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        pos: SeekFrom,
+    ) -> Poll<std::io::Result<u64>> {
+        ready!(self.poll_drain(cx))?;
+        Pin::new(&mut self.phase1).poll_seek(cx, pos)
+    }
+}
+
+impl<'a, 'cs> Write for AsyncStdPhazerWriter<'a, 'cs> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        if self.cancelled.load(Ordering::Relaxed) {
+            return Poll::Ready(Err(crate::cancellation::cancelled_error()));
+        }
+        if !self.buffer.is_direct() {
+            return self.poll_buffered(cx, buf);
+        }
+        if let Some(size_budget) = self.size_budget {
+            size_budget.reserve(buf.len())?;
+        }
+        let rv = poll_write_retrying(&mut self.phase1, cx, buf);
+        if let Some(size_budget) = self.size_budget {
+            // Return whatever was reserved but not written
+            match rv {
+                Poll::Ready(Ok(n)) => size_budget.release(buf.len() - n),
+                _ => size_budget.release(buf.len()),
+            }
+        }
+        rv
+    }
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        ready!(self.poll_drain(cx))?;
+        Pin::new(&mut self.phase1).poll_flush(cx)
+    }
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        ready!(self.poll_drain(cx))?;
+        Pin::new(&mut self.phase1).poll_close(cx)
+    }
+}
+
+impl<'a, 'cs> Drop for AsyncStdPhazerWriter<'a, 'cs> {
+    fn drop(&mut self) {
+        // Buffered bytes cannot be written here; they must not be lost silently
+        if !self.buffer.is_empty() {
+            self.unfinished_writer.store(true, Ordering::Relaxed);
+        }
+    }
+}
@@ -0,0 +1,175 @@
+#![cfg(feature = "simple")]
+//
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An owned file-like thing that builds and commits a single working file.
+//!
+//! This module is available when the `simple` feature is enabled.
+//!
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::simple_writer::{drain_buffer, write_working_file};
+use crate::write_buffering::WriteBuffer;
+use crate::Phazer;
+
+impl<'cs> Phazer<'cs> {
+    /// Converts the [`Phazer`] into an [`AtomicFile`] that owns the one and only writer.
+    ///
+    /// The builder options (commit strategy, write buffering, size budget, and so on) apply as
+    /// usual.
+    ///
+    /// This method is available when the `simple` feature is enabled.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the working file cannot be created or opened.
+    ///
+    /// [ioe]: std::io::Error
+    ///
+    pub fn into_atomic_file(self) -> std::io::Result<AtomicFile<'cs>> {
+        let file = self.open_working_file()?;
+        let buffer = WriteBuffer::new(self.write_buffering);
+        Ok(AtomicFile {
+            file,
+            buffer,
+            phazer: self,
+        })
+    }
+}
+
+/// [`AtomicFile`] builds a working file and commits it to the target in a single value.
+///
+/// With a [`Phazer`] the writer has to be dropped before [`Phazer::commit`] is called.
+/// [`AtomicFile`] owns both so the ordering is handled internally.  [`AtomicFile::commit`] writes
+/// any buffered bytes, closes the working file, then commits it.  [`AtomicFile::abort`], or
+/// dropping the [`AtomicFile`], closes then removes the working file leaving the target
+/// untouched.
+///
+/// Create one with [`AtomicFile::create`] or, to use builder options, with
+/// [`Phazer::into_atomic_file`].
+///
+/// This struct is available when the `simple` feature is enabled.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(feature = "simple")]
+/// # {
+/// use std::io::Write;
+///
+/// use phazer::atomic_file::AtomicFile;
+///
+/// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let mut file = AtomicFile::create("settings.ini")?;
+///     writeln!(file, "[display]")?;
+///     writeln!(file, "brightness = 80")?;
+///     file.commit()?;
+///     Ok(())
+/// }
+/// # }
+/// ```
+///
+// The file is declared before the Phazer so it is closed before the Phazer removes the working
+// file.
+pub struct AtomicFile<'cs> {
+    file: File,
+    buffer: WriteBuffer,
+    phazer: Phazer<'cs>,
+}
+
+impl AtomicFile<'static> {
+    /// Creates an [`AtomicFile`] where `path` is the target file.
+    ///
+    /// The default commit strategy is used.  The working file is created immediately.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Target file.  Ideally, the full path is specified so changes to the working
+    ///   directory do not cause problems.  [canonicalize][std::fs::canonicalize] is helpful.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the working file cannot be created.
+    ///
+    /// [ioe]: std::io::Error
+    ///
+    pub fn create<P>(path: P) -> std::io::Result<Self>
+    where
+        P: Into<PathBuf>,
+    {
+        Phazer::new(path).into_atomic_file()
+    }
+}
+
+impl<'cs> AtomicFile<'cs> {
+    /// Returns the target path.
+    ///
+    pub fn target_path(&self) -> &Path {
+        self.phazer.target_path()
+    }
+    /// Writes any buffered bytes, closes the working file, then commits it.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the buffered bytes cannot be written or the working file
+    /// cannot be transferred to the target file.  In either case the working file is removed.
+    ///
+    /// [ioe]: std::io::Error
+    ///
+    pub fn commit(self) -> std::io::Result<()> {
+        let Self {
+            mut file,
+            mut buffer,
+            phazer,
+        } = self;
+        drain_buffer(&mut file, &mut buffer)?;
+        file.flush()?;
+        drop(file);
+        phazer.commit()
+    }
+    /// Closes then removes the working file without committing it.
+    ///
+    /// Dropping an [`AtomicFile`] does the same but ignores any failure.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the working file cannot be removed.
+    ///
+    /// [ioe]: std::io::Error
+    ///
+    pub fn abort(self) -> std::io::Result<()> {
+        let Self { file, phazer, .. } = self;
+        drop(file);
+        phazer.discard()
+    }
+}
+
+impl<'cs> Write for AtomicFile<'cs> {
+    fn flush(&mut self) -> std::io::Result<()> {
+        drain_buffer(&mut self.file, &mut self.buffer)?;
+        self.file.flush()
+    }
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        write_working_file(
+            &mut self.file,
+            &mut self.buffer,
+            &self.phazer.cancelled,
+            self.phazer.size_budget.as_ref(),
+            buf,
+        )
+    }
+}
@@ -0,0 +1,89 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Cancel a [`Phazer`]'s writers from elsewhere; for example, from a shutdown handler.
+//!
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::Phazer;
+
+impl<'cs> Phazer<'cs> {
+    /// Returns a handle that cancels this [`Phazer`]'s writers.
+    ///
+    /// After [`CancellationHandle::cancel`] is called, every write through a writer created by
+    /// this [`Phazer`] fails and [`Phazer::commit`] fails.  A producer loop writing to the working
+    /// file exits at its next write; dropping the [`Phazer`] then removes the working file.
+    ///
+    /// The error kind is [`Other`][eo].  [`Interrupted`][ei] is not used because
+    /// [`write_all`](std::io::Write::write_all) retries interrupted writes.
+    ///
+    /// [eo]: std::io::ErrorKind::Other
+    /// [ei]: std::io::ErrorKind::Interrupted
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "simple")]
+    /// # {
+    /// use std::io::Write;
+    ///
+    /// use phazer::Phazer;
+    ///
+    /// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let phazer = Phazer::new("cancelled.txt");
+    ///     let handle = phazer.cancellation_handle();
+    ///     let mut writer = phazer.simple_writer()?;
+    ///     writer.write_all(b"first")?;
+    ///     // Typically called from a shutdown handler on another thread
+    ///     handle.cancel();
+    ///     assert!(writer.write_all(b"second").is_err());
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    ///
+    pub fn cancellation_handle(&self) -> CancellationHandle {
+        CancellationHandle {
+            cancelled: Arc::clone(&self.cancelled),
+        }
+    }
+}
+
+/// A clonable trigger that cancels a [`Phazer`]'s writers.
+///
+/// This struct is created by [`Phazer::cancellation_handle`].
+///
+#[derive(Clone)]
+pub struct CancellationHandle {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationHandle {
+    /// Cancels the [`Phazer`].  Subsequent writes and the commit fail.
+    ///
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+    /// Returns `true` if [`CancellationHandle::cancel`] has been called.
+    ///
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+// Return the error used when a write or commit is attempted after cancellation.
+pub(crate) fn cancelled_error() -> std::io::Error {
+    std::io::Error::other("the Phazer was cancelled")
+}
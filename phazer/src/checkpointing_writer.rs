@@ -0,0 +1,197 @@
+#![cfg(feature = "simple")]
+//
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A file-like thing that periodically publishes a snapshot of the working file to the target.
+//!
+//! This module is available when the `simple` feature is enabled.
+//!
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::simple_writer::SimplePhazerWriter;
+use crate::strategy::copy_then_rename;
+use crate::{current_phazer_id, working_path_for, Phazer};
+
+// Callback run after each checkpoint.
+type OnCheckpoint<'a> = Box<dyn FnMut(&Path, u64) + 'a>;
+
+impl<'cs> Phazer<'cs> {
+    /// Returns a synchronous file-like thing that builds the working file and periodically
+    /// publishes a consistent snapshot of it to the target.
+    ///
+    /// This is meant for very long running producers of append-style data.  If the process
+    /// crashes, the target holds the most recent checkpoint instead of nothing.  A checkpoint is
+    /// taken before the next write once [`CheckpointingWriter::every_bytes`] bytes have been
+    /// written or [`CheckpointingWriter::every`] has elapsed since the last checkpoint.  A
+    /// checkpoint can also be taken with [`CheckpointingWriter::checkpoint`].
+    ///
+    /// Each checkpoint copies the working file to a second working file beside the target, flushes
+    /// that copy to disk, then [renames](crate::file_system::FileSystem::rename) it to the target.
+    /// The copy is named like any other working file so, if the process dies during a checkpoint,
+    /// [`Phazer::sweep_stale_working_files`] removes it and [`Phazer::for_each_in_dir`] skips it.
+    /// The working file is left open so writing continues where it left off.  The configured
+    /// commit strategy is not used for checkpoints.  Call [`Phazer::commit`] as usual to publish
+    /// the final version.
+    ///
+    /// This method is available when the `simple` feature is enabled.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the working file cannot be created or opened.
+    ///
+    /// [ioe]: std::io::Error
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "simple")]
+    /// # {
+    /// use std::io::Write;
+    /// use std::time::Duration;
+    ///
+    /// use phazer::Phazer;
+    ///
+    /// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let phazer = Phazer::new("measurements.csv");
+    ///     let mut writer = phazer
+    ///         .checkpointing_writer()?
+    ///         .every(Duration::from_secs(60))
+    ///         .on_checkpoint(|path, len| println!("{} bytes saved to {}", len, path.display()));
+    ///     for i in 0..10 {
+    ///         writeln!(writer, "{},{}", i, i * i)?;
+    ///     }
+    ///     drop(writer);
+    ///     phazer.commit()?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    ///
+    pub fn checkpointing_writer<'a>(&'a self) -> std::io::Result<CheckpointingWriter<'a, 'cs>> {
+        let writer = self.wrapped_simple_writer()?;
+        let unique = format!("{}-{}-checkpoint", std::process::id(), current_phazer_id());
+        Ok(CheckpointingWriter {
+            writer,
+            parent: self,
+            checkpoint_path: working_path_for(&self.target_path, &unique),
+            every_bytes: None,
+            every: None,
+            on_checkpoint: None,
+            written: 0,
+            written_at_checkpoint: 0,
+            last_checkpoint: Instant::now(),
+        })
+    }
+}
+
+/// CheckpointingWriter is a synchronous file-like thing that builds the working file and
+/// periodically publishes a snapshot of it to the target.
+///
+/// It maintains a reference to the [`Phazer`] used to construct it, ensuring [`Phazer::commit`]
+/// cannot be called if there are any writers.
+///
+/// This struct is available when the `simple` feature is enabled.
+pub struct CheckpointingWriter<'a, 'cs> {
+    writer: SimplePhazerWriter<'a, 'cs>,
+    parent: &'a Phazer<'cs>,
+    checkpoint_path: PathBuf,
+    every_bytes: Option<u64>,
+    every: Option<Duration>,
+    on_checkpoint: Option<OnCheckpoint<'a>>,
+    written: u64,
+    written_at_checkpoint: u64,
+    last_checkpoint: Instant,
+}
+
+impl<'a, 'cs> CheckpointingWriter<'a, 'cs> {
+    /// Takes a checkpoint once `value` bytes have been written since the last checkpoint.
+    ///
+    pub fn every_bytes(mut self, value: u64) -> Self {
+        self.every_bytes = Some(value);
+        self
+    }
+    /// Takes a checkpoint once `value` has elapsed since the last checkpoint.
+    ///
+    pub fn every(mut self, value: Duration) -> Self {
+        self.every = Some(value);
+        self
+    }
+    /// Calls `value` after each checkpoint with the target path and the number of bytes written
+    /// so far.
+    ///
+    pub fn on_checkpoint<F>(mut self, value: F) -> Self
+    where
+        F: FnMut(&Path, u64) + 'a,
+    {
+        self.on_checkpoint = Some(Box::new(value));
+        self
+    }
+    /// Publishes a snapshot of the working file to the target now.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the snapshot cannot be made or renamed to the target.
+    /// The working file is not affected so writing can continue.
+    ///
+    /// [ioe]: std::io::Error
+    ///
+    pub fn checkpoint(&mut self) -> std::io::Result<()> {
+        let parent = self.parent;
+        self.writer.flush()?;
+        let rv = copy_then_rename(
+            parent.file_system,
+            &parent.working_path,
+            &self.checkpoint_path,
+            &parent.target_path,
+        );
+        if let Err(e) = rv {
+            let _ = parent.file_system.remove_file(&self.checkpoint_path);
+            return Err(e);
+        }
+        self.written_at_checkpoint = self.written;
+        self.last_checkpoint = Instant::now();
+        if let Some(on_checkpoint) = &mut self.on_checkpoint {
+            on_checkpoint(&parent.target_path, self.written);
+        }
+        Ok(())
+    }
+    // Return true if a checkpoint is due.
+    fn checkpoint_due(&self) -> bool {
+        let bytes_due = self
+            .every_bytes
+            .is_some_and(|n| self.written - self.written_at_checkpoint >= n);
+        let time_due = self
+            .every
+            .is_some_and(|d| self.last_checkpoint.elapsed() >= d);
+        bytes_due || time_due
+    }
+}
+
+impl<'a, 'cs> Write for CheckpointingWriter<'a, 'cs> {
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        // Checkpoint first so a failed checkpoint never hides bytes that were written
+        if self.checkpoint_due() {
+            self.checkpoint()?;
+        }
+        let n = self.writer.write(buf)?;
+        self.written += n as u64;
+        Ok(n)
+    }
+}
@@ -0,0 +1,103 @@
+#![cfg(feature = "sha256")]
+//
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Commit the working file along with a sidecar checksum file.
+//
+// This module is available when the `sha256` feature is enabled.
+
+use std::ffi::OsString;
+use std::fs::OpenOptions;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::sync::atomic::Ordering;
+
+use sha2::{Digest, Sha256};
+
+use crate::file_system::FileSystem;
+use crate::Phazer;
+
+impl<'cs> Phazer<'cs> {
+    /// Commits the target and a sidecar checksum file named like the target with `.sha256`
+    /// appended (e.g. `file.tar.gz` and `file.tar.gz.sha256`).
+    ///
+    /// The SHA-256 digest of the working file is calculated and the sidecar file is built in its
+    /// own working file before either is committed.  The target is committed first then,
+    /// immediately after, the sidecar file is committed.  The sidecar contains the hex digest and
+    /// the target's file name in the format used by `sha256sum` so `sha256sum --check` can verify
+    /// the target.
+    ///
+    /// Each commit is atomic but the two commits are **not atomic as a pair**.  For a brief period
+    /// (or indefinitely if the second commit fails) the new target may be paired with an old
+    /// sidecar file.
+    ///
+    /// This method is available when the `sha256` feature is enabled.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the working file cannot be read, the sidecar file cannot be
+    /// built, or either commit fails.  If the working file was not created then nothing is
+    /// committed and `Ok(())` is returned.
+    ///
+    /// [ioe]: std::io::Error
+    ///
+    pub fn commit_with_sidecar_checksum(self) -> std::io::Result<()> {
+        if !self.file_created.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        let digest = sha256_hex(self.file_system, &self.working_path)?;
+        let mut sidecar_target: OsString = self.target_path.clone().into();
+        sidecar_target.push(".sha256");
+        let sidecar = Phazer::inner_new(
+            sidecar_target.into(),
+            self.commit_strategy,
+            self.file_system,
+        );
+        let name = self
+            .target_path
+            .file_name()
+            .map(|n| n.to_string_lossy())
+            .unwrap_or_default();
+        sidecar.file_created.store(true, Ordering::Relaxed);
+        let mut file = sidecar.file_system.open(
+            &sidecar.working_path,
+            OpenOptions::new().write(true).create(true).truncate(true),
+        )?;
+        writeln!(file, "{}  {}", digest, name)?;
+        drop(file);
+        self.commit()?;
+        sidecar.commit()
+    }
+}
+
+// Return the SHA-256 digest of the file at `path` as lowercase hex.
+fn sha256_hex(file_system: &dyn FileSystem, path: &Path) -> std::io::Result<String> {
+    let mut file = file_system.open(path, OpenOptions::new().read(true))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 8192];
+    loop {
+        let n = file.read(&mut buffer)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buffer[..n]);
+    }
+    Ok(to_hex(&hasher.finalize()))
+}
+
+// Return `bytes` as lowercase hex.
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -0,0 +1,148 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Debounced commits.  Updates to the same target are coalesced so only the latest is committed.
+//!
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crate::Phazer;
+
+/// [`CommitQueue`] collects [`Phazer`]s and commits them at most once per interval.
+///
+/// A user interface that saves configuration changes as they are made is a good example.  Rather
+/// than committing after every edit, each edit is written to a new [`Phazer`] which is enqueued.
+/// If a [`Phazer`] for the same target is already waiting, the waiting one is dropped (which
+/// removes its working file) and replaced.  Only the latest update for each target is committed.
+///
+/// [`CommitQueue`] does not create threads or timers.  The application calls
+/// [`CommitQueue::flush_if_due`] periodically (for example, from an idle handler) and
+/// [`CommitQueue::flush`] when it must commit immediately (for example, at shutdown).  Dropping a
+/// [`CommitQueue`] discards everything waiting in it.
+///
+/// Coalescing is keyed on the target path exactly as it was given to the [`Phazer`].  Use full
+/// paths so the same file is not enqueued under two different names.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(feature = "simple")]
+/// # {
+/// use std::io::Write;
+/// use std::time::Duration;
+///
+/// use phazer::commit_queue::CommitQueue;
+/// use phazer::Phazer;
+///
+/// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let mut queue = CommitQueue::new(Duration::from_secs(2));
+///     for port in 1..=3 {
+///         let phazer = Phazer::new("settings.cfg");
+///         let mut writer = phazer.simple_writer()?;
+///         writeln!(writer, "Port={}", port)?;
+///         drop(writer);
+///         queue.enqueue(phazer);
+///     }
+///     // Only "Port=3" is committed
+///     for (target, result) in queue.flush() {
+///         result?;
+///         println!("{} saved", target.display());
+///     }
+///     Ok(())
+/// }
+/// # }
+/// ```
+///
+pub struct CommitQueue<'cs> {
+    interval: Duration,
+    pending: HashMap<PathBuf, Phazer<'cs>>,
+    oldest: Option<Instant>,
+}
+
+impl<'cs> CommitQueue<'cs> {
+    /// Creates an empty [`CommitQueue`].
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - [`CommitQueue::flush_if_due`] commits when the oldest waiting update has
+    ///   been waiting at least this long.
+    ///
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            pending: HashMap::new(),
+            oldest: None,
+        }
+    }
+    /// Adds `phazer` to the queue replacing (and discarding) any waiting [`Phazer`] with the same
+    /// target.
+    ///
+    /// All writers for `phazer` must be dropped before it can be enqueued.
+    ///
+    pub fn enqueue(&mut self, phazer: Phazer<'cs>) {
+        if self.oldest.is_none() {
+            self.oldest = Some(Instant::now());
+        }
+        self.pending
+            .insert(phazer.target_path().to_path_buf(), phazer);
+    }
+    /// Returns the number of targets waiting to be committed.
+    ///
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+    /// Returns `true` if nothing is waiting to be committed.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+    /// Returns `true` if the oldest waiting update has been waiting at least the interval.
+    ///
+    pub fn is_due(&self) -> bool {
+        match self.oldest {
+            Some(oldest) => oldest.elapsed() >= self.interval,
+            None => false,
+        }
+    }
+    /// Commits everything waiting if [`CommitQueue::is_due`] returns `true`.
+    ///
+    /// # Return Value
+    ///
+    /// The target path and commit result for each [`Phazer`] committed.  The returned vector is
+    /// empty if nothing was due.
+    ///
+    pub fn flush_if_due(&mut self) -> Vec<(PathBuf, std::io::Result<()>)> {
+        if self.is_due() {
+            self.flush()
+        } else {
+            Vec::new()
+        }
+    }
+    /// Commits everything waiting.
+    ///
+    /// A failed commit does not stop the remaining commits.
+    ///
+    /// # Return Value
+    ///
+    /// The target path and commit result for each [`Phazer`] committed.
+    ///
+    pub fn flush(&mut self) -> Vec<(PathBuf, std::io::Result<()>)> {
+        self.oldest = None;
+        self.pending
+            .drain()
+            .map(|(target, phazer)| (target, phazer.commit()))
+            .collect()
+    }
+}
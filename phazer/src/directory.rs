@@ -0,0 +1,194 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Two-phase commit for a whole directory tree.
+//!
+use std::fs::{create_dir, remove_dir_all, rename, symlink_metadata};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use crate::{current_phazer_id, os, working_path_for};
+
+/// [`DirectoryPhazer`] is the directory analogue of [`Phazer`]; a directory tree is built in a
+/// staging directory then swapped into place so the target directory appears complete or not at
+/// all.
+///
+/// The staging directory is in the same parent as the target with a unique name.  It is created
+/// by the first call to [`DirectoryPhazer::staging_dir`].  If the [`DirectoryPhazer`] is dropped
+/// without a commit, the staging directory and everything in it is removed.
+///
+/// If the target does not exist, the commit is a single rename.  If the target exists, Linux
+/// swaps the staging directory and the target atomically with `renameat2` (`RENAME_EXCHANGE`)
+/// then removes the old tree.  Elsewhere, or if the filesystem does not support the exchange, a
+/// commit is not a single atomic step.  The target is renamed aside, the staging directory is
+/// renamed into place, then the old tree is removed.  Between the two renames the target does
+/// not exist.  If the second rename fails, the old tree is renamed back.
+///
+/// [`Phazer`]: crate::Phazer
+///
+/// # Example
+///
+/// ```
+/// use std::fs::write;
+///
+/// use phazer::directory::DirectoryPhazer;
+///
+/// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let mut phazer = DirectoryPhazer::new("site");
+///     let staging = phazer.staging_dir()?;
+///     write(staging.join("index.html"), "<h1>Hello</h1>")?;
+///     write(staging.join("about.html"), "<h1>About</h1>")?;
+///     phazer.commit()?;
+///     # std::fs::remove_dir_all("site")?;
+///     Ok(())
+/// }
+/// ```
+///
+pub struct DirectoryPhazer {
+    target_dir: PathBuf,
+    staging_dir: PathBuf,
+    aside_dir: PathBuf,
+    staging_created: bool,
+    committed: bool,
+}
+
+impl DirectoryPhazer {
+    /// Creates a [`DirectoryPhazer`] for the target directory `target_dir`.
+    ///
+    /// Nothing is created until [`DirectoryPhazer::staging_dir`] is called.
+    ///
+    /// # Arguments
+    ///
+    /// * `target_dir` - Path of the directory to create or replace.
+    ///
+    pub fn new<P>(target_dir: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        let target_dir = target_dir.into();
+        let unique = format!("{}-{}", std::process::id(), current_phazer_id());
+        let staging_dir = working_path_for(&target_dir, &unique);
+        let aside_dir = working_path_for(&target_dir, &format!("{}-old", unique));
+        Self {
+            target_dir,
+            staging_dir,
+            aside_dir,
+            staging_created: false,
+            committed: false,
+        }
+    }
+    /// Returns the path of the target directory.
+    ///
+    pub fn target_dir(&self) -> &Path {
+        &self.target_dir
+    }
+    /// Returns the path of the staging directory.
+    ///
+    /// The staging directory may not exist yet; [`DirectoryPhazer::staging_dir`] creates it.
+    ///
+    pub fn working_path(&self) -> &Path {
+        &self.staging_dir
+    }
+    /// Returns the staging directory, creating it if necessary, so it can be populated.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the staging directory cannot be created.  Otherwise the
+    /// path of the staging directory is returned.
+    ///
+    /// [ioe]: std::io::Error
+    ///
+    pub fn staging_dir(&mut self) -> std::io::Result<&Path> {
+        if !self.staging_created {
+            create_dir(&self.staging_dir)?;
+            self.staging_created = true;
+        }
+        Ok(&self.staging_dir)
+    }
+    /// Swaps the staging directory into place as the target directory.
+    ///
+    /// If the staging directory was never created then nothing is committed.  If the target
+    /// exists it is exchanged with the staging directory where that is supported or, otherwise,
+    /// renamed aside before the staging directory is renamed to the target.  The old tree is then
+    /// removed.  A failure to remove the old tree is ignored; the commit has succeeded.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the target cannot be renamed aside or if the staging
+    /// directory cannot be renamed to the target.  In the second case the old tree is renamed
+    /// back to the target.  In both cases the staging directory is removed.
+    ///
+    /// [ioe]: std::io::Error
+    ///
+    pub fn commit(mut self) -> std::io::Result<()> {
+        if !self.staging_created {
+            return Ok(());
+        }
+        let moved_aside = match symlink_metadata(&self.target_dir) {
+            Ok(_) => {
+                match os::rename_exchange(&self.staging_dir, &self.target_dir) {
+                    Ok(()) => {
+                        self.committed = true;
+                        // The old tree now has the staging name.  A leftover is only clutter.
+                        let _ = remove_dir_all(&self.staging_dir);
+                        return Ok(());
+                    }
+                    Err(e) if e.kind() == ErrorKind::Unsupported => {}
+                    Err(e) => return Err(e),
+                }
+                rename(&self.target_dir, &self.aside_dir)?;
+                true
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => false,
+            Err(e) => return Err(e),
+        };
+        if let Err(e) = rename(&self.staging_dir, &self.target_dir) {
+            if moved_aside {
+                // Put the old tree back.  If that fails there is nothing more to be done.
+                let _ = rename(&self.aside_dir, &self.target_dir);
+            }
+            return Err(e);
+        }
+        self.committed = true;
+        if moved_aside {
+            // The new tree is in place.  A leftover old tree is only clutter.
+            let _ = remove_dir_all(&self.aside_dir);
+        }
+        Ok(())
+    }
+    /// Removes the staging directory without committing.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the staging directory cannot be removed.
+    ///
+    /// [ioe]: std::io::Error
+    ///
+    pub fn discard(mut self) -> std::io::Result<()> {
+        if !self.staging_created {
+            return Ok(());
+        }
+        // Nothing is left for drop to do
+        self.staging_created = false;
+        remove_dir_all(&self.staging_dir)
+    }
+}
+
+impl Drop for DirectoryPhazer {
+    fn drop(&mut self) {
+        if self.staging_created && !self.committed {
+            let _ = remove_dir_all(&self.staging_dir);
+        }
+    }
+}
@@ -0,0 +1,360 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// The error types returned by a Phazer.
+
+use std::io::ErrorKind;
+
+/// [`AfterCommitError`] wraps an error returned by an [`after_commit`][ac] hook.
+///
+/// When a commit fails with an [`Error`][ioe] holding an [`AfterCommitError`], the target was
+/// replaced; only the hook failed.
+///
+/// [ac]: crate::PhazerBuilderWithTarget::after_commit
+/// [ioe]: std::io::Error
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(feature = "simple")]
+/// # {
+/// use std::io::Write;
+///
+/// use phazer::{AfterCommitError, PhazerBuilder};
+///
+/// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let phazer = PhazerBuilder::with_target("indexed.txt")
+///         .after_commit(|details| {
+///             println!("update the index for {}", details.get_target_path().display());
+///             Ok(())
+///         })
+///         .build();
+///     let mut writer = phazer.simple_writer()?;
+///     writer.write_all(b"indexed content")?;
+///     drop(writer);
+///     match phazer.commit() {
+///         Ok(()) => {}
+///         Err(e) if AfterCommitError::is_after_commit(&e) => eprintln!("committed but {}", e),
+///         Err(e) => return Err(e.into()),
+///     }
+///     Ok(())
+/// }
+/// # }
+/// ```
+///
+#[derive(Debug)]
+pub struct AfterCommitError {
+    source: std::io::Error,
+}
+
+impl AfterCommitError {
+    /// Returns `true` if `error` holds an [`AfterCommitError`].
+    ///
+    pub fn is_after_commit(error: &std::io::Error) -> bool {
+        error
+            .get_ref()
+            .is_some_and(|inner| inner.is::<AfterCommitError>())
+    }
+    /// Returns the error returned by the hook.
+    ///
+    pub fn into_inner(self) -> std::io::Error {
+        self.source
+    }
+    // Wrap the hook's error keeping its kind.
+    pub(crate) fn wrap(source: std::io::Error) -> std::io::Error {
+        std::io::Error::new(source.kind(), AfterCommitError { source })
+    }
+}
+
+impl std::fmt::Display for AfterCommitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "the after-commit hook failed: {}", self.source)
+    }
+}
+
+impl std::error::Error for AfterCommitError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// [`PruneError`] wraps an error from the pruning done by
+/// [`Phazer::commit_and_prune`](crate::Phazer::commit_and_prune).
+///
+/// When [`Phazer::commit_and_prune`](crate::Phazer::commit_and_prune) fails with an [`Error`][ioe]
+/// holding a [`PruneError`], the target was replaced; only the pruning failed.  Some files may have
+/// been removed.
+///
+/// [ioe]: std::io::Error
+///
+/// # Example
+///
+/// ```no_run
+/// # #[cfg(feature = "simple")]
+/// # {
+/// use std::io::Write;
+///
+/// use phazer::{Phazer, PruneError};
+///
+/// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let phazer = Phazer::new("logs/log-20240101.txt");
+///     let mut writer = phazer.simple_writer()?;
+///     writer.write_all(b"logged")?;
+///     drop(writer);
+///     match phazer.commit_and_prune(30, |p| p.extension().is_some_and(|e| e == "txt")) {
+///         Ok(_) => {}
+///         Err(e) if PruneError::is_prune_error(&e) => eprintln!("committed but {}", e),
+///         Err(e) => return Err(e.into()),
+///     }
+///     Ok(())
+/// }
+/// # }
+/// ```
+///
+#[derive(Debug)]
+pub struct PruneError {
+    source: std::io::Error,
+}
+
+impl PruneError {
+    /// Returns `true` if `error` holds a [`PruneError`].
+    ///
+    pub fn is_prune_error(error: &std::io::Error) -> bool {
+        error
+            .get_ref()
+            .is_some_and(|inner| inner.is::<PruneError>())
+    }
+    /// Returns the error from the pruning.
+    ///
+    pub fn into_inner(self) -> std::io::Error {
+        self.source
+    }
+    // Wrap the pruning's error keeping its kind.
+    pub(crate) fn wrap(source: std::io::Error) -> std::io::Error {
+        std::io::Error::new(source.kind(), PruneError { source })
+    }
+}
+
+impl std::fmt::Display for PruneError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "pruning after the commit failed: {}", self.source)
+    }
+}
+
+impl std::error::Error for PruneError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// The error returned when the working file is smaller than the size given to
+/// [`PhazerBuilderWithTarget::min_commit_bytes`](crate::PhazerBuilderWithTarget::min_commit_bytes).
+///
+/// The error is wrapped in an [`InvalidData`][id] [`std::io::Error`].  Use
+/// [`TooSmallError::is_too_small`] to tell it apart from other commit failures.
+///
+/// [id]: std::io::ErrorKind::InvalidData
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(feature = "simple")]
+/// # {
+/// use std::io::Write;
+///
+/// use phazer::{PhazerBuilder, TooSmallError};
+///
+/// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let phazer = PhazerBuilder::with_target("names.zip")
+///         .min_commit_bytes(1024)
+///         .build();
+///     let mut writer = phazer.simple_writer()?;
+///     writer.write_all(b"<html>Not Found</html>")?;
+///     drop(writer);
+///     match phazer.commit() {
+///         Ok(()) => {}
+///         Err(e) if TooSmallError::is_too_small(&e) => eprintln!("not committed: {}", e),
+///         Err(e) => return Err(e.into()),
+///     }
+///     Ok(())
+/// }
+/// # }
+/// ```
+///
+#[derive(Debug)]
+pub struct TooSmallError {
+    pub(crate) size: u64,
+    pub(crate) minimum: u64,
+}
+
+impl TooSmallError {
+    /// Returns `true` if `error` holds a [`TooSmallError`].
+    ///
+    pub fn is_too_small(error: &std::io::Error) -> bool {
+        error
+            .get_ref()
+            .is_some_and(|inner| inner.is::<TooSmallError>())
+    }
+    /// Returns the size of the working file.
+    ///
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+    /// Returns the minimum size that was required.
+    ///
+    pub fn minimum(&self) -> u64 {
+        self.minimum
+    }
+}
+
+impl std::fmt::Display for TooSmallError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "the working file is {} bytes; at least {} bytes are required",
+            self.size, self.minimum
+        )
+    }
+}
+
+impl std::error::Error for TooSmallError {}
+
+/// The error returned by [`Phazer::commit2`](crate::Phazer::commit2) identifying the step of the
+/// commit that failed.
+///
+/// Each variant holds the [`std::io::Error`] from the failed step.  The step tells the
+/// application what state the target is in; the target is untouched after a failed
+/// [`Prepare`][pep], [`Lock`][pel], [`Sync`][pes], or [`Rename`][per] (except a failed
+/// [`Sync`][pes] of the target's directory) and has been replaced after a failed
+/// [`Verify`][pev], a [`ChecksumMismatch`][pecm], or a failed [`AfterCommit`][peac].
+///
+/// A [`PhazerError`] converts into the [`std::io::Error`] it holds so `?` works in functions that
+/// return [`std::io::Result`].  The converted error is the same error
+/// [`Phazer::commit`](crate::Phazer::commit) returns.
+///
+/// [pep]: PhazerError::Prepare
+/// [pel]: PhazerError::Lock
+/// [pes]: PhazerError::Sync
+/// [per]: PhazerError::Rename
+/// [pev]: PhazerError::Verify
+/// [pecm]: PhazerError::ChecksumMismatch
+/// [peac]: PhazerError::AfterCommit
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(feature = "simple")]
+/// # {
+/// use std::io::Write;
+///
+/// use phazer::{Phazer, PhazerError};
+///
+/// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let phazer = Phazer::new("classified.txt");
+///     let mut writer = phazer.simple_writer()?;
+///     writer.write_all(b"classified content")?;
+///     drop(writer);
+///     match phazer.commit2() {
+///         Ok(()) => {}
+///         Err((PhazerError::Rename(e), p)) => {
+///             eprintln!("the target is unchanged: {}", e);
+///             p.discard()?;
+///         }
+///         Err((e, _)) => return Err(e.into()),
+///     }
+///     Ok(())
+/// }
+/// # }
+/// ```
+///
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum PhazerError {
+    /// A check or hook that runs before the commit failed.  For example, the
+    /// [`Phazer`](crate::Phazer) was cancelled, the working file was too small, the `before_commit`
+    /// hook failed, or the commit marker or the backup of the target could not be written.
+    Prepare(std::io::Error),
+    /// The lock file that serializes commits (`serialize_with_lock`) could not be opened or locked.
+    Lock(std::io::Error),
+    /// The working file or the target's directory could not be synchronized.
+    Sync(std::io::Error),
+    /// The commit strategy failed to replace the target with the working file.
+    Rename(std::io::Error),
+    /// The committed target could not be read back or could not be given the modification time
+    /// chosen by `set_mtime`.
+    Verify(std::io::Error),
+    /// The ends of the committed target read back by `verify_boundaries` did not match the ends of
+    /// the working file.  The held error is an [`InvalidData`][ekid] error.
+    ///
+    /// [ekid]: ErrorKind::InvalidData
+    ChecksumMismatch(std::io::Error),
+    /// The `after_commit` hook failed.  The held error is an [`AfterCommitError`].
+    AfterCommit(std::io::Error),
+}
+
+impl PhazerError {
+    /// Returns the kind of the held error.
+    ///
+    pub fn kind(&self) -> ErrorKind {
+        self.io_error().kind()
+    }
+    /// Returns the held error.
+    ///
+    pub fn io_error(&self) -> &std::io::Error {
+        match self {
+            PhazerError::Prepare(e)
+            | PhazerError::Lock(e)
+            | PhazerError::Sync(e)
+            | PhazerError::Rename(e)
+            | PhazerError::Verify(e)
+            | PhazerError::ChecksumMismatch(e)
+            | PhazerError::AfterCommit(e) => e,
+        }
+    }
+}
+
+impl std::fmt::Display for PhazerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let step = match self {
+            PhazerError::Prepare(_) => "preparing the commit",
+            PhazerError::Lock(_) => "locking the target",
+            PhazerError::Sync(_) => "synchronizing",
+            PhazerError::Rename(_) => "replacing the target",
+            PhazerError::Verify(_) => "verifying the target",
+            PhazerError::ChecksumMismatch(_) => "comparing the target with the working file",
+            PhazerError::AfterCommit(_) => "running the after-commit hook",
+        };
+        write!(f, "{} failed: {}", step, self.io_error())
+    }
+}
+
+impl std::error::Error for PhazerError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.io_error())
+    }
+}
+
+impl From<PhazerError> for std::io::Error {
+    fn from(value: PhazerError) -> Self {
+        match value {
+            PhazerError::Prepare(e)
+            | PhazerError::Lock(e)
+            | PhazerError::Sync(e)
+            | PhazerError::Rename(e)
+            | PhazerError::Verify(e)
+            | PhazerError::ChecksumMismatch(e)
+            | PhazerError::AfterCommit(e) => e,
+        }
+    }
+}
@@ -0,0 +1,317 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A minimal abstraction over the filesystem operations used to commit and clean up.
+//!
+//! Commit strategies reach the filesystem through [`CommitDetails::get_file_system`][gfs] instead
+//! of calling the Standard Library directly.  That makes it possible to test a commit strategy
+//! (including the retry / backoff logic) with a mock filesystem.
+//!
+//! [`MemoryFileSystem`] keeps files in memory so a commit strategy can be exercised
+//! deterministically without touching the disk.
+//!
+//! [gfs]: crate::CommitDetails::get_file_system
+//!
+use std::collections::HashMap;
+use std::fs::{File, Metadata, OpenOptions};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+/// The filesystem operations used by [`Phazer`][p] and the commit strategies.
+///
+/// [`StdFileSystem`] is the implementation used unless a different one is assigned with
+/// [`PhazerBuilderWithTarget::file_system`][fs].
+///
+/// A few operations always go to the operating system: creating the working file's directory,
+/// locking the lock file, reading commit markers during recovery, following symbolic links,
+/// `renameat2` / `ReplaceFileW`, and syncing files and directories to disk.  The associated functions that are not
+/// tied to a [`Phazer`][p] ([`Phazer::for_each_in_dir`][fed], [`Phazer::recover_markers`][rm],
+/// [`Phazer::sweep_stale_working_files`][sswf], and [`swap`][s]) use [`STD_FILE_SYSTEM`].
+///
+/// [p]: crate::Phazer
+/// [fs]: crate::PhazerBuilderWithTarget::file_system
+/// [fed]: crate::Phazer::for_each_in_dir
+/// [rm]: crate::Phazer::recover_markers
+/// [sswf]: crate::Phazer::sweep_stale_working_files
+/// [s]: crate::swap
+///
+pub trait FileSystem: Sync {
+    /// Renames `from` to `to` replacing `to` if it exists.  See [`std::fs::rename`].
+    fn rename(&self, from: &Path, to: &Path) -> std::io::Result<()>;
+    /// Removes the file `path`.  See [`std::fs::remove_file`].
+    fn remove_file(&self, path: &Path) -> std::io::Result<()>;
+    /// Returns the metadata for `path`.  See [`std::fs::metadata`].
+    fn metadata(&self, path: &Path) -> std::io::Result<Metadata>;
+    /// Opens `path` using `options`.  See [`OpenOptions::open`].
+    fn open(&self, path: &Path, options: &OpenOptions) -> std::io::Result<File>;
+    /// Returns the paths of the entries in the directory `path`.  See [`std::fs::read_dir`].
+    ///
+    /// The default lists the directory with the Standard Library.
+    fn read_dir(&self, path: &Path) -> std::io::Result<Vec<PathBuf>> {
+        std::fs::read_dir(path)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect()
+    }
+    /// Creates `link` as a new name for the file `original`.  `link` must not exist.  See
+    /// [`std::fs::hard_link`].
+    ///
+    /// The default links the file with the Standard Library.
+    fn hard_link(&self, original: &Path, link: &Path) -> std::io::Result<()> {
+        std::fs::hard_link(original, link)
+    }
+    /// Returns the absolute form of `path` with every symbolic link resolved.  See
+    /// [`std::fs::canonicalize`].
+    ///
+    /// The default resolves the path with the Standard Library.
+    fn canonicalize(&self, path: &Path) -> std::io::Result<PathBuf> {
+        std::fs::canonicalize(path)
+    }
+    /// Renames `from` to `to` replacing `to` if it exists and does not return until the rename is
+    /// on the disk.  For Windows, [`Phazer`][p] uses this for every rename when
+    /// [`SyncMode::Full`][smf] is selected.
+    ///
+    /// The default calls `MoveFileExW` with `MOVEFILE_WRITE_THROUGH` for Windows and
+    /// [`FileSystem::rename`] otherwise.
+    ///
+    /// [p]: crate::Phazer
+    /// [smf]: crate::sync_mode::SyncMode::Full
+    fn rename_write_through(&self, from: &Path, to: &Path) -> std::io::Result<()> {
+        #[cfg(windows)]
+        return crate::os::move_file_ex(from, to, true);
+        #[cfg(not(windows))]
+        self.rename(from, to)
+    }
+}
+
+/// [`StdFileSystem`] forwards every operation to the Standard Library.
+///
+/// This crate provides a ready-to-use [`StdFileSystem`] instance named [`STD_FILE_SYSTEM`].
+///
+/// By default, this filesystem is used.
+///
+#[derive(Default)]
+pub struct StdFileSystem {}
+
+impl FileSystem for StdFileSystem {
+    fn rename(&self, from: &Path, to: &Path) -> std::io::Result<()> {
+        std::fs::rename(from, to)
+    }
+    fn remove_file(&self, path: &Path) -> std::io::Result<()> {
+        std::fs::remove_file(path)
+    }
+    fn metadata(&self, path: &Path) -> std::io::Result<Metadata> {
+        std::fs::metadata(path)
+    }
+    fn open(&self, path: &Path, options: &OpenOptions) -> std::io::Result<File> {
+        options.open(path)
+    }
+}
+
+/// A ready-to-use instance of [`StdFileSystem`].
+pub const STD_FILE_SYSTEM: &dyn FileSystem = &StdFileSystem {};
+
+/// [`MemoryFileSystem`] keeps the contents of each file in memory.
+///
+/// Renames, removals, hard links, and directory listings operate on the in-memory files so a
+/// [`CommitStrategy`][cs] can be tested without touching the disk.  Rename failures can be
+/// injected with [`MemoryFileSystem::fail_renames`] to exercise retry logic deterministically.
+///
+/// A hard link copies the contents; nothing writes to a file in place so the copy cannot be told
+/// apart from a link.  There are no directories or symbolic links so [`canonicalize`][fsc]
+/// returns the path unchanged.  [`metadata`][fsm] and [`open`][fso] return the Standard Library
+/// types which cannot be built from memory so both return an [`Unsupported`][eku] error.  The working file is built on disk
+/// by the writers so a [`Phazer`][p] cannot commit through a [`MemoryFileSystem`].  Call the
+/// strategy directly with a [`CommitDetails`][cd] that returns a [`MemoryFileSystem`] instead.
+///
+/// Only the strategies that use nothing but [`rename`][fsr], [`remove_file`][fsrf], and
+/// [`hard_link`][fshl] work with a [`MemoryFileSystem`]...
+/// * [`SimpleRenameStrategy`][srs]
+/// * [`RenameWithRetryStrategy`][rwrs] and [`ConfigurableRetryStrategy`][crs]
+/// * `RemoveThenRenameStrategy` (Windows)
+/// * [`CrossDeviceSafeStrategy`][cdss] unless a rename crosses devices
+/// * [`CreateNewStrategy`][cns]
+/// * [`FallbackStrategy`][fbs] built from the strategies above
+///
+/// The other strategies read metadata, open files, or call the operating system directly so they
+/// fail or bypass the [`MemoryFileSystem`].
+///
+/// [cs]: crate::CommitStrategy
+/// [fsr]: FileSystem::rename
+/// [fsrf]: FileSystem::remove_file
+/// [fshl]: FileSystem::hard_link
+/// [fsc]: FileSystem::canonicalize
+/// [srs]: crate::SimpleRenameStrategy
+/// [rwrs]: crate::RenameWithRetryStrategy
+/// [crs]: crate::ConfigurableRetryStrategy
+/// [cdss]: crate::CrossDeviceSafeStrategy
+/// [cns]: crate::CreateNewStrategy
+/// [fbs]: crate::FallbackStrategy
+/// [fsm]: FileSystem::metadata
+/// [fso]: FileSystem::open
+/// [eku]: std::io::ErrorKind::Unsupported
+/// [p]: crate::Phazer
+/// [cd]: crate::CommitDetails
+///
+/// # Example
+///
+/// ```
+/// use std::path::Path;
+///
+/// use phazer::file_system::{FileSystem, MemoryFileSystem};
+/// use phazer::{CommitDetails, SIMPLE_RENAME_STRATEGY};
+///
+/// struct Details<'a> {
+///     file_system: &'a MemoryFileSystem,
+/// }
+///
+/// impl CommitDetails for Details<'_> {
+///     fn get_working_path(&self) -> &Path {
+///         Path::new("config.toml.phazer-working")
+///     }
+///     fn get_target_path(&self) -> &Path {
+///         Path::new("config.toml")
+///     }
+///     fn get_jitter(&self) -> usize {
+///         0
+///     }
+///     fn get_file_system(&self) -> &dyn FileSystem {
+///         self.file_system
+///     }
+/// }
+///
+/// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let file_system = MemoryFileSystem::new();
+///     file_system.insert("config.toml", b"old".to_vec());
+///     file_system.insert("config.toml.phazer-working", b"new".to_vec());
+///     SIMPLE_RENAME_STRATEGY.commit(&Details { file_system: &file_system })?;
+///     assert_eq!(file_system.contents("config.toml"), Some(b"new".to_vec()));
+///     assert!(!file_system.contains("config.toml.phazer-working"));
+///     Ok(())
+/// }
+/// ```
+///
+#[derive(Default)]
+pub struct MemoryFileSystem {
+    state: Mutex<MemoryState>,
+}
+
+#[derive(Default)]
+struct MemoryState {
+    files: HashMap<PathBuf, Vec<u8>>,
+    rename_failures: usize,
+    rename_failure_kind: Option<ErrorKind>,
+}
+
+impl MemoryFileSystem {
+    /// Creates an empty [`MemoryFileSystem`].
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Creates or replaces the file `path` holding `contents`.
+    ///
+    pub fn insert<P: AsRef<Path>>(&self, path: P, contents: Vec<u8>) {
+        self.state()
+            .files
+            .insert(path.as_ref().to_path_buf(), contents);
+    }
+    /// Returns a copy of the contents of the file `path` or `None` if it does not exist.
+    ///
+    pub fn contents<P: AsRef<Path>>(&self, path: P) -> Option<Vec<u8>> {
+        self.state().files.get(path.as_ref()).cloned()
+    }
+    /// Returns `true` if the file `path` exists.
+    ///
+    pub fn contains<P: AsRef<Path>>(&self, path: P) -> bool {
+        self.state().files.contains_key(path.as_ref())
+    }
+    /// Makes the next `count` renames fail with `kind`.  The files are not changed by a failed
+    /// rename.
+    ///
+    pub fn fail_renames(&self, count: usize, kind: ErrorKind) {
+        let mut state = self.state();
+        state.rename_failures = count;
+        state.rename_failure_kind = Some(kind);
+    }
+    // A panic while the lock is held cannot leave the map half updated so ignore poisoning.
+    fn state(&self) -> MutexGuard<'_, MemoryState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl FileSystem for MemoryFileSystem {
+    fn rename(&self, from: &Path, to: &Path) -> std::io::Result<()> {
+        let mut state = self.state();
+        if state.rename_failures > 0 {
+            state.rename_failures -= 1;
+            let kind = state.rename_failure_kind.unwrap_or(ErrorKind::Other);
+            return Err(kind.into());
+        }
+        let contents = state
+            .files
+            .remove(from)
+            .ok_or_else(|| std::io::Error::from(ErrorKind::NotFound))?;
+        state.files.insert(to.to_path_buf(), contents);
+        Ok(())
+    }
+    fn rename_write_through(&self, from: &Path, to: &Path) -> std::io::Result<()> {
+        self.rename(from, to)
+    }
+    fn remove_file(&self, path: &Path) -> std::io::Result<()> {
+        match self.state().files.remove(path) {
+            Some(_) => Ok(()),
+            None => Err(ErrorKind::NotFound.into()),
+        }
+    }
+    fn metadata(&self, _path: &Path) -> std::io::Result<Metadata> {
+        Err(std::io::Error::new(
+            ErrorKind::Unsupported,
+            "metadata is not available from a MemoryFileSystem",
+        ))
+    }
+    fn open(&self, _path: &Path, _options: &OpenOptions) -> std::io::Result<File> {
+        Err(std::io::Error::new(
+            ErrorKind::Unsupported,
+            "files cannot be opened from a MemoryFileSystem",
+        ))
+    }
+    fn hard_link(&self, original: &Path, link: &Path) -> std::io::Result<()> {
+        let mut state = self.state();
+        if state.files.contains_key(link) {
+            return Err(ErrorKind::AlreadyExists.into());
+        }
+        let contents = state
+            .files
+            .get(original)
+            .cloned()
+            .ok_or_else(|| std::io::Error::from(ErrorKind::NotFound))?;
+        state.files.insert(link.to_path_buf(), contents);
+        Ok(())
+    }
+    fn canonicalize(&self, path: &Path) -> std::io::Result<PathBuf> {
+        Ok(path.to_path_buf())
+    }
+    fn read_dir(&self, path: &Path) -> std::io::Result<Vec<PathBuf>> {
+        let mut paths: Vec<_> = self
+            .state()
+            .files
+            .keys()
+            .filter(|p| p.parent() == Some(path))
+            .cloned()
+            .collect();
+        paths.sort();
+        Ok(paths)
+    }
+}
@@ -0,0 +1,240 @@
+#![cfg(feature = "gzip")]
+//
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! File-like things that use gzip to transform what's written to the working file.
+//!
+//! This module is available when the `gzip` feature is enabled.
+//!
+use std::io::Write;
+use std::sync::atomic::Ordering;
+
+use flate2::write::{GzDecoder, GzEncoder};
+use flate2::Compression;
+
+use crate::simple_writer::SimplePhazerWriter;
+use crate::Phazer;
+
+impl<'cs> Phazer<'cs> {
+    /// Returns a synchronous file-like thing that decompresses (gunzips) what's written to it
+    /// before writing to the working file.
+    ///
+    /// This is useful when compressed data arrives (e.g. over the network) but the target is
+    /// stored decompressed.  The committed target is the decompressed form.
+    ///
+    /// [`GunzipPhazerWriter::finish`] must be called after the last byte is written.  It ensures
+    /// a complete gzip stream was received.  Until it succeeds [`Phazer::commit`] fails with an
+    /// [`InvalidData`][id] error so a truncated download is never committed.
+    ///
+    /// This method is available when the `gzip` feature is enabled.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the working file cannot be opened / created.  Otherwise a
+    /// new [`GunzipPhazerWriter`] is returned.
+    ///
+    /// [id]: std::io::ErrorKind::InvalidData
+    /// [ioe]: std::io::Error
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "gzip")]
+    /// # {
+    /// use std::io::Write;
+    ///
+    /// use phazer::Phazer;
+    ///
+    /// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     // Compressed bytes would normally arrive from the network
+    ///     let compressed: Vec<u8> = Vec::new();
+    ///     let phazer = Phazer::new("decompressed.txt");
+    ///     let mut writer = phazer.gunzip_writer()?;
+    ///     writer.write_all(&compressed)?;
+    ///     // Fails if the gzip stream is incomplete
+    ///     writer.finish()?;
+    ///     phazer.commit()?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    ///
+    pub fn gunzip_writer<'a>(&'a self) -> std::io::Result<GunzipPhazerWriter<'a, 'cs>> {
+        let writer = self.wrapped_simple_writer()?;
+        self.unfinished_writer.store(true, Ordering::Relaxed);
+        Ok(GunzipPhazerWriter {
+            decoder: GzDecoder::new(writer),
+            parent: self,
+        })
+    }
+    /// Returns a synchronous file-like thing that compresses (gzips) what's written to it before
+    /// writing to the working file.
+    ///
+    /// This is meant for compressed snapshots like `state.json.gz`; the data is compressed as it
+    /// is written instead of being collected in memory first.  The committed target is the
+    /// compressed form.
+    ///
+    /// The gzip stream is finished when the writer is dropped (or when
+    /// [`GzipPhazerWriter::finish`] is called) so the working file always holds a complete
+    /// stream.  If the stream cannot be finished [`Phazer::commit`] fails with an
+    /// [`InvalidData`][id] error so a partial stream is never committed.
+    ///
+    /// This method is available when the `gzip` feature is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - Compression level from 0 (none) to 9 (best).  Values above 9 are treated as 9.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the working file cannot be opened / created.  Otherwise a
+    /// new [`GzipPhazerWriter`] is returned.
+    ///
+    /// [id]: std::io::ErrorKind::InvalidData
+    /// [ioe]: std::io::Error
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "gzip")]
+    /// # {
+    /// use std::io::Write;
+    ///
+    /// use phazer::Phazer;
+    ///
+    /// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let phazer = Phazer::new("snapshot.json.gz");
+    ///     let mut writer = phazer.gzip_writer(6)?;
+    ///     writer.write_all(br#"{"answer": 42}"#)?;
+    ///     // Finishes the gzip stream
+    ///     drop(writer);
+    ///     phazer.commit()?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    ///
+    pub fn gzip_writer<'a>(&'a self, level: u32) -> std::io::Result<GzipPhazerWriter<'a, 'cs>> {
+        let writer = self.wrapped_simple_writer()?;
+        Ok(GzipPhazerWriter {
+            encoder: Some(GzEncoder::new(writer, Compression::new(level.min(9)))),
+            parent: self,
+        })
+    }
+}
+
+/// GzipPhazerWriter is a synchronous file-like thing that compresses what's written to it into
+/// the working file.
+///
+/// It maintains a reference to the [`Phazer`] used to construct it, ensuring [`Phazer::commit`]
+/// cannot be called if there are any writers.  The gzip stream is finished when the writer is
+/// dropped.
+///
+/// This struct is available when the `gzip` feature is enabled.
+pub struct GzipPhazerWriter<'a, 'cs> {
+    encoder: Option<GzEncoder<SimplePhazerWriter<'a, 'cs>>>,
+    parent: &'a Phazer<'cs>,
+}
+
+impl<'a, 'cs> GzipPhazerWriter<'a, 'cs> {
+    /// Finishes the gzip stream and flushes it to the working file.
+    ///
+    /// Dropping the writer does the same but a failure is only reported by [`Phazer::commit`].
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the gzip stream cannot be written.  In that case
+    /// [`Phazer::commit`] fails.
+    ///
+    /// [ioe]: std::io::Error
+    ///
+    pub fn finish(mut self) -> std::io::Result<()> {
+        self.try_finish()
+    }
+    // Finish the stream; if that fails the commit must fail.
+    fn try_finish(&mut self) -> std::io::Result<()> {
+        let Some(encoder) = self.encoder.take() else {
+            return Ok(());
+        };
+        let rv = encoder.finish().and_then(|mut writer| writer.flush());
+        if rv.is_err() {
+            self.parent.unfinished_writer.store(true, Ordering::Relaxed);
+        }
+        rv
+    }
+    // The encoder is only taken by try_finish which consumes or drops the writer.
+    fn encoder(&mut self) -> &mut GzEncoder<SimplePhazerWriter<'a, 'cs>> {
+        self.encoder
+            .as_mut()
+            .expect("the encoder exists until the writer is finished")
+    }
+}
+
+impl<'a, 'cs> Drop for GzipPhazerWriter<'a, 'cs> {
+    fn drop(&mut self) {
+        let _ = self.try_finish();
+    }
+}
+
+impl<'a, 'cs> Write for GzipPhazerWriter<'a, 'cs> {
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.encoder().flush()
+    }
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.encoder().write(buf)
+    }
+}
+
+/// GunzipPhazerWriter is a synchronous file-like thing that decompresses what's written to it
+/// into the working file.
+///
+/// It maintains a reference to the [`Phazer`] used to construct it, ensuring [`Phazer::commit`]
+/// cannot be called if there are any writers.
+///
+/// This struct is available when the `gzip` feature is enabled.
+pub struct GunzipPhazerWriter<'a, 'cs> {
+    decoder: GzDecoder<SimplePhazerWriter<'a, 'cs>>,
+    parent: &'a Phazer<'cs>,
+}
+
+impl<'a, 'cs> GunzipPhazerWriter<'a, 'cs> {
+    /// Ensures a complete gzip stream was written and flushes the decompressed data to the working
+    /// file.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the gzip stream is truncated or corrupt.  In that case
+    /// [`Phazer::commit`] continues to fail.
+    ///
+    /// [ioe]: std::io::Error
+    ///
+    pub fn finish(self) -> std::io::Result<()> {
+        let mut writer = self.decoder.finish()?;
+        writer.flush()?;
+        self.parent
+            .unfinished_writer
+            .store(false, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+impl<'a, 'cs> Write for GunzipPhazerWriter<'a, 'cs> {
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.decoder.flush()
+    }
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.decoder.write(buf)
+    }
+}
@@ -0,0 +1,167 @@
+#![cfg(feature = "serde")]
+//
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Commit helpers for JSON targets.
+//
+// This module is available when the `serde` feature is enabled.
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::Phazer;
+
+impl<'cs> Phazer<'cs> {
+    /// Reads the target as JSON, lets `f` modify the value, then writes and commits the result.
+    ///
+    /// This turns the usual read-modify-write-commit chore for configuration files into one call.
+    /// If the target does not exist, or is empty, `f` is given an empty object.  The result is
+    /// written as pretty-printed JSON.
+    ///
+    /// Anything already written to the working file is replaced.
+    ///
+    /// This method is available when the `serde` feature is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - Closure that modifies the value.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the target cannot be read, the target is not valid JSON
+    /// ([`InvalidData`][id]), the working file cannot be written, or the commit fails.
+    ///
+    /// [ioe]: std::io::Error
+    /// [id]: std::io::ErrorKind::InvalidData
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "serde")]
+    /// # {
+    /// use phazer::Phazer;
+    ///
+    /// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let phazer = Phazer::new("settings.json");
+    ///     phazer.update_json(|value| {
+    ///         value["volume"] = 11.into();
+    ///     })?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    ///
+    pub fn update_json<F>(self, f: F) -> std::io::Result<()>
+    where
+        F: FnOnce(&mut Value),
+    {
+        let mut value = self.read_target_json()?;
+        f(&mut value);
+        self.write_json(&value)?;
+        self.commit()
+    }
+    /// Writes `value` as JSON to the working file then commits the working file.
+    ///
+    /// This replaces the create writer, serialize, flush, drop writer, commit sequence with one
+    /// call.  The result is written as pretty-printed JSON followed by a newline.  Anything
+    /// already written to the working file is replaced.  If `value` cannot be serialized nothing
+    /// is committed and the working file is removed.
+    ///
+    /// This method is available when the `serde` feature is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to write.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if `value` cannot be serialized, the working file cannot be
+    /// written, or the commit fails.  In each case the working file is removed.
+    ///
+    /// [ioe]: std::io::Error
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "serde")]
+    /// # {
+    /// use std::collections::BTreeMap;
+    ///
+    /// use phazer::Phazer;
+    ///
+    /// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mut limits = BTreeMap::new();
+    ///     limits.insert("connections", 100);
+    ///     limits.insert("requests_per_second", 250);
+    ///     Phazer::new("limits.json").commit_json(&limits)?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    ///
+    pub fn commit_json<T>(self, value: &T) -> std::io::Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.write_json(value)?;
+        self.commit()
+    }
+    // Replace the working file with `value` as pretty-printed JSON.
+    fn write_json<T>(&self, value: &T) -> std::io::Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.replace_working_file(|writer| {
+            serde_json::to_writer_pretty(&mut *writer, value)?;
+            writer.write_all(b"\n")
+        })
+    }
+    // Replace the working file with whatever `f` writes.
+    pub(crate) fn replace_working_file<F>(&self, f: F) -> std::io::Result<()>
+    where
+        F: FnOnce(&mut BufWriter<File>) -> std::io::Result<()>,
+    {
+        self.prepare_working_file()?;
+        // Whatever was written before is replaced so it does not matter who was first
+        let _ = self.first_writer();
+        let mut options = OpenOptions::new();
+        options.write(true).truncate(true).create(true);
+        self.apply_working_mode(&mut options);
+        let file = self.file_system.open(&self.working_path, &options)?;
+        let mut writer = BufWriter::new(file);
+        f(&mut writer)?;
+        writer.flush()
+    }
+    // Read and parse the target.  A missing or empty target is an empty object.
+    fn read_target_json(&self) -> std::io::Result<Value> {
+        let file = match self
+            .file_system
+            .open(&self.target_path, OpenOptions::new().read(true))
+        {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Value::Object(Map::new())),
+            Err(e) => return Err(e),
+        };
+        let mut text = String::new();
+        BufReader::new(file).read_to_string(&mut text)?;
+        if text.trim().is_empty() {
+            return Ok(Value::Object(Map::new()));
+        }
+        Ok(serde_json::from_str(&text)?)
+    }
+}
@@ -0,0 +1,103 @@
+#![cfg(feature = "length_delimited")]
+//
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Commit a stream of length-delimited records (the framing used for protobuf streams).
+//
+// This module is available when the `length_delimited` feature is enabled.
+
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+
+use crate::Phazer;
+
+impl<'cs> Phazer<'cs> {
+    /// Writes each message prefixed with its length as a varint then commits the working file.
+    ///
+    /// This is the framing used for streams of length-delimited protobuf messages (for example,
+    /// the framing produced by `writeDelimitedTo` in Java).  The length is encoded as an unsigned
+    /// base 128 varint.
+    ///
+    /// If the working file was already created (for example, by a writer) the records are
+    /// appended to it.  Otherwise the working file is created.  With
+    /// [`seed_from_target`][sft] it is created holding a copy of the target so the records are
+    /// appended to the target's records.
+    ///
+    /// [sft]: crate::PhazerBuilderWithTarget::seed_from_target
+    ///
+    /// This method is available when the `length_delimited` feature is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `msgs` - The encoded messages.  Each message becomes one record.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the working file cannot be written or the commit fails.
+    ///
+    /// [ioe]: std::io::Error
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "length_delimited")]
+    /// # {
+    /// use phazer::Phazer;
+    ///
+    /// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     // Messages would normally be encoded with a protobuf library
+    ///     let msgs = vec![b"first".to_vec(), b"second".to_vec()];
+    ///     let phazer = Phazer::new("records.bin");
+    ///     phazer.commit_length_delimited(msgs)?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    ///
+    pub fn commit_length_delimited<I>(self, msgs: I) -> std::io::Result<()>
+    where
+        I: IntoIterator<Item = Vec<u8>>,
+    {
+        let options = self.working_file_options()?;
+        let mut file = self.file_system.open(&self.working_path, &options)?;
+        // The records follow anything already in the working file
+        file.seek(SeekFrom::End(0))?;
+        let mut writer = BufWriter::new(file);
+        for msg in msgs {
+            write_varint(&mut writer, msg.len() as u64)?;
+            writer.write_all(&msg)?;
+        }
+        writer.flush()?;
+        drop(writer);
+        self.commit()
+    }
+}
+
+// Write `value` as an unsigned base 128 varint.
+fn write_varint<W: Write>(writer: &mut W, mut value: u64) -> std::io::Result<()> {
+    let mut buffer = [0u8; 10];
+    let mut i = 0;
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            buffer[i] = byte;
+            i += 1;
+            break;
+        }
+        buffer[i] = byte | 0x80;
+        i += 1;
+    }
+    writer.write_all(&buffer[..i])
+}
@@ -66,11 +66,11 @@
 //!
 //! Two important terms are used throughout this documentation...
 //! * target - This is the "final" file.  Continuing from the earlier examples, this would be the
-//! downloaded file when it has been successfully downloaded or the new configuration file when it's
-//! ready to be used.
+//!   downloaded file when it has been successfully downloaded or the new configuration file when
+//!   it's ready to be used.
 //! * working - This is the "temporary" file.  Writing is to the working file.  This crate manages
-//! the working file including generating a unique filename and discarding the file if it is not
-//! committed.
+//!   the working file including generating a unique filename and discarding the file if it is not
+//!   committed.
 //!
 //! # Getting Started
 //!
@@ -92,28 +92,171 @@
 //! [tpw]: crate::tokio_writer::TokioPhazerWriter
 //!
 //! By default, [`Phazer`] uses a simple rename commit strategy ([`SIMPLE_RENAME_STRATEGY`]).  When
-//! [`Phazer::commit`] is called, [`rename`] is used to replace the target file with the working
-//! file.  [`PhazerBuilder`] can be used to construct a [`Phazer`] with a different commit strategy.
+//! [`Phazer::commit`] is called, [`rename`](std::fs::rename) is used to replace the target file
+//! with the working file.  [`PhazerBuilder`] can be used to construct a [`Phazer`] with a different
+//! commit strategy.
 //! The one other commit strategy available with this crate is [`RENAME_WITH_RETRY_STRATEGY`].
 //!
 
+pub mod archive;
+pub mod async_std_writer;
+pub mod atomic_file;
+pub mod cancellation;
+pub mod checkpointing_writer;
+mod checksum;
+pub mod commit_queue;
+pub mod directory;
+mod error;
+pub mod file_system;
+pub mod gzip;
+mod json;
+mod length_delimited;
+pub mod mtime_policy;
+mod os;
+mod path_template;
+mod pointer;
+pub mod release_barrier;
+mod serialized;
 pub mod simple_writer;
+pub mod size_budget;
+mod strategy;
+pub mod strict;
+pub mod sync_mode;
+pub mod text_writer;
 pub mod tokio_writer;
+pub mod uring_writer;
+pub mod write_buffering;
+pub mod zip_archive;
 
-use std::fs::{remove_file, rename};
+use std::ffi::{OsStr, OsString};
+use std::fs::{create_dir, remove_dir, File, Metadata, OpenOptions};
+use std::io::{ErrorKind, Read, Seek, SeekFrom, Write};
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
 use std::path::{Path, PathBuf};
-use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 
-#[doc(hidden)]
+use file_system::{FileSystem, STD_FILE_SYSTEM};
+use mtime_policy::MtimePolicy;
+use size_budget::SizeBudget;
+use strategy::copy_file;
+use sync_mode::SyncMode;
+use write_buffering::WriteBuffering;
+
+pub use error::{AfterCommitError, PhazerError, PruneError, TooSmallError};
+pub use strategy::{
+    available_strategies, Backoff, ConfigurableRetryStrategy, CreateNewStrategy,
+    CrossDeviceSafeStrategy, DryRunStrategy, DurableRenameStrategy, FallbackStrategy,
+    LinkPreservingStrategy, RenameWithRetryStrategy, ReplaceContentsStrategy, SimpleRenameStrategy,
+    StrategyInfo, CREATE_NEW_STRATEGY, CROSS_DEVICE_SAFE_STRATEGY, DURABLE_RENAME_STRATEGY,
+    LINK_PRESERVING_STRATEGY, RENAME_WITH_RETRY_STRATEGY, REPLACE_CONTENTS_STRATEGY,
+    SIMPLE_RENAME_STRATEGY,
+};
+#[cfg(windows)]
+pub use strategy::{
+    MoveFileExStrategy, RemoveThenRenameStrategy, ReplaceFileStrategy, WindowsBestEffortStrategy,
+    MOVE_FILE_EX_STRATEGY, MOVE_FILE_EX_WRITE_THROUGH_STRATEGY, REMOVE_THEN_RENAME_STRATEGY,
+    REPLACE_FILE_STRATEGY, WINDOWS_BEST_EFFORT_STRATEGY,
+};
+#[cfg(feature = "trash")]
+pub use strategy::{TrashingStrategy, TRASHING_STRATEGY};
+
+/// [`CommitDetails`] provides a [`CommitStrategy`] with what it needs to know about a [`Phazer`].
 pub trait CommitDetails {
+    /// Returns the path of the working file.
+    ///
+    /// When [`CommitStrategy::commit`] is called the working file is complete and no writers are
+    /// open.
     fn get_working_path(&self) -> &Path;
+    /// Returns the path of the target file.
+    ///
+    /// A strategy that publishes somewhere other than the local filesystem (e.g. object storage)
+    /// can treat the target path as a key.
     fn get_target_path(&self) -> &Path;
+    /// Returns a value that differs between [`Phazer`]s.  It is useful for spreading out retries.
     fn get_jitter(&self) -> usize;
+    /// Returns the filesystem the strategy should use.
+    fn get_file_system(&self) -> &dyn FileSystem {
+        STD_FILE_SYSTEM
+    }
+    /// Records how many attempts the strategy has made.  A strategy that retries calls this so
+    /// [`Phazer::commit_verbose`] can report the count.  Strategies that make a single attempt
+    /// do not need to call it.
+    fn set_attempts(&self, _attempts: u32) {}
 }
 
-#[doc(hidden)]
+/// [`CommitStrategy`] performs the final step of a commit; transitioning the working file to the
+/// target.
+///
+/// This crate provides strategies that [rename](std::fs::rename) the working file.  Other
+/// strategies are possible.  For example, a strategy for object storage can read the working file
+/// and upload it using the target path as the key.  The `remote-upload-strategy` example shows
+/// such a strategy.
+///
+/// A strategy does not need to remove the working file.  If the working file still exists when
+/// the [`Phazer`] is dropped it is removed.
+///
+/// # Example
+///
+/// ```
+/// use phazer::{CommitDetails, CommitStrategy, PhazerBuilder};
+///
+/// // Commit by copying (leaving the working file for the Phazer to remove)
+/// struct CopyStrategy {}
+///
+/// impl CommitStrategy for CopyStrategy {
+///     fn commit(&self, phazer: &dyn CommitDetails) -> std::io::Result<()> {
+///         std::fs::copy(phazer.get_working_path(), phazer.get_target_path())?;
+///         Ok(())
+///     }
+/// }
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let phazer = PhazerBuilder::with_target("uses-copy-strategy.txt")
+///         .commit_strategy(&CopyStrategy {})
+///         .build();
+///
+///     // Build the working file
+///
+///     phazer.commit()?;
+///
+///     Ok(())
+/// }
+/// ```
+///
 pub trait CommitStrategy: Sync {
+    /// Transitions the working file to the target.
+    ///
+    /// # Arguments
+    ///
+    /// * `phazer` - Details about the [`Phazer`] being committed.
+    ///
     fn commit(&self, phazer: &dyn CommitDetails) -> std::io::Result<()>;
+    /// Returns `true` if the strategy may write into the existing target instead of replacing it.
+    ///
+    /// A backup (see [`backup_with_extension`][bwe]) is normally a hard link to the target.  For a
+    /// strategy that writes in-place the backup is a copy so the new content does not reach it.
+    /// The default is `false`.
+    ///
+    /// [bwe]: PhazerBuilderWithTarget::backup_with_extension
+    ///
+    fn writes_in_place(&self) -> bool {
+        false
+    }
+    /// Returns `true` if a successful commit leaves the working file in place.
+    ///
+    /// Once a commit succeeds the working file is normally gone and its name may be reused so a
+    /// [`Phazer`] leaves that name alone.  For a strategy that keeps the working file the
+    /// [`Phazer`] removes it when dropped (or when [`commit_ref`][pcr] starts over).  The default
+    /// is `false`.
+    ///
+    /// [pcr]: Phazer::commit_ref
+    ///
+    fn keeps_working_file(&self) -> bool {
+        false
+    }
 }
 
 /// [`Phazer`] manages the transition of the working file to the target file.
@@ -123,16 +266,53 @@ pub trait CommitStrategy: Sync {
 /// downloads three files from the internet then one [`Phazer`] is created for each file.
 ///
 /// By default, [`Phazer`] uses a simple rename commit strategy ([`SIMPLE_RENAME_STRATEGY`]).  When
-/// [`Phazer::commit`] is called, [`rename`] is used to replace the target file with the working
-/// file.  [`PhazerBuilder`] can be used to construct a [`Phazer`] with a different commit strategy.
+/// [`Phazer::commit`] is called, [`rename`](std::fs::rename) is used to replace the target file
+/// with the working file.  [`PhazerBuilder`] can be used to construct a [`Phazer`] with a different
+/// commit strategy.
 /// The one other commit strategy available with this crate is [`RENAME_WITH_RETRY_STRATEGY`].
 ///
+/// # Case-Insensitive Filesystems
+///
+/// The working file's name is the target's name plus a unique suffix so, even on a
+/// case-insensitive filesystem (the default for macOS and Windows), it never names the same file
+/// as the target.  A working file that would differ from the target only by case is refused
+/// before it is created.  If the target differs from an existing file only by case
+/// (`Config.toml` versus `config.toml`), a case-insensitive filesystem treats them as the same
+/// file; the commit replaces the existing file's content.  Whether the name's case changes
+/// depends on the filesystem.
+///
 pub struct Phazer<'cs> {
     file_created: AtomicBool,
     commit_strategy: &'cs dyn CommitStrategy,
+    file_system: &'cs dyn FileSystem,
     working_path: PathBuf,
     target_path: PathBuf,
     phazer_id: usize,
+    working_subdir: Option<PathBuf>,
+    working_subdir_created: AtomicBool,
+    preflight_target: bool,
+    expected_extension: Option<String>,
+    unfinished_writer: AtomicBool,
+    serialize_with_lock: bool,
+    commit_marker: bool,
+    backup_extension: Option<OsString>,
+    cancelled: Arc<AtomicBool>,
+    verify_boundaries: usize,
+    commit_on_panic: bool,
+    min_commit_bytes: u64,
+    restore_selinux_context: bool,
+    mtime_policy: Option<MtimePolicy>,
+    seed_from_target: bool,
+    size_budget: Option<SizeBudget>,
+    write_buffering: WriteBuffering,
+    #[cfg(unix)]
+    working_mode: Option<u32>,
+    sync_mode: SyncMode,
+    committed: AtomicBool,
+    commit_attempts: AtomicU32,
+    backed_up: AtomicBool,
+    before_commit: Option<BeforeCommit<'cs>>,
+    after_commit: Option<AfterCommit<'cs>>,
 }
 
 impl<'cs> Phazer<'cs> {
@@ -141,7 +321,7 @@ impl<'cs> Phazer<'cs> {
     /// # Arguments
     ///
     /// * `path` - Target file.  Ideally, the full path is specified so changes to the working
-    /// directory do not cause problems.  [canonicalize][std::fs::canonicalize] is helpful.
+    ///   directory do not cause problems.  [canonicalize][std::fs::canonicalize] is helpful.
     ///
     /// # Return Value
     ///
@@ -151,30 +331,285 @@ impl<'cs> Phazer<'cs> {
     where
         P: Into<PathBuf>,
     {
-        Self::inner_new(path.into(), SIMPLE_RENAME_STRATEGY)
+        Self::inner_new(path.into(), SIMPLE_RENAME_STRATEGY, STD_FILE_SYSTEM)
+    }
+    /// Creates a [`Phazer`] where `path` is the target file after checking that the target can
+    /// plausibly be committed.
+    ///
+    /// [`Phazer::new`] is infallible so obviously bad targets are only discovered when the first
+    /// writer is created or when [`Phazer::commit`] is called.  [`Phazer::new_checked`] performs a
+    /// few checks up front...
+    /// * The parent of the target must exist and must be a directory.
+    /// * If the target exists it must be a regular file (not a directory, socket, device, etc.).
+    ///
+    /// The checks are a snapshot.  Nothing prevents the filesystem from changing after
+    /// [`Phazer::new_checked`] returns.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Target file.  Ideally, the full path is specified so changes to the working
+    ///   directory do not cause problems.  [canonicalize][std::fs::canonicalize] is helpful.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the parent does not exist, the parent is not a directory,
+    /// or the target exists and is not a regular file.  Otherwise a new [`Phazer`] is returned.
+    ///
+    /// [ioe]: std::io::Error
+    ///
+    pub fn new_checked<P>(path: P) -> std::io::Result<Self>
+    where
+        P: Into<PathBuf>,
+    {
+        let target_path = path.into();
+        check_target(STD_FILE_SYSTEM, &target_path)?;
+        Ok(Self::inner_new(
+            target_path,
+            SIMPLE_RENAME_STRATEGY,
+            STD_FILE_SYSTEM,
+        ))
+    }
+    /// Returns an iterator that yields one [`Phazer`] for each regular file in `dir`.
+    ///
+    /// This is useful for jobs that rewrite every file in a directory (for example, re-encrypting
+    /// all the configuration files).  Each yielded [`Phazer`] targets an existing file so the file
+    /// can be read, transformed, written to the working file, and committed.
+    ///
+    /// Working files (this crate's temporary files) are skipped so the iterator never yields a
+    /// [`Phazer`] for a working file created while iterating.  Directories, symbolic links to
+    /// directories, and other non-regular files are also skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The directory to iterate.
+    /// * `commit_strategy` - The commit strategy used by each yielded [`Phazer`].
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if `dir` cannot be read.  Otherwise an iterator is returned.
+    /// The iterator yields an [`Error`][ioe] for each entry that cannot be inspected.
+    ///
+    /// [ioe]: std::io::Error
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "simple")]
+    /// # {
+    /// use std::fs::read_to_string;
+    /// use std::io::Write;
+    /// use std::path::Path;
+    ///
+    /// use phazer::{Phazer, SIMPLE_RENAME_STRATEGY};
+    ///
+    /// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     for phazer in Phazer::for_each_in_dir(Path::new("configs"), SIMPLE_RENAME_STRATEGY)? {
+    ///         let phazer = phazer?;
+    ///         // Read the existing target, transform it, and commit the result
+    ///         let text = read_to_string(phazer.target_path())?;
+    ///         let mut writer = phazer.simple_writer()?;
+    ///         writer.write_all(text.to_uppercase().as_bytes())?;
+    ///         drop(writer);
+    ///         phazer.commit()?;
+    ///     }
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    ///
+    pub fn for_each_in_dir(
+        dir: &Path,
+        commit_strategy: &'cs dyn CommitStrategy,
+    ) -> std::io::Result<PhazersInDir<'cs>> {
+        Ok(PhazersInDir {
+            commit_strategy,
+            paths: STD_FILE_SYSTEM.read_dir(dir)?.into_iter(),
+        })
+    }
+    /// Removes the commit markers left in `dir` by interrupted commits.
+    ///
+    /// Call this at startup, before any commits to targets in `dir`, when
+    /// [`commit_marker`][cm] is used.  A stale marker means the process died while committing the
+    /// target; the target holds either the old or the new content.  A marker holds the id of the
+    /// process committing the target.  If that process is still running its commit is in
+    /// progress and the marker is left alone.  The working file, if it still exists, is not
+    /// removed.
+    ///
+    /// [cm]: PhazerBuilderWithTarget::commit_marker
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The directory holding the targets.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if `dir` cannot be read or a marker cannot be removed.
+    /// Otherwise the paths of the targets whose commit was interrupted are returned.
+    ///
+    /// [ioe]: std::io::Error
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    ///
+    /// use phazer::Phazer;
+    ///
+    /// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     for target in Phazer::recover_markers(Path::new("data"))? {
+    ///         println!("a commit of {} was interrupted", target.display());
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    pub fn recover_markers(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+        let mut targets = Vec::new();
+        for path in STD_FILE_SYSTEM.read_dir(dir)? {
+            if !is_marker_path(&path) {
+                continue;
+            }
+            // A marker without a process id was interrupted before it was written
+            if marker_pid(&path).is_some_and(os::process_is_running) {
+                continue;
+            }
+            match STD_FILE_SYSTEM.remove_file(&path) {
+                Ok(()) => {}
+                // The commit finished first
+                Err(e) if e.kind() == ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            }
+            targets.push(path.with_extension(""));
+        }
+        Ok(targets)
+    }
+    /// Removes the working files left in `dir` by processes that ended without committing or
+    /// cleaning up (for example, a crash or a power failure).
+    ///
+    /// A working file's name includes the id of the process that created it.  A working file is
+    /// removed only if that process is no longer running so working files in use by this process,
+    /// or by another running process, are left alone.  Working files named with a tag (the
+    /// `deterministic_names` feature) do not include a process id and are never removed.  Only
+    /// regular files are considered.  Process ids are reused; a working file whose creator ended
+    /// but whose process id now belongs to an unrelated process is left for a later sweep.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The directory holding the working files.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if `dir` cannot be read or a working file cannot be removed.
+    /// Otherwise the number of working files removed is returned.
+    ///
+    /// [ioe]: std::io::Error
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    ///
+    /// use phazer::Phazer;
+    ///
+    /// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let removed = Phazer::sweep_stale_working_files(Path::new("downloads"))?;
+    ///     println!("removed {} stale working files", removed);
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    pub fn sweep_stale_working_files(dir: &Path) -> std::io::Result<usize> {
+        let mut removed = 0;
+        for path in STD_FILE_SYSTEM.read_dir(dir)? {
+            let Some(pid) = working_path_pid(&path) else {
+                continue;
+            };
+            match STD_FILE_SYSTEM.metadata(&path) {
+                Ok(m) if m.is_file() => {}
+                Ok(_) => continue,
+                // Someone else removed it first
+                Err(e) if e.kind() == ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            }
+            if os::process_is_running(pid) {
+                continue;
+            }
+            match STD_FILE_SYSTEM.remove_file(&path) {
+                Ok(()) => removed += 1,
+                // Someone else removed it first
+                Err(e) if e.kind() == ErrorKind::NotFound => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(removed)
     }
-    fn inner_new(target_path: PathBuf, commit_strategy: &'cs dyn CommitStrategy) -> Phazer {
+    fn inner_new(
+        target_path: PathBuf,
+        commit_strategy: &'cs dyn CommitStrategy,
+        file_system: &'cs dyn FileSystem,
+    ) -> Phazer<'cs> {
         let phazer_id = current_phazer_id();
         let process_id = std::process::id();
-        let lft = if let Some(ext) = target_path.extension() {
-            format!("{}.phazer-", Path::new(ext).display())
-        } else {
-            "phazer-".into()
-        };
-        let rgt = format!("-{}-{}", process_id, phazer_id);
-        let working_ext = format!("{}working{}", lft, rgt);
-        let mut working_path = target_path.clone();
-        working_path.set_extension(working_ext);
+        let working_path = working_path_for(&target_path, &format!("{}-{}", process_id, phazer_id));
         Phazer {
             file_created: AtomicBool::new(false),
             commit_strategy,
+            file_system,
             target_path,
             working_path,
             phazer_id,
+            working_subdir: None,
+            working_subdir_created: AtomicBool::new(false),
+            preflight_target: false,
+            expected_extension: None,
+            unfinished_writer: AtomicBool::new(false),
+            serialize_with_lock: false,
+            commit_marker: false,
+            backup_extension: None,
+            cancelled: Arc::new(AtomicBool::new(false)),
+            verify_boundaries: 0,
+            commit_on_panic: false,
+            min_commit_bytes: 0,
+            restore_selinux_context: false,
+            mtime_policy: None,
+            seed_from_target: false,
+            size_budget: None,
+            write_buffering: WriteBuffering::None,
+            #[cfg(unix)]
+            working_mode: None,
+            sync_mode: SyncMode::None,
+            committed: AtomicBool::new(false),
+            commit_attempts: AtomicU32::new(0),
+            backed_up: AtomicBool::new(false),
+            before_commit: None,
+            after_commit: None,
+        }
+    }
+    // Use `tag` in place of the process id and serial number in the working file's name.
+    fn use_working_name_tag(&mut self, tag: &str) {
+        self.working_path = working_path_for(&self.target_path, tag);
+    }
+    // Move the working file into `subdir` of the target's directory (same directory as the target
+    // so the rename stays on one filesystem).
+    fn use_working_subdir(&mut self, subdir: PathBuf) {
+        let parent = self.target_path.parent().unwrap_or(Path::new(""));
+        let subdir = parent.join(subdir);
+        if let Some(name) = self.working_path.file_name() {
+            self.working_path = subdir.join(name);
+        }
+        self.working_subdir = Some(subdir);
+    }
+    // Move the working file into `dir`.  The caller accepts that the rename may cross
+    // filesystems.
+    fn use_working_dir(&mut self, dir: PathBuf) {
+        if let Some(name) = self.working_path.file_name() {
+            self.working_path = dir.join(name);
         }
+        // The working subdirectory is no longer used
+        self.working_subdir = None;
     }
     /// [`commit`][pc] transfers the working file to the target file; by default this is done with
-    /// a [rename].
+    /// a [rename](std::fs::rename).
     ///
     /// If the working file was not created then [`commit`][pc] simply returns `Ok(())`.
     ///
@@ -228,11 +663,184 @@ impl<'cs> Phazer<'cs> {
     /// ```
     ///
     pub fn commit(self) -> Result<(), std::io::Error> {
-        self.commit2().map_err(|e| e.0)
+        self.commit2().map_err(|e| e.0.into())
+    }
+
+    /// [`commit_ref`][pcr] transfers the working file to the target file without consuming the
+    /// [`Phazer`].
+    ///
+    /// This is useful when the [`Phazer`] is a field in a long-lived struct.  Once the working file
+    /// has been transferred the [`Phazer`] is reset so it can build the working file again; the
+    /// next writer creates a fresh working file with the same name.  This makes it possible to
+    /// rewrite the same target (a periodic status file, for example) with one [`Phazer`].  Calls
+    /// to [`commit_ref`][pcr] when nothing has been written since the last commit do nothing.
+    ///
+    /// A mutable borrow still cannot coexist with a writer so, as with [`commit`][pc], the
+    /// working file cannot be open.  [`commit`][pc] remains the preferred way to commit.
+    ///
+    /// [pc]: Phazer::commit
+    /// [pcr]: Phazer::commit_ref
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the working file cannot be transferred to the target file.
+    /// Unlike [`commit`][pc], the working file is kept so the commit can be tried again.
+    ///
+    /// [ioe]: std::io::Error
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "simple")]
+    /// # {
+    /// use std::io::Write;
+    ///
+    /// use phazer::Phazer;
+    ///
+    /// struct Report<'cs> {
+    ///     phazer: Phazer<'cs>,
+    /// }
+    ///
+    /// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mut report = Report {
+    ///         phazer: Phazer::new("report.txt"),
+    ///     };
+    ///     let mut writer = report.phazer.simple_writer()?;
+    ///     writer.write_all(b"all systems go\n")?;
+    ///     drop(writer);
+    ///     report.phazer.commit_ref()?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    ///
+    pub fn commit_ref(&mut self) -> Result<(), std::io::Error> {
+        let rv = self.try_commit();
+        // Even if a later step failed the working file is gone so start over
+        if self.committed.load(Ordering::Relaxed) {
+            if self.commit_strategy.keeps_working_file() {
+                let _ = remove_working_file(self.file_system, &self.working_path);
+            }
+            self.reset_for_reuse();
+        }
+        rv
+    }
+    // Return to the state before the first writer so the working file can be built again.
+    fn reset_for_reuse(&self) {
+        self.file_created.store(false, Ordering::Relaxed);
+        self.committed.store(false, Ordering::Relaxed);
+        self.backed_up.store(false, Ordering::Relaxed);
+    }
+    /// [`commit_verbose`][pcv] commits the working file then returns the number of attempts the
+    /// commit strategy made.
+    ///
+    /// This is a lightweight way to monitor contention and tune retries.  With
+    /// [`RenameWithRetryStrategy`] the count includes every rename that was tried; `1` means no
+    /// retries were needed.  Strategies that do not retry always report `1`.  If the working file
+    /// was not created then nothing is committed and `0` is returned.
+    ///
+    /// [pcv]: Phazer::commit_verbose
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the working file cannot be transferred to the target file.
+    /// Otherwise the number of attempts is returned.
+    ///
+    /// [ioe]: std::io::Error
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "simple")]
+    /// # {
+    /// use std::io::Write;
+    ///
+    /// use phazer::{PhazerBuilder, RENAME_WITH_RETRY_STRATEGY};
+    ///
+    /// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let phazer = PhazerBuilder::with_target("contended.txt")
+    ///         .commit_strategy(RENAME_WITH_RETRY_STRATEGY)
+    ///         .build();
+    ///     let mut writer = phazer.simple_writer()?;
+    ///     writer.write_all(b"popular content")?;
+    ///     drop(writer);
+    ///     let attempts = phazer.commit_verbose()?;
+    ///     if attempts > 1 {
+    ///         println!("the commit needed {} retries", attempts - 1);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    ///
+    pub fn commit_verbose(self) -> std::io::Result<u32> {
+        self.try_commit()?;
+        Ok(self.commit_attempts.load(Ordering::Relaxed))
+    }
+    /// [`commit_with_backup`][pcb] transfers the working file to the target file then returns the
+    /// path of the backup of the previous target.
+    ///
+    /// The backup is made when the [`Phazer`] was built with
+    /// [`backup_with_extension`][bwe].  See [`commit`][pc] for the details of the commit.
+    ///
+    /// [pcb]: Phazer::commit_with_backup
+    /// [bwe]: PhazerBuilderWithTarget::backup_with_extension
+    /// [pc]: Phazer::commit
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the target cannot be renamed to the backup or if the
+    /// working file cannot be transferred to the target file.  Otherwise the backup's path is
+    /// returned if a backup was made.  `None` is returned if a backup was not requested, the
+    /// target did not exist, or nothing was committed.
+    ///
+    /// [ioe]: std::io::Error
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "simple")]
+    /// # {
+    /// use std::io::Write;
+    ///
+    /// use phazer::PhazerBuilder;
+    ///
+    /// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let phazer = PhazerBuilder::with_target("config.toml")
+    ///         .backup_with_extension("bak")
+    ///         .build();
+    ///     let mut writer = phazer.simple_writer()?;
+    ///     writer.write_all(b"[Serial Port]\nbaud = 250000\n")?;
+    ///     drop(writer);
+    ///     if let Some(backup) = phazer.commit_with_backup()? {
+    ///         println!("the previous configuration is in {}", backup.display());
+    ///     }
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    ///
+    pub fn commit_with_backup(self) -> std::io::Result<Option<PathBuf>> {
+        self.try_commit()?;
+        match self.backed_up.load(Ordering::Relaxed) {
+            true => Ok(self.backup_path()),
+            false => Ok(None),
+        }
+    }
+    /// Returns the path of the backup made by a commit or `None` if a backup was not requested.
+    ///
+    /// See [`backup_with_extension`][bwe].
+    ///
+    /// [bwe]: PhazerBuilderWithTarget::backup_with_extension
+    ///
+    pub fn backup_path(&self) -> Option<PathBuf> {
+        self.backup_extension
+            .as_deref()
+            .map(|ext| backup_path_for(&self.target_path, ext))
     }
 
     /// [`commit2`][pc] transfers the working file to the target file; by default this is done with
-    /// a [rename].
+    /// a [rename](std::fs::rename).
     ///
     /// If the working file was not created then [`commit2`][pc] simply returns `Ok(())`.
     ///
@@ -250,10 +858,12 @@ impl<'cs> Phazer<'cs> {
     ///
     /// # Return Value
     ///
-    /// An [`Error`][ioe] and the [`Phazer`] are returned if the working file cannot be transferred
+    /// A [`PhazerError`] and the [`Phazer`] are returned if the working file cannot be transferred
     /// to the target file.  This allows for error recovery not provided by this crate.  For
     /// example, on Windows, a target file with the read-only attribute set cannot be replaced with
-    /// a [`rename`].  This is demonstrated in the example.
+    /// a [`rename`](std::fs::rename).  This is demonstrated in the example.  The [`PhazerError`]
+    /// identifies the step that failed and converts into the [`Error`][ioe] [`Phazer::commit`]
+    /// would have returned.
     ///
     /// [ioe]: std::io::Error
     ///
@@ -305,6 +915,10 @@ impl<'cs> Phazer<'cs> {
     ///         Err((e, p)) => {
     ///             // If the error is anything except PermissionDenied then return it
     ///             if e.kind() != ErrorKind::PermissionDenied {
+    ///                 // Remove the working file and report if that also failed
+    ///                 if let Err(de) = p.discard() {
+    ///                     eprintln!("Discard failed: {}", de);
+    ///                 }
     ///                 return Err(e.into());
     ///             }
     ///             // Clear the read-only attribute
@@ -318,33 +932,1040 @@ impl<'cs> Phazer<'cs> {
     /// # }
     /// ```
     ///
-    pub fn commit2(self) -> Result<(), (std::io::Error, Phazer<'cs>)> {
-        if self.file_created.load(Ordering::Relaxed) {
-            match self.commit_strategy.commit(&self) {
-                Ok(()) => Ok(()),
-                Err(e) => Err((e, self)),
-            }
-        } else {
-            Ok(())
+    // Returning the Phazer on failure is the point so the large error is expected
+    #[allow(clippy::result_large_err)]
+    pub fn commit2(self) -> Result<(), (PhazerError, Phazer<'cs>)> {
+        match self.try_commit_classified() {
+            Ok(()) => Ok(()),
+            Err(e) => Err((e, self)),
         }
     }
-    /// `first_writer` returns if the working file has not yet been created; if the caller is the
-    /// one creating the first writer.  It only returns `true` once.
-    #[allow(dead_code)]
-    fn first_writer(&self) -> bool {
-        !self.file_created.swap(true, Ordering::Relaxed)
+    /// `try_commit` performs the commit without consuming the [`Phazer`].  Callers are responsible
+    /// for not using the [`Phazer`] after a successful commit.
+    pub(crate) fn try_commit(&self) -> std::io::Result<()> {
+        self.try_commit_classified().map_err(std::io::Error::from)
     }
-    #[doc(hidden)]
-    #[cfg(feature = "test_helpers")]
-    pub fn working_path(&self) -> &Path {
-        &self.working_path
+    // `try_commit` with the failure classified by the step that failed.
+    fn try_commit_classified(&self) -> Result<(), PhazerError> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!(
+            "phazer.commit",
+            target = %self.target_path.display(),
+            id = self.phazer_id
+        )
+        .entered();
+        let rv = self.try_commit_steps();
+        #[cfg(feature = "tracing")]
+        match &rv {
+            Ok(()) => tracing::debug!("commit succeeded"),
+            Err(e) => tracing::warn!(error = %e, "commit failed"),
+        }
+        rv
     }
-}
-
-impl<'cs> Drop for Phazer<'cs> {
-    /// `drop` removes the working file if it still exists (if the Phazer was not committed).
-    fn drop(&mut self) {
-        let _ = remove_file(&self.working_path);
+    // The steps of a commit.
+    fn try_commit_steps(&self) -> Result<(), PhazerError> {
+        if !self.prepare_commit().map_err(PhazerError::Prepare)? {
+            return Ok(());
+        }
+        self.commit_and_verify()?;
+        self.run_after_commit().map_err(PhazerError::AfterCommit)
+    }
+    // Run the checks that precede every commit.  Returns false if there is nothing to commit.
+    pub(crate) fn prepare_commit(&self) -> std::io::Result<bool> {
+        if self.committed.load(Ordering::Relaxed) {
+            return Ok(false);
+        }
+        if self.cancelled.load(Ordering::Relaxed) {
+            return Err(cancellation::cancelled_error());
+        }
+        self.check_extension()?;
+        if self.unfinished_writer.load(Ordering::Relaxed) {
+            return Err(std::io::Error::new(
+                ErrorKind::InvalidData,
+                "a writer that must be finished was not finished",
+            ));
+        }
+        if !self.file_created.load(Ordering::Relaxed) {
+            return Ok(false);
+        }
+        self.check_min_commit_bytes()?;
+        if self.restore_selinux_context {
+            os::copy_selinux_context(&self.target_path, &self.working_path)?;
+        }
+        if let Some(hook) = &self.before_commit {
+            hook(self)?;
+        }
+        Ok(true)
+    }
+    // Call the after_commit hook, if there is one.
+    pub(crate) fn run_after_commit(&self) -> std::io::Result<()> {
+        match &self.after_commit {
+            Some(hook) => hook(self).map_err(AfterCommitError::wrap),
+            None => Ok(()),
+        }
+    }
+    // Commit then, if requested, check the ends of the target.
+    fn commit_and_verify(&self) -> Result<(), PhazerError> {
+        let n = self.verify_boundaries;
+        let before = match n {
+            0 => None,
+            _ => Some(
+                read_boundaries(self.file_system, &self.working_path, n)
+                    .map_err(PhazerError::Verify)?,
+            ),
+        };
+        let mtime = self.apply_mtime_policy().map_err(PhazerError::Prepare)?;
+        self.sync_working_file().map_err(PhazerError::Sync)?;
+        self.commit_with_strategy()?;
+        // The working file is gone.  Its name could be reused so it must not be removed.
+        self.committed.store(true, Ordering::Relaxed);
+        if let Some(mtime) = mtime {
+            self.apply_mtime_to_target(mtime)
+                .map_err(PhazerError::Verify)?;
+        }
+        // For Windows the renames were written through
+        #[cfg(unix)]
+        if self.sync_mode == SyncMode::Full {
+            os::sync_rename(self.file_system, &self.target_path).map_err(PhazerError::Sync)?;
+        }
+        let Some(before) = before else {
+            return Ok(());
+        };
+        let after =
+            read_boundaries(self.file_system, &self.target_path, n).map_err(PhazerError::Verify)?;
+        if before != after {
+            return Err(PhazerError::ChecksumMismatch(std::io::Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "the committed target ({}) does not match the working file",
+                    self.target_path.display()
+                ),
+            )));
+        }
+        Ok(())
+    }
+    /// [`discard`][pd] removes the working file without committing it.
+    ///
+    /// Dropping a [`Phazer`] also removes the working file but any failure is ignored.
+    /// [`discard`][pd] is the explicit alternative that reports a failure.  It is especially useful
+    /// after [`commit2`][pc2] returns the [`Phazer`] and the application decides not to try again.
+    ///
+    /// # Return Value
+    ///
+    /// `Ok(())` is returned if the working file was removed or was never created.  Otherwise the
+    /// [`Error`][ioe] from the removal is returned.
+    ///
+    /// [pd]: Phazer::discard
+    /// [pc2]: Phazer::commit2
+    /// [ioe]: std::io::Error
+    ///
+    pub fn discard(self) -> std::io::Result<()> {
+        if !self.file_created.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        match remove_working_file(self.file_system, &self.working_path) {
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            rv => rv,
+        }
+    }
+    /// [`commit_copy_of`][pc] copies `src` to the working file then commits the working file.
+    ///
+    /// This is a convenient way to "atomically replace file A with the current contents of file
+    /// B"; for example, publishing a freshly built file to its live location.  Both files are
+    /// opened through the [`FileSystem`] and the copy is made with [`std::io::copy`] which uses the
+    /// operating system's fast-copy path when one is available.  Anything previously written to
+    /// the working file is replaced.
+    ///
+    /// [pc]: Phazer::commit_copy_of
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The file copied to the target.  `src` is left untouched.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if `src` cannot be copied to the working file or if the
+    /// working file cannot be transferred to the target file.  In either case the working file is
+    /// removed.  Otherwise the number of bytes copied is returned.
+    ///
+    /// [ioe]: std::io::Error
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # {
+    /// use std::path::Path;
+    ///
+    /// use phazer::Phazer;
+    ///
+    /// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     // Publish the freshly built site map
+    ///     let phazer = Phazer::new("public/sitemap.xml");
+    ///     let copied = phazer.commit_copy_of(Path::new("build/sitemap.xml"))?;
+    ///     println!("{} bytes published", copied);
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    ///
+    pub fn commit_copy_of(self, src: &Path) -> std::io::Result<u64> {
+        self.prepare_working_file()?;
+        self.file_created.store(true, Ordering::Relaxed);
+        let mut source = self.file_system.open(src, OpenOptions::new().read(true))?;
+        let mut options = OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        self.apply_working_mode(&mut options);
+        let mut working = self.file_system.open(&self.working_path, &options)?;
+        let copied = std::io::copy(&mut source, &mut working)?;
+        drop(working);
+        self.commit()?;
+        Ok(copied)
+    }
+    /// [`touch_commit`][ptc] commits a copy of the target; the content is preserved and the
+    /// modification time is updated.
+    ///
+    /// This is meant for heartbeat, marker, and lease files.  If the target exists, the working
+    /// file is seeded with the target's content.  If the target does not exist, the working file
+    /// is empty.  The working file is then committed so the modification time changes atomically
+    /// without risking the content.  Anything previously written to the working file is replaced.
+    ///
+    /// [ptc]: Phazer::touch_commit
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the target cannot be read, the working file cannot be
+    /// written, or the working file cannot be transferred to the target file.
+    ///
+    /// [ioe]: std::io::Error
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use phazer::Phazer;
+    ///
+    /// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     // Let the supervisor know this worker is alive
+    ///     Phazer::new("worker.heartbeat").touch_commit()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    pub fn touch_commit(self) -> std::io::Result<()> {
+        let working = self.seeded_working_file()?;
+        drop(working);
+        self.commit()
+    }
+    /// [`append_commit`][pac] copies the target to the working file, appends `extra`, then
+    /// commits.
+    ///
+    /// This is a read-modify-append-write for small files expressed as a single call.  A crash
+    /// never leaves a half-appended target visible; readers see the old content or the new
+    /// content.  If the target does not exist it is created with only `extra`.  Anything
+    /// previously written to the working file is replaced.  The whole file is rewritten so this
+    /// is meant for files of a few kilobytes.
+    ///
+    /// Concurrent calls for the same target are not merged; the last commit wins so an append
+    /// can be lost.
+    ///
+    /// [pac]: Phazer::append_commit
+    ///
+    /// # Arguments
+    ///
+    /// * `extra` - The bytes appended to the target's content.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the target cannot be read, the working file cannot be
+    /// written, or the working file cannot be transferred to the target file.
+    ///
+    /// [ioe]: std::io::Error
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use phazer::Phazer;
+    ///
+    /// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let phazer = Phazer::new("history.log");
+    ///     phazer.append_commit(b"deployed version 1.2.3\n")?;
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    pub fn append_commit(self, extra: &[u8]) -> std::io::Result<()> {
+        let mut working = self.seeded_working_file()?;
+        working.write_all(extra)?;
+        drop(working);
+        self.commit()
+    }
+    // Prepare then create the working file holding a copy of the target.
+    fn seeded_working_file(&self) -> std::io::Result<File> {
+        self.prepare_working_file()?;
+        self.file_created.store(true, Ordering::Relaxed);
+        self.create_seeded_working_file()
+    }
+    // Create the working file holding a copy of the target (or nothing if there is no target).
+    pub(crate) fn create_seeded_working_file(&self) -> std::io::Result<File> {
+        let mut options = OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        self.apply_working_mode(&mut options);
+        let mut working = self.file_system.open(&self.working_path, &options)?;
+        match self
+            .file_system
+            .open(&self.target_path, OpenOptions::new().read(true))
+        {
+            Ok(mut target) => {
+                std::io::copy(&mut target, &mut working)?;
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+        Ok(working)
+    }
+    // Preflight the target then return the options a writer uses to open the working file for
+    // read / write access.  The first writer creates the working file; empty or, if requested,
+    // holding a copy of the target.
+    #[allow(dead_code)]
+    pub(crate) fn working_file_options(&self) -> std::io::Result<OpenOptions> {
+        let mut options = OpenOptions::new();
+        options.read(true).write(true);
+        self.prepare_working_file()?;
+        if self.first_writer() {
+            if self.seed_from_target {
+                self.create_seeded_working_file()?;
+            } else {
+                options.truncate(true).create(true);
+                self.apply_working_mode(&mut options);
+            }
+        }
+        Ok(options)
+    }
+    // Give a working file created with `options` the mode set by working_mode.
+    #[cfg_attr(not(unix), allow(unused_variables))]
+    pub(crate) fn apply_working_mode(&self, options: &mut OpenOptions) {
+        #[cfg(unix)]
+        if let Some(mode) = self.working_mode {
+            options.mode(mode);
+        }
+    }
+    /// [`commit_if_older_than`][pco] commits the working file only if the target was last
+    /// modified at least `age` ago.
+    ///
+    /// This is a time-based guard for rate-limited outputs; it keeps a misbehaving caller from
+    /// replacing the target too often.  If the target does not exist, the working file is
+    /// committed.  If the target is too new (or its modification time is in the future), the
+    /// working file is removed and `false` is returned.
+    ///
+    /// [pco]: Phazer::commit_if_older_than
+    ///
+    /// # Arguments
+    ///
+    /// * `age` - Minimum time since the target was last modified.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the target's modification time cannot be determined or if
+    /// the working file cannot be transferred to the target file.  Otherwise `true` is returned if
+    /// the working file was committed and `false` if it was discarded.
+    ///
+    /// [ioe]: std::io::Error
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "simple")]
+    /// # {
+    /// use std::io::Write;
+    /// use std::time::Duration;
+    ///
+    /// use phazer::Phazer;
+    ///
+    /// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let phazer = Phazer::new("status.json");
+    ///     let mut writer = phazer.simple_writer()?;
+    ///     writer.write_all(b"{\"healthy\": true}")?;
+    ///     drop(writer);
+    ///     // At most one update per minute
+    ///     if !phazer.commit_if_older_than(Duration::from_secs(60))? {
+    ///         println!("status.json was updated recently; skipped");
+    ///     }
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    ///
+    pub fn commit_if_older_than(self, age: Duration) -> std::io::Result<bool> {
+        let old_enough = match self.file_system.metadata(&self.target_path) {
+            Ok(m) => m.modified()?.elapsed().is_ok_and(|elapsed| elapsed >= age),
+            Err(e) if e.kind() == ErrorKind::NotFound => true,
+            Err(e) => return Err(e),
+        };
+        if !old_enough {
+            self.discard()?;
+            return Ok(false);
+        }
+        self.commit()?;
+        Ok(true)
+    }
+    /// [`commit_and_prune`][pcp] commits the working file then removes the oldest files in the
+    /// target's directory beyond a retention count.
+    ///
+    /// This bundles the retention chore for a directory of time-stamped outputs with the commit
+    /// so the directory stays bounded.  After the commit, every regular file in the target's
+    /// directory for which `pattern` returns `true` is a candidate.  Working files, lock files, and
+    /// commit markers created by this crate are never candidates.  The candidates are sorted by
+    /// modification time and all but the newest `keep` are removed.  The committed target is a
+    /// candidate if `pattern` matches it; it is the newest file so it is only removed if `keep` is
+    /// zero.
+    ///
+    /// Nothing is pruned if the commit fails.
+    ///
+    /// [pcp]: Phazer::commit_and_prune
+    ///
+    /// # Arguments
+    ///
+    /// * `keep` - Number of matching files to retain.
+    /// * `pattern` - Returns `true` for paths that take part in the retention.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the working file cannot be transferred to the target file.
+    /// An [`Error`][ioe] holding a [`PruneError`] is returned if the commit succeeded but the
+    /// target's directory cannot be read or a file cannot be removed.  Otherwise the paths of the
+    /// removed files are returned.  The directory is listed and the files are removed through the
+    /// [`FileSystem`].
+    ///
+    /// [ioe]: std::io::Error
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "simple")]
+    /// # {
+    /// use std::io::Write;
+    ///
+    /// use phazer::Phazer;
+    ///
+    /// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let phazer = Phazer::new("backups/backup-20240101.db");
+    ///     let mut writer = phazer.simple_writer()?;
+    ///     writer.write_all(b"backup")?;
+    ///     drop(writer);
+    ///     // Keep the seven newest backups
+    ///     let removed = phazer.commit_and_prune(7, |p| {
+    ///         p.file_name().is_some_and(|n| n.to_string_lossy().starts_with("backup-"))
+    ///     })?;
+    ///     for path in removed {
+    ///         println!("pruned {}", path.display());
+    ///     }
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    ///
+    pub fn commit_and_prune(
+        self,
+        keep: usize,
+        pattern: impl Fn(&Path) -> bool,
+    ) -> std::io::Result<Vec<PathBuf>> {
+        let file_system = self.file_system;
+        let dir = target_parent(&self.target_path).to_path_buf();
+        self.commit()?;
+        prune(file_system, &dir, keep, pattern).map_err(PruneError::wrap)
+    }
+    /// [`commit_and_open`][pc] commits the working file then opens the target for reading.
+    ///
+    /// This is a convenient way to read back, checksum, or serve the file that was just
+    /// committed.  There is an unavoidable gap between the rename and the open in which another
+    /// writer could replace the target.  [`commit_and_open`][pc] opens the target immediately
+    /// after the commit succeeds which keeps that gap as small as possible; no user code runs
+    /// between the two steps.
+    ///
+    /// If the working file was not created then nothing is committed and the existing target (if
+    /// any) is opened.
+    ///
+    /// [pc]: Phazer::commit_and_open
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the working file cannot be transferred to the target file
+    /// (in which case the working file is removed) or if the target cannot be opened.  Otherwise
+    /// the target opened read-only is returned.
+    ///
+    /// [ioe]: std::io::Error
+    ///
+    pub fn commit_and_open(self) -> std::io::Result<File> {
+        let target_path = self.target_path.clone();
+        let file_system = self.file_system;
+        self.commit()?;
+        file_system.open(&target_path, OpenOptions::new().read(true))
+    }
+    /// [`commit2_and_open`][pc] is [`commit_and_open`][pco] that returns the [`Phazer`] on failure
+    /// like [`commit2`][pc2].
+    ///
+    /// If the commit fails the [`Phazer`] is returned, with the working file intact, so the
+    /// application can try again or [`discard`][pd] it.  If the commit succeeds but the target
+    /// cannot be opened, the [`Phazer`] is also returned; it has been committed so a later commit
+    /// does nothing and dropping it leaves the target alone.
+    ///
+    /// [pc]: Phazer::commit2_and_open
+    /// [pco]: Phazer::commit_and_open
+    /// [pc2]: Phazer::commit2
+    /// [pd]: Phazer::discard
+    ///
+    /// # Return Value
+    ///
+    /// A [`PhazerError`] and the [`Phazer`] are returned if the working file cannot be transferred
+    /// to the target file or if the target cannot be opened (a [`Verify`][pev] error).  Otherwise
+    /// the target opened read-only is returned.
+    ///
+    /// [pev]: PhazerError::Verify
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "simple")]
+    /// # {
+    /// use std::io::{Read, Write};
+    ///
+    /// use phazer::Phazer;
+    ///
+    /// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let phazer = Phazer::new("config.toml");
+    ///     let mut writer = phazer.simple_writer()?;
+    ///     writer.write_all(b"[Serial Port]\nbaud = 250000\n")?;
+    ///     drop(writer);
+    ///     let mut target = match phazer.commit2_and_open() {
+    ///         Ok(target) => target,
+    ///         Err((e, p)) => {
+    ///             // Decide whether to try again; here the working file is removed
+    ///             p.discard()?;
+    ///             return Err(e.into());
+    ///         }
+    ///     };
+    ///     let mut verify = String::new();
+    ///     target.read_to_string(&mut verify)?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    ///
+    // Returning the Phazer on failure is the point so the large error is expected
+    #[allow(clippy::result_large_err)]
+    pub fn commit2_and_open(self) -> Result<File, (PhazerError, Phazer<'cs>)> {
+        if let Err(e) = self.try_commit_classified() {
+            return Err((e, self));
+        }
+        match self
+            .file_system
+            .open(&self.target_path, OpenOptions::new().read(true))
+        {
+            Ok(file) => Ok(file),
+            Err(e) => Err((PhazerError::Verify(e), self)),
+        }
+    }
+    /// [`commit_stat`][pc] commits the working file then returns the target's metadata.
+    ///
+    /// The metadata provides the size, permissions, times, and (for POSIX) the inode of the
+    /// committed target in one call; which is what deployment tools typically log.  Like
+    /// [`Phazer::commit_and_open`], the metadata is read immediately after the commit succeeds to
+    /// keep the gap in which another writer could replace the target as small as possible.
+    ///
+    /// If the working file was not created then nothing is committed and the metadata of the
+    /// existing target (if any) is returned.
+    ///
+    /// [pc]: Phazer::commit_stat
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the working file cannot be transferred to the target file
+    /// (in which case the working file is removed) or if the target's metadata cannot be read.
+    /// Otherwise the target's metadata is returned.
+    ///
+    /// [ioe]: std::io::Error
+    ///
+    pub fn commit_stat(self) -> std::io::Result<Metadata> {
+        let target_path = self.target_path.clone();
+        let file_system = self.file_system;
+        self.commit()?;
+        file_system.metadata(&target_path)
+    }
+    /// [`commit_with_stats`][pc] commits the working file and reports what was committed.
+    ///
+    /// The working file's size is read just before the commit so logging or metrics do not need a
+    /// separate stat of the target.  Like [`commit2`][pc2], the [`Phazer`] is returned when the
+    /// commit fails so the application can try again.
+    ///
+    /// If the working file was not created then nothing is committed and the reported size is
+    /// zero.
+    ///
+    /// [pc]: Phazer::commit_with_stats
+    /// [pc2]: Phazer::commit2
+    ///
+    /// # Return Value
+    ///
+    /// A [`PhazerError`] and the [`Phazer`] are returned if the working file's size cannot be read
+    /// (a [`Prepare`][pep] error) or the working file cannot be transferred to the target file.
+    /// Otherwise a [`CommitStats`] is returned.
+    ///
+    /// [pep]: PhazerError::Prepare
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "simple")]
+    /// # {
+    /// use std::io::Write;
+    ///
+    /// use phazer::Phazer;
+    ///
+    /// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let phazer = Phazer::new("report.csv");
+    ///     let mut writer = phazer.simple_writer()?;
+    ///     writer.write_all(b"region,total\nnorth,42\n")?;
+    ///     drop(writer);
+    ///     let stats = phazer.commit_with_stats().map_err(|(e, _)| e)?;
+    ///     println!("committed {} bytes", stats.bytes);
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    ///
+    // Returning the Phazer on failure is the point so the large error is expected
+    #[allow(clippy::result_large_err)]
+    pub fn commit_with_stats(self) -> Result<CommitStats, (PhazerError, Phazer<'cs>)> {
+        let pending =
+            self.file_created.load(Ordering::Relaxed) && !self.committed.load(Ordering::Relaxed);
+        let bytes = match pending {
+            false => 0,
+            true => match self.file_system.metadata(&self.working_path) {
+                Ok(m) => m.len(),
+                Err(e) => return Err((PhazerError::Prepare(e), self)),
+            },
+        };
+        match self.try_commit_classified() {
+            Ok(()) => Ok(CommitStats { bytes }),
+            Err(e) => Err((e, self)),
+        }
+    }
+    /// `first_writer` returns if the working file has not yet been created; if the caller is the
+    /// one creating the first writer.  It only returns `true` once.
+    #[allow(dead_code)]
+    fn first_writer(&self) -> bool {
+        !self.file_created.swap(true, Ordering::Relaxed)
+    }
+    /// `commit_with_strategy` calls the commit strategy holding the target's lock if commits are
+    /// serialized.
+    fn commit_with_strategy(&self) -> Result<(), PhazerError> {
+        self.commit_attempts.store(1, Ordering::Relaxed);
+        #[cfg(feature = "tracing")]
+        tracing::debug!(working = %self.working_path.display(), "transferring the working file");
+        if !self.serialize_with_lock {
+            return self.commit_with_marker();
+        }
+        let lock = self
+            .file_system
+            .open(
+                &lock_path_for(&self.target_path),
+                OpenOptions::new().write(true).create(true).truncate(false),
+            )
+            .and_then(|lock| lock.lock().map(|()| lock))
+            .map_err(PhazerError::Lock)?;
+        let rv = self.commit_with_marker();
+        // Closing the file releases the lock
+        drop(lock);
+        rv
+    }
+    /// `commit_with_marker` calls the commit strategy with the commit marker in place if markers
+    /// were requested.
+    fn commit_with_marker(&self) -> Result<(), PhazerError> {
+        if !self.commit_marker {
+            return self.commit_with_backup_of_target();
+        }
+        let marker_path = marker_path_for(&self.target_path);
+        self.write_marker(&marker_path)
+            .map_err(PhazerError::Prepare)?;
+        let rv = self.commit_with_backup_of_target();
+        // The commit is no longer in progress whatever the outcome
+        let _ = self.file_system.remove_file(&marker_path);
+        rv
+    }
+    /// `write_marker` writes this process's id and the working path to the commit marker.
+    fn write_marker(&self, marker_path: &Path) -> std::io::Result<()> {
+        let mut marker = self.file_system.open(
+            marker_path,
+            OpenOptions::new().write(true).create(true).truncate(true),
+        )?;
+        writeln!(marker, "{}", std::process::id())?;
+        writeln!(marker, "{}", self.working_path.display())?;
+        // The marker is evidence of the commit should there be a crash; it has to reach the disk
+        marker.sync_all()
+    }
+    /// `commit_with_backup_of_target` calls the commit strategy after preserving the existing
+    /// target if a backup was requested.  The target is left in place.  It is hard linked (or, if
+    /// that fails or the strategy writes in-place, copied) to a working name beside the backup;
+    /// after a successful commit that file is renamed to the backup.
+    fn commit_with_backup_of_target(&self) -> Result<(), PhazerError> {
+        let Some(backup_path) = self.backup_path() else {
+            return self.run_commit_strategy().map_err(PhazerError::Rename);
+        };
+        let unique = format!("{}-{}-backup", std::process::id(), current_phazer_id());
+        let staged_path = working_path_for(&backup_path, &unique);
+        let staged = match self.commit_strategy.writes_in_place() {
+            true => copy_file(self.file_system, &self.target_path, &staged_path),
+            false => self
+                .file_system
+                .hard_link(&self.target_path, &staged_path)
+                .or_else(|e| match e.kind() {
+                    ErrorKind::NotFound => Err(e),
+                    _ => copy_file(self.file_system, &self.target_path, &staged_path),
+                }),
+        };
+        match staged {
+            Ok(()) => {}
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                return self.run_commit_strategy().map_err(PhazerError::Rename)
+            }
+            Err(e) => {
+                let _ = self.file_system.remove_file(&staged_path);
+                return Err(PhazerError::Prepare(e));
+            }
+        }
+        if let Err(e) = self.run_commit_strategy() {
+            // The target was not replaced; an older backup is kept
+            let _ = self.file_system.remove_file(&staged_path);
+            return Err(PhazerError::Rename(e));
+        }
+        // The commit is done.  If the backup cannot be put in place it is not reported.
+        match self.file_system.rename(&staged_path, &backup_path) {
+            Ok(()) => self.backed_up.store(true, Ordering::Relaxed),
+            Err(_) => {
+                let _ = self.file_system.remove_file(&staged_path);
+            }
+        }
+        Ok(())
+    }
+    /// `run_commit_strategy` calls the commit strategy.  For Windows, [`SyncMode::Full`] makes
+    /// every rename the strategy does a [write-through rename][rwt].
+    ///
+    /// [rwt]: FileSystem::rename_write_through
+    fn run_commit_strategy(&self) -> std::io::Result<()> {
+        #[cfg(windows)]
+        if self.sync_mode == SyncMode::Full {
+            return self.commit_strategy.commit(&WriteThroughDetails(self));
+        }
+        self.commit_strategy.commit(self)
+    }
+    /// `check_extension` returns an error if an extension is enforced and the target's extension
+    /// does not match.
+    fn check_extension(&self) -> std::io::Result<()> {
+        let Some(expected) = &self.expected_extension else {
+            return Ok(());
+        };
+        let actual = self.target_path.extension();
+        if actual == Some(std::ffi::OsStr::new(expected)) {
+            return Ok(());
+        }
+        Err(std::io::Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "the target ({}) does not have the expected extension ({})",
+                self.target_path.display(),
+                expected
+            ),
+        ))
+    }
+    // Set the working file's modification time as requested by set_mtime.  Returns the time set
+    // or `None` if there is nothing to set.
+    pub(crate) fn apply_mtime_policy(&self) -> std::io::Result<Option<SystemTime>> {
+        let mtime = match self.mtime_policy {
+            None => return Ok(None),
+            Some(MtimePolicy::Now) => SystemTime::now(),
+            Some(MtimePolicy::Exact(mtime)) => mtime,
+            Some(MtimePolicy::FromTarget) => match self.file_system.metadata(&self.target_path) {
+                Ok(m) => m.modified()?,
+                Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+                Err(e) => return Err(e),
+            },
+        };
+        os::set_modified(self.file_system, &self.working_path, mtime)?;
+        Ok(Some(mtime))
+    }
+    // A strategy that writes into the existing target leaves the target's time alone.  Give the
+    // target the time the working file was given.
+    fn apply_mtime_to_target(&self, mtime: SystemTime) -> std::io::Result<()> {
+        if !self.commit_strategy.writes_in_place() {
+            return Ok(());
+        }
+        if self.file_system.metadata(&self.target_path)?.modified()? == mtime {
+            return Ok(());
+        }
+        os::set_modified(self.file_system, &self.target_path, mtime)
+    }
+    // Flush the working file as requested by sync_mode.
+    fn sync_working_file(&self) -> std::io::Result<()> {
+        if self.sync_mode == SyncMode::None {
+            return Ok(());
+        }
+        // Windows requires write access to flush
+        let file = self
+            .file_system
+            .open(&self.working_path, OpenOptions::new().write(true))?;
+        match self.sync_mode {
+            SyncMode::Data => file.sync_data(),
+            _ => file.sync_all(),
+        }
+    }
+    // Fail if the working file is smaller than min_commit_bytes.
+    fn check_min_commit_bytes(&self) -> std::io::Result<()> {
+        if self.min_commit_bytes == 0 {
+            return Ok(());
+        }
+        let size = self.file_system.metadata(&self.working_path)?.len();
+        if size >= self.min_commit_bytes {
+            return Ok(());
+        }
+        Err(std::io::Error::new(
+            ErrorKind::InvalidData,
+            TooSmallError {
+                size,
+                minimum: self.min_commit_bytes,
+            },
+        ))
+    }
+    /// `prepare_working_file` checks the target (if preflight was requested) and creates the
+    /// working subdirectory (if one was configured) before the working file is created.  Whether
+    /// or not this [`Phazer`] created the subdirectory is remembered so `drop` only removes a
+    /// subdirectory this [`Phazer`] created.
+    fn prepare_working_file(&self) -> std::io::Result<()> {
+        if self.file_created.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!(
+            "phazer.writer",
+            target = %self.target_path.display(),
+            id = self.phazer_id
+        )
+        .entered();
+        #[cfg(feature = "tracing")]
+        tracing::debug!(working = %self.working_path.display(), "creating the working file");
+        if self.preflight_target {
+            preflight_target(self.file_system, &self.target_path)?;
+        }
+        check_working_collision(self.file_system, &self.working_path, &self.target_path)?;
+        let Some(subdir) = &self.working_subdir else {
+            return Ok(());
+        };
+        match create_dir(subdir) {
+            Ok(()) => {
+                self.working_subdir_created.store(true, Ordering::Relaxed);
+                Ok(())
+            }
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+    /// [`commit_with_ownership`][pco] sets the working file's owner, group, and mode then commits
+    /// the working file.
+    ///
+    /// A deployment running as root often needs the target owned by a service account with a
+    /// specific mode.  Setting the ownership and mode before the commit means the target never
+    /// appears root-owned or with the wrong permissions; it is correct the moment it is visible.
+    /// The owner and group are changed first because changing them can clear the set-user-ID and
+    /// set-group-ID bits.
+    ///
+    /// Changing the owner usually requires privileges.  If the working file was not created then
+    /// nothing is changed or committed.  The working file is opened through the [`FileSystem`] and
+    /// the changes are made to the open file.
+    ///
+    /// This method is only available for Unix systems; it does not exist for other platforms.
+    ///
+    /// [pco]: Phazer::commit_with_ownership
+    ///
+    /// # Arguments
+    ///
+    /// * `uid` - The user id of the new owner.
+    /// * `gid` - The group id of the new group.
+    /// * `mode` - The permission bits; for example, `0o640`.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the owner, group, or mode cannot be changed or if the
+    /// working file cannot be transferred to the target file.  In either case the working file is
+    /// removed.
+    ///
+    /// [ioe]: std::io::Error
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # #[cfg(all(unix, feature = "simple"))]
+    /// # {
+    /// use std::io::Write;
+    ///
+    /// use phazer::Phazer;
+    ///
+    /// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let phazer = Phazer::new("service.conf");
+    ///     let mut writer = phazer.simple_writer()?;
+    ///     writer.write_all(b"listen = 8080\n")?;
+    ///     drop(writer);
+    ///     // Owned by the service account and not readable by everyone
+    ///     phazer.commit_with_ownership(980, 980, 0o640)?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    ///
+    #[cfg(unix)]
+    pub fn commit_with_ownership(self, uid: u32, gid: u32, mode: u32) -> std::io::Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        if self.file_created.load(Ordering::Relaxed) {
+            let working = self
+                .file_system
+                .open(&self.working_path, OpenOptions::new().read(true))?;
+            std::os::unix::fs::fchown(&working, Some(uid), Some(gid))?;
+            working.set_permissions(std::fs::Permissions::from_mode(mode))?;
+        }
+        self.commit()
+    }
+    /// Returns `true` if the filesystem that will hold the working file has at least `needed` bytes
+    /// available.
+    ///
+    /// This makes it possible to fail fast.  For example, a download with a known
+    /// `Content-Length` can be refused up front rather than filling the disk and failing part way
+    /// through.  The answer is a snapshot; other processes can consume the space after this
+    /// method returns.
+    ///
+    /// # Arguments
+    ///
+    /// * `needed` - The number of bytes that will be written to the working file.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the available space cannot be determined (for example,
+    /// the directory that will hold the working file does not exist).  Otherwise `true` is
+    /// returned if at least `needed` bytes are available.
+    ///
+    /// [ioe]: std::io::Error
+    ///
+    pub fn check_space(&self, needed: u64) -> std::io::Result<bool> {
+        Ok(os::available_space(self.working_dir())? >= needed)
+    }
+    /// Returns the target path.
+    ///
+    /// This is the path passed to the constructor (or builder).  It is especially useful with
+    /// [`Phazer::for_each_in_dir`] where the target is discovered rather than specified.
+    ///
+    pub fn target_path(&self) -> &Path {
+        &self.target_path
+    }
+    /// Returns the [`Phazer`]'s serial number.
+    ///
+    /// Each [`Phazer`] created by a process gets a different serial number which does not change
+    /// for the life of the [`Phazer`].  Unless a working name tag is used (`deterministic_names`
+    /// feature), the serial number is part of the working file's name.  It is meant for logging
+    /// and diagnostics; for example, to tie log lines to a particular working file.
+    ///
+    pub fn id(&self) -> usize {
+        self.phazer_id
+    }
+    /// Returns the working file's path.
+    ///
+    /// The working file may not exist yet; it is created by the first writer.  Monitoring tools
+    /// can use the path to validate or clean up the staging location.
+    ///
+    pub fn working_path(&self) -> &Path {
+        &self.working_path
+    }
+    /// Returns `true` if a writer created the working file.
+    ///
+    /// If nothing was written then [`Phazer::commit`] has nothing to do; it returns `Ok(())` and
+    /// the target is untouched.  This allows an application to tell "no changes; nothing
+    /// committed" from "committed new content" before committing.  After
+    /// [`Phazer::commit_ref`] succeeds this returns `false` until a writer is created again.
+    ///
+    pub fn was_written(&self) -> bool {
+        self.file_created.load(Ordering::Relaxed)
+    }
+    /// Returns the directory that holds the working file.
+    ///
+    /// This is the target's directory unless a [working subdirectory][ws] or a
+    /// [working directory][wd] was requested.  A relative target in the current directory returns
+    /// `.`.
+    ///
+    /// [ws]: PhazerBuilderWithTarget::working_subdir
+    /// [wd]: PhazerBuilderWithTarget::working_dir
+    ///
+    pub fn working_dir(&self) -> &Path {
+        match self.working_path.parent() {
+            Some(p) if !p.as_os_str().is_empty() => p,
+            _ => Path::new("."),
+        }
+    }
+}
+
+impl<'cs> Drop for Phazer<'cs> {
+    /// `drop` removes the working file if it still exists (if the Phazer created it and it was not
+    /// committed).  Once a [`Phazer`] has been committed, by any of the commit methods, the working
+    /// file is left alone; another file may have reused its name.  The exception is a commit
+    /// strategy that [keeps the working file][kwf]; that working file is removed.
+    ///
+    /// On Windows, an antivirus scanner or indexer may briefly hold the working file open.  In
+    /// that case the removal is tried a few more times with a short sleep between each try.  The
+    /// removal is best-effort; failures are ignored.
+    ///
+    /// If this [`Phazer`] created the working subdirectory, an attempt is made to remove it.  The
+    /// attempt fails (harmlessly) if the subdirectory is not empty.
+    ///
+    /// If the [`Phazer`] was built with [`commit_on_panic`][cp] and the thread is panicking, a
+    /// best-effort commit is made before the working file is removed.
+    ///
+    /// [cp]: PhazerBuilderWithTarget::commit_on_panic
+    /// [kwf]: CommitStrategy::keeps_working_file
+    fn drop(&mut self) {
+        if !self.committed.load(Ordering::Relaxed) {
+            if self.commit_on_panic && std::thread::panicking() {
+                let _ = self.try_commit();
+            }
+            if self.file_created.load(Ordering::Relaxed) {
+                let _ = remove_working_file(self.file_system, &self.working_path);
+            }
+        } else if self.commit_strategy.keeps_working_file() {
+            let _ = remove_working_file(self.file_system, &self.working_path);
+        }
+        if let Some(subdir) = &self.working_subdir {
+            if self.working_subdir_created.load(Ordering::Relaxed) {
+                let _ = remove_dir(subdir);
+            }
+        }
+    }
+}
+
+// Remove the working file once.
+#[cfg(not(windows))]
+fn remove_working_file(file_system: &dyn FileSystem, working_path: &Path) -> std::io::Result<()> {
+    file_system.remove_file(working_path)
+}
+
+// Remove the working file retrying briefly if something else has it open.
+#[cfg(windows)]
+fn remove_working_file(file_system: &dyn FileSystem, working_path: &Path) -> std::io::Result<()> {
+    let mut tries = 0;
+    loop {
+        tries += 1;
+        match file_system.remove_file(working_path) {
+            Ok(()) => return Ok(()),
+            Err(e) => {
+                if e.kind() != ErrorKind::PermissionDenied || tries >= 4 {
+                    return Err(e);
+                }
+            }
+        }
+        // Worst case is 5 + 10 + 15 = 30 milliseconds
+        std::thread::sleep(std::time::Duration::from_millis(5 * tries));
     }
 }
 
@@ -352,277 +1973,1320 @@ impl<'cs> CommitDetails for Phazer<'cs> {
     fn get_working_path(&self) -> &Path {
         self.working_path.as_path()
     }
-    fn get_target_path(&self) -> &Path {
-        self.target_path.as_path()
+    fn get_target_path(&self) -> &Path {
+        self.target_path.as_path()
+    }
+    fn get_jitter(&self) -> usize {
+        self.phazer_id
+    }
+    fn get_file_system(&self) -> &dyn FileSystem {
+        self.file_system
+    }
+    fn set_attempts(&self, attempts: u32) {
+        self.commit_attempts.store(attempts, Ordering::Relaxed);
+    }
+}
+
+// The details of a commit whose renames are written through; used for SyncMode::Full on Windows.
+#[cfg(windows)]
+struct WriteThroughDetails<'a, 'cs>(&'a Phazer<'cs>);
+
+#[cfg(windows)]
+impl CommitDetails for WriteThroughDetails<'_, '_> {
+    fn get_working_path(&self) -> &Path {
+        self.0.get_working_path()
+    }
+    fn get_target_path(&self) -> &Path {
+        self.0.get_target_path()
+    }
+    fn get_jitter(&self) -> usize {
+        self.0.get_jitter()
+    }
+    fn get_file_system(&self) -> &dyn FileSystem {
+        self
+    }
+    fn set_attempts(&self, attempts: u32) {
+        self.0.set_attempts(attempts)
+    }
+}
+
+#[cfg(windows)]
+impl FileSystem for WriteThroughDetails<'_, '_> {
+    fn rename(&self, from: &Path, to: &Path) -> std::io::Result<()> {
+        self.0.file_system.rename_write_through(from, to)
+    }
+    fn remove_file(&self, path: &Path) -> std::io::Result<()> {
+        self.0.file_system.remove_file(path)
+    }
+    fn metadata(&self, path: &Path) -> std::io::Result<Metadata> {
+        self.0.file_system.metadata(path)
+    }
+    fn open(&self, path: &Path, options: &OpenOptions) -> std::io::Result<File> {
+        self.0.file_system.open(path, options)
+    }
+    fn read_dir(&self, path: &Path) -> std::io::Result<Vec<PathBuf>> {
+        self.0.file_system.read_dir(path)
+    }
+    fn rename_write_through(&self, from: &Path, to: &Path) -> std::io::Result<()> {
+        self.0.file_system.rename_write_through(from, to)
+    }
+}
+
+/// Swaps two existing files; `a` gets the content of `b` and `b` gets the content of `a`.
+///
+/// This is meant for blue / green deployments where two already-committed files (or
+/// directories) trade places.  For Linux, `renameat2` with `RENAME_EXCHANGE` swaps them
+/// atomically.  Elsewhere, or if the filesystem does not support the exchange, three renames are
+/// used: `a` to a temporary name, `b` to `a`, then the temporary name to `b`.  The three-rename
+/// swap is **not** atomic; between the renames `a` or `b` does not exist.  If the second or third
+/// rename fails the earlier renames are undone on a best-effort basis.
+///
+/// Both paths must be on the same filesystem.
+///
+/// # Arguments
+///
+/// * `a` - The first path.
+/// * `b` - The second path.
+///
+/// # Return Value
+///
+/// An [`Error`][ioe] is returned if either path does not exist or the files cannot be renamed.
+///
+/// [ioe]: std::io::Error
+///
+/// # Example
+///
+/// ```
+/// use std::fs::write;
+/// use std::path::Path;
+///
+/// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     write("blue.cfg", "blue")?;
+///     write("green.cfg", "green")?;
+///     // Promote green
+///     phazer::swap(Path::new("blue.cfg"), Path::new("green.cfg"))?;
+///     # std::fs::remove_file("blue.cfg")?;
+///     # std::fs::remove_file("green.cfg")?;
+///     Ok(())
+/// }
+/// ```
+///
+pub fn swap(a: &Path, b: &Path) -> std::io::Result<()> {
+    match os::rename_exchange(a, b) {
+        Err(e) if e.kind() == ErrorKind::Unsupported => {}
+        rv => return rv,
+    }
+    // rename would quietly replace a missing b
+    std::fs::symlink_metadata(b)?;
+    let file_system = STD_FILE_SYSTEM;
+    let unique = format!("{}-{}-swap", std::process::id(), current_phazer_id());
+    let temporary = working_path_for(a, &unique);
+    file_system.rename(a, &temporary)?;
+    if let Err(e) = file_system.rename(b, a) {
+        let _ = file_system.rename(&temporary, a);
+        return Err(e);
+    }
+    if let Err(e) = file_system.rename(&temporary, b) {
+        let _ = file_system.rename(a, b);
+        let _ = file_system.rename(&temporary, a);
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// [`CommitStats`] describes a commit made by [`Phazer::commit_with_stats`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct CommitStats {
+    /// The size of the committed file in bytes.
+    pub bytes: u64,
+}
+
+// Derives the working path from the target path and the Phazer's id.
+type WorkingNamer<'cs> = Box<dyn Fn(&Path, usize) -> PathBuf + Send + Sync + 'cs>;
+
+// Hook run just before a commit.
+type BeforeCommit<'cs> = Box<dyn Fn(&dyn CommitDetails) -> std::io::Result<()> + Send + Sync + 'cs>;
+
+// Hook run after a successful commit.
+type AfterCommit<'cs> = Box<dyn Fn(&dyn CommitDetails) -> std::io::Result<()> + Send + Sync + 'cs>;
+
+#[doc = include_str!("doc/phazer-builder-overview.md")]
+pub struct PhazerBuilder<'cs> {
+    commit_strategy: Option<&'cs dyn CommitStrategy>,
+    file_system: Option<&'cs dyn FileSystem>,
+    working_subdir: Option<PathBuf>,
+    working_dir: Option<PathBuf>,
+    preflight_target: bool,
+    expected_extension: Option<String>,
+    working_name_tag: Option<String>,
+    working_namer: Option<WorkingNamer<'cs>>,
+    serialize_with_lock: bool,
+    commit_marker: bool,
+    backup_extension: Option<OsString>,
+    verify_boundaries: usize,
+    commit_on_panic: bool,
+    min_commit_bytes: u64,
+    restore_selinux_context: bool,
+    mtime_policy: Option<MtimePolicy>,
+    seed_from_target: bool,
+    follow_symlinks: bool,
+    size_budget: Option<SizeBudget>,
+    write_buffering: WriteBuffering,
+    #[cfg(unix)]
+    working_mode: Option<u32>,
+    sync_mode: SyncMode,
+    before_commit: Option<BeforeCommit<'cs>>,
+    after_commit: Option<AfterCommit<'cs>>,
+}
+
+#[doc = include_str!("doc/phazer-builder-overview.md")]
+pub struct PhazerBuilderWithTarget<'cs> {
+    commit_strategy: Option<&'cs dyn CommitStrategy>,
+    file_system: Option<&'cs dyn FileSystem>,
+    target_path: PathBuf,
+    working_subdir: Option<PathBuf>,
+    working_dir: Option<PathBuf>,
+    preflight_target: bool,
+    expected_extension: Option<String>,
+    working_name_tag: Option<String>,
+    working_namer: Option<WorkingNamer<'cs>>,
+    serialize_with_lock: bool,
+    commit_marker: bool,
+    backup_extension: Option<OsString>,
+    verify_boundaries: usize,
+    commit_on_panic: bool,
+    min_commit_bytes: u64,
+    restore_selinux_context: bool,
+    mtime_policy: Option<MtimePolicy>,
+    seed_from_target: bool,
+    follow_symlinks: bool,
+    size_budget: Option<SizeBudget>,
+    write_buffering: WriteBuffering,
+    #[cfg(unix)]
+    working_mode: Option<u32>,
+    sync_mode: SyncMode,
+    before_commit: Option<BeforeCommit<'cs>>,
+    after_commit: Option<AfterCommit<'cs>>,
+}
+
+impl<'cs> PhazerBuilder<'cs> {
+    /// Creates an uninitialized [`PhazerBuilder`].
+    ///
+    /// The target path must be set (which converts a [`PhazerBuilder`] into a
+    /// [`PhazerBuilderWithTarget`]) before a [`Phazer`] can be built.
+    ///
+    /// By default a simple rename commit strategy ([`SIMPLE_RENAME_STRATEGY`]) is used.
+    ///
+    /// # Return Value
+    ///
+    /// A new [`PhazerBuilder`] is always returned; [`PhazerBuilder::new`] is infallible.
+    ///
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self {
+            commit_strategy: None,
+            file_system: None,
+            working_subdir: None,
+            working_dir: None,
+            preflight_target: false,
+            expected_extension: None,
+            working_name_tag: None,
+            working_namer: None,
+            serialize_with_lock: false,
+            commit_marker: false,
+            backup_extension: None,
+            verify_boundaries: 0,
+            commit_on_panic: false,
+            min_commit_bytes: 0,
+            restore_selinux_context: false,
+            mtime_policy: None,
+            seed_from_target: false,
+            follow_symlinks: false,
+            size_budget: None,
+            write_buffering: WriteBuffering::None,
+            #[cfg(unix)]
+            working_mode: None,
+            sync_mode: SyncMode::None,
+            before_commit: None,
+            after_commit: None,
+        }
+    }
+    /// Creates a [`PhazerBuilderWithTarget`].
+    ///
+    /// With the target path set, a [`Phazer`] can be built with the default commit strategy
+    /// ([`SIMPLE_RENAME_STRATEGY`]) or a different commit strategy can be used with a call to
+    /// [`PhazerBuilderWithTarget::commit_strategy`].
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Target file.  Ideally, the full path is specified so changes to the working
+    ///   directory do not cause problems.  [canonicalize][std::fs::canonicalize] is helpful.
+    ///
+    /// # Return Value
+    ///
+    /// A new [`PhazerBuilderWithTarget`] is always returned; [`PhazerBuilder::with_target`] is
+    /// infallible.
+    ///
+    pub fn with_target<P>(path: P) -> PhazerBuilderWithTarget<'cs>
+    where
+        P: Into<PathBuf>,
+    {
+        PhazerBuilderWithTarget {
+            commit_strategy: None,
+            file_system: None,
+            target_path: path.into(),
+            working_subdir: None,
+            working_dir: None,
+            preflight_target: false,
+            expected_extension: None,
+            working_name_tag: None,
+            working_namer: None,
+            serialize_with_lock: false,
+            commit_marker: false,
+            backup_extension: None,
+            verify_boundaries: 0,
+            commit_on_panic: false,
+            min_commit_bytes: 0,
+            restore_selinux_context: false,
+            mtime_policy: None,
+            seed_from_target: false,
+            follow_symlinks: false,
+            size_budget: None,
+            write_buffering: WriteBuffering::None,
+            #[cfg(unix)]
+            working_mode: None,
+            sync_mode: SyncMode::None,
+            before_commit: None,
+            after_commit: None,
+        }
+    }
+    /// Converts a [`PhazerBuilder`] to a [`PhazerBuilderWithTarget`] by adding the specified target
+    /// path.
+    ///
+    /// If a commit strategy was specified in a previous call to [`PhazerBuilder::commit_strategy`],
+    /// that strategy is passed to the returned [`PhazerBuilderWithTarget`].
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Target file.  Ideally, the full path is specified so changes to the working
+    ///   directory do not cause problems.  [canonicalize][std::fs::canonicalize] is helpful.
+    ///
+    /// # Return Value
+    ///
+    /// A new [`PhazerBuilderWithTarget`] is always returned; [`PhazerBuilder::target`] is infallible.
+    ///
+    pub fn target<P>(self, value: P) -> PhazerBuilderWithTarget<'cs>
+    where
+        P: Into<PathBuf>,
+    {
+        PhazerBuilderWithTarget {
+            commit_strategy: self.commit_strategy,
+            file_system: self.file_system,
+            target_path: value.into(),
+            working_subdir: self.working_subdir,
+            working_dir: self.working_dir,
+            preflight_target: self.preflight_target,
+            expected_extension: self.expected_extension,
+            working_name_tag: self.working_name_tag,
+            working_namer: self.working_namer,
+            serialize_with_lock: self.serialize_with_lock,
+            commit_marker: self.commit_marker,
+            backup_extension: self.backup_extension,
+            verify_boundaries: self.verify_boundaries,
+            commit_on_panic: self.commit_on_panic,
+            min_commit_bytes: self.min_commit_bytes,
+            restore_selinux_context: self.restore_selinux_context,
+            mtime_policy: self.mtime_policy,
+            seed_from_target: self.seed_from_target,
+            follow_symlinks: self.follow_symlinks,
+            size_budget: self.size_budget,
+            write_buffering: self.write_buffering,
+            #[cfg(unix)]
+            working_mode: self.working_mode,
+            sync_mode: self.sync_mode,
+            before_commit: self.before_commit,
+            after_commit: self.after_commit,
+        }
+    }
+    /// Changes the commit strategy the [`Phazer`] uses when [`commit`][pc] is called.
+    ///
+    /// The default commit strategy ([`SIMPLE_RENAME_STRATEGY`]) is used if a strategy is never
+    /// assigned.  This crate provides one other strategy ([`RENAME_WITH_RETRY_STRATEGY`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The commit strategy that's used by the created [`Phazer`].
+    ///
+    /// [pc]: crate::Phazer::commit
+    ///
+    pub fn commit_strategy(mut self, value: &'cs dyn CommitStrategy) -> Self {
+        self.commit_strategy = Some(value);
+        self
+    }
+    /// Changes the filesystem the [`Phazer`] and its commit strategy use.
+    ///
+    /// The Standard Library is used ([`STD_FILE_SYSTEM`]) if a filesystem is never assigned.
+    /// Assigning a different [`FileSystem`] is primarily useful for testing commit strategies.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The filesystem that's used by the created [`Phazer`].
+    ///
+    pub fn file_system(mut self, value: &'cs dyn FileSystem) -> Self {
+        self.file_system = Some(value);
+        self
+    }
+    /// Places the working file in a subdirectory of the target's directory.
+    ///
+    /// Some environments forbid temporary files in the target's directory; for example, when a
+    /// watcher reacts to every new file.  With a working subdirectory (like `.phazer`) the
+    /// in-progress file is not visible in the target's directory.  The subdirectory is within the
+    /// target's directory so the working file and the target stay on the same filesystem.
+    ///
+    /// The subdirectory is created when the working file is created.  If the [`Phazer`] created the
+    /// subdirectory, it tries to remove the subdirectory when dropped; the removal only succeeds if
+    /// the subdirectory is empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The subdirectory, relative to the target's directory, that holds the working
+    ///   file.
+    ///
+    pub fn working_subdir<P>(mut self, value: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.working_subdir = Some(value.into());
+        self
+    }
+    /// Places the working file in `value`; any directory, not necessarily near the target.
+    ///
+    /// This is meant for a target directory that is read-only except through a staging area or
+    /// for building the working file on a fast local disk.  The working file's unique name is
+    /// preserved; only its directory changes.  The directory must exist.  [`commit`][pc] still
+    /// renames the working file to the target.  This option takes precedence over
+    /// [`working_subdir`][ws].
+    ///
+    /// A rename cannot cross filesystems.  If `value` and the target's directory are on different
+    /// filesystems, the default commit strategy fails with [`CrossesDevices`][cd].  Use
+    /// [`CROSS_DEVICE_SAFE_STRATEGY`] to opt into copying the working file beside the target
+    /// (which costs time and space proportional to the working file) in that case.
+    ///
+    /// [pc]: Phazer::commit
+    /// [ws]: Self::working_subdir
+    /// [cd]: std::io::ErrorKind::CrossesDevices
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The directory that holds the working file.
+    ///
+    pub fn working_dir<P>(mut self, value: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.working_dir = Some(value.into());
+        self
+    }
+    /// Derives the working file's path with `value` instead of the default scheme.
+    ///
+    /// By default the working file for `data.txt` is `data.txt.phazer-working-{pid}-{id}`.  A
+    /// directory watcher or another tool may react badly to those names.  `value` is called once,
+    /// when the [`Phazer`] is built, with the target path and the [`Phazer`]'s
+    /// [`id`][pid] and returns the working path; for example, a hidden `.data.txt.{id}.tmp`
+    /// beside the target.  The returned path is used like the default; it is created by the first
+    /// writer, renamed to the target by [`commit`][pc], and removed if the [`Phazer`] is dropped
+    /// without a commit.  [`working_subdir`][ws] and [`working_dir`][wd] still move the working
+    /// file (keeping its name).  This option takes precedence over a working name tag.
+    ///
+    /// The caller is responsible for making the path unique; including the id does that within a
+    /// process.  When the working file is created, a path that names the target is refused; the
+    /// paths are compared ignoring case and with their directories resolved, and, if both files
+    /// already exist, by file identity (on Unix).  The check cannot foresee every alias (for
+    /// example, a hard link created after the check) so the namer should not return one.
+    /// Working files with custom names are not recognized by
+    /// [`for_each_in_dir`][fe] or [`sweep_stale_working_files`][ss].
+    ///
+    /// [pid]: Phazer::id
+    /// [pc]: Phazer::commit
+    /// [ws]: Self::working_subdir
+    /// [wd]: Self::working_dir
+    /// [fe]: Phazer::for_each_in_dir
+    /// [ss]: Phazer::sweep_stale_working_files
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Called with the target path and the id; returns the working path.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use phazer::PhazerBuilder;
+    ///
+    /// fn main() {
+    ///     let phazer = PhazerBuilder::with_target("watched/data.txt")
+    ///         .working_namer(|target, id| {
+    ///             let name = target.file_name().unwrap_or_default().to_string_lossy();
+    ///             target.with_file_name(format!(".{}.{}.tmp", name, id))
+    ///         })
+    ///         .build();
+    ///     assert!(phazer.working_path().ends_with(format!(".data.txt.{}.tmp", phazer.id())));
+    /// }
+    /// ```
+    ///
+    pub fn working_namer<F>(mut self, value: F) -> Self
+    where
+        F: Fn(&Path, usize) -> PathBuf + Send + Sync + 'cs,
+    {
+        self.working_namer = Some(Box::new(value));
+        self
+    }
+    /// Checks the target when the working file is created.
+    ///
+    /// Normally, problems with the target are discovered when [`commit`][pc] is called; possibly
+    /// after a large working file was built.  With preflight enabled the checks below are performed
+    /// when the first writer is created.  If any check fails the writer is not created.
+    /// * The parent of the target must exist and must be a directory.
+    /// * If the target exists it must be a regular file.
+    /// * For POSIX systems, the parent of the target must be writable.
+    /// * For Windows, the target must not be read-only.
+    ///
+    /// Like [`Phazer::new_checked`], the checks are a snapshot.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - `true` to check the target when the working file is created.
+    ///
+    /// [pc]: crate::Phazer::commit
+    ///
+    pub fn preflight_target(mut self, value: bool) -> Self {
+        self.preflight_target = value;
+        self
+    }
+    /// Serializes commits to the same target using an advisory lock.
+    ///
+    /// Normally, concurrent commits to the same target race.  With this option the commit locks a
+    /// lock file beside the target (`{target}.phazer-lock`) for the duration of the commit so
+    /// commits happen one at a time and the last writer wins.
+    ///
+    /// The lock is advisory.  It only coordinates [`Phazer`]s (in this process or others) that also
+    /// use this option.  The lock file is left in place; removing it would allow two writers to
+    /// lock different files.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - `true` to hold the lock during the commit.
+    ///
+    pub fn serialize_with_lock(mut self, value: bool) -> Self {
+        self.serialize_with_lock = value;
+        self
+    }
+    /// Writes a marker file beside the target while the commit is in progress.
+    ///
+    /// A bare rename leaves no trace if the process dies in the middle of a commit.  With this
+    /// option a small marker file (`{target}.phazer-committing`) is written just before the commit
+    /// strategy runs and removed just after it returns (whether or not the commit succeeded).  A
+    /// marker that still exists means a commit was interrupted.  The marker holds the process id
+    /// and the working file's path for recovery tooling.  [`Phazer::recover_markers`] finds and
+    /// removes stale markers.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - `true` to write the marker.  The default is `false`.
+    ///
+    pub fn commit_marker(mut self, value: bool) -> Self {
+        self.commit_marker = value;
+        self
+    }
+    /// Keeps the previous target as a backup; for example, `config.toml.bak`.
+    ///
+    /// When the commit runs and the target exists, the target is hard linked to the backup (the
+    /// target's name followed by `.` and `value`) replacing any older backup.  The target stays
+    /// in place so the commit strategy works as it would without a backup; an atomic commit stays
+    /// atomic.  If hard links are not supported, or the strategy writes into the existing target
+    /// (like [`LinkPreservingStrategy`]), the backup is a copy instead.  The backup is made under
+    /// a working name and only replaces an older backup after a successful commit.
+    /// [`Phazer::commit_with_backup`] returns the backup's path.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The extension, without the leading `.`, appended to the target's name.
+    ///
+    pub fn backup_with_extension<S>(mut self, value: S) -> Self
+    where
+        S: Into<OsString>,
+    {
+        self.backup_extension = Some(value.into());
+        self
+    }
+    /// Verifies the first and last `value` bytes of the target after the commit.
+    ///
+    /// This is a cheap smoke test for gross filesystem misbehavior (silent truncation, the wrong
+    /// file, etc.) that is much less expensive than hashing a large file.  Before the commit, the
+    /// length of the working file and its first and last `value` bytes are read.  After the commit,
+    /// the target is opened and the same checks are made.  If anything differs the commit returns
+    /// an [`InvalidData`][id] error.  Note that the target has already been replaced at that point.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The number of bytes to check at each end of the file.  Zero disables the check.
+    ///
+    /// [id]: std::io::ErrorKind::InvalidData
+    ///
+    pub fn verify_boundaries(mut self, value: usize) -> Self {
+        self.verify_boundaries = value;
+        self
+    }
+    /// Commits the working file, instead of removing it, if the [`Phazer`] is dropped while the
+    /// thread is panicking.
+    ///
+    /// Normally a panic abandons the working file, which is almost always the correct thing to do.
+    /// This option is meant for diagnostic artifacts, like a crash log, where the partial content
+    /// is more valuable than nothing.  The commit is best-effort; failures are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - `true` to commit on unwind.  The default is `false`.
+    ///
+    pub fn commit_on_panic(mut self, value: bool) -> Self {
+        self.commit_on_panic = value;
+        self
+    }
+    /// Refuses to commit a working file smaller than `value` bytes.
+    ///
+    /// For downloads, a suspiciously tiny file often means an error page was saved instead of the
+    /// real content.  Before the commit, the size of the working file is checked.  If it is below
+    /// the threshold the commit fails with a [`TooSmallError`] and the target is left alone; the
+    /// working file is removed when the [`Phazer`] is dropped.  Combine with a [`SizeBudget`] for
+    /// a size window.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The minimum size of the working file.  Zero disables the check.
+    ///
+    pub fn min_commit_bytes(mut self, value: u64) -> Self {
+        self.min_commit_bytes = value;
+        self
+    }
+    /// Copies the SELinux context of the existing target onto the working file just before the
+    /// commit.
+    ///
+    /// On SELinux systems a freshly created working file may get a default context that differs
+    /// from the context the policy gives the target so, after the rename, the target has the
+    /// wrong label.  With this option the target's context (its `security.selinux` extended
+    /// attribute) is copied to the working file before the commit so the label is preserved.
+    /// libselinux is not required.  If the target does not exist, the working file keeps the
+    /// context it was created with (the default for its directory).
+    ///
+    /// When SELinux is not in use (the target has no context or the filesystem does not support
+    /// extended attributes) and on operating systems other than Linux this option does nothing.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - `true` to copy the target's context.  The default is `false`.
+    ///
+    pub fn restore_selinux_context(mut self, value: bool) -> Self {
+        self.restore_selinux_context = value;
+        self
+    }
+    /// Sets the modification time the target has after the commit.
+    ///
+    /// The time is set on the working file just before the commit so no separate (and not
+    /// atomic) touch of the target is needed after the commit.  A strategy that writes into the
+    /// existing target (like [`ReplaceContentsStrategy`]) cannot carry the working file's time so
+    /// the time is set on the target right after the commit.  The working file does not need to
+    /// be writable; a read-only `working_mode` works.  See [`MtimePolicy`] for the choices.  By
+    /// default the target has the time the working file was last written.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - How the modification time is chosen.
+    ///
+    pub fn set_mtime(mut self, value: MtimePolicy) -> Self {
+        self.mtime_policy = Some(value);
+        self
     }
-    fn get_jitter(&self) -> usize {
-        self.phazer_id
+    /// Starts the working file with a copy of the existing target.
+    ///
+    /// Normally the first writer creates an empty working file.  With this option the first
+    /// writer's working file holds the target's content and the writer is positioned at the
+    /// start; seek to the end to append.  This makes a read-modify-write edit atomic without
+    /// copying the target by hand.  If the target does not exist the working file starts empty.
+    /// Later writers open the working file as usual.  The copy is made with blocking I/O, even
+    /// for the asynchronous writers.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - `true` to copy the target into the working file.  The default is `false`.
+    ///
+    pub fn seed_from_target(mut self, value: bool) -> Self {
+        self.seed_from_target = value;
+        self
+    }
+    /// Commits to the file a symbolic link target points to instead of replacing the link.
+    ///
+    /// By default a target that is a symbolic link is replaced; the commit renames the working
+    /// file over the link so the target becomes a regular file and the file the link pointed to is
+    /// unchanged.  With this option the link (and any chain of links) is followed when the
+    /// [`Phazer`] is built.  The working file is created beside the resolved file, so on the
+    /// resolved file's filesystem, and the commit replaces the resolved file leaving the link
+    /// intact.  This suits layouts where configuration paths are links into a versioned
+    /// directory.  [`Phazer::target_path`] returns the resolved path.
+    ///
+    /// A link that cannot be read is not followed.  A dangling link is followed so the commit
+    /// creates the file it points to.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - `true` to follow a symbolic link target.  The default is `false`.
+    ///
+    pub fn follow_symlinks(mut self, value: bool) -> Self {
+        self.follow_symlinks = value;
+        self
+    }
+    /// Charges the bytes written to the working file against a shared [`SizeBudget`].
+    ///
+    /// Writes fail with a [`QuotaExceeded`][qe] error once the budget would be exceeded.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Budget shared with other [`Phazer`]s.
+    ///
+    /// [qe]: std::io::ErrorKind::QuotaExceeded
+    ///
+    pub fn size_budget(mut self, value: SizeBudget) -> Self {
+        self.size_budget = Some(value);
+        self
+    }
+    /// Chooses how the writers returned by [`Phazer::simple_writer`] and [`Phazer::tokio_writer`]
+    /// buffer writes to the working file.
+    ///
+    /// See [`WriteBuffering`] for the modes and for when buffered bytes are written.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The buffering mode.  The default is [`WriteBuffering::None`].
+    ///
+    pub fn write_buffering(mut self, value: WriteBuffering) -> Self {
+        self.write_buffering = value;
+        self
+    }
+    /// Sets the permission bits used when the working file is created; for example, `0o600`.
+    ///
+    /// Without this option the working file is created with the default mode (`0o666` less the
+    /// umask) so, for a moment, sensitive content can be readable by other users.  The mode is
+    /// applied by the first writer (or [`Phazer::commit_copy_of`]) when it creates the working
+    /// file; the source's permissions are not copied.  An existing working file is not changed.
+    /// The umask still applies.  The committed target has the working file's mode.
+    ///
+    /// This method is only available for POSIX systems.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The permission bits for the new working file.
+    ///
+    #[cfg(unix)]
+    pub fn working_mode(mut self, value: u32) -> Self {
+        self.working_mode = Some(value);
+        self
+    }
+    /// Chooses how much of the commit is flushed to disk before the commit returns.
+    ///
+    /// See [`SyncMode`] for the modes and how each maps to the platform.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The sync mode.  The default is [`SyncMode::None`].
+    ///
+    pub fn sync_mode(mut self, value: SyncMode) -> Self {
+        self.sync_mode = value;
+        self
+    }
+    /// Runs `value` just before each commit transfers the working file to the target.
+    ///
+    /// The hook receives the [`CommitDetails`] the commit strategy will receive.  It is a place for
+    /// steps like logging or a final check of the working file without writing a
+    /// [`CommitStrategy`].  The hook runs after the working file passes the other checks.  If the
+    /// hook returns an error the commit is abandoned, the error is returned unchanged, and the
+    /// working file is removed as it would be for any failed commit.
+    ///
+    /// The hook is not run if there is no working file to commit.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Hook called with the details of the pending commit.
+    ///
+    pub fn before_commit<F>(mut self, value: F) -> Self
+    where
+        F: Fn(&dyn CommitDetails) -> std::io::Result<()> + Send + Sync + 'cs,
+    {
+        self.before_commit = Some(Box::new(value));
+        self
+    }
+    /// Runs `value` after each successful commit.
+    ///
+    /// The hook receives the [`CommitDetails`] the commit strategy received; the working file no
+    /// longer exists.  It is a place for follow-up steps like flushing the directory, setting
+    /// flags, or updating an index.  The hook runs after the target has been
+    /// replaced so a failure cannot undo the commit.  A failure is returned from the commit wrapped
+    /// in an [`AfterCommitError`] (with the same [`ErrorKind`]) so it can be told apart from a
+    /// failed commit.
+    ///
+    /// The hook is not run if there was no working file to commit.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Hook called with the details of the finished commit.
+    ///
+    pub fn after_commit<F>(mut self, value: F) -> Self
+    where
+        F: Fn(&dyn CommitDetails) -> std::io::Result<()> + Send + Sync + 'cs,
+    {
+        self.after_commit = Some(Box::new(value));
+        self
+    }
+    /// Requires the target's extension to match `value` when [`commit`][pc] is called.
+    ///
+    /// This is a lightweight guard against wiring bugs; for example, committing JSON content to a
+    /// `.toml` target.  The check is a case-sensitive comparison of the target path's extension
+    /// with `value`.  The contents of the working file are not examined.  If the extension does not
+    /// match, the commit fails with an [`InvalidInput`][ii] error.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The expected extension without the dot (e.g. `"json"`).  A leading dot is
+    ///   ignored.
+    ///
+    /// [pc]: crate::Phazer::commit
+    /// [ii]: std::io::ErrorKind::InvalidInput
+    ///
+    pub fn enforce_extension<S>(mut self, value: S) -> Self
+    where
+        S: Into<String>,
+    {
+        let value = value.into();
+        self.expected_extension = Some(value.trim_start_matches('.').into());
+        self
+    }
+    /// Replaces the process id and serial number in the working file's name with `value`.
+    ///
+    /// Normally the working file's name includes the process id and a serial number so it is
+    /// unique.  Those change from run to run which makes working files hard to identify when a test
+    /// fails.  With a tag the working file for `data.txt` is `data.txt.phazer-working-{tag}`.  A
+    /// good tag is the name of the test.  The caller is responsible for making the tag unique and
+    /// valid in a filename.
+    ///
+    /// This method is available when the `deterministic_names` feature is enabled.  It is meant
+    /// for tests.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Replaces the process id and serial number in the working file's name.
+    ///
+    #[cfg(feature = "deterministic_names")]
+    pub fn working_name_tag<S>(mut self, value: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.working_name_tag = Some(value.into());
+        self
     }
 }
 
-/// [`SimpleRenameStrategy`] uses the Standard Library [`rename`] function to transition the working
-/// file to the target file.
-///
-/// The other commit strategy available is [`RenameWithRetryStrategy`].
-///
-/// For POSIX systems and Windows systems in which there is no contention for the target file,
-/// [`SimpleRenameStrategy`] is a good choice.  For Windows systems in which two or more threads are
-/// simultaneously trying to update the target, [`RenameWithRetryStrategy`] is a good choice.
-///
-/// This crate provides a ready-to-use [`SimpleRenameStrategy`] instance named
-/// [`SIMPLE_RENAME_STRATEGY`].
-///
-/// By default, this commit strategy is used.
-///
-/// # Example
-///
-/// ```
-/// use phazer::{PhazerBuilder, SIMPLE_RENAME_STRATEGY};
-///
-/// fn main() -> Result<(), Box<dyn std::error::Error>> {
-///
-///     let phazer = PhazerBuilder::with_target("uses-simple-rename-strategy.txt")
-///         .commit_strategy(SIMPLE_RENAME_STRATEGY)
-///         .build();
-///
-///     // Build the working file
-///
-///     // `rename` is called to transition the working file to the target
-///     phazer.commit()?;
-///
-///     Ok(())
-/// }
-/// ```
-///
-
-pub struct SimpleRenameStrategy {}
-
-impl CommitStrategy for SimpleRenameStrategy {
-    fn commit(&self, phazer: &dyn CommitDetails) -> std::io::Result<()> {
-        rename(phazer.get_working_path(), phazer.get_target_path())
+impl<'cs> PhazerBuilderWithTarget<'cs> {
+    /// Changes the target path.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Target file.  Ideally, the full path is specified so changes to the working
+    ///   directory do not cause problems.  [canonicalize][std::fs::canonicalize] is helpful.
+    ///
+    pub fn target<P>(mut self, value: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.target_path = value.into();
+        self
+    }
+    /// Changes the commit strategy the [`Phazer`] uses when [`commit`][pc] is called.
+    ///
+    /// The default commit strategy ([`SIMPLE_RENAME_STRATEGY`]) is used if a strategy is never
+    /// assigned.  This crate provides one other strategy ([`RENAME_WITH_RETRY_STRATEGY`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The commit strategy that's used by the created [`Phazer`].
+    ///
+    /// [pc]: crate::Phazer::commit
+    ///
+    pub fn commit_strategy(mut self, value: &'cs dyn CommitStrategy) -> Self {
+        self.commit_strategy = Some(value);
+        self
+    }
+    /// Changes the filesystem the [`Phazer`] and its commit strategy use.
+    ///
+    /// The Standard Library is used ([`STD_FILE_SYSTEM`]) if a filesystem is never assigned.
+    /// Assigning a different [`FileSystem`] is primarily useful for testing commit strategies.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The filesystem that's used by the created [`Phazer`].
+    ///
+    pub fn file_system(mut self, value: &'cs dyn FileSystem) -> Self {
+        self.file_system = Some(value);
+        self
+    }
+    /// Places the working file in a subdirectory of the target's directory.
+    ///
+    /// Some environments forbid temporary files in the target's directory; for example, when a
+    /// watcher reacts to every new file.  With a working subdirectory (like `.phazer`) the
+    /// in-progress file is not visible in the target's directory.  The subdirectory is within the
+    /// target's directory so the working file and the target stay on the same filesystem.
+    ///
+    /// The subdirectory is created when the working file is created.  If the [`Phazer`] created the
+    /// subdirectory, it tries to remove the subdirectory when dropped; the removal only succeeds if
+    /// the subdirectory is empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The subdirectory, relative to the target's directory, that holds the working
+    ///   file.
+    ///
+    pub fn working_subdir<P>(mut self, value: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.working_subdir = Some(value.into());
+        self
+    }
+    /// Places the working file in `value`; any directory, not necessarily near the target.
+    ///
+    /// This is meant for a target directory that is read-only except through a staging area or
+    /// for building the working file on a fast local disk.  The working file's unique name is
+    /// preserved; only its directory changes.  The directory must exist.  [`commit`][pc] still
+    /// renames the working file to the target.  This option takes precedence over
+    /// [`working_subdir`][ws].
+    ///
+    /// A rename cannot cross filesystems.  If `value` and the target's directory are on different
+    /// filesystems, the default commit strategy fails with [`CrossesDevices`][cd].  Use
+    /// [`CROSS_DEVICE_SAFE_STRATEGY`] to opt into copying the working file beside the target
+    /// (which costs time and space proportional to the working file) in that case.
+    ///
+    /// [pc]: Phazer::commit
+    /// [ws]: Self::working_subdir
+    /// [cd]: std::io::ErrorKind::CrossesDevices
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The directory that holds the working file.
+    ///
+    pub fn working_dir<P>(mut self, value: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.working_dir = Some(value.into());
+        self
+    }
+    /// Derives the working file's path with `value` instead of the default scheme.
+    ///
+    /// By default the working file for `data.txt` is `data.txt.phazer-working-{pid}-{id}`.  A
+    /// directory watcher or another tool may react badly to those names.  `value` is called once,
+    /// when the [`Phazer`] is built, with the target path and the [`Phazer`]'s
+    /// [`id`][pid] and returns the working path; for example, a hidden `.data.txt.{id}.tmp`
+    /// beside the target.  The returned path is used like the default; it is created by the first
+    /// writer, renamed to the target by [`commit`][pc], and removed if the [`Phazer`] is dropped
+    /// without a commit.  [`working_subdir`][ws] and [`working_dir`][wd] still move the working
+    /// file (keeping its name).  This option takes precedence over a working name tag.
+    ///
+    /// The caller is responsible for making the path unique; including the id does that within a
+    /// process.  When the working file is created, a path that names the target is refused; the
+    /// paths are compared ignoring case and with their directories resolved, and, if both files
+    /// already exist, by file identity (on Unix).  The check cannot foresee every alias (for
+    /// example, a hard link created after the check) so the namer should not return one.
+    /// Working files with custom names are not recognized by
+    /// [`for_each_in_dir`][fe] or [`sweep_stale_working_files`][ss].
+    ///
+    /// [pid]: Phazer::id
+    /// [pc]: Phazer::commit
+    /// [ws]: Self::working_subdir
+    /// [wd]: Self::working_dir
+    /// [fe]: Phazer::for_each_in_dir
+    /// [ss]: Phazer::sweep_stale_working_files
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Called with the target path and the id; returns the working path.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use phazer::PhazerBuilder;
+    ///
+    /// fn main() {
+    ///     let phazer = PhazerBuilder::with_target("watched/data.txt")
+    ///         .working_namer(|target, id| {
+    ///             let name = target.file_name().unwrap_or_default().to_string_lossy();
+    ///             target.with_file_name(format!(".{}.{}.tmp", name, id))
+    ///         })
+    ///         .build();
+    ///     assert!(phazer.working_path().ends_with(format!(".data.txt.{}.tmp", phazer.id())));
+    /// }
+    /// ```
+    ///
+    pub fn working_namer<F>(mut self, value: F) -> Self
+    where
+        F: Fn(&Path, usize) -> PathBuf + Send + Sync + 'cs,
+    {
+        self.working_namer = Some(Box::new(value));
+        self
+    }
+    /// Checks the target when the working file is created.
+    ///
+    /// Normally, problems with the target are discovered when [`commit`][pc] is called; possibly
+    /// after a large working file was built.  With preflight enabled the checks below are performed
+    /// when the first writer is created.  If any check fails the writer is not created.
+    /// * The parent of the target must exist and must be a directory.
+    /// * If the target exists it must be a regular file.
+    /// * For POSIX systems, the parent of the target must be writable.
+    /// * For Windows, the target must not be read-only.
+    ///
+    /// Like [`Phazer::new_checked`], the checks are a snapshot.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - `true` to check the target when the working file is created.
+    ///
+    /// [pc]: crate::Phazer::commit
+    ///
+    pub fn preflight_target(mut self, value: bool) -> Self {
+        self.preflight_target = value;
+        self
+    }
+    /// Serializes commits to the same target using an advisory lock.
+    ///
+    /// Normally, concurrent commits to the same target race.  With this option the commit locks a
+    /// lock file beside the target (`{target}.phazer-lock`) for the duration of the commit so
+    /// commits happen one at a time and the last writer wins.
+    ///
+    /// The lock is advisory.  It only coordinates [`Phazer`]s (in this process or others) that also
+    /// use this option.  The lock file is left in place; removing it would allow two writers to
+    /// lock different files.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - `true` to hold the lock during the commit.
+    ///
+    pub fn serialize_with_lock(mut self, value: bool) -> Self {
+        self.serialize_with_lock = value;
+        self
+    }
+    /// Writes a marker file beside the target while the commit is in progress.
+    ///
+    /// A bare rename leaves no trace if the process dies in the middle of a commit.  With this
+    /// option a small marker file (`{target}.phazer-committing`) is written just before the commit
+    /// strategy runs and removed just after it returns (whether or not the commit succeeded).  A
+    /// marker that still exists means a commit was interrupted.  The marker holds the process id
+    /// and the working file's path for recovery tooling.  [`Phazer::recover_markers`] finds and
+    /// removes stale markers.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - `true` to write the marker.  The default is `false`.
+    ///
+    pub fn commit_marker(mut self, value: bool) -> Self {
+        self.commit_marker = value;
+        self
+    }
+    /// Keeps the previous target as a backup; for example, `config.toml.bak`.
+    ///
+    /// When the commit runs and the target exists, the target is hard linked to the backup (the
+    /// target's name followed by `.` and `value`) replacing any older backup.  The target stays
+    /// in place so the commit strategy works as it would without a backup; an atomic commit stays
+    /// atomic.  If hard links are not supported, or the strategy writes into the existing target
+    /// (like [`LinkPreservingStrategy`]), the backup is a copy instead.  The backup is made under
+    /// a working name and only replaces an older backup after a successful commit.
+    /// [`Phazer::commit_with_backup`] returns the backup's path.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The extension, without the leading `.`, appended to the target's name.
+    ///
+    pub fn backup_with_extension<S>(mut self, value: S) -> Self
+    where
+        S: Into<OsString>,
+    {
+        self.backup_extension = Some(value.into());
+        self
+    }
+    /// Verifies the first and last `value` bytes of the target after the commit.
+    ///
+    /// This is a cheap smoke test for gross filesystem misbehavior (silent truncation, the wrong
+    /// file, etc.) that is much less expensive than hashing a large file.  Before the commit, the
+    /// length of the working file and its first and last `value` bytes are read.  After the commit,
+    /// the target is opened and the same checks are made.  If anything differs the commit returns
+    /// an [`InvalidData`][id] error.  Note that the target has already been replaced at that point.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The number of bytes to check at each end of the file.  Zero disables the check.
+    ///
+    /// [id]: std::io::ErrorKind::InvalidData
+    ///
+    pub fn verify_boundaries(mut self, value: usize) -> Self {
+        self.verify_boundaries = value;
+        self
+    }
+    /// Commits the working file, instead of removing it, if the [`Phazer`] is dropped while the
+    /// thread is panicking.
+    ///
+    /// Normally a panic abandons the working file, which is almost always the correct thing to do.
+    /// This option is meant for diagnostic artifacts, like a crash log, where the partial content
+    /// is more valuable than nothing.  The commit is best-effort; failures are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - `true` to commit on unwind.  The default is `false`.
+    ///
+    pub fn commit_on_panic(mut self, value: bool) -> Self {
+        self.commit_on_panic = value;
+        self
+    }
+    /// Refuses to commit a working file smaller than `value` bytes.
+    ///
+    /// For downloads, a suspiciously tiny file often means an error page was saved instead of the
+    /// real content.  Before the commit, the size of the working file is checked.  If it is below
+    /// the threshold the commit fails with a [`TooSmallError`] and the target is left alone; the
+    /// working file is removed when the [`Phazer`] is dropped.  Combine with a [`SizeBudget`] for
+    /// a size window.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The minimum size of the working file.  Zero disables the check.
+    ///
+    pub fn min_commit_bytes(mut self, value: u64) -> Self {
+        self.min_commit_bytes = value;
+        self
+    }
+    /// Copies the SELinux context of the existing target onto the working file just before the
+    /// commit.
+    ///
+    /// On SELinux systems a freshly created working file may get a default context that differs
+    /// from the context the policy gives the target so, after the rename, the target has the
+    /// wrong label.  With this option the target's context (its `security.selinux` extended
+    /// attribute) is copied to the working file before the commit so the label is preserved.
+    /// libselinux is not required.  If the target does not exist, the working file keeps the
+    /// context it was created with (the default for its directory).
+    ///
+    /// When SELinux is not in use (the target has no context or the filesystem does not support
+    /// extended attributes) and on operating systems other than Linux this option does nothing.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - `true` to copy the target's context.  The default is `false`.
+    ///
+    pub fn restore_selinux_context(mut self, value: bool) -> Self {
+        self.restore_selinux_context = value;
+        self
     }
-}
-
-impl Default for SimpleRenameStrategy {
-    fn default() -> Self {
-        Self {}
+    /// Sets the modification time the target has after the commit.
+    ///
+    /// The time is set on the working file just before the commit so no separate (and not
+    /// atomic) touch of the target is needed after the commit.  A strategy that writes into the
+    /// existing target (like [`ReplaceContentsStrategy`]) cannot carry the working file's time so
+    /// the time is set on the target right after the commit.  The working file does not need to
+    /// be writable; a read-only `working_mode` works.  See [`MtimePolicy`] for the choices.  By
+    /// default the target has the time the working file was last written.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - How the modification time is chosen.
+    ///
+    pub fn set_mtime(mut self, value: MtimePolicy) -> Self {
+        self.mtime_policy = Some(value);
+        self
     }
-}
-
-/// A ready-to-use instance of [`SimpleRenameStrategy`].
-pub const SIMPLE_RENAME_STRATEGY: &dyn CommitStrategy = &SimpleRenameStrategy {};
-
-/// [`RenameWithRetryStrategy`] uses the Standard Library [`rename`] function to transition the
-/// working file to the target file and retries if that fails with a [`PermissionDenied`][pd] error.
-///
-/// The other commit strategy available is [`SimpleRenameStrategy`].
-///
-/// For POSIX systems and Windows systems in which there is no contention for the target file,
-/// [`SimpleRenameStrategy`] is a good choice.  For Windows systems in which two or more threads are
-/// simultaneously trying to update the target, [`RenameWithRetryStrategy`] is a good choice.
-///
-/// This crate provides a ready-to-use [`RenameWithRetryStrategy`] instance named
-/// [`RENAME_WITH_RETRY_STRATEGY`].
-///
-/// By default, the [`SimpleRenameStrategy`] commit strategy is used.
-///
-/// [pd]: std::io::ErrorKind::PermissionDenied
-///
-/// This retry strategy has been shown to work well with Windows 10 and Windows 11 on local SSD
-/// drives and with a NAS using as many as 10 threads contending for the target file...
-/// * Use a "jitter" between 0 and 15 to avoid threads being synchronized during contention
-/// * Calculate a "base sleep" value: 11 + (3 * jitter)
-/// * Try to commit
-/// * If that succeeds then we're done
-/// * If that fails with any error except [`PermissionDenied`][pd] then return that error
-/// * Otherwise sleep for the base sleep value multiplied by the try count.  For example...
-///     * If the jitter is 1
-///     * Then the base sleep is 11 + (3 * 1) = 14
-///     * For the first try, this strategy would sleep for 14 * 1 milliseconds
-///     * For the second try, this strategy would sleep for 14 * 2 milleconds
-/// * Until 7 attempts have been made at which point the [`PermissionDenied`][pd] error is returned.
-///
-/// In the worst case, this strategy sleeps for a total of (11 + (3 * 15)) * (7 * (7+1) / 2) = 1568 milliseconds.
-///
-/// # Example
-///
-/// ```
-/// use phazer::{PhazerBuilder, RENAME_WITH_RETRY_STRATEGY};
-///
-/// fn main() -> Result<(), Box<dyn std::error::Error>> {
-///
-///     let phazer = PhazerBuilder::with_target("uses-rename-with-retry-strategy.txt")
-///         .commit_strategy(RENAME_WITH_RETRY_STRATEGY)
-///         .build();
-///
-///     // Build the working file
-///
-///     // `rename` is called to transition the working file to the target
-///     phazer.commit()?;
-///
-///     Ok(())
-/// }
-/// ```
-///
-pub struct RenameWithRetryStrategy {}
-
-impl CommitStrategy for RenameWithRetryStrategy {
-    fn commit(&self, phazer: &dyn CommitDetails) -> std::io::Result<()> {
-        let mut tries = 0;
-        let jitter = (phazer.get_jitter() as u64) & 0xF;
-        let base_sleep = 11 + (3 * jitter);
-        loop {
-            tries += 1;
-            let rv = rename(phazer.get_working_path(), phazer.get_target_path());
-            match &rv {
-                Ok(()) => return rv,
-                Err(e) => {
-                    if e.kind() != std::io::ErrorKind::PermissionDenied {
-                        return rv;
-                    }
-                    // With 10 threads and the sleep code as it is below (start with 10ms), seven
-                    // has been a good threshold.
-                    if tries >= 7 {
-                        return rv;
-                    }
-                }
-            }
-            std::thread::sleep(std::time::Duration::from_millis(base_sleep * tries));
-        }
+    /// Starts the working file with a copy of the existing target.
+    ///
+    /// Normally the first writer creates an empty working file.  With this option the first
+    /// writer's working file holds the target's content and the writer is positioned at the
+    /// start; seek to the end to append.  This makes a read-modify-write edit atomic without
+    /// copying the target by hand.  If the target does not exist the working file starts empty.
+    /// Later writers open the working file as usual.  The copy is made with blocking I/O, even
+    /// for the asynchronous writers.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - `true` to copy the target into the working file.  The default is `false`.
+    ///
+    pub fn seed_from_target(mut self, value: bool) -> Self {
+        self.seed_from_target = value;
+        self
     }
-}
-
-impl Default for RenameWithRetryStrategy {
-    fn default() -> Self {
-        Self {}
+    /// Commits to the file a symbolic link target points to instead of replacing the link.
+    ///
+    /// By default a target that is a symbolic link is replaced; the commit renames the working
+    /// file over the link so the target becomes a regular file and the file the link pointed to is
+    /// unchanged.  With this option the link (and any chain of links) is followed when the
+    /// [`Phazer`] is built.  The working file is created beside the resolved file, so on the
+    /// resolved file's filesystem, and the commit replaces the resolved file leaving the link
+    /// intact.  This suits layouts where configuration paths are links into a versioned
+    /// directory.  [`Phazer::target_path`] returns the resolved path.
+    ///
+    /// A link that cannot be read is not followed.  A dangling link is followed so the commit
+    /// creates the file it points to.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - `true` to follow a symbolic link target.  The default is `false`.
+    ///
+    pub fn follow_symlinks(mut self, value: bool) -> Self {
+        self.follow_symlinks = value;
+        self
     }
-}
-
-/// A ready-to-use instance of [`RenameWithRetryStrategy`].
-pub const RENAME_WITH_RETRY_STRATEGY: &dyn CommitStrategy = &RenameWithRetryStrategy {};
-
-#[doc = include_str!("doc/phazer-builder-overview.md")]
-pub struct PhazerBuilder<'cs> {
-    commit_strategy: Option<&'cs dyn CommitStrategy>,
-}
-
-#[doc = include_str!("doc/phazer-builder-overview.md")]
-pub struct PhazerBuilderWithTarget<'cs> {
-    commit_strategy: Option<&'cs dyn CommitStrategy>,
-    target_path: PathBuf,
-}
-
-impl<'cs> PhazerBuilder<'cs> {
-    /// Creates an uninitialized [`PhazerBuilder`].
+    /// Charges the bytes written to the working file against a shared [`SizeBudget`].
     ///
-    /// The target path must be set (which converts a [`PhazerBuilder`] into a
-    /// [`PhazerBuilderWithTarget`]) before a [`Phazer`] can be built.
+    /// Writes fail with a [`QuotaExceeded`][qe] error once the budget would be exceeded.
     ///
-    /// By default a simple rename commit strategy ([`SIMPLE_RENAME_STRATEGY`]) is used.
+    /// # Arguments
     ///
-    /// # Return Value
+    /// * `value` - Budget shared with other [`Phazer`]s.
     ///
-    /// A new [`PhazerBuilder`] is always returned; [`PhazerBuilder::new`] is infallible.
+    /// [qe]: std::io::ErrorKind::QuotaExceeded
     ///
-    pub fn new() -> Self {
-        Self {
-            commit_strategy: None,
-        }
+    pub fn size_budget(mut self, value: SizeBudget) -> Self {
+        self.size_budget = Some(value);
+        self
     }
-    /// Creates a [`PhazerBuilderWithTarget`].
+    /// Chooses how the writers returned by [`Phazer::simple_writer`] and [`Phazer::tokio_writer`]
+    /// buffer writes to the working file.
     ///
-    /// With the target path set, a [`Phazer`] can be built with the default commit strategy
-    /// ([`SIMPLE_RENAME_STRATEGY`]) or a different commit strategy can be used with a call to
-    /// [`PhazerBuilderWithTarget::commit_strategy`].
+    /// See [`WriteBuffering`] for the modes and for when buffered bytes are written.
     ///
     /// # Arguments
     ///
-    /// * `path` - Target file.  Ideally, the full path is specified so changes to the working
-    /// directory do not cause problems.  [canonicalize][std::fs::canonicalize] is helpful.
+    /// * `value` - The buffering mode.  The default is [`WriteBuffering::None`].
     ///
-    /// # Return Value
+    pub fn write_buffering(mut self, value: WriteBuffering) -> Self {
+        self.write_buffering = value;
+        self
+    }
+    /// Sets the permission bits used when the working file is created; for example, `0o600`.
     ///
-    /// A new [`PhazerBuilderWithTarget`] is always returned; [`PhazerBuilder::with_target`] is
-    /// infallible.
+    /// Without this option the working file is created with the default mode (`0o666` less the
+    /// umask) so, for a moment, sensitive content can be readable by other users.  The mode is
+    /// applied by the first writer (or [`Phazer::commit_copy_of`]) when it creates the working
+    /// file; the source's permissions are not copied.  An existing working file is not changed.
+    /// The umask still applies.  The committed target has the working file's mode.
     ///
-    pub fn with_target<P>(path: P) -> PhazerBuilderWithTarget<'cs>
-    where
-        P: Into<PathBuf>,
-    {
-        PhazerBuilderWithTarget {
-            commit_strategy: None,
-            target_path: path.into(),
-        }
+    /// This method is only available for POSIX systems.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The permission bits for the new working file.
+    ///
+    #[cfg(unix)]
+    pub fn working_mode(mut self, value: u32) -> Self {
+        self.working_mode = Some(value);
+        self
     }
-    /// Converts a [`PhazerBuilder`] to a [`PhazerBuilderWithTarget`] by adding the specified target
-    /// path.
+    /// Chooses how much of the commit is flushed to disk before the commit returns.
     ///
-    /// If a commit strategy was specified in a previous call to [`PhazerBuilder::commit_strategy`],
-    /// that strategy is passed to the returned [`PhazerBuilderWithTarget`].
+    /// See [`SyncMode`] for the modes and how each maps to the platform.
     ///
     /// # Arguments
     ///
-    /// * `value` - Target file.  Ideally, the full path is specified so changes to the working
-    /// directory do not cause problems.  [canonicalize][std::fs::canonicalize] is helpful.
+    /// * `value` - The sync mode.  The default is [`SyncMode::None`].
     ///
-    /// # Return Value
+    pub fn sync_mode(mut self, value: SyncMode) -> Self {
+        self.sync_mode = value;
+        self
+    }
+    /// Runs `value` just before each commit transfers the working file to the target.
     ///
-    /// A new [`PhazerBuilderWithTarget`] is always returned; [`PhazerBuilder::target`] is infallible.
+    /// The hook receives the [`CommitDetails`] the commit strategy will receive.  It is a place for
+    /// steps like logging or a final check of the working file without writing a
+    /// [`CommitStrategy`].  The hook runs after the working file passes the other checks.  If the
+    /// hook returns an error the commit is abandoned, the error is returned unchanged, and the
+    /// working file is removed as it would be for any failed commit.
     ///
-    pub fn target<P>(self, value: P) -> PhazerBuilderWithTarget<'cs>
+    /// The hook is not run if there is no working file to commit.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Hook called with the details of the pending commit.
+    ///
+    pub fn before_commit<F>(mut self, value: F) -> Self
     where
-        P: Into<PathBuf>,
+        F: Fn(&dyn CommitDetails) -> std::io::Result<()> + Send + Sync + 'cs,
     {
-        PhazerBuilderWithTarget {
-            commit_strategy: self.commit_strategy,
-            target_path: value.into(),
-        }
+        self.before_commit = Some(Box::new(value));
+        self
     }
-    /// Changes the commit strategy the [`Phazer`] uses when [`commit`][pc] is called.
+    /// Runs `value` after each successful commit.
     ///
-    /// The default commit strategy ([`SIMPLE_RENAME_STRATEGY`]) is used if a strategy is never
-    /// assigned.  This crate provides one other strategy ([`RENAME_WITH_RETRY_STRATEGY`]).
+    /// The hook receives the [`CommitDetails`] the commit strategy received; the working file no
+    /// longer exists.  It is a place for follow-up steps like flushing the directory, setting
+    /// flags, or updating an index.  The hook runs after the target has been
+    /// replaced so a failure cannot undo the commit.  A failure is returned from the commit wrapped
+    /// in an [`AfterCommitError`] (with the same [`ErrorKind`]) so it can be told apart from a
+    /// failed commit.
     ///
-    /// # Arguments
+    /// The hook is not run if there was no working file to commit.
     ///
-    /// * `value` - The commit strategy that's used by the created [`Phazer`].
+    /// # Arguments
     ///
-    /// [pc]: crate::Phazer::commit
+    /// * `value` - Hook called with the details of the finished commit.
     ///
-    pub fn commit_strategy(mut self, value: &'cs dyn CommitStrategy) -> Self {
-        self.commit_strategy = Some(value);
+    pub fn after_commit<F>(mut self, value: F) -> Self
+    where
+        F: Fn(&dyn CommitDetails) -> std::io::Result<()> + Send + Sync + 'cs,
+    {
+        self.after_commit = Some(Box::new(value));
         self
     }
-}
-
-impl<'cs> PhazerBuilderWithTarget<'cs> {
-    /// Changes the target path.
+    /// Requires the target's extension to match `value` when [`commit`][pc] is called.
+    ///
+    /// This is a lightweight guard against wiring bugs; for example, committing JSON content to a
+    /// `.toml` target.  The check is a case-sensitive comparison of the target path's extension
+    /// with `value`.  The contents of the working file are not examined.  If the extension does not
+    /// match, the commit fails with an [`InvalidInput`][ii] error.
     ///
     /// # Arguments
     ///
-    /// * `value` - Target file.  Ideally, the full path is specified so changes to the working
-    /// directory do not cause problems.  [canonicalize][std::fs::canonicalize] is helpful.
+    /// * `value` - The expected extension without the dot (e.g. `"json"`).  A leading dot is
+    ///   ignored.
     ///
-    pub fn target<P>(mut self, value: P) -> Self
+    /// [pc]: crate::Phazer::commit
+    /// [ii]: std::io::ErrorKind::InvalidInput
+    ///
+    pub fn enforce_extension<S>(mut self, value: S) -> Self
     where
-        P: Into<PathBuf>,
+        S: Into<String>,
     {
-        self.target_path = value.into();
+        let value = value.into();
+        self.expected_extension = Some(value.trim_start_matches('.').into());
         self
     }
-    /// Changes the commit strategy the [`Phazer`] uses when [`commit`][pc] is called.
+    /// Replaces the process id and serial number in the working file's name with `value`.
     ///
-    /// The default commit strategy ([`SIMPLE_RENAME_STRATEGY`]) is used if a strategy is never
-    /// assigned.  This crate provides one other strategy ([`RENAME_WITH_RETRY_STRATEGY`]).
+    /// Normally the working file's name includes the process id and a serial number so it is
+    /// unique.  Those change from run to run which makes working files hard to identify when a test
+    /// fails.  With a tag the working file for `data.txt` is `data.txt.phazer-working-{tag}`.  A
+    /// good tag is the name of the test.  The caller is responsible for making the tag unique and
+    /// valid in a filename.
     ///
-    /// # Arguments
+    /// This method is available when the `deterministic_names` feature is enabled.  It is meant
+    /// for tests.
     ///
-    /// * `value` - The commit strategy that's used by the created [`Phazer`].
+    /// # Arguments
     ///
-    /// [pc]: crate::Phazer::commit
+    /// * `value` - Replaces the process id and serial number in the working file's name.
     ///
-    pub fn commit_strategy(mut self, value: &'cs dyn CommitStrategy) -> Self {
-        self.commit_strategy = Some(value);
+    #[cfg(feature = "deterministic_names")]
+    pub fn working_name_tag<S>(mut self, value: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.working_name_tag = Some(value.into());
         self
     }
     /// Builds a new [`Phazer`] using the target path and commit strategy.
@@ -630,15 +3294,416 @@ impl<'cs> PhazerBuilderWithTarget<'cs> {
     pub fn build(self) -> Phazer<'cs> {
         let Self {
             commit_strategy,
+            file_system,
             target_path,
+            working_subdir,
+            working_dir,
+            preflight_target,
+            expected_extension,
+            working_name_tag,
+            working_namer,
+            serialize_with_lock,
+            commit_marker,
+            backup_extension,
+            verify_boundaries,
+            commit_on_panic,
+            min_commit_bytes,
+            restore_selinux_context,
+            mtime_policy,
+            seed_from_target,
+            follow_symlinks,
+            size_budget,
+            write_buffering,
+            #[cfg(unix)]
+            working_mode,
+            sync_mode,
+            before_commit,
+            after_commit,
         } = self;
         let commit_strategy = commit_strategy.unwrap_or(SIMPLE_RENAME_STRATEGY);
-        Phazer::inner_new(target_path, commit_strategy)
+        let file_system = file_system.unwrap_or(STD_FILE_SYSTEM);
+        let target_path = match follow_symlinks {
+            true => resolve_symlink(target_path),
+            false => target_path,
+        };
+        let mut phazer = Phazer::inner_new(target_path, commit_strategy, file_system);
+        if let Some(tag) = working_name_tag {
+            phazer.use_working_name_tag(&tag);
+        }
+        if let Some(namer) = working_namer {
+            phazer.working_path = namer(&phazer.target_path, phazer.phazer_id);
+        }
+        if let Some(subdir) = working_subdir {
+            phazer.use_working_subdir(subdir);
+        }
+        if let Some(dir) = working_dir {
+            phazer.use_working_dir(dir);
+        }
+        phazer.preflight_target = preflight_target;
+        phazer.expected_extension = expected_extension;
+        phazer.serialize_with_lock = serialize_with_lock;
+        phazer.commit_marker = commit_marker;
+        phazer.backup_extension = backup_extension;
+        phazer.verify_boundaries = verify_boundaries;
+        phazer.commit_on_panic = commit_on_panic;
+        phazer.min_commit_bytes = min_commit_bytes;
+        phazer.restore_selinux_context = restore_selinux_context;
+        phazer.mtime_policy = mtime_policy;
+        phazer.seed_from_target = seed_from_target;
+        phazer.size_budget = size_budget;
+        phazer.write_buffering = write_buffering;
+        #[cfg(unix)]
+        {
+            phazer.working_mode = working_mode;
+        }
+        phazer.sync_mode = sync_mode;
+        phazer.before_commit = before_commit;
+        phazer.after_commit = after_commit;
+        phazer
+    }
+}
+
+/// An iterator that yields one [`Phazer`] for each regular file in a directory.
+///
+/// This struct is created by [`Phazer::for_each_in_dir`].
+///
+pub struct PhazersInDir<'cs> {
+    commit_strategy: &'cs dyn CommitStrategy,
+    paths: std::vec::IntoIter<PathBuf>,
+}
+
+impl<'cs> Iterator for PhazersInDir<'cs> {
+    type Item = std::io::Result<Phazer<'cs>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let path = self.paths.next()?;
+            if is_working_path(&path) || is_lock_path(&path) || is_marker_path(&path) {
+                continue;
+            }
+            // Follow symbolic links so a link to a regular file is included
+            match STD_FILE_SYSTEM.metadata(&path) {
+                Ok(m) if m.is_file() => {}
+                Ok(_) => continue,
+                Err(e) => return Some(Err(e)),
+            }
+            return Some(Ok(Phazer::inner_new(
+                path,
+                self.commit_strategy,
+                STD_FILE_SYSTEM,
+            )));
+        }
+    }
+}
+
+// Return true if path looks like a working file created by this crate.
+fn is_working_path(path: &Path) -> bool {
+    match path.extension() {
+        Some(ext) => ext.to_string_lossy().starts_with(WORKING_PREFIX),
+        None => false,
+    }
+}
+
+// Return the file `path` refers to after following symbolic links in its final component.  A link
+// that cannot be read, or a chain that is too long (a loop), stops the resolution.
+fn resolve_symlink(mut path: PathBuf) -> PathBuf {
+    for _ in 0..MAX_SYMLINK_HOPS {
+        let is_link = std::fs::symlink_metadata(&path).is_ok_and(|m| m.file_type().is_symlink());
+        if !is_link {
+            break;
+        }
+        let Ok(link) = std::fs::read_link(&path) else {
+            break;
+        };
+        // A relative link is relative to the directory holding the link
+        path = match path.parent() {
+            Some(parent) => parent.join(link),
+            None => link,
+        };
+    }
+    path
+}
+
+// The most symbolic links followed when resolving a target; Linux uses the same limit.
+const MAX_SYMLINK_HOPS: usize = 40;
+
+// Return the id of the process that wrote the commit marker at `path`.  `None` is returned if the
+// marker cannot be read or does not start with a process id.
+fn marker_pid(path: &Path) -> Option<u32> {
+    let contents = std::fs::read_to_string(path).ok()?;
+    contents.lines().next()?.trim().parse().ok()
+}
+
+// Return the id of the process that created the working file at `path`.  `None` is returned if
+// `path` is not a working file or its name does not include a process id (a tagged name).
+fn working_path_pid(path: &Path) -> Option<u32> {
+    let ext = path.extension()?.to_str()?;
+    let mut parts = ext.strip_prefix(WORKING_PREFIX)?.split('-');
+    let pid = parts.next()?.parse().ok()?;
+    // The serial number follows the process id
+    parts.next()?.parse::<usize>().ok()?;
+    Some(pid)
+}
+
+// Fail if the working file and the target could be the same file.  The paths are compared
+// ignoring case (case-insensitive filesystems are the default for macOS and Windows), then again
+// with their directories resolved so `./data.txt`, an absolute form of the target, or a path
+// through a symbolic link is caught.  If both files exist their identities are compared too.
+// The first writer truncates the working file so letting the target through destroys it.
+fn check_working_collision(
+    file_system: &dyn FileSystem,
+    working_path: &Path,
+    target_path: &Path,
+) -> std::io::Result<()> {
+    let fold = |p: &Path| p.to_string_lossy().to_lowercase();
+    let collides = fold(working_path) == fold(target_path)
+        || match (
+            resolve_parent(file_system, working_path),
+            resolve_parent(file_system, target_path),
+        ) {
+            (Some(working), Some(target)) => fold(&working) == fold(&target),
+            _ => false,
+        }
+        || is_same_file(file_system, working_path, target_path);
+    if !collides {
+        return Ok(());
+    }
+    Err(std::io::Error::new(
+        ErrorKind::InvalidInput,
+        format!(
+            "the working file ({}) could be the target",
+            working_path.display()
+        ),
+    ))
+}
+
+// Return `path` with its directory canonicalized.  The final component is left alone so a
+// symbolic link there is not followed.  `None` is returned if the directory does not exist.
+fn resolve_parent(file_system: &dyn FileSystem, path: &Path) -> Option<PathBuf> {
+    let parent = match path.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => Path::new("."),
+    };
+    Some(
+        file_system
+            .canonicalize(parent)
+            .ok()?
+            .join(path.file_name()?),
+    )
+}
+
+// Return true if `a` and `b` both exist and are the same file.
+#[cfg(unix)]
+fn is_same_file(file_system: &dyn FileSystem, a: &Path, b: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    match (file_system.metadata(a), file_system.metadata(b)) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+        _ => false,
+    }
+}
+
+// The standard library does not expose a file identity on other platforms.
+#[cfg(not(unix))]
+fn is_same_file(_file_system: &dyn FileSystem, _a: &Path, _b: &Path) -> bool {
+    false
+}
+
+// Remove all but the newest `keep` regular files in `dir` for which `pattern` returns `true`.
+// Files created by this crate are never removed.
+fn prune(
+    file_system: &dyn FileSystem,
+    dir: &Path,
+    keep: usize,
+    pattern: impl Fn(&Path) -> bool,
+) -> std::io::Result<Vec<PathBuf>> {
+    let mut candidates = Vec::new();
+    for path in file_system.read_dir(dir)? {
+        if is_working_path(&path) || is_lock_path(&path) || is_marker_path(&path) || !pattern(&path)
+        {
+            continue;
+        }
+        let m = file_system.metadata(&path)?;
+        if m.is_file() {
+            candidates.push((m.modified()?, path));
+        }
+    }
+    // Newest first
+    candidates.sort_by_key(|c| std::cmp::Reverse(c.0));
+    let mut removed = Vec::new();
+    for (_, path) in candidates.into_iter().skip(keep) {
+        file_system.remove_file(&path)?;
+        removed.push(path);
+    }
+    Ok(removed)
+}
+
+// Return the length, the first `n` bytes, and the last `n` bytes of the file at `path`.
+fn read_boundaries(
+    file_system: &dyn FileSystem,
+    path: &Path,
+    n: usize,
+) -> std::io::Result<(u64, Vec<u8>, Vec<u8>)> {
+    let mut file = file_system.open(path, OpenOptions::new().read(true))?;
+    let len = file.metadata()?.len();
+    let n = len.min(n as u64);
+    let mut first = vec![0; n as usize];
+    file.read_exact(&mut first)?;
+    let mut last = vec![0; n as usize];
+    file.seek(SeekFrom::Start(len - n))?;
+    file.read_exact(&mut last)?;
+    Ok((len, first, last))
+}
+
+// Return true if path looks like a lock file created by this crate.
+fn is_lock_path(path: &Path) -> bool {
+    match path.extension() {
+        Some(ext) => ext == LOCK_EXTENSION,
+        None => false,
+    }
+}
+
+// Every working file extension starts with this.
+const WORKING_PREFIX: &str = "phazer-working-";
+
+// Every lock file extension is this.
+const LOCK_EXTENSION: &str = "phazer-lock";
+
+// Every commit marker extension is this.
+const MARKER_EXTENSION: &str = "phazer-committing";
+
+// Return true if path looks like a commit marker created by this crate.
+fn is_marker_path(path: &Path) -> bool {
+    match path.extension() {
+        Some(ext) => ext == MARKER_EXTENSION,
+        None => false,
     }
 }
 
+// Return the lock path for `target_path`.
+fn lock_path_for(target_path: &Path) -> PathBuf {
+    sidecar_path_for(target_path, LOCK_EXTENSION)
+}
+
+// Return the commit marker path for `target_path`.
+fn marker_path_for(target_path: &Path) -> PathBuf {
+    sidecar_path_for(target_path, MARKER_EXTENSION)
+}
+
+// Return `target_path` with `extension` added.
+fn sidecar_path_for(target_path: &Path, extension: &str) -> PathBuf {
+    let sidecar_ext = match target_path.extension() {
+        Some(ext) => format!("{}.{}", Path::new(ext).display(), extension),
+        None => extension.into(),
+    };
+    let mut sidecar_path = target_path.to_path_buf();
+    sidecar_path.set_extension(sidecar_ext);
+    sidecar_path
+}
+
+// Return the path of the backup of `target_path`; `extension` is appended to the full name.
+fn backup_path_for(target_path: &Path, extension: &OsStr) -> PathBuf {
+    let mut name = target_path.as_os_str().to_os_string();
+    name.push(".");
+    name.push(extension);
+    PathBuf::from(name)
+}
+
+// Return the working path for `target_path` where `unique` makes the name unique.
+fn working_path_for(target_path: &Path, unique: &str) -> PathBuf {
+    let lft = if let Some(ext) = target_path.extension() {
+        format!("{}.phazer-", Path::new(ext).display())
+    } else {
+        "phazer-".into()
+    };
+    let working_ext = format!("{}working-{}", lft, unique);
+    let mut working_path = target_path.to_path_buf();
+    working_path.set_extension(working_ext);
+    working_path
+}
+
 // Return a serial number for this application to ensure the working filename is unique.
 fn current_phazer_id() -> usize {
-    static NEXT_PHAZER_ID: AtomicUsize = AtomicUsize::new(0);
     NEXT_PHAZER_ID.fetch_add(1, Ordering::Relaxed)
 }
+
+static NEXT_PHAZER_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Sets the serial number used for the next [`Phazer`]'s working file name.
+///
+/// Each [`Phazer`] includes the process id and a serial number in the working file's name.
+/// Resetting the serial number makes working file names reproducible within a test.  Other
+/// [`Phazer`]s created at the same time (for example, by tests running in parallel) also take
+/// numbers from the counter.
+///
+/// Going back to a serial number already handed out gives the next [`Phazer`] the same working file
+/// name as an earlier one for the same target.  If the earlier [`Phazer`] is still alive, the two
+/// silently overwrite each other's working file.  Only reset the counter when no [`Phazer`] for
+/// the same target is alive.
+///
+/// # Arguments
+///
+/// * `start` - The serial number given to the next [`Phazer`].
+///
+#[doc(hidden)]
+#[cfg(feature = "test_helpers")]
+pub fn reset_id_counter(start: usize) {
+    NEXT_PHAZER_ID.store(start, Ordering::Relaxed);
+}
+
+// Ensure the target's parent is an existing directory and the target, if it exists, is a regular
+// file.
+fn check_target(file_system: &dyn FileSystem, target_path: &Path) -> std::io::Result<()> {
+    let parent = target_parent(target_path);
+    if !file_system.metadata(parent)?.is_dir() {
+        return Err(std::io::Error::new(
+            ErrorKind::NotADirectory,
+            format!(
+                "the parent of the target ({}) is not a directory",
+                parent.display()
+            ),
+        ));
+    }
+    match file_system.metadata(target_path) {
+        Ok(m) => {
+            if m.is_file() {
+                Ok(())
+            } else {
+                Err(std::io::Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "the target ({}) is not a regular file",
+                        target_path.display()
+                    ),
+                ))
+            }
+        }
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+// Return the directory containing the target.
+fn target_parent(target_path: &Path) -> &Path {
+    match target_path.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => Path::new("."),
+    }
+}
+
+// Perform the checks from check_target plus checks for problems that predictably cause the commit
+// to fail.
+fn preflight_target(file_system: &dyn FileSystem, target_path: &Path) -> std::io::Result<()> {
+    check_target(file_system, target_path)?;
+    os::check_writable_dir(target_parent(target_path))?;
+    // Windows refuses to replace a read-only target
+    #[cfg(windows)]
+    if let Ok(m) = file_system.metadata(target_path) {
+        if m.permissions().readonly() {
+            return Err(std::io::Error::new(
+                ErrorKind::PermissionDenied,
+                format!("the target ({}) is read-only", target_path.display()),
+            ));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,63 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Choose the modification time the target has after a commit.
+//!
+use std::time::SystemTime;
+
+/// The modification time given to the working file just before it is committed.
+///
+/// Tools like `make` and `rsync` decide what to do from a file's modification time.  Touching the
+/// target after the commit is a second step that is not atomic with the commit.  [`MtimePolicy`]
+/// is set on the builder with [`set_mtime`][sm] and the time is set on the working file before
+/// the commit so the target appears with the chosen time.  For a strategy that writes into the
+/// existing target the time is set on the target right after the commit.
+///
+/// [sm]: crate::PhazerBuilderWithTarget::set_mtime
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(feature = "simple")]
+/// # {
+/// use std::io::Write;
+/// use std::time::{Duration, SystemTime};
+///
+/// use phazer::mtime_policy::MtimePolicy;
+/// use phazer::PhazerBuilder;
+///
+/// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let built = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+///     let phazer = PhazerBuilder::with_target("generated.h")
+///         .set_mtime(MtimePolicy::Exact(built))
+///         .build();
+///     let mut writer = phazer.simple_writer()?;
+///     writer.write_all(b"#define GENERATED 1\n")?;
+///     drop(writer);
+///     phazer.commit()?;
+///     Ok(())
+/// }
+/// # }
+/// ```
+///
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MtimePolicy {
+    /// The modification time is set to the time of the commit.
+    Now,
+    /// The modification time is set to the given time; for example, the time of a source file.
+    Exact(SystemTime),
+    /// The modification time of the target being replaced is kept.  If the target does not exist
+    /// the working file keeps its modification time.
+    FromTarget,
+}
@@ -0,0 +1,360 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Thin wrappers over the operating system APIs this crate needs that the Standard Library does
+// not provide.
+
+use std::fs::OpenOptions;
+use std::path::Path;
+use std::time::SystemTime;
+
+use crate::file_system::FileSystem;
+
+// Return the number of bytes available to this process on the filesystem containing `path`.
+#[cfg(unix)]
+pub(crate) fn available_space(path: &Path) -> std::io::Result<u64> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes())
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+    let mut stats = std::mem::MaybeUninit::<libc::statvfs>::uninit();
+    // SAFETY: c_path is a valid NUL terminated string and stats is large enough for the result.
+    let rv = unsafe { libc::statvfs(c_path.as_ptr(), stats.as_mut_ptr()) };
+    if rv != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    // SAFETY: statvfs succeeded so stats has been initialized.
+    let stats = unsafe { stats.assume_init() };
+    #[allow(clippy::unnecessary_cast)]
+    Ok((stats.f_bavail as u64).saturating_mul(stats.f_frsize as u64))
+}
+
+// Return the number of bytes available to this process on the volume containing `path`.
+#[cfg(windows)]
+pub(crate) fn available_space(path: &Path) -> std::io::Result<u64> {
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let wide = to_wide(path);
+    let mut available: u64 = 0;
+    // SAFETY: wide is NUL terminated and the output pointers are either valid or null.
+    let rv = unsafe {
+        GetDiskFreeSpaceExW(
+            wide.as_ptr(),
+            &mut available,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    };
+    if rv == 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(available)
+}
+
+// Encode `path` as a NUL terminated wide string.
+#[cfg(windows)]
+fn to_wide(path: &Path) -> Vec<u16> {
+    use std::os::windows::ffi::OsStrExt;
+
+    path.as_os_str().encode_wide().chain(Some(0)).collect()
+}
+
+// Rename `from` to `to` using MoveFileExW with MOVEFILE_REPLACE_EXISTING and, optionally,
+// MOVEFILE_WRITE_THROUGH.
+#[cfg(windows)]
+pub(crate) fn move_file_ex(from: &Path, to: &Path, write_through: bool) -> std::io::Result<()> {
+    use windows_sys::Win32::Storage::FileSystem::{
+        MoveFileExW, MOVEFILE_REPLACE_EXISTING, MOVEFILE_WRITE_THROUGH,
+    };
+
+    let from = to_wide(from);
+    let to = to_wide(to);
+    let mut flags = MOVEFILE_REPLACE_EXISTING;
+    if write_through {
+        flags |= MOVEFILE_WRITE_THROUGH;
+    }
+    // SAFETY: both paths are NUL terminated.
+    let rv = unsafe { MoveFileExW(from.as_ptr(), to.as_ptr(), flags) };
+    if rv == 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+// Replace `target` with `replacement` using ReplaceFileW.  Unlike a rename, ReplaceFileW keeps
+// the target's identity (attributes, ACLs, alternate streams) and tolerates readers that opened
+// the target with delete sharing.
+#[cfg(windows)]
+pub(crate) fn replace_file(target: &Path, replacement: &Path) -> std::io::Result<()> {
+    use windows_sys::Win32::Storage::FileSystem::{ReplaceFileW, REPLACEFILE_IGNORE_MERGE_ERRORS};
+
+    let target = to_wide(target);
+    let replacement = to_wide(replacement);
+    // SAFETY: both paths are NUL terminated and the optional arguments are null.
+    let rv = unsafe {
+        ReplaceFileW(
+            target.as_ptr(),
+            replacement.as_ptr(),
+            std::ptr::null(),
+            REPLACEFILE_IGNORE_MERGE_ERRORS,
+            std::ptr::null(),
+            std::ptr::null(),
+        )
+    };
+    if rv == 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+// Return the number of hard links to `path`.
+#[cfg(unix)]
+pub(crate) fn hard_link_count(file_system: &dyn FileSystem, path: &Path) -> std::io::Result<u64> {
+    use std::os::unix::fs::MetadataExt;
+
+    Ok(file_system.metadata(path)?.nlink())
+}
+
+// Return the number of hard links to `path`.
+#[cfg(windows)]
+pub(crate) fn hard_link_count(file_system: &dyn FileSystem, path: &Path) -> std::io::Result<u64> {
+    use std::os::windows::fs::OpenOptionsExt;
+    use std::os::windows::io::AsRawHandle;
+
+    use windows_sys::Win32::Storage::FileSystem::{
+        GetFileInformationByHandle, BY_HANDLE_FILE_INFORMATION,
+    };
+
+    // No access is needed to query the file information
+    let file = file_system.open(path, OpenOptions::new().access_mode(0))?;
+    let mut info = std::mem::MaybeUninit::<BY_HANDLE_FILE_INFORMATION>::uninit();
+    // SAFETY: the handle is valid while file is open and info is large enough for the result.
+    let rv = unsafe { GetFileInformationByHandle(file.as_raw_handle() as _, info.as_mut_ptr()) };
+    if rv == 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    // SAFETY: GetFileInformationByHandle succeeded so info is initialized.
+    let info = unsafe { info.assume_init() };
+    Ok(info.nNumberOfLinks.into())
+}
+
+// Set the modification time of `path` through a handle that does not need write permission so a
+// read-only working file (see working_mode) can still be given a time.
+#[cfg(unix)]
+pub(crate) fn set_modified(
+    file_system: &dyn FileSystem,
+    path: &Path,
+    mtime: SystemTime,
+) -> std::io::Result<()> {
+    file_system
+        .open(path, OpenOptions::new().read(true))?
+        .set_modified(mtime)
+}
+
+// Set the modification time of `path` through a handle that only has the right to change
+// attributes so a read-only working file can still be given a time.
+#[cfg(windows)]
+pub(crate) fn set_modified(
+    file_system: &dyn FileSystem,
+    path: &Path,
+    mtime: SystemTime,
+) -> std::io::Result<()> {
+    use std::os::windows::fs::OpenOptionsExt;
+    use windows_sys::Win32::Storage::FileSystem::FILE_WRITE_ATTRIBUTES;
+
+    file_system
+        .open(path, OpenOptions::new().access_mode(FILE_WRITE_ATTRIBUTES))?
+        .set_modified(mtime)
+}
+
+// Return an error if the process cannot create files in the directory `path`.
+#[cfg(unix)]
+pub(crate) fn check_writable_dir(path: &Path) -> std::io::Result<()> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes())
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+    // SAFETY: c_path is a valid NUL terminated string.
+    let rv = unsafe { libc::access(c_path.as_ptr(), libc::W_OK | libc::X_OK) };
+    if rv != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+// Return an error if the process cannot create files in the directory `path`.  For Windows the
+// read-only attribute on a directory does not prevent creating files so there is nothing to
+// check.
+#[cfg(windows)]
+pub(crate) fn check_writable_dir(_path: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
+// Copy the SELinux context (the `security.selinux` extended attribute) of `from` onto `to`.
+// Nothing is done if `from` does not exist, `from` has no context, or the filesystem does not
+// support extended attributes (SELinux is not in use).
+#[cfg(target_os = "linux")]
+pub(crate) fn copy_selinux_context(from: &Path, to: &Path) -> std::io::Result<()> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    const NAME: &[u8] = b"security.selinux\0";
+
+    let c_from = CString::new(from.as_os_str().as_bytes())
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+    let c_to = CString::new(to.as_os_str().as_bytes())
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+    let not_in_use = |e: &std::io::Error| {
+        matches!(
+            e.raw_os_error(),
+            Some(libc::ENOENT) | Some(libc::ENODATA) | Some(libc::ENOTSUP)
+        )
+    };
+    // A context is a short string; grow the buffer if it is not
+    let mut value = vec![0u8; 256];
+    let len = loop {
+        // SAFETY: both strings are NUL terminated and value is valid for value.len() bytes.
+        let rv = unsafe {
+            libc::getxattr(
+                c_from.as_ptr(),
+                NAME.as_ptr().cast(),
+                value.as_mut_ptr().cast(),
+                value.len(),
+            )
+        };
+        if rv >= 0 {
+            break rv as usize;
+        }
+        let e = std::io::Error::last_os_error();
+        match e.raw_os_error() {
+            Some(libc::ERANGE) => value.resize(value.len() * 2, 0),
+            _ if not_in_use(&e) => return Ok(()),
+            _ => return Err(e),
+        }
+    };
+    // SAFETY: both strings are NUL terminated and value holds len initialized bytes.
+    let rv = unsafe {
+        libc::setxattr(
+            c_to.as_ptr(),
+            NAME.as_ptr().cast(),
+            value.as_ptr().cast(),
+            len,
+            0,
+        )
+    };
+    if rv != 0 {
+        let e = std::io::Error::last_os_error();
+        if !not_in_use(&e) {
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+// SELinux only exists for Linux so there is nothing to copy.
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn copy_selinux_context(_from: &Path, _to: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
+// Flush the rename that placed `target_path`; for POSIX that means the containing directory.
+#[cfg(unix)]
+pub(crate) fn sync_rename(file_system: &dyn FileSystem, target_path: &Path) -> std::io::Result<()> {
+    use std::fs::OpenOptions;
+
+    let parent = crate::target_parent(target_path);
+    file_system
+        .open(parent, OpenOptions::new().read(true))?
+        .sync_all()
+}
+
+// Return true if the process `pid` is running.  If the answer is uncertain `true` is returned.
+#[cfg(unix)]
+pub(crate) fn process_is_running(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return true;
+    };
+    // SAFETY: signal 0 only checks that the process exists and can be signalled.
+    let rv = unsafe { libc::kill(pid, 0) };
+    // EPERM means the process exists but belongs to someone else
+    rv == 0 || std::io::Error::last_os_error().raw_os_error() != Some(libc::ESRCH)
+}
+
+// Return true if the process `pid` is running.  If the answer is uncertain `true` is returned.
+#[cfg(windows)]
+pub(crate) fn process_is_running(pid: u32) -> bool {
+    use windows_sys::Win32::Foundation::{
+        CloseHandle, GetLastError, ERROR_INVALID_PARAMETER, STILL_ACTIVE,
+    };
+    use windows_sys::Win32::System::Threading::{
+        GetExitCodeProcess, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    // SAFETY: OpenProcess has no pointer arguments.
+    let handle = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid) };
+    if handle == 0 {
+        // An invalid parameter means there is no such process
+        // SAFETY: GetLastError has no arguments.
+        return unsafe { GetLastError() } != ERROR_INVALID_PARAMETER;
+    }
+    let mut code = 0;
+    // SAFETY: handle is valid and code is a valid output location.
+    let rv = unsafe { GetExitCodeProcess(handle, &mut code) };
+    // SAFETY: handle is valid and no longer used.
+    unsafe { CloseHandle(handle) };
+    rv == 0 || code == STILL_ACTIVE as u32
+}
+
+// Atomically exchange `a` and `b` with renameat2(RENAME_EXCHANGE).  An Unsupported error is
+// returned if the kernel or filesystem does not support the exchange.
+#[cfg(target_os = "linux")]
+pub(crate) fn rename_exchange(a: &Path, b: &Path) -> std::io::Result<()> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_a = CString::new(a.as_os_str().as_bytes())
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+    let c_b = CString::new(b.as_os_str().as_bytes())
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+    // The system call is used directly; the glibc wrapper is newer than some supported systems.
+    // SAFETY: both strings are NUL terminated and remain alive for the call.
+    let rv = unsafe {
+        libc::syscall(
+            libc::SYS_renameat2,
+            libc::AT_FDCWD,
+            c_a.as_ptr(),
+            libc::AT_FDCWD,
+            c_b.as_ptr(),
+            libc::RENAME_EXCHANGE,
+        )
+    };
+    if rv != 0 {
+        let e = std::io::Error::last_os_error();
+        return match e.raw_os_error() {
+            Some(libc::ENOSYS) | Some(libc::EINVAL) | Some(libc::ENOTSUP) => {
+                Err(std::io::Error::new(std::io::ErrorKind::Unsupported, e))
+            }
+            _ => Err(e),
+        };
+    }
+    Ok(())
+}
+
+// Only Linux has an atomic exchange.
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn rename_exchange(_a: &Path, _b: &Path) -> std::io::Result<()> {
+    Err(std::io::ErrorKind::Unsupported.into())
+}
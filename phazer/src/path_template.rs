@@ -0,0 +1,172 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Build a target path from a template with named placeholders.
+
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{PhazerBuilder, PhazerBuilderWithTarget};
+
+impl<'cs> PhazerBuilder<'cs> {
+    /// Converts a [`PhazerBuilder`] to a [`PhazerBuilderWithTarget`] using a target path built from
+    /// `template`.
+    ///
+    /// Each placeholder in `template` is a name in braces (`{name}`).  A placeholder is replaced
+    /// with the matching entry from `values`.  These placeholders are filled from the current time
+    /// (UTC) if `values` does not include them...
+    /// * `{year}` - Four digit year
+    /// * `{month}` - Two digit month (`01` through `12`)
+    /// * `{day}` - Two digit day of the month
+    /// * `{hour}`, `{minute}`, `{second}` - Two digit time of day
+    ///
+    /// The target's parent directories are created.
+    ///
+    /// # Arguments
+    ///
+    /// * `template` - Target file with placeholders; for example, `logs/{year}/{month}/{name}.log`.
+    /// * `values` - Name / value pairs used to fill placeholders.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] with a kind of [`InvalidInput`][ii] is returned if a placeholder is not
+    /// closed or has no value.  An [`Error`][ioe] is returned if the parent directories cannot be
+    /// created.  Otherwise a new [`PhazerBuilderWithTarget`] is returned.
+    ///
+    /// [ioe]: std::io::Error
+    /// [ii]: std::io::ErrorKind::InvalidInput
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # #[cfg(feature = "simple")]
+    /// # {
+    /// use std::io::Write;
+    ///
+    /// use phazer::PhazerBuilder;
+    ///
+    /// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let phazer = PhazerBuilder::new()
+    ///         .target_template("logs/{year}/{month}/{name}.log", &[("name", "app")])?
+    ///         .build();
+    ///     let mut writer = phazer.simple_writer()?;
+    ///     writer.write_all(b"started\n")?;
+    ///     drop(writer);
+    ///     phazer.commit()?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    ///
+    pub fn target_template(
+        self,
+        template: &str,
+        values: &[(&str, &str)],
+    ) -> std::io::Result<PhazerBuilderWithTarget<'cs>> {
+        let target_path = PathBuf::from(render(template, values, SystemTime::now())?);
+        if let Some(parent) = target_path.parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        Ok(self.target(target_path))
+    }
+}
+
+// Replace each `{name}` in `template`.  `values` takes precedence over the time fields.
+fn render(template: &str, values: &[(&str, &str)], now: SystemTime) -> std::io::Result<String> {
+    let mut fields: HashMap<&str, String> = time_fields(now).into_iter().collect();
+    for (name, value) in values {
+        fields.insert(name, value.to_string());
+    }
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        rendered.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let close = after.find('}').ok_or_else(|| {
+            std::io::Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "the path template ({}) has an unclosed placeholder",
+                    template
+                ),
+            )
+        })?;
+        let name = &after[..close];
+        let value = fields.get(name).ok_or_else(|| {
+            std::io::Error::new(
+                ErrorKind::InvalidInput,
+                format!("the path template placeholder {{{}}} has no value", name),
+            )
+        })?;
+        rendered.push_str(value);
+        rest = &after[close + 1..];
+    }
+    rendered.push_str(rest);
+    Ok(rendered)
+}
+
+// The current date and time (UTC) broken into placeholder values.
+fn time_fields(now: SystemTime) -> [(&'static str, String); 6] {
+    let seconds = now
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let (year, month, day) = civil_from_days((seconds / 86400) as i64);
+    let time = seconds % 86400;
+    [
+        ("year", format!("{:04}", year)),
+        ("month", format!("{:02}", month)),
+        ("day", format!("{:02}", day)),
+        ("hour", format!("{:02}", time / 3600)),
+        ("minute", format!("{:02}", time / 60 % 60)),
+        ("second", format!("{:02}", time % 60)),
+    ]
+}
+
+// Convert days since 1970-01-01 to a (year, month, day).  This is Howard Hinnant's
+// `civil_from_days` algorithm.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::civil_from_days;
+
+    #[test]
+    fn civil_from_days_matches_known_dates() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        // 2000 is a leap year (divisible by 400)
+        assert_eq!(civil_from_days(11016), (2000, 2, 29));
+        assert_eq!(civil_from_days(11017), (2000, 3, 1));
+        // 2100 is not (divisible by 100)
+        assert_eq!(civil_from_days(47540), (2100, 2, 28));
+        assert_eq!(civil_from_days(47541), (2100, 3, 1));
+        assert_eq!(civil_from_days(-135081), (1600, 2, 29));
+    }
+}
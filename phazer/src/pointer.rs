@@ -0,0 +1,139 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Commit a versioned target then point a "latest" pointer at it.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+
+use crate::file_system::FileSystem;
+use crate::{resolve_parent, Phazer, PhazerBuilder};
+
+impl<'cs> Phazer<'cs> {
+    /// Commits the working file then atomically updates `pointer_path` to refer to the target.
+    ///
+    /// This supports versioned output schemes; for example, writing `data-42.bin` and keeping
+    /// `latest` pointing at the newest version.  The pointer is replaced the same way the target
+    /// is; it is built as a working file then committed with the same commit strategy and
+    /// filesystem.  Readers see either the old pointer or the new pointer.
+    ///
+    /// On POSIX systems, if `pointer_path` is an existing symbolic link, the pointer is replaced
+    /// with a new symbolic link.  Otherwise the pointer is a small text file holding the reference
+    /// followed by a newline.  The reference is relative to the pointer's directory (just the
+    /// target's file name if the target and pointer are in the same directory) so it resolves the
+    /// same way for a symbolic link, for any process, and after the tree is moved.  If the target
+    /// cannot be reached by a relative path (it is on another Windows drive) the reference is the
+    /// target's absolute path.
+    ///
+    /// The target is committed first.  If updating the pointer fails, the target has been
+    /// committed but the pointer still refers to the previous version.
+    ///
+    /// # Arguments
+    ///
+    /// * `pointer_path` - The pointer to update.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the working file cannot be transferred to the target or if
+    /// the pointer cannot be updated.
+    ///
+    /// [ioe]: std::io::Error
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "simple")]
+    /// # {
+    /// use std::io::Write;
+    ///
+    /// use phazer::Phazer;
+    ///
+    /// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let phazer = Phazer::new("data-42.bin");
+    ///     let mut writer = phazer.simple_writer()?;
+    ///     writer.write_all(b"version 42")?;
+    ///     drop(writer);
+    ///     // `latest` now holds "data-42.bin"
+    ///     phazer.commit_and_update_pointer("latest")?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    ///
+    pub fn commit_and_update_pointer<P>(self, pointer_path: P) -> std::io::Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let pointer_path = pointer_path.as_ref();
+        let reference = pointer_reference(self.file_system, &self.target_path, pointer_path)?;
+        let pointer = PhazerBuilder::with_target(pointer_path)
+            .commit_strategy(self.commit_strategy)
+            .file_system(self.file_system)
+            .build();
+        self.commit()?;
+        pointer.prepare_working_file()?;
+        let _ = pointer.first_writer();
+        #[cfg(unix)]
+        if std::fs::symlink_metadata(pointer_path).is_ok_and(|m| m.file_type().is_symlink()) {
+            std::os::unix::fs::symlink(&reference, &pointer.working_path)?;
+            return pointer.commit();
+        }
+        let mut file = pointer.file_system.open(
+            &pointer.working_path,
+            OpenOptions::new().write(true).create(true).truncate(true),
+        )?;
+        writeln!(file, "{}", reference.display())?;
+        file.sync_all()?;
+        drop(file);
+        pointer.commit()
+    }
+}
+
+// The target relative to the pointer's directory; just the file name if they share a directory.
+// Both directories are resolved first so `..` and symbolic links cannot mislead.  If there is no
+// relative path (another Windows drive) the target's absolute path is returned.
+fn pointer_reference(
+    file_system: &dyn FileSystem,
+    target_path: &Path,
+    pointer_path: &Path,
+) -> std::io::Result<PathBuf> {
+    let (Some(target), Some(pointer)) = (
+        resolve_parent(file_system, target_path),
+        resolve_parent(file_system, pointer_path),
+    ) else {
+        return std::path::absolute(target_path);
+    };
+    let mut target_components = target.components().peekable();
+    let mut pointer_components = pointer
+        .parent()
+        .unwrap_or(Path::new(""))
+        .components()
+        .peekable();
+    let mut shared = 0;
+    while let (Some(t), Some(p)) = (target_components.peek(), pointer_components.peek()) {
+        if t != p {
+            break;
+        }
+        target_components.next();
+        pointer_components.next();
+        shared += 1;
+    }
+    if shared == 0 {
+        return Ok(target);
+    }
+    let mut reference: PathBuf = pointer_components.map(|_| Component::ParentDir).collect();
+    reference.extend(target_components);
+    Ok(reference)
+}
@@ -0,0 +1,116 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Coordinated commits.  Several targets are committed back-to-back so they become visible at
+//! nearly the same time.
+//!
+use std::path::PathBuf;
+
+use crate::Phazer;
+
+/// [`ReleaseBarrier`] collects fully built [`Phazer`]s and commits them all at once.
+///
+/// When releasing several files it is often desirable for all of them to become visible as close
+/// to simultaneously as possible.  Committing each file as soon as it is built leaves a large
+/// window in which some files are new and others are old.  With [`ReleaseBarrier`] the slow part
+/// (building the working files) is done first.  Then [`ReleaseBarrier::publish_all`] performs the
+/// commits back-to-back with no application code in between.  The working files of committed
+/// [`Phazer`]s are cleaned up after the last commit.
+///
+/// This is **not** atomic.  It narrows the window; it does not eliminate it.  A failed commit does
+/// not undo the commits before it.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(feature = "simple")]
+/// # {
+/// use std::io::Write;
+///
+/// use phazer::release_barrier::ReleaseBarrier;
+/// use phazer::Phazer;
+///
+/// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let mut barrier = ReleaseBarrier::new();
+///     for name in ["index.html", "app.js", "app.css"] {
+///         let phazer = Phazer::new(name);
+///         let mut writer = phazer.simple_writer()?;
+///         writeln!(writer, "the new {}", name)?;
+///         drop(writer);
+///         barrier.add(phazer);
+///     }
+///     // All three are committed back-to-back
+///     for (target, result) in barrier.publish_all() {
+///         result?;
+///         println!("{} released", target.display());
+///     }
+///     Ok(())
+/// }
+/// # }
+/// ```
+///
+#[derive(Default)]
+pub struct ReleaseBarrier<'cs> {
+    pending: Vec<Phazer<'cs>>,
+}
+
+impl<'cs> ReleaseBarrier<'cs> {
+    /// Creates an empty [`ReleaseBarrier`].
+    ///
+    pub fn new() -> Self {
+        Self {
+            pending: Vec::new(),
+        }
+    }
+    /// Adds `phazer` to the barrier.  It is committed when [`ReleaseBarrier::publish_all`] is
+    /// called.
+    ///
+    /// All writers for `phazer` must be dropped before it can be added.
+    ///
+    pub fn add(&mut self, phazer: Phazer<'cs>) {
+        self.pending.push(phazer);
+    }
+    /// Returns the number of [`Phazer`]s waiting to be committed.
+    ///
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+    /// Returns `true` if nothing is waiting to be committed.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+    /// Commits everything in the order it was added.
+    ///
+    /// The commits are performed back-to-back.  Cleanup (removing the working files of failed
+    /// commits) is done after the last commit.  A failed commit does not stop the remaining
+    /// commits.
+    ///
+    /// # Return Value
+    ///
+    /// The target path and commit result for each [`Phazer`] in the order it was added.
+    ///
+    pub fn publish_all(self) -> Vec<(PathBuf, std::io::Result<()>)> {
+        let results: Vec<std::io::Result<()>> = self
+            .pending
+            .iter()
+            .map(|phazer| phazer.try_commit())
+            .collect();
+        self.pending
+            .into_iter()
+            .zip(results)
+            .map(|(phazer, result)| (phazer.target_path().to_path_buf(), result))
+            .collect()
+    }
+}
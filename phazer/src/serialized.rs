@@ -0,0 +1,128 @@
+#![cfg(any(feature = "toml", feature = "yaml"))]
+//
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Commit helpers for TOML and YAML targets.
+//
+// This module is available when the `toml` or `yaml` feature is enabled.
+
+#[cfg(feature = "toml")]
+use std::io::{ErrorKind, Write};
+
+use serde::Serialize;
+
+use crate::Phazer;
+
+impl<'cs> Phazer<'cs> {
+    /// Writes `value` as TOML to the working file then commits the working file.
+    ///
+    /// This is the TOML counterpart of [`Phazer::commit_json`].  The result is written as
+    /// pretty-printed TOML.  Anything already written to the working file is replaced.  If `value`
+    /// cannot be serialized nothing is committed and the working file is removed.
+    ///
+    /// This method is available when the `toml` feature is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to write.  TOML requires the top level be a table (e.g. a struct or
+    ///   a map).
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if `value` cannot be serialized, the working file cannot be
+    /// written, or the commit fails.  In each case the working file is removed.  A serialization
+    /// failure is an [`InvalidData`][id] error.
+    ///
+    /// [id]: std::io::ErrorKind::InvalidData
+    /// [ioe]: std::io::Error
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "toml")]
+    /// # {
+    /// use std::collections::BTreeMap;
+    ///
+    /// use phazer::Phazer;
+    ///
+    /// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mut limits = BTreeMap::new();
+    ///     limits.insert("connections", 100);
+    ///     limits.insert("requests_per_second", 250);
+    ///     Phazer::new("limits.toml").commit_toml(&limits)?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    ///
+    #[cfg(feature = "toml")]
+    pub fn commit_toml<T>(self, value: &T) -> std::io::Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        let text = toml::to_string_pretty(value)
+            .map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e))?;
+        self.replace_working_file(|writer| writer.write_all(text.as_bytes()))?;
+        self.commit()
+    }
+    /// Writes `value` as YAML to the working file then commits the working file.
+    ///
+    /// This is the YAML counterpart of [`Phazer::commit_json`].  Anything already written to the
+    /// working file is replaced.  If `value` cannot be serialized nothing is committed and the
+    /// working file is removed.
+    ///
+    /// This method is available when the `yaml` feature is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to write.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if `value` cannot be serialized, the working file cannot be
+    /// written, or the commit fails.  In each case the working file is removed.
+    ///
+    /// [ioe]: std::io::Error
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "yaml")]
+    /// # {
+    /// use std::collections::BTreeMap;
+    ///
+    /// use phazer::Phazer;
+    ///
+    /// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mut limits = BTreeMap::new();
+    ///     limits.insert("connections", 100);
+    ///     limits.insert("requests_per_second", 250);
+    ///     Phazer::new("limits.yaml").commit_yaml(&limits)?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    ///
+    #[cfg(feature = "yaml")]
+    pub fn commit_yaml<T>(self, value: &T) -> std::io::Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.replace_working_file(|writer| {
+            serde_yaml_ng::to_writer(writer, value).map_err(std::io::Error::other)
+        })?;
+        self.commit()
+    }
+}
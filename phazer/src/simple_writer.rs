@@ -14,15 +14,23 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-//! A file-like thing used to build a working file using the Standard Library.
+//! File-like things used to build a working file using the Standard Library.
 //!
 //! This module is available when the `simple` feature is enabled.
 //!
 use crate::Phazer;
 
-use std::fs::{File, OpenOptions};
-use std::io::{Read, Seek, Write};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
 use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::cancellation::cancelled_error;
+use crate::size_budget::SizeBudget;
+use crate::write_buffering::{WriteBuffer, WriteBuffering};
+
+// Matches the capacity of std::io::BufWriter.
+const DEFAULT_BUFFER_CAPACITY: usize = 8 * 1024;
 
 impl<'cs> Phazer<'cs> {
     /// Returns a synchronous file-like thing that's used to build the working file.
@@ -77,21 +85,88 @@ impl<'cs> Phazer<'cs> {
     /// # }
     /// ```
     ///
-    pub fn simple_writer<'a>(&'a self) -> std::io::Result<SimplePhazerWriter> {
-        let mut options = OpenOptions::new();
-        // Always allow read / write
-        options.read(true).write(true);
-        // Is this the first writer?  Create and truncate.
-        if self.first_writer() {
-            options.truncate(true).create(true);
-        }
-        // Try to open / create the file
-        let phase1 = options.open(&self.working_path)?;
+    pub fn simple_writer<'a>(&'a self) -> std::io::Result<SimplePhazerWriter<'a, 'a>> {
+        self.wrapped_simple_writer()
+    }
+    // `simple_writer` for the writers that wrap a SimplePhazerWriter and carry the Phazer's
+    // lifetime.
+    pub(crate) fn wrapped_simple_writer<'a>(
+        &'a self,
+    ) -> std::io::Result<SimplePhazerWriter<'a, 'cs>> {
+        self.open_simple_writer(self.write_buffering)
+    }
+    /// Returns a buffered synchronous file-like thing that's used to build the working file.
+    ///
+    /// This is [`Phazer::simple_writer`] with the writes collected in memory (like a
+    /// [`BufWriter`][bw]) so building a file with many small writes, even one byte at a time, does
+    /// not make a system call for each write.  The buffered bytes are written when the buffer is
+    /// full, when the writer is flushed, before the writer reads or seeks, and when the writer is
+    /// dropped.  If the bytes cannot be written when the writer is dropped the commit fails.
+    ///
+    /// If [`write_buffering`][wb] was used to select a buffering mode that mode is used.
+    /// Otherwise the buffer holds 8 KiB.
+    ///
+    /// This method is available when the `simple` feature is enabled.
+    ///
+    /// # Return Value
+    ///
+    /// The same as [`Phazer::simple_writer`].
+    ///
+    /// [bw]: std::io::BufWriter
+    /// [wb]: crate::PhazerBuilderWithTarget::write_buffering
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "simple")]
+    /// # {
+    /// use std::io::Write;
+    ///
+    /// use phazer::Phazer;
+    ///
+    /// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let phazer = Phazer::new("one-byte-at-a-time.txt");
+    ///     let mut writer = phazer.simple_buffered_writer()?;
+    ///     for b in b"chatty".iter() {
+    ///         writer.write_all(&[*b])?;
+    ///     }
+    ///     drop(writer);
+    ///     phazer.commit()?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    ///
+    pub fn simple_buffered_writer<'a>(
+        &'a self,
+    ) -> std::io::Result<BufferedSimplePhazerWriter<'a, 'cs>> {
+        let buffering = match self.write_buffering {
+            WriteBuffering::None => WriteBuffering::Buffered(DEFAULT_BUFFER_CAPACITY),
+            buffering => buffering,
+        };
+        Ok(BufferedSimplePhazerWriter {
+            writer: self.open_simple_writer(buffering)?,
+        })
+    }
+    fn open_simple_writer<'a>(
+        &'a self,
+        buffering: WriteBuffering,
+    ) -> std::io::Result<SimplePhazerWriter<'a, 'cs>> {
         Ok(SimplePhazerWriter {
-            phase1,
+            phase1: self.open_working_file()?,
+            cancelled: &self.cancelled,
+            size_budget: self.size_budget.as_ref(),
+            dense: false,
+            buffer: WriteBuffer::new(buffering),
+            unfinished_writer: &self.unfinished_writer,
             _parent: PhantomData::<&'a Self>,
         })
     }
+    // Open the working file for a writer; the first writer creates it.
+    pub(crate) fn open_working_file(&self) -> std::io::Result<File> {
+        let options = self.working_file_options()?;
+        self.file_system.open(&self.working_path, &options)
+    }
 }
 
 /// SimplePhazerWriter is a synchronous file-like thing that's used to build the working file.
@@ -102,30 +177,301 @@ impl<'cs> Phazer<'cs> {
 /// This struct is available when the `simple` feature is enabled.
 pub struct SimplePhazerWriter<'a, 'cs> {
     phase1: File,
+    cancelled: &'a AtomicBool,
+    size_budget: Option<&'a SizeBudget>,
+    dense: bool,
+    buffer: WriteBuffer,
+    unfinished_writer: &'a AtomicBool,
     _parent: PhantomData<&'a Phazer<'cs>>,
 }
 
+impl<'a, 'cs> SimplePhazerWriter<'a, 'cs> {
+    /// Zero-fills the gap when seeking past the end of the working file.
+    ///
+    /// Seeking past the end of a file then writing leaves a hole (a sparse region) on most
+    /// filesystems.  Some tools do not handle sparse files well.  When `value` is `true`, a seek
+    /// past the end of the working file writes zeros up to the new position so the committed file
+    /// is fully allocated.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - `true` to zero-fill gaps.  The default is `false`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "simple")]
+    /// # {
+    /// use std::io::{Seek, SeekFrom, Write};
+    ///
+    /// use phazer::Phazer;
+    ///
+    /// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let phazer = Phazer::new("fixed-layout.bin");
+    ///     let mut writer = phazer.simple_writer()?.dense(true);
+    ///     writer.seek(SeekFrom::Start(4096))?;
+    ///     writer.write_all(b"second block")?;
+    ///     drop(writer);
+    ///     phazer.commit()?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    ///
+    pub fn dense(mut self, value: bool) -> Self {
+        self.dense = value;
+        self
+    }
+    // Write the buffered bytes to the working file.
+    fn drain(&mut self) -> std::io::Result<()> {
+        drain_buffer(&mut self.phase1, &mut self.buffer)
+    }
+}
+
+// Write the bytes in `buffer` to `file`.
+pub(crate) fn drain_buffer(file: &mut File, buffer: &mut WriteBuffer) -> std::io::Result<()> {
+    while !buffer.is_empty() {
+        let n = write_retrying(file, buffer.pending())?;
+        if n == 0 {
+            return Err(std::io::ErrorKind::WriteZero.into());
+        }
+        buffer.consume(n);
+    }
+    Ok(())
+}
+
+// Write `buf` to the working file `file` through `buffer` on behalf of a writer; the cancellation
+// flag and size budget come from the writer's Phazer.
+pub(crate) fn write_working_file(
+    file: &mut File,
+    buffer: &mut WriteBuffer,
+    cancelled: &AtomicBool,
+    size_budget: Option<&SizeBudget>,
+    buf: &[u8],
+) -> std::io::Result<usize> {
+    if cancelled.load(Ordering::Relaxed) {
+        return Err(cancelled_error());
+    }
+    if buffer.is_direct() {
+        return write_direct(file, size_budget, buf);
+    }
+    // Bytes left over from a failed drain are written before anything new is accepted
+    if buffer.drain_needed() {
+        drain_buffer(file, buffer)?;
+    }
+    if let Some(size_budget) = size_budget {
+        size_budget.reserve(buf.len())?;
+    }
+    buffer.accept(buf);
+    // buf has been accepted; a failure here is reported by the next write, flush, or drop
+    if buffer.drain_needed() {
+        let _ = drain_buffer(file, buffer);
+    }
+    Ok(buf.len())
+}
+
+// Charge the size budget for `buf` then write it.
+fn write_direct(
+    file: &mut File,
+    size_budget: Option<&SizeBudget>,
+    buf: &[u8],
+) -> std::io::Result<usize> {
+    let Some(size_budget) = size_budget else {
+        return write_retrying(file, buf);
+    };
+    size_budget.reserve(buf.len())?;
+    match write_retrying(file, buf) {
+        Ok(n) => {
+            size_budget.release(buf.len() - n);
+            Ok(n)
+        }
+        Err(e) => {
+            size_budget.release(buf.len());
+            Err(e)
+        }
+    }
+}
+
+// Write to `file` retrying if a signal interrupts the write.
+fn write_retrying(file: &mut File, buf: &[u8]) -> std::io::Result<usize> {
+    loop {
+        match file.write(buf) {
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            rv => return rv,
+        }
+    }
+}
+
 impl<'p, 'cs> Drop for SimplePhazerWriter<'p, 'cs> {
-    fn drop(&mut self) {}
+    fn drop(&mut self) {
+        // Buffered bytes that cannot be written must not be committed
+        if self.drain().is_err() {
+            self.unfinished_writer.store(true, Ordering::Relaxed);
+        }
+    }
 }
 
 impl<'a, 'cs> Read for SimplePhazerWriter<'a, 'cs> {
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.drain()?;
         self.phase1.read(buf)
     }
 }
 
 impl<'a, 'cs> Seek for SimplePhazerWriter<'a, 'cs> {
-    fn seek(&mut self, pos: std::io::SeekFrom) -> std::io::Result<u64> {
-        self.phase1.seek(pos)
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.drain()?;
+        let position = self.phase1.seek(pos)?;
+        if self.dense {
+            let len = self.phase1.metadata()?.len();
+            if position > len {
+                // Fill the gap with zeros which leaves the file at the requested position
+                self.phase1.seek(SeekFrom::Start(len))?;
+                std::io::copy(
+                    &mut std::io::repeat(0).take(position - len),
+                    &mut self.phase1,
+                )?;
+            }
+        }
+        Ok(position)
     }
 }
 
 impl<'a, 'cs> Write for SimplePhazerWriter<'a, 'cs> {
     fn flush(&mut self) -> std::io::Result<()> {
+        self.drain()?;
         self.phase1.flush()
     }
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        self.phase1.write(buf)
+        write_working_file(
+            &mut self.phase1,
+            &mut self.buffer,
+            self.cancelled,
+            self.size_budget,
+            buf,
+        )
+    }
+}
+
+impl<'cs> Phazer<'cs> {
+    /// Returns a synchronous file-like thing that writes to the working file and also echoes
+    /// everything written to `other`.
+    ///
+    /// This is handy for diagnosing exactly what a generator produced; for example, by echoing to
+    /// standard error or a log.  The working file remains the authoritative content; `other` is
+    /// only an observer.  A write fails if either the working file or `other` fails.  Each write
+    /// goes to `other` first; bytes `other` does not accept are not written to the working file.
+    ///
+    /// This method is available when the `simple` feature is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The second sink.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the working file cannot be created or opened for read
+    /// / write access.  Otherwise a new [`TeePhazerWriter`] is returned.
+    ///
+    /// [ioe]: std::io::Error
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "simple")]
+    /// # {
+    /// use std::io::Write;
+    ///
+    /// use phazer::Phazer;
+    ///
+    /// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let phazer = Phazer::new("generated.rs");
+    ///     let mut writer = phazer.simple_tee_writer(std::io::stderr())?;
+    ///     writeln!(writer, "pub const ANSWER: u32 = 42;")?;
+    ///     drop(writer);
+    ///     phazer.commit()?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    ///
+    pub fn simple_tee_writer<'a, W>(
+        &'a self,
+        other: W,
+    ) -> std::io::Result<TeePhazerWriter<'a, 'cs, W>>
+    where
+        W: Write,
+    {
+        Ok(TeePhazerWriter {
+            writer: self.wrapped_simple_writer()?,
+            other,
+        })
+    }
+}
+
+/// BufferedSimplePhazerWriter is a buffered synchronous file-like thing that's used to build the
+/// working file.
+///
+/// It maintains a reference to the [`Phazer`] used to construct it, ensuring [`Phazer::commit`]
+/// cannot be called if there are any writers.  Buffered bytes are written before the working file
+/// is closed.
+///
+/// This struct is available when the `simple` feature is enabled.
+pub struct BufferedSimplePhazerWriter<'a, 'cs> {
+    writer: SimplePhazerWriter<'a, 'cs>,
+}
+
+impl<'a, 'cs> Read for BufferedSimplePhazerWriter<'a, 'cs> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.writer.read(buf)
+    }
+}
+
+impl<'a, 'cs> Seek for BufferedSimplePhazerWriter<'a, 'cs> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.writer.seek(pos)
+    }
+}
+
+impl<'a, 'cs> Write for BufferedSimplePhazerWriter<'a, 'cs> {
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.writer.write(buf)
+    }
+}
+
+/// TeePhazerWriter is a synchronous file-like thing that writes to the working file and echoes
+/// to a second sink.
+///
+/// It maintains a reference to the [`Phazer`] used to construct it, ensuring [`Phazer::commit`]
+/// cannot be called if there are any writers.
+///
+/// This struct is available when the `simple` feature is enabled.
+pub struct TeePhazerWriter<'a, 'cs, W> {
+    writer: SimplePhazerWriter<'a, 'cs>,
+    other: W,
+}
+
+impl<'a, 'cs, W> TeePhazerWriter<'a, 'cs, W> {
+    /// Drops the working file's writer and returns the second sink.
+    ///
+    pub fn into_other(self) -> W {
+        self.other
+    }
+}
+
+impl<'a, 'cs, W: Write> Write for TeePhazerWriter<'a, 'cs, W> {
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()?;
+        self.other.flush()
+    }
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        // `other` goes first so a failure never leaves bytes in the working file that a retry
+        // would repeat
+        let n = self.other.write(buf)?;
+        self.writer.write_all(&buf[..n])?;
+        Ok(n)
     }
 }
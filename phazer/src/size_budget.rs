@@ -0,0 +1,117 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Share a limit on the total bytes written by many [`Phazer`]s.
+//!
+//! [`Phazer`]: crate::Phazer
+//!
+use std::io::ErrorKind;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// A clonable budget for the total number of bytes written by a group of [`Phazer`]s.
+///
+/// Each [`Phazer`] built with [`size_budget`][sb] charges the bytes written by its writers to the
+/// budget.  Once a write would take the total past the limit, that write fails with a
+/// [`QuotaExceeded`][qe] error and nothing is written.  This is useful for generating many files
+/// under a global cap; for example, a cache directory limited to 1 GiB.
+///
+/// The budget counts bytes written.  Overwriting part of a working file (after a seek) is charged
+/// again.  Bytes are not returned to the budget when a [`Phazer`] is dropped without a commit.
+///
+/// [`Phazer`]: crate::Phazer
+/// [sb]: crate::PhazerBuilderWithTarget::size_budget
+/// [qe]: std::io::ErrorKind::QuotaExceeded
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(feature = "simple")]
+/// # {
+/// use std::io::Write;
+///
+/// use phazer::size_budget::SizeBudget;
+/// use phazer::PhazerBuilder;
+///
+/// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let budget = SizeBudget::new(1024 * 1024 * 1024);
+///     for name in ["first.bin", "second.bin"] {
+///         let phazer = PhazerBuilder::with_target(name)
+///             .size_budget(budget.clone())
+///             .build();
+///         let mut writer = phazer.simple_writer()?;
+///         writer.write_all(b"cached content")?;
+///         drop(writer);
+///         phazer.commit()?;
+///     }
+///     println!("{} bytes used", budget.used());
+///     Ok(())
+/// }
+/// # }
+/// ```
+///
+#[derive(Clone)]
+pub struct SizeBudget {
+    limit: u64,
+    used: Arc<AtomicU64>,
+}
+
+impl SizeBudget {
+    /// Creates a [`SizeBudget`] with nothing used.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - Maximum total number of bytes.
+    ///
+    pub fn new(limit: u64) -> Self {
+        Self {
+            limit,
+            used: Arc::new(AtomicU64::new(0)),
+        }
+    }
+    /// Returns the maximum total number of bytes.
+    ///
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+    /// Returns the number of bytes charged to the budget so far.
+    ///
+    pub fn used(&self) -> u64 {
+        self.used.load(Ordering::Relaxed)
+    }
+    /// Returns the number of bytes that can still be written.
+    ///
+    pub fn remaining(&self) -> u64 {
+        self.limit.saturating_sub(self.used())
+    }
+    // Charge `amount` bytes or fail if that would exceed the limit.
+    #[allow(dead_code)]
+    pub(crate) fn reserve(&self, amount: usize) -> std::io::Result<()> {
+        let amount = amount as u64;
+        self.used
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| {
+                used.checked_add(amount)
+                    .filter(|total| *total <= self.limit)
+            })
+            .map(|_| ())
+            .map_err(|_| {
+                std::io::Error::new(ErrorKind::QuotaExceeded, "the size budget is exhausted")
+            })
+    }
+    // Return `amount` bytes that were reserved but not written.
+    #[allow(dead_code)]
+    pub(crate) fn release(&self, amount: usize) {
+        self.used.fetch_sub(amount as u64, Ordering::Relaxed);
+    }
+}
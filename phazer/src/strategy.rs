@@ -0,0 +1,1408 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// The commit strategies provided by this crate.
+
+use std::fs::OpenOptions;
+use std::io::{ErrorKind, Read};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use crate::file_system::FileSystem;
+use crate::{
+    current_phazer_id, os, remove_working_file, working_path_for, CommitDetails, CommitStrategy,
+};
+
+/// [`SimpleRenameStrategy`] uses the Standard Library [`rename`](std::fs::rename) function to
+/// transition the working file to the target file.
+///
+/// The other commit strategy available is [`RenameWithRetryStrategy`].
+///
+/// For POSIX systems and Windows systems in which there is no contention for the target file,
+/// [`SimpleRenameStrategy`] is a good choice.  For Windows systems in which two or more threads are
+/// simultaneously trying to update the target, [`RenameWithRetryStrategy`] is a good choice.
+///
+/// [`SimpleRenameStrategy`] is also the choice when a file watcher reloads the target.  Reloaders
+/// built on Linux's inotify commonly watch the target's directory and reload when `IN_MOVED_TO`
+/// (or `IN_CREATE`) names the target.  The event a watcher sees depends on how the target is
+/// replaced...
+/// * A rename ([`SimpleRenameStrategy`], [`RenameWithRetryStrategy`], and [`TrashingStrategy`])
+///   produces `IN_MOVED_FROM` for the working file and `IN_MOVED_TO` for the target.  A watch on
+///   the old target itself gets `IN_DELETE_SELF` because the old inode is gone.
+/// * A hard link ([`CreateNewStrategy`]) produces `IN_CREATE` for the target.
+/// * An in-place copy ([`LinkPreservingStrategy`] when the target has more than one link)
+///   produces `IN_MODIFY` and `IN_CLOSE_WRITE` for the target.  A watcher waiting for
+///   `IN_MOVED_TO` never fires.
+///
+/// [`SimpleRenameStrategy`] never falls back to another technique; if the rename fails the error
+/// is returned and the target is untouched.  The working file is in the target's directory (or a
+/// [`working_subdir`][pws] on the same filesystem) so the rename never degrades to a copy.
+///
+/// This crate provides a ready-to-use [`SimpleRenameStrategy`] instance named
+/// [`SIMPLE_RENAME_STRATEGY`].
+///
+/// By default, this commit strategy is used.
+///
+/// [pws]: crate::PhazerBuilder::working_subdir
+///
+/// # Example
+///
+/// ```
+/// use phazer::{PhazerBuilder, SIMPLE_RENAME_STRATEGY};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///
+///     let phazer = PhazerBuilder::with_target("uses-simple-rename-strategy.txt")
+///         .commit_strategy(SIMPLE_RENAME_STRATEGY)
+///         .build();
+///
+///     // Build the working file
+///
+///     // `rename` is called to transition the working file to the target
+///     phazer.commit()?;
+///
+///     Ok(())
+/// }
+/// ```
+///
+#[derive(Default)]
+pub struct SimpleRenameStrategy {}
+
+impl CommitStrategy for SimpleRenameStrategy {
+    fn commit(&self, phazer: &dyn CommitDetails) -> std::io::Result<()> {
+        phazer
+            .get_file_system()
+            .rename(phazer.get_working_path(), phazer.get_target_path())
+    }
+}
+
+/// A ready-to-use instance of [`SimpleRenameStrategy`].
+pub const SIMPLE_RENAME_STRATEGY: &dyn CommitStrategy = &SimpleRenameStrategy {};
+
+/// [`RenameWithRetryStrategy`] uses the Standard Library [`rename`](std::fs::rename) function to
+/// transition the working file to the target file and retries if that fails with a
+/// [`PermissionDenied`][pd] error.
+///
+/// The other commit strategy available is [`SimpleRenameStrategy`].
+///
+/// For POSIX systems and Windows systems in which there is no contention for the target file,
+/// [`SimpleRenameStrategy`] is a good choice.  For Windows systems in which two or more threads are
+/// simultaneously trying to update the target, [`RenameWithRetryStrategy`] is a good choice.
+///
+/// This crate provides a ready-to-use [`RenameWithRetryStrategy`] instance named
+/// [`RENAME_WITH_RETRY_STRATEGY`].
+///
+/// By default, the [`SimpleRenameStrategy`] commit strategy is used.
+///
+/// [pd]: std::io::ErrorKind::PermissionDenied
+///
+/// This retry strategy has been shown to work well with Windows 10 and Windows 11 on local SSD
+/// drives and with a NAS using as many as 10 threads contending for the target file...
+/// * Use a "jitter" between 0 and 15 to avoid threads being synchronized during contention
+/// * Calculate a "base sleep" value: 11 + (3 * jitter)
+/// * Try to commit
+/// * If that succeeds then we're done
+/// * If that fails with any error except [`PermissionDenied`][pd] then return that error
+/// * On Windows, if that fails with `ERROR_ACCESS_DENIED` and the target has the read-only
+///   attribute set or is a directory then return that error; trying again cannot succeed.
+///   `ERROR_SHARING_VIOLATION` (typically an antivirus scanner or indexer briefly holding the file
+///   open) is always retried.
+/// * Otherwise sleep for the base sleep value multiplied by the try count.  For example...
+///     * If the jitter is 1
+///     * Then the base sleep is 11 + (3 * 1) = 14
+///     * For the first try, this strategy would sleep for 14 * 1 milliseconds
+///     * For the second try, this strategy would sleep for 14 * 2 milleconds
+/// * Until 7 attempts have been made at which point the [`PermissionDenied`][pd] error is returned.
+///
+/// In the worst case, this strategy sleeps for a total of (11 + (3 * 15)) * (7 * (7+1) / 2) = 1568
+/// milliseconds.
+///
+/// # Example
+///
+/// ```
+/// use phazer::{PhazerBuilder, RENAME_WITH_RETRY_STRATEGY};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///
+///     let phazer = PhazerBuilder::with_target("uses-rename-with-retry-strategy.txt")
+///         .commit_strategy(RENAME_WITH_RETRY_STRATEGY)
+///         .build();
+///
+///     // Build the working file
+///
+///     // `rename` is called to transition the working file to the target
+///     phazer.commit()?;
+///
+///     Ok(())
+/// }
+/// ```
+///
+#[derive(Default)]
+pub struct RenameWithRetryStrategy {}
+
+impl CommitStrategy for RenameWithRetryStrategy {
+    fn commit(&self, phazer: &dyn CommitDetails) -> std::io::Result<()> {
+        // With 10 threads and the sleep code as it is (start with 10ms), seven has been a good
+        // threshold.
+        ConfigurableRetryStrategy::new().commit(phazer)
+    }
+}
+
+/// [`Backoff`] is the shape of the sleep between the tries made by a
+/// [`ConfigurableRetryStrategy`].
+///
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Backoff {
+    /// Sleep for the base sleep multiplied by the try count; 1x, 2x, 3x, and so on.  This is
+    /// what [`RenameWithRetryStrategy`] does.
+    #[default]
+    Linear,
+    /// Sleep for the base sleep doubled for each try; 1x, 2x, 4x, 8x, and so on.
+    Exponential,
+}
+
+impl Backoff {
+    // Return the sleep, in milliseconds, after the failed try number `tries` (starting at one).
+    fn sleep_millis(self, base_sleep: u64, tries: u32) -> u64 {
+        match self {
+            Backoff::Linear => base_sleep.saturating_mul(tries as u64),
+            Backoff::Exponential => {
+                base_sleep.saturating_mul(1u64.checked_shl(tries - 1).unwrap_or(u64::MAX))
+            }
+        }
+    }
+}
+
+/// [`ConfigurableRetryStrategy`] is [`RenameWithRetryStrategy`] with adjustable limits.
+///
+/// The rename is retried if it fails with a [`PermissionDenied`][pd] error that might be
+/// transient.  The base sleep is `base_millis + (3 * jitter)` where the jitter is a
+/// per-[`Phazer`](crate::Phazer) value masked with `jitter_mask`.  After each failed try the
+/// strategy sleeps as determined by the [`Backoff`].  When `max_tries` tries have been made the
+/// last error is returned.
+///
+/// [`ConfigurableRetryStrategy::new`] starts with the values used by [`RenameWithRetryStrategy`]:
+/// seven tries, a base of 11 milliseconds, a jitter mask of `0xF`, and [`Backoff::Linear`].  A busy
+/// NAS may need more tries and a longer sleep; a fast local SSD may want fewer.
+///
+/// [pd]: std::io::ErrorKind::PermissionDenied
+///
+/// # Example
+///
+/// ```
+/// use phazer::{Backoff, ConfigurableRetryStrategy, PhazerBuilder};
+///
+/// // Tuned for a very busy NAS
+/// static NAS_RETRY_STRATEGY: ConfigurableRetryStrategy = ConfigurableRetryStrategy::new()
+///     .max_tries(12)
+///     .base_millis(25)
+///     .backoff(Backoff::Exponential);
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///
+///     let phazer = PhazerBuilder::with_target("uses-configurable-retry-strategy.txt")
+///         .commit_strategy(&NAS_RETRY_STRATEGY)
+///         .build();
+///
+///     // Build the working file
+///
+///     phazer.commit()?;
+///
+///     Ok(())
+/// }
+/// ```
+///
+#[derive(Clone, Copy, Debug)]
+pub struct ConfigurableRetryStrategy {
+    max_tries: u32,
+    base_millis: u64,
+    jitter_mask: u64,
+    backoff: Backoff,
+}
+
+impl ConfigurableRetryStrategy {
+    /// Creates a [`ConfigurableRetryStrategy`] that behaves like [`RenameWithRetryStrategy`].
+    ///
+    pub const fn new() -> Self {
+        Self {
+            max_tries: 7,
+            base_millis: 11,
+            jitter_mask: 0xF,
+            backoff: Backoff::Linear,
+        }
+    }
+    /// Sets the number of tries made before the error is returned.  Zero is treated as one.
+    ///
+    pub const fn max_tries(mut self, value: u32) -> Self {
+        self.max_tries = value;
+        self
+    }
+    /// Sets the base sleep, in milliseconds, before the jitter is added.
+    ///
+    pub const fn base_millis(mut self, value: u64) -> Self {
+        self.base_millis = value;
+        self
+    }
+    /// Sets the mask applied to the jitter.  Zero disables the jitter.
+    ///
+    pub const fn jitter_mask(mut self, value: u64) -> Self {
+        self.jitter_mask = value;
+        self
+    }
+    /// Sets the shape of the sleep between tries.
+    ///
+    pub const fn backoff(mut self, value: Backoff) -> Self {
+        self.backoff = value;
+        self
+    }
+}
+
+impl Default for ConfigurableRetryStrategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CommitStrategy for ConfigurableRetryStrategy {
+    fn commit(&self, phazer: &dyn CommitDetails) -> std::io::Result<()> {
+        let mut tries = 0;
+        let jitter = (phazer.get_jitter() as u64) & self.jitter_mask;
+        let base_sleep = self.base_millis.saturating_add(jitter.saturating_mul(3));
+        loop {
+            tries += 1;
+            phazer.set_attempts(tries);
+            let e = match phazer
+                .get_file_system()
+                .rename(phazer.get_working_path(), phazer.get_target_path())
+            {
+                Ok(()) => return Ok(()),
+                Err(e) => e,
+            };
+            if !worth_retrying(&e, phazer) || tries >= self.max_tries {
+                return Err(e);
+            }
+            let sleep = self.backoff.sleep_millis(base_sleep, tries);
+            #[cfg(feature = "tracing")]
+            tracing::info!(try_number = tries, sleep_millis = sleep, error = %e, "rename failed; trying again");
+            std::thread::sleep(std::time::Duration::from_millis(sleep));
+        }
+    }
+}
+
+// Windows maps both of these to PermissionDenied.
+#[cfg(windows)]
+const ERROR_ACCESS_DENIED: i32 = 5;
+#[cfg(windows)]
+const ERROR_SHARING_VIOLATION: i32 = 32;
+
+// Return true if the rename failure might be transient.
+#[cfg(windows)]
+fn worth_retrying(e: &std::io::Error, phazer: &dyn CommitDetails) -> bool {
+    if e.kind() != ErrorKind::PermissionDenied {
+        return false;
+    }
+    match e.raw_os_error() {
+        Some(ERROR_SHARING_VIOLATION) => true,
+        // Contention for the target also produces access denied.  A read-only target or a
+        // directory in the way does not.
+        Some(ERROR_ACCESS_DENIED) => {
+            match phazer.get_file_system().metadata(phazer.get_target_path()) {
+                Ok(m) => !m.permissions().readonly() && !m.is_dir(),
+                Err(_) => true,
+            }
+        }
+        _ => true,
+    }
+}
+
+// Return true if the rename failure might be transient.
+#[cfg(not(windows))]
+fn worth_retrying(e: &std::io::Error, _phazer: &dyn CommitDetails) -> bool {
+    e.kind() == ErrorKind::PermissionDenied
+}
+
+/// A ready-to-use instance of [`RenameWithRetryStrategy`].
+pub const RENAME_WITH_RETRY_STRATEGY: &dyn CommitStrategy = &RenameWithRetryStrategy {};
+
+/// [`MoveFileExStrategy`] calls the Windows `MoveFileExW` function directly to transition the
+/// working file to the target file.
+///
+/// The Standard Library [`rename`](std::fs::rename) also uses `MoveFileExW` but with a fixed set of
+/// flags.  [`MoveFileExStrategy`] always includes `MOVEFILE_REPLACE_EXISTING` and optionally
+/// includes `MOVEFILE_WRITE_THROUGH`.  With write-through, the commit does not return until the
+/// move has been flushed to disk.  That provides durability without a separate flush of the
+/// working file.
+///
+/// This crate provides two ready-to-use instances: [`MOVE_FILE_EX_STRATEGY`] and
+/// [`MOVE_FILE_EX_WRITE_THROUGH_STRATEGY`].
+///
+/// This strategy calls the operating system directly; it does not use the [`FileSystem`] assigned
+/// to the [`Phazer`](crate::Phazer).
+///
+/// This strategy is only available for Windows.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(windows)]
+/// # {
+/// use phazer::{PhazerBuilder, MOVE_FILE_EX_WRITE_THROUGH_STRATEGY};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///
+///     let phazer = PhazerBuilder::with_target("uses-move-file-ex-strategy.txt")
+///         .commit_strategy(MOVE_FILE_EX_WRITE_THROUGH_STRATEGY)
+///         .build();
+///
+///     // Build the working file
+///
+///     // `MoveFileExW` is called to transition the working file to the target
+///     phazer.commit()?;
+///
+///     Ok(())
+/// }
+/// # }
+/// ```
+///
+#[cfg(windows)]
+#[derive(Default)]
+pub struct MoveFileExStrategy {
+    write_through: bool,
+}
+
+#[cfg(windows)]
+impl MoveFileExStrategy {
+    /// Creates a [`MoveFileExStrategy`].
+    ///
+    /// # Arguments
+    ///
+    /// * `write_through` - Include `MOVEFILE_WRITE_THROUGH` so the commit does not return until
+    ///   the move has been flushed to disk.
+    ///
+    pub const fn new(write_through: bool) -> Self {
+        Self { write_through }
+    }
+}
+
+#[cfg(windows)]
+impl CommitStrategy for MoveFileExStrategy {
+    fn commit(&self, phazer: &dyn CommitDetails) -> std::io::Result<()> {
+        os::move_file_ex(
+            phazer.get_working_path(),
+            phazer.get_target_path(),
+            self.write_through,
+        )
+    }
+}
+
+/// A ready-to-use instance of [`MoveFileExStrategy`] without write-through.
+#[cfg(windows)]
+pub const MOVE_FILE_EX_STRATEGY: &dyn CommitStrategy = &MoveFileExStrategy::new(false);
+
+/// A ready-to-use instance of [`MoveFileExStrategy`] with write-through.
+#[cfg(windows)]
+pub const MOVE_FILE_EX_WRITE_THROUGH_STRATEGY: &dyn CommitStrategy = &MoveFileExStrategy::new(true);
+
+/// [`WindowsBestEffortStrategy`] picks the most reader-tolerant Windows primitive for each commit.
+///
+/// On Windows, a reader holding the target open can block a rename.  This strategy chooses...
+/// * If the target does not exist, a hard link to the working file is created at the target
+///   (`CreateHardLinkW`) then the working file is removed.
+/// * If the target exists, `ReplaceFileW` replaces it.
+/// * If either of those fails, `MoveFileExW` with `MOVEFILE_REPLACE_EXISTING` is the last resort.
+///
+/// This crate provides a ready-to-use [`WindowsBestEffortStrategy`] instance named
+/// [`WINDOWS_BEST_EFFORT_STRATEGY`].
+///
+/// This strategy calls the operating system directly; it does not use the [`FileSystem`] assigned
+/// to the [`Phazer`](crate::Phazer) (except to check if the target exists).
+///
+/// This strategy is only available for Windows.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(windows)]
+/// # {
+/// use phazer::{PhazerBuilder, WINDOWS_BEST_EFFORT_STRATEGY};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///
+///     let phazer = PhazerBuilder::with_target("uses-windows-best-effort-strategy.txt")
+///         .commit_strategy(WINDOWS_BEST_EFFORT_STRATEGY)
+///         .build();
+///
+///     // Build the working file
+///
+///     phazer.commit()?;
+///
+///     Ok(())
+/// }
+/// # }
+/// ```
+///
+#[cfg(windows)]
+#[derive(Default)]
+pub struct WindowsBestEffortStrategy {}
+
+#[cfg(windows)]
+impl CommitStrategy for WindowsBestEffortStrategy {
+    fn commit(&self, phazer: &dyn CommitDetails) -> std::io::Result<()> {
+        let file_system = phazer.get_file_system();
+        let working_path = phazer.get_working_path();
+        let target_path = phazer.get_target_path();
+        match file_system.metadata(target_path) {
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                if file_system.hard_link(working_path, target_path).is_ok() {
+                    // The target has the content.  A leftover working file is only clutter.
+                    let _ = remove_working_file(file_system, working_path);
+                    return Ok(());
+                }
+            }
+            _ => {
+                if os::replace_file(target_path, working_path).is_ok() {
+                    return Ok(());
+                }
+            }
+        }
+        os::move_file_ex(working_path, target_path, false)
+    }
+}
+
+/// A ready-to-use instance of [`WindowsBestEffortStrategy`].
+#[cfg(windows)]
+pub const WINDOWS_BEST_EFFORT_STRATEGY: &dyn CommitStrategy = &WindowsBestEffortStrategy {};
+
+/// [`ReplaceFileStrategy`] replaces the target with `ReplaceFileW` so the target keeps its
+/// identity.
+///
+/// A rename discards the target and puts the working file in its place.  Anything attached to the
+/// target (attributes, the ACL, alternate data streams, the creation time) is lost.
+/// `ReplaceFileW` moves the working file's content into the target and merges the target's
+/// attributes and security onto the result.  If the target does not exist there is nothing to
+/// preserve so the working file is [renamed](std::fs::rename) to the target.
+///
+/// This crate provides a ready-to-use [`ReplaceFileStrategy`] instance named
+/// [`REPLACE_FILE_STRATEGY`].
+///
+/// Replacing the target calls the operating system directly; it does not use the [`FileSystem`]
+/// assigned to the [`Phazer`](crate::Phazer).
+///
+/// This strategy is only available for Windows.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(windows)]
+/// # {
+/// use phazer::{PhazerBuilder, REPLACE_FILE_STRATEGY};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///
+///     let phazer = PhazerBuilder::with_target("uses-replace-file-strategy.txt")
+///         .commit_strategy(REPLACE_FILE_STRATEGY)
+///         .build();
+///
+///     // Build the working file
+///
+///     phazer.commit()?;
+///
+///     Ok(())
+/// }
+/// # }
+/// ```
+///
+#[cfg(windows)]
+#[derive(Default)]
+pub struct ReplaceFileStrategy {}
+
+#[cfg(windows)]
+impl CommitStrategy for ReplaceFileStrategy {
+    fn commit(&self, phazer: &dyn CommitDetails) -> std::io::Result<()> {
+        let file_system = phazer.get_file_system();
+        let working_path = phazer.get_working_path();
+        let target_path = phazer.get_target_path();
+        match file_system.metadata(target_path) {
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                file_system.rename(working_path, target_path)
+            }
+            _ => match os::replace_file(target_path, working_path) {
+                // The target vanished after the check
+                Err(e) if e.kind() == ErrorKind::NotFound => {
+                    file_system.rename(working_path, target_path)
+                }
+                rv => rv,
+            },
+        }
+    }
+}
+
+/// A ready-to-use instance of [`ReplaceFileStrategy`].
+#[cfg(windows)]
+pub const REPLACE_FILE_STRATEGY: &dyn CommitStrategy = &ReplaceFileStrategy {};
+
+/// [`RemoveThenRenameStrategy`] [renames](std::fs::rename) the working file to the target and, if
+/// that fails, removes the target then renames the working file into the vacant name.
+///
+/// On Windows, a target held open by another process sometimes cannot be replaced but can be
+/// removed (for example, when the other process allowed delete sharing).  This strategy is a
+/// pragmatic last resort for those deployments.
+///
+/// **This strategy is not atomic.**  Between the removal and the rename the target does not
+/// exist.  A reader may find the target missing and a crash in that window leaves no target at
+/// all (the working file is removed when the [`Phazer`](crate::Phazer) is dropped).  Only use it
+/// when a brief absence of the target is acceptable.
+///
+/// This crate provides a ready-to-use [`RemoveThenRenameStrategy`] instance named
+/// [`REMOVE_THEN_RENAME_STRATEGY`].
+///
+/// This strategy is only available for Windows.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(windows)]
+/// # {
+/// use phazer::{PhazerBuilder, REMOVE_THEN_RENAME_STRATEGY};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///
+///     let phazer = PhazerBuilder::with_target("uses-remove-then-rename-strategy.txt")
+///         .commit_strategy(REMOVE_THEN_RENAME_STRATEGY)
+///         .build();
+///
+///     // Build the working file
+///
+///     // If the rename fails, the target is removed then the rename is tried again
+///     phazer.commit()?;
+///
+///     Ok(())
+/// }
+/// # }
+/// ```
+///
+#[cfg(windows)]
+#[derive(Default)]
+pub struct RemoveThenRenameStrategy {}
+
+#[cfg(windows)]
+impl CommitStrategy for RemoveThenRenameStrategy {
+    fn commit(&self, phazer: &dyn CommitDetails) -> std::io::Result<()> {
+        let file_system = phazer.get_file_system();
+        let working_path = phazer.get_working_path();
+        let target_path = phazer.get_target_path();
+        let e = match file_system.rename(working_path, target_path) {
+            Ok(()) => return Ok(()),
+            Err(e) => e,
+        };
+        if e.kind() != ErrorKind::PermissionDenied {
+            return Err(e);
+        }
+        // The original error is more useful than a failure to remove the target
+        if file_system.remove_file(target_path).is_err() {
+            return Err(e);
+        }
+        file_system.rename(working_path, target_path)
+    }
+}
+
+/// A ready-to-use instance of [`RemoveThenRenameStrategy`].
+#[cfg(windows)]
+pub const REMOVE_THEN_RENAME_STRATEGY: &dyn CommitStrategy = &RemoveThenRenameStrategy {};
+
+/// [`LinkPreservingStrategy`] keeps every hard link to the target pointing at the new content.
+///
+/// A rename replaces the directory entry for the target.  If the target has other hard links
+/// (other names for the same file) those names continue to refer to the old content.
+/// [`LinkPreservingStrategy`] checks the number of links to an existing target...
+/// * If the target has one link (or does not exist), the working file is
+///   [renamed](std::fs::rename) to the target.  The update is atomic.
+/// * If the target has more than one link, the target is truncated, the working file is copied
+///   into the target, and the working file is removed.  Every name sees the update but **the
+///   update is not atomic**.  A reader may see a partially written target and a crash may leave
+///   the target partially written.
+///
+/// The detection is automatic so the atomic rename is used whenever it does not break a link.
+///
+/// This crate provides a ready-to-use [`LinkPreservingStrategy`] instance named
+/// [`LINK_PRESERVING_STRATEGY`].
+///
+/// # Example
+///
+/// ```
+/// use phazer::{PhazerBuilder, LINK_PRESERVING_STRATEGY};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///
+///     let phazer = PhazerBuilder::with_target("uses-link-preserving-strategy.txt")
+///         .commit_strategy(LINK_PRESERVING_STRATEGY)
+///         .build();
+///
+///     // Build the working file
+///
+///     // The target is replaced in-place if it has more than one link
+///     phazer.commit()?;
+///
+///     Ok(())
+/// }
+/// ```
+///
+#[derive(Default)]
+pub struct LinkPreservingStrategy {}
+
+impl CommitStrategy for LinkPreservingStrategy {
+    fn commit(&self, phazer: &dyn CommitDetails) -> std::io::Result<()> {
+        let file_system = phazer.get_file_system();
+        let working_path = phazer.get_working_path();
+        let target_path = phazer.get_target_path();
+        let links = match os::hard_link_count(file_system, target_path) {
+            Ok(links) => links,
+            Err(e) if e.kind() == ErrorKind::NotFound => 1,
+            Err(e) => return Err(e),
+        };
+        if links <= 1 {
+            return file_system.rename(working_path, target_path);
+        }
+        // Overwrite the target in-place so every link sees the new content
+        overwrite_in_place(file_system, working_path, target_path)
+    }
+    fn writes_in_place(&self) -> bool {
+        true
+    }
+}
+
+/// A ready-to-use instance of [`LinkPreservingStrategy`].
+pub const LINK_PRESERVING_STRATEGY: &dyn CommitStrategy = &LinkPreservingStrategy {};
+
+/// [`ReplaceContentsStrategy`] writes the new content into the existing target so the target
+/// keeps its identity.
+///
+/// An existing target is opened, truncated, the working file is copied into it, the target is
+/// flushed ([`sync_all`](std::fs::File::sync_all)), then the working file is removed.  If the
+/// target does not exist the working file is [renamed](std::fs::rename) to the target.
+///
+/// Because the target is the same file (the same inode for POSIX) every hard link, open handle,
+/// and anything else tied to the file's identity continues to refer to the target.  This is what
+/// a content-addressed store with hard linked entries needs.  Unlike [`LinkPreservingStrategy`],
+/// which only writes in-place when the target has more than one link, this strategy always does.
+///
+/// The cost is atomicity.  **The update is not atomic**.  Between the truncate and the end of the
+/// copy a reader sees an empty or partially written target and a crash in that window leaves the
+/// target partially written.  Prefer a rename based strategy unless the target's identity matters
+/// more than an atomic update.
+///
+/// This crate provides a ready-to-use [`ReplaceContentsStrategy`] instance named
+/// [`REPLACE_CONTENTS_STRATEGY`].
+///
+/// # Example
+///
+/// ```
+/// use phazer::{PhazerBuilder, REPLACE_CONTENTS_STRATEGY};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///
+///     let phazer = PhazerBuilder::with_target("uses-replace-contents-strategy.txt")
+///         .commit_strategy(REPLACE_CONTENTS_STRATEGY)
+///         .build();
+///
+///     // Build the working file
+///
+///     // An existing target is overwritten in-place
+///     phazer.commit()?;
+///
+///     Ok(())
+/// }
+/// ```
+///
+#[derive(Default)]
+pub struct ReplaceContentsStrategy {}
+
+impl CommitStrategy for ReplaceContentsStrategy {
+    fn commit(&self, phazer: &dyn CommitDetails) -> std::io::Result<()> {
+        let file_system = phazer.get_file_system();
+        let working_path = phazer.get_working_path();
+        let target_path = phazer.get_target_path();
+        match file_system.metadata(target_path) {
+            Ok(_) => overwrite_in_place(file_system, working_path, target_path),
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                file_system.rename(working_path, target_path)
+            }
+            Err(e) => Err(e),
+        }
+    }
+    fn writes_in_place(&self) -> bool {
+        true
+    }
+}
+
+/// A ready-to-use instance of [`ReplaceContentsStrategy`].
+pub const REPLACE_CONTENTS_STRATEGY: &dyn CommitStrategy = &ReplaceContentsStrategy {};
+
+// Copy `from` to a new file at `to` with the same permissions.
+pub(crate) fn copy_file(
+    file_system: &dyn FileSystem,
+    from: &Path,
+    to: &Path,
+) -> std::io::Result<()> {
+    let mut source = file_system.open(from, OpenOptions::new().read(true))?;
+    let mut copy = file_system.open(to, OpenOptions::new().write(true).create_new(true))?;
+    std::io::copy(&mut source, &mut copy)?;
+    copy.set_permissions(source.metadata()?.permissions())
+}
+
+// Copy the working file into the existing target, flush the target, then remove the working file.
+// Not atomic.
+fn overwrite_in_place(
+    file_system: &dyn FileSystem,
+    working_path: &Path,
+    target_path: &Path,
+) -> std::io::Result<()> {
+    let mut working = file_system.open(working_path, OpenOptions::new().read(true))?;
+    let mut target =
+        file_system.open(target_path, OpenOptions::new().write(true).truncate(true))?;
+    std::io::copy(&mut working, &mut target)?;
+    target.sync_all()?;
+    drop(working);
+    file_system.remove_file(working_path)
+}
+
+/// [`CreateNewStrategy`] commits only if the target does not exist; an existing target is never
+/// replaced.
+///
+/// This makes "one wins" deterministic when several writers race to create a file for the first
+/// time.  The working file is [hard linked](std::fs::hard_link) to the target which, like opening
+/// with `create_new` (`O_EXCL`), fails atomically if the target already exists.  The first
+/// committer wins; every other committer gets an [`AlreadyExists`][ae] error and its working file
+/// is removed when its [`Phazer`](crate::Phazer) is dropped.  After a successful link the working
+/// file is removed.
+///
+/// This strategy is meant for first-time creation.  Do not use it to update an existing target.
+/// The filesystem holding the target must support hard links.
+///
+/// This crate provides a ready-to-use [`CreateNewStrategy`] instance named
+/// [`CREATE_NEW_STRATEGY`].
+///
+/// [ae]: std::io::ErrorKind::AlreadyExists
+///
+/// # Example
+///
+/// ```
+/// use std::io::ErrorKind;
+///
+/// use phazer::{PhazerBuilder, CREATE_NEW_STRATEGY};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///
+///     let phazer = PhazerBuilder::with_target("created-once.txt")
+///         .commit_strategy(CREATE_NEW_STRATEGY)
+///         .build();
+///
+///     // Build the working file
+///
+///     match phazer.commit() {
+///         Ok(()) => println!("this process created created-once.txt"),
+///         Err(e) if e.kind() == ErrorKind::AlreadyExists => println!("someone else won"),
+///         Err(e) => return Err(e.into()),
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+///
+#[derive(Default)]
+pub struct CreateNewStrategy {}
+
+impl CommitStrategy for CreateNewStrategy {
+    fn commit(&self, phazer: &dyn CommitDetails) -> std::io::Result<()> {
+        let file_system = phazer.get_file_system();
+        let working_path = phazer.get_working_path();
+        file_system.hard_link(working_path, phazer.get_target_path())?;
+        // The target has the content.  A leftover working file is only clutter.
+        let _ = remove_working_file(file_system, working_path);
+        Ok(())
+    }
+}
+
+/// A ready-to-use instance of [`CreateNewStrategy`].
+pub const CREATE_NEW_STRATEGY: &dyn CommitStrategy = &CreateNewStrategy {};
+
+/// [`DurableRenameStrategy`] flushes the working file to disk, [renames](std::fs::rename) it to the
+/// target, then flushes the target's directory so the commit survives a crash or power loss.
+///
+/// A plain rename only changes the directory in memory.  If the system crashes shortly after
+/// [`Phazer::commit`](crate::Phazer::commit) returns, POSIX does not guarantee that the new content
+/// or the rename reached the disk; the target may be empty or may be the old file.
+/// [`DurableRenameStrategy`]...
+/// * Opens the working file and calls [`sync_all`](std::fs::File::sync_all)
+/// * Renames the working file to the target
+/// * For POSIX systems, opens the target's directory and calls
+///   [`sync_all`](std::fs::File::sync_all) (`fsync`) on it.  Windows cannot open a directory for
+///   flushing so this step is skipped.
+///
+/// The flushes are slow compared to the rename.  Use this strategy for files, like
+/// configuration, that must survive a power loss immediately after the commit.  The
+/// [`sync_mode`][psm] builder option provides the same guarantees for any strategy.
+///
+/// This crate provides a ready-to-use [`DurableRenameStrategy`] instance named
+/// [`DURABLE_RENAME_STRATEGY`].
+///
+/// [psm]: crate::PhazerBuilder::sync_mode
+///
+/// # Example
+///
+/// ```
+/// use phazer::{PhazerBuilder, DURABLE_RENAME_STRATEGY};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///
+///     let phazer = PhazerBuilder::with_target("uses-durable-rename-strategy.txt")
+///         .commit_strategy(DURABLE_RENAME_STRATEGY)
+///         .build();
+///
+///     // Build the working file
+///
+///     // The working file and the rename are on disk when `commit` returns
+///     phazer.commit()?;
+///
+///     Ok(())
+/// }
+/// ```
+///
+#[derive(Default)]
+pub struct DurableRenameStrategy {}
+
+impl CommitStrategy for DurableRenameStrategy {
+    fn commit(&self, phazer: &dyn CommitDetails) -> std::io::Result<()> {
+        let file_system = phazer.get_file_system();
+        let working_path = phazer.get_working_path();
+        let target_path = phazer.get_target_path();
+        // Windows requires write access to flush
+        file_system
+            .open(working_path, OpenOptions::new().write(true))?
+            .sync_all()?;
+        file_system.rename(working_path, target_path)?;
+        #[cfg(unix)]
+        os::sync_rename(file_system, target_path)?;
+        Ok(())
+    }
+}
+
+/// A ready-to-use instance of [`DurableRenameStrategy`].
+pub const DURABLE_RENAME_STRATEGY: &dyn CommitStrategy = &DurableRenameStrategy {};
+
+/// [`CrossDeviceSafeStrategy`] [renames](std::fs::rename) the working file to the target or, if
+/// the two are on different filesystems, copies the working file next to the target then renames
+/// the copy into place.
+///
+/// A rename cannot cross filesystems; it fails with [`CrossesDevices`][cd] (`EXDEV` for POSIX,
+/// `ERROR_NOT_SAME_DEVICE` for Windows).  That happens when the working file is on a different
+/// mount than the target; for example, a [`working_dir`][pwd] on a fast local disk.  When the
+/// rename fails that way, [`CrossDeviceSafeStrategy`]...
+/// * Copies the working file to a sibling of the target (a working file name in the target's
+///   directory)
+/// * Flushes the copy to disk with [`sync_all`](std::fs::File::sync_all)
+/// * Renames the copy to the target
+/// * Removes the working file
+///
+/// The target is still replaced atomically; a reader sees the old content or the new content.
+/// If any step fails the copy is removed and the error is returned.  The copy costs time and
+/// space proportional to the size of the working file.
+///
+/// This crate provides a ready-to-use [`CrossDeviceSafeStrategy`] instance named
+/// [`CROSS_DEVICE_SAFE_STRATEGY`].
+///
+/// [cd]: std::io::ErrorKind::CrossesDevices
+/// [pwd]: crate::PhazerBuilder::working_dir
+///
+/// # Example
+///
+/// ```
+/// use phazer::{PhazerBuilder, CROSS_DEVICE_SAFE_STRATEGY};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///
+///     let phazer = PhazerBuilder::with_target("uses-cross-device-safe-strategy.txt")
+///         .commit_strategy(CROSS_DEVICE_SAFE_STRATEGY)
+///         .build();
+///
+///     // Build the working file
+///
+///     // Renamed or, if the rename would cross filesystems, copied then renamed
+///     phazer.commit()?;
+///
+///     Ok(())
+/// }
+/// ```
+///
+#[derive(Default)]
+pub struct CrossDeviceSafeStrategy {}
+
+impl CommitStrategy for CrossDeviceSafeStrategy {
+    fn commit(&self, phazer: &dyn CommitDetails) -> std::io::Result<()> {
+        let file_system = phazer.get_file_system();
+        let working_path = phazer.get_working_path();
+        let target_path = phazer.get_target_path();
+        match file_system.rename(working_path, target_path) {
+            Err(e) if e.kind() == ErrorKind::CrossesDevices => {}
+            rv => return rv,
+        }
+        let unique = format!("{}-{}-copy", std::process::id(), current_phazer_id());
+        let copy_path = working_path_for(target_path, &unique);
+        if let Err(e) = copy_then_rename(file_system, working_path, &copy_path, target_path) {
+            let _ = file_system.remove_file(&copy_path);
+            return Err(e);
+        }
+        // The target has the content.  A leftover working file is only clutter.
+        let _ = remove_working_file(file_system, working_path);
+        Ok(())
+    }
+}
+
+// Copy `working_path` to `copy_path`, flush the copy, then rename the copy to `target_path`.
+pub(crate) fn copy_then_rename(
+    file_system: &dyn FileSystem,
+    working_path: &Path,
+    copy_path: &Path,
+    target_path: &Path,
+) -> std::io::Result<()> {
+    let mut working = file_system.open(working_path, OpenOptions::new().read(true))?;
+    let mut copy = file_system.open(copy_path, OpenOptions::new().write(true).create_new(true))?;
+    std::io::copy(&mut working, &mut copy)?;
+    copy.set_permissions(working.metadata()?.permissions())?;
+    copy.sync_all()?;
+    drop(copy);
+    file_system.rename(copy_path, target_path)
+}
+
+/// A ready-to-use instance of [`CrossDeviceSafeStrategy`].
+pub const CROSS_DEVICE_SAFE_STRATEGY: &dyn CommitStrategy = &CrossDeviceSafeStrategy {};
+
+/// [`DryRunStrategy`] records that a commit was requested without touching the target.
+///
+/// This is meant for tests that exercise the full write path of code using
+/// [`Phazer`](crate::Phazer) but must not replace a real target.  Each commit increments a count
+/// and captures the content of the working file so a test can assert "we would have committed X".
+/// The working file is left in place and removed when the [`Phazer`](crate::Phazer) is dropped; the
+/// target is never created, replaced, or opened.
+///
+/// The [`Phazer`](crate::Phazer) considers itself committed.  Options that inspect the target after
+/// the commit see the untouched target; [`verify_boundaries`][pvb] fails and the
+/// [`after_commit`][pac] hook is called with a target that was not replaced.
+///
+/// A [`DryRunStrategy`] has state so there is no ready-to-use instance; create one for each test.
+///
+/// [pvb]: crate::PhazerBuilder::verify_boundaries
+/// [pac]: crate::PhazerBuilder::after_commit
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(feature = "simple")]
+/// # {
+/// use std::io::Write;
+///
+/// use phazer::{DryRunStrategy, PhazerBuilder};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let dry_run = DryRunStrategy::new();
+///
+///     let phazer = PhazerBuilder::with_target("/etc/important.conf")
+///         .commit_strategy(&dry_run)
+///         .build();
+///     let mut writer = phazer.simple_writer()?;
+///     writer.write_all(b"setting = 1\n")?;
+///     drop(writer);
+///     phazer.commit()?;
+///
+///     assert!(dry_run.was_committed());
+///     assert_eq!(dry_run.last_content().as_deref(), Some(&b"setting = 1\n"[..]));
+///     Ok(())
+/// }
+/// # }
+/// ```
+///
+#[derive(Default)]
+pub struct DryRunStrategy {
+    commits: AtomicUsize,
+    last_content: Mutex<Option<Vec<u8>>>,
+}
+
+impl DryRunStrategy {
+    /// Creates a [`DryRunStrategy`] that has not recorded any commits.
+    ///
+    pub const fn new() -> Self {
+        Self {
+            commits: AtomicUsize::new(0),
+            last_content: Mutex::new(None),
+        }
+    }
+    /// Returns `true` if at least one commit was requested.
+    ///
+    pub fn was_committed(&self) -> bool {
+        self.commit_count() > 0
+    }
+    /// Returns the number of commits requested.
+    ///
+    pub fn commit_count(&self) -> usize {
+        self.commits.load(Ordering::Relaxed)
+    }
+    /// Returns the content of the working file from the most recent commit or `None` if no commit
+    /// was requested.
+    ///
+    pub fn last_content(&self) -> Option<Vec<u8>> {
+        self.last_content
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
+impl CommitStrategy for DryRunStrategy {
+    fn commit(&self, phazer: &dyn CommitDetails) -> std::io::Result<()> {
+        let file_system = phazer.get_file_system();
+        let working_path = phazer.get_working_path();
+        let mut content = Vec::new();
+        file_system
+            .open(working_path, OpenOptions::new().read(true))?
+            .read_to_end(&mut content)?;
+        *self.last_content.lock().unwrap_or_else(|e| e.into_inner()) = Some(content);
+        self.commits.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+    fn keeps_working_file(&self) -> bool {
+        true
+    }
+}
+
+/// [`TrashingStrategy`] moves the existing target to the operating system's trash (recycle bin)
+/// then [renames](std::fs::rename) the working file to the target.
+///
+/// Desktop applications replacing a user's file can use [`TrashingStrategy`] so the user is able
+/// to recover the previous version.  If the target does not exist, [`TrashingStrategy`] behaves
+/// like [`SimpleRenameStrategy`].
+///
+/// The target is missing between the move to the trash and the rename.  If the rename fails the
+/// previous version is only available from the trash.
+///
+/// This crate provides a ready-to-use [`TrashingStrategy`] instance named [`TRASHING_STRATEGY`].
+///
+/// This strategy is available when the `trash` feature is enabled.  The [trash crate][tc] is used
+/// to move the target to the trash.
+///
+/// [tc]: https://crates.io/crates/trash
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(feature = "trash")]
+/// # {
+/// use phazer::{PhazerBuilder, TRASHING_STRATEGY};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///
+///     let phazer = PhazerBuilder::with_target("uses-trashing-strategy.txt")
+///         .commit_strategy(TRASHING_STRATEGY)
+///         .build();
+///
+///     // Build the working file
+///
+///     // The old target goes to the trash then `rename` is called
+///     phazer.commit()?;
+///
+///     Ok(())
+/// }
+/// # }
+/// ```
+///
+#[cfg(feature = "trash")]
+#[derive(Default)]
+pub struct TrashingStrategy {}
+
+#[cfg(feature = "trash")]
+impl CommitStrategy for TrashingStrategy {
+    fn commit(&self, phazer: &dyn CommitDetails) -> std::io::Result<()> {
+        let file_system = phazer.get_file_system();
+        let target_path = phazer.get_target_path();
+        match file_system.metadata(target_path) {
+            Ok(_) => trash::delete(target_path).map_err(std::io::Error::other)?,
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+        file_system.rename(phazer.get_working_path(), target_path)
+    }
+}
+
+/// A ready-to-use instance of [`TrashingStrategy`].
+#[cfg(feature = "trash")]
+pub const TRASHING_STRATEGY: &dyn CommitStrategy = &TrashingStrategy {};
+
+/// [`FallbackStrategy`] tries one strategy then, if that fails, tries a second strategy.
+///
+/// Composing strategies is a way to build resilient commit behavior from the provided building
+/// blocks.  If the `primary` strategy succeeds, the `secondary` strategy is not used.  If the
+/// `primary` strategy fails, the `secondary` strategy is tried.  If both fail, the error from the
+/// `primary` strategy is returned.
+///
+/// The `primary` strategy should leave the working file in place when it fails otherwise there is
+/// nothing for the `secondary` strategy to commit.
+///
+/// # Example
+///
+/// ```
+/// use phazer::{
+///     CommitStrategy, FallbackStrategy, PhazerBuilder, LINK_PRESERVING_STRATEGY,
+///     RENAME_WITH_RETRY_STRATEGY,
+/// };
+///
+/// // Preserve links if possible otherwise keep trying to rename
+/// const RESILIENT_STRATEGY: &dyn CommitStrategy =
+///     &FallbackStrategy::new(LINK_PRESERVING_STRATEGY, RENAME_WITH_RETRY_STRATEGY);
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///
+///     let phazer = PhazerBuilder::with_target("uses-fallback-strategy.txt")
+///         .commit_strategy(RESILIENT_STRATEGY)
+///         .build();
+///
+///     // Build the working file
+///
+///     phazer.commit()?;
+///
+///     Ok(())
+/// }
+/// ```
+///
+pub struct FallbackStrategy<'a> {
+    primary: &'a dyn CommitStrategy,
+    secondary: &'a dyn CommitStrategy,
+}
+
+impl<'a> FallbackStrategy<'a> {
+    /// Creates a [`FallbackStrategy`].
+    ///
+    /// # Arguments
+    ///
+    /// * `primary` - The strategy tried first.
+    /// * `secondary` - The strategy tried if `primary` fails.
+    ///
+    pub const fn new(primary: &'a dyn CommitStrategy, secondary: &'a dyn CommitStrategy) -> Self {
+        Self { primary, secondary }
+    }
+}
+
+impl<'a> CommitStrategy for FallbackStrategy<'a> {
+    fn commit(&self, phazer: &dyn CommitDetails) -> std::io::Result<()> {
+        match self.primary.commit(phazer) {
+            Ok(()) => Ok(()),
+            Err(primary_error) => self.secondary.commit(phazer).map_err(|_| primary_error),
+        }
+    }
+    fn writes_in_place(&self) -> bool {
+        self.primary.writes_in_place() || self.secondary.writes_in_place()
+    }
+    fn keeps_working_file(&self) -> bool {
+        self.primary.keeps_working_file() || self.secondary.keeps_working_file()
+    }
+}
+
+/// [`StrategyInfo`] describes one of the commit strategies provided by this crate.
+///
+/// The list returned by [`available_strategies`] includes strategies that are not available on
+/// the current platform (or that need a feature which is not enabled) so tooling can explain why
+/// a choice is missing.
+///
+pub struct StrategyInfo {
+    name: &'static str,
+    description: &'static str,
+    available: bool,
+    recommended: bool,
+    strategy: Option<&'static dyn CommitStrategy>,
+}
+
+impl StrategyInfo {
+    /// Returns the name of the strategy's type; for example, `SimpleRenameStrategy`.
+    ///
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+    /// Returns a one sentence description of the strategy.
+    ///
+    pub fn description(&self) -> &'static str {
+        self.description
+    }
+    /// Returns `true` if the strategy can be used on this platform with the enabled features.
+    ///
+    pub fn is_available(&self) -> bool {
+        self.available
+    }
+    /// Returns `true` if the strategy is a good default for this platform.
+    ///
+    pub fn is_recommended(&self) -> bool {
+        self.recommended
+    }
+    /// Returns the ready-to-use strategy or `None` if it is not available.
+    ///
+    pub fn strategy(&self) -> Option<&'static dyn CommitStrategy> {
+        self.strategy
+    }
+}
+
+#[cfg(windows)]
+const MOVE_FILE_EX_INFO: Option<&dyn CommitStrategy> = Some(MOVE_FILE_EX_STRATEGY);
+#[cfg(not(windows))]
+const MOVE_FILE_EX_INFO: Option<&dyn CommitStrategy> = None;
+
+#[cfg(windows)]
+const MOVE_FILE_EX_WRITE_THROUGH_INFO: Option<&dyn CommitStrategy> =
+    Some(MOVE_FILE_EX_WRITE_THROUGH_STRATEGY);
+#[cfg(not(windows))]
+const MOVE_FILE_EX_WRITE_THROUGH_INFO: Option<&dyn CommitStrategy> = None;
+
+#[cfg(windows)]
+const WINDOWS_BEST_EFFORT_INFO: Option<&dyn CommitStrategy> = Some(WINDOWS_BEST_EFFORT_STRATEGY);
+#[cfg(not(windows))]
+const WINDOWS_BEST_EFFORT_INFO: Option<&dyn CommitStrategy> = None;
+
+#[cfg(windows)]
+const REPLACE_FILE_INFO: Option<&dyn CommitStrategy> = Some(REPLACE_FILE_STRATEGY);
+#[cfg(not(windows))]
+const REPLACE_FILE_INFO: Option<&dyn CommitStrategy> = None;
+
+#[cfg(windows)]
+const REMOVE_THEN_RENAME_INFO: Option<&dyn CommitStrategy> = Some(REMOVE_THEN_RENAME_STRATEGY);
+#[cfg(not(windows))]
+const REMOVE_THEN_RENAME_INFO: Option<&dyn CommitStrategy> = None;
+
+#[cfg(feature = "trash")]
+const TRASHING_INFO: Option<&dyn CommitStrategy> = Some(TRASHING_STRATEGY);
+#[cfg(not(feature = "trash"))]
+const TRASHING_INFO: Option<&dyn CommitStrategy> = None;
+
+static STRATEGIES: [StrategyInfo; 13] = [
+    StrategyInfo {
+        name: "SimpleRenameStrategy",
+        description: "Renames the working file to the target.",
+        available: true,
+        recommended: cfg!(not(windows)),
+        strategy: Some(SIMPLE_RENAME_STRATEGY),
+    },
+    StrategyInfo {
+        name: "RenameWithRetryStrategy",
+        description: "Renames the working file to the target retrying when the target is busy.",
+        available: true,
+        recommended: cfg!(windows),
+        strategy: Some(RENAME_WITH_RETRY_STRATEGY),
+    },
+    StrategyInfo {
+        name: "DurableRenameStrategy",
+        description: "Flushes the working file, renames it, then flushes the target's directory.",
+        available: true,
+        recommended: false,
+        strategy: Some(DURABLE_RENAME_STRATEGY),
+    },
+    StrategyInfo {
+        name: "MoveFileExStrategy",
+        description: "Calls the Windows MoveFileExW function to replace the target.",
+        available: cfg!(windows),
+        recommended: false,
+        strategy: MOVE_FILE_EX_INFO,
+    },
+    StrategyInfo {
+        name: "MoveFileExStrategy (write-through)",
+        description: "Calls MoveFileExW and waits for the move to be flushed to disk.",
+        available: cfg!(windows),
+        recommended: false,
+        strategy: MOVE_FILE_EX_WRITE_THROUGH_INFO,
+    },
+    StrategyInfo {
+        name: "WindowsBestEffortStrategy",
+        description: "Uses CreateHardLinkW, ReplaceFileW, or MoveFileExW; whichever works.",
+        available: cfg!(windows),
+        recommended: false,
+        strategy: WINDOWS_BEST_EFFORT_INFO,
+    },
+    StrategyInfo {
+        name: "ReplaceFileStrategy",
+        description: "Calls ReplaceFileW so the target keeps its attributes and ACL.",
+        available: cfg!(windows),
+        recommended: false,
+        strategy: REPLACE_FILE_INFO,
+    },
+    StrategyInfo {
+        name: "RemoveThenRenameStrategy",
+        description: "Renames or, if that fails, removes the target then renames (not atomic).",
+        available: cfg!(windows),
+        recommended: false,
+        strategy: REMOVE_THEN_RENAME_INFO,
+    },
+    StrategyInfo {
+        name: "LinkPreservingStrategy",
+        description: "Renames the working file or, if the target has hard links, copies into it.",
+        available: true,
+        recommended: false,
+        strategy: Some(LINK_PRESERVING_STRATEGY),
+    },
+    StrategyInfo {
+        name: "ReplaceContentsStrategy",
+        description:
+            "Copies into the existing target so it keeps its inode and links (not atomic).",
+        available: true,
+        recommended: false,
+        strategy: Some(REPLACE_CONTENTS_STRATEGY),
+    },
+    StrategyInfo {
+        name: "CreateNewStrategy",
+        description: "Links the working file to the target only if the target does not exist.",
+        available: true,
+        recommended: false,
+        strategy: Some(CREATE_NEW_STRATEGY),
+    },
+    StrategyInfo {
+        name: "CrossDeviceSafeStrategy",
+        description: "Renames or, across filesystems, copies beside the target then renames.",
+        available: true,
+        recommended: false,
+        strategy: Some(CROSS_DEVICE_SAFE_STRATEGY),
+    },
+    StrategyInfo {
+        name: "TrashingStrategy",
+        description: "Moves the target to the trash then renames the working file to the target.",
+        available: cfg!(feature = "trash"),
+        recommended: false,
+        strategy: TRASHING_INFO,
+    },
+];
+
+/// Returns a description of every commit strategy provided by this crate.
+///
+/// This is meant for tooling, like a configuration screen, that lets an operator choose a
+/// strategy.  Composing strategies, like [`FallbackStrategy`], are not included.
+///
+/// # Example
+///
+/// ```
+/// use phazer::available_strategies;
+///
+/// fn main() {
+///     for info in available_strategies().iter().filter(|i| i.is_available()) {
+///         let marker = if info.is_recommended() { "*" } else { " " };
+///         println!("{} {} - {}", marker, info.name(), info.description());
+///     }
+/// }
+/// ```
+///
+pub fn available_strategies() -> &'static [StrategyInfo] {
+    &STRATEGIES
+}
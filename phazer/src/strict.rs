@@ -0,0 +1,133 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A [`Phazer`] that must be explicitly committed or aborted.
+//!
+//! [`Phazer`]: crate::Phazer
+//!
+use std::ops::Deref;
+
+use crate::Phazer;
+
+/// [`StrictPhazer`] wraps a [`Phazer`] and complains if it is dropped without an explicit
+/// [`commit`][sc] or [`abort`][sa].
+///
+/// Dropping a [`Phazer`] quietly abandons the working file.  That is the right thing on an error
+/// path but it is a bug if the commit was simply forgotten.  [`StrictPhazer`] catches the "forgot
+/// to commit" mistake.  If it is dropped without [`commit`][sc] or [`abort`][sa], the working file
+/// is still removed and then, in a debug build, the drop panics; in a release build a warning is
+/// written to standard error.  Nothing extra happens while the thread is already panicking.
+///
+/// [`StrictPhazer`] dereferences to the [`Phazer`] so writers are created as usual.  It is
+/// opt-in; a plain [`Phazer`] keeps the relaxed drop-to-discard behaviour.
+///
+/// [sc]: StrictPhazer::commit
+/// [sa]: StrictPhazer::abort
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(feature = "simple")]
+/// # {
+/// use std::io::Write;
+///
+/// use phazer::Phazer;
+///
+/// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let phazer = Phazer::new("must-commit.txt").strict();
+///     let mut writer = phazer.simple_writer()?;
+///     writer.write_all(b"important")?;
+///     drop(writer);
+///     // Forgetting this line is reported when phazer is dropped
+///     phazer.commit()?;
+///     Ok(())
+/// }
+/// # }
+/// ```
+///
+#[must_use = "a StrictPhazer must be committed or aborted"]
+pub struct StrictPhazer<'cs> {
+    phazer: Option<Phazer<'cs>>,
+}
+
+impl<'cs> StrictPhazer<'cs> {
+    /// Wraps `phazer` so dropping it without a commit or abort is reported.
+    ///
+    pub fn new(phazer: Phazer<'cs>) -> Self {
+        Self {
+            phazer: Some(phazer),
+        }
+    }
+    /// Commits the working file; see [`Phazer::commit`].
+    ///
+    pub fn commit(mut self) -> std::io::Result<()> {
+        self.take().commit()
+    }
+    /// Explicitly abandons the working file; see [`Phazer::discard`].
+    ///
+    pub fn abort(mut self) -> std::io::Result<()> {
+        self.take().discard()
+    }
+    /// Returns the [`Phazer`] without the strict check.
+    ///
+    pub fn into_inner(mut self) -> Phazer<'cs> {
+        self.take()
+    }
+    // Remove the Phazer so drop has nothing to report.
+    fn take(&mut self) -> Phazer<'cs> {
+        self.phazer
+            .take()
+            .expect("the Phazer is only taken by a method that consumes self")
+    }
+}
+
+impl<'cs> Deref for StrictPhazer<'cs> {
+    type Target = Phazer<'cs>;
+
+    fn deref(&self) -> &Self::Target {
+        self.phazer
+            .as_ref()
+            .expect("the Phazer is only taken by a method that consumes self")
+    }
+}
+
+impl<'cs> Drop for StrictPhazer<'cs> {
+    fn drop(&mut self) {
+        let Some(phazer) = self.phazer.take() else {
+            return;
+        };
+        let target_path = phazer.target_path.clone();
+        // Remove the working file before complaining
+        drop(phazer);
+        if std::thread::panicking() {
+            return;
+        }
+        let message = format!(
+            "a StrictPhazer for {} was dropped without commit or abort; the work was discarded",
+            target_path.display()
+        );
+        if cfg!(debug_assertions) {
+            panic!("{}", message);
+        }
+        eprintln!("warning: {}", message);
+    }
+}
+
+impl<'cs> Phazer<'cs> {
+    /// Wraps this [`Phazer`] in a [`StrictPhazer`] that must be explicitly committed or aborted.
+    ///
+    pub fn strict(self) -> StrictPhazer<'cs> {
+        StrictPhazer::new(self)
+    }
+}
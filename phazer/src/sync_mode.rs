@@ -0,0 +1,66 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Choose how much of a commit is flushed to disk.
+//!
+
+/// How much of a commit is flushed to disk before the commit returns.
+///
+/// A rename is atomic but not necessarily durable; after a power failure the target may be
+/// empty, old, or missing.  [`SyncMode`] is set on the builder with [`sync_mode`][sm] and maps
+/// the intent to the right platform primitives.
+///
+/// [sm]: crate::PhazerBuilderWithTarget::sync_mode
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(feature = "simple")]
+/// # {
+/// use std::io::Write;
+///
+/// use phazer::sync_mode::SyncMode;
+/// use phazer::PhazerBuilder;
+///
+/// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let phazer = PhazerBuilder::with_target("ledger.dat")
+///         .sync_mode(SyncMode::Full)
+///         .build();
+///     let mut writer = phazer.simple_writer()?;
+///     writer.write_all(b"balance=100")?;
+///     drop(writer);
+///     phazer.commit()?;
+///     Ok(())
+/// }
+/// # }
+/// ```
+///
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SyncMode {
+    /// Nothing is flushed; the operating system writes the data when it chooses.  This is the
+    /// default.
+    #[default]
+    None,
+    /// The working file's data is flushed (`fdatasync`) before the commit so the target never
+    /// has missing content.  The rename itself may be lost after a power failure.
+    Data,
+    /// The working file's data and metadata are flushed (`fsync`) before the commit and the
+    /// rename is flushed after the commit.  For POSIX the directory holding the target is
+    /// flushed.  For Windows, which cannot flush a directory, each rename the commit strategy does
+    /// uses `MoveFileExW` with `MOVEFILE_WRITE_THROUGH` (see
+    /// [`FileSystem::rename_write_through`][rwt]).
+    ///
+    /// [rwt]: crate::file_system::FileSystem::rename_write_through
+    Full,
+}
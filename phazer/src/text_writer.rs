@@ -0,0 +1,360 @@
+#![cfg(feature = "encoding")]
+//
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! File-like things that write text to the working file in an encoding required by other
+//! software or with normalized line endings.
+//!
+//! This module is available when the `encoding` feature is enabled.
+//!
+use std::io::{Seek, SeekFrom, Write};
+use std::sync::atomic::Ordering;
+
+use crate::simple_writer::SimplePhazerWriter;
+use crate::Phazer;
+
+/// The encoding of the target written by a [`TextPhazerWriter`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Encoding {
+    /// UTF-8 preceded by a byte order mark (`EF BB BF`).
+    Utf8Bom,
+    /// UTF-16 little-endian preceded by a byte order mark (`FF FE`).  This is what Windows
+    /// software usually means by "Unicode".
+    Utf16Le,
+    /// UTF-16 little-endian without a byte order mark.
+    Utf16LeNoBom,
+}
+
+impl Encoding {
+    fn bom(&self) -> &'static [u8] {
+        match self {
+            Encoding::Utf8Bom => &[0xEF, 0xBB, 0xBF],
+            Encoding::Utf16Le => &[0xFF, 0xFE],
+            Encoding::Utf16LeNoBom => &[],
+        }
+    }
+}
+
+impl<'cs> Phazer<'cs> {
+    /// Returns a synchronous file-like thing that accepts UTF-8 text and writes it to the working
+    /// file in `encoding`.
+    ///
+    /// Some Windows software requires a UTF-8 byte order mark or UTF-16LE files.  The byte order
+    /// mark (if any) is written when the first writer is created.  A later writer for the same
+    /// [`Phazer`] continues after the text already in the working file.  UTF-8 written to the
+    /// writer is transcoded as it is written.  A character may be split across writes.
+    ///
+    /// [`TextPhazerWriter::finish`] must be called after the last byte is written.  It ensures no
+    /// partial character is pending.  Until it succeeds [`Phazer::commit`] fails with an
+    /// [`InvalidData`][id] error.
+    ///
+    /// This method is available when the `encoding` feature is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `encoding` - The encoding of the target.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the working file cannot be opened / created or the byte
+    /// order mark cannot be written.  Otherwise a new [`TextPhazerWriter`] is returned.
+    ///
+    /// [id]: std::io::ErrorKind::InvalidData
+    /// [ioe]: std::io::Error
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "encoding")]
+    /// # {
+    /// use std::io::Write;
+    ///
+    /// use phazer::text_writer::Encoding;
+    /// use phazer::Phazer;
+    ///
+    /// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let phazer = Phazer::new("legacy.ini");
+    ///     let mut writer = phazer.text_writer(Encoding::Utf16Le)?;
+    ///     writeln!(writer, "[Settings]")?;
+    ///     writer.finish()?;
+    ///     phazer.commit()?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    ///
+    pub fn text_writer<'a>(
+        &'a self,
+        encoding: Encoding,
+    ) -> std::io::Result<TextPhazerWriter<'a, 'cs>> {
+        let first = !self.was_written();
+        let mut writer = self.wrapped_simple_writer()?;
+        if first {
+            writer.write_all(encoding.bom())?;
+        } else {
+            writer.seek(SeekFrom::End(0))?;
+        }
+        self.unfinished_writer.store(true, Ordering::Relaxed);
+        Ok(TextPhazerWriter {
+            writer,
+            encoding,
+            pending: Vec::new(),
+            parent: self,
+        })
+    }
+}
+
+/// TextPhazerWriter is a synchronous file-like thing that writes UTF-8 text to the working file
+/// in a specific [`Encoding`].
+///
+/// It maintains a reference to the [`Phazer`] used to construct it, ensuring [`Phazer::commit`]
+/// cannot be called if there are any writers.
+///
+/// This struct is available when the `encoding` feature is enabled.
+pub struct TextPhazerWriter<'a, 'cs> {
+    writer: SimplePhazerWriter<'a, 'cs>,
+    encoding: Encoding,
+    pending: Vec<u8>,
+    parent: &'a Phazer<'cs>,
+}
+
+impl<'a, 'cs> TextPhazerWriter<'a, 'cs> {
+    /// Ensures no partial character is pending and flushes the working file.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the text ends with an incomplete UTF-8 sequence.  In that
+    /// case [`Phazer::commit`] continues to fail.
+    ///
+    /// [ioe]: std::io::Error
+    ///
+    pub fn finish(mut self) -> std::io::Result<()> {
+        if !self.pending.is_empty() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "the text ends with an incomplete UTF-8 sequence",
+            ));
+        }
+        self.writer.flush()?;
+        self.parent
+            .unfinished_writer
+            .store(false, Ordering::Relaxed);
+        Ok(())
+    }
+    // Transcode and write everything in pending that is complete.
+    fn write_pending(&mut self) -> std::io::Result<()> {
+        let valid = match std::str::from_utf8(&self.pending) {
+            Ok(s) => s.len(),
+            // The end of pending is the start of a character; wait for the rest
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(e) => return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, e)),
+        };
+        let text = std::str::from_utf8(&self.pending[..valid]).map_err(std::io::Error::other)?;
+        let bytes: Vec<u8> = text.encode_utf16().flat_map(|u| u.to_le_bytes()).collect();
+        self.writer.write_all(&bytes)?;
+        self.pending.drain(..valid);
+        Ok(())
+    }
+}
+
+impl<'a, 'cs> Write for TextPhazerWriter<'a, 'cs> {
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self.encoding {
+            Encoding::Utf8Bom => self.writer.write(buf),
+            Encoding::Utf16Le | Encoding::Utf16LeNoBom => {
+                self.pending.extend_from_slice(buf);
+                if let Err(e) = self.write_pending() {
+                    self.pending.truncate(self.pending.len() - buf.len());
+                    return Err(e);
+                }
+                Ok(buf.len())
+            }
+        }
+    }
+}
+
+/// The line ending written by a [`NormalizedPhazerWriter`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LineEnding {
+    /// `\n`; the POSIX convention.
+    Lf,
+    /// `\r\n`; the Windows convention.
+    CrLf,
+}
+
+impl LineEnding {
+    fn bytes(&self) -> &'static [u8] {
+        match self {
+            LineEnding::Lf => b"\n",
+            LineEnding::CrLf => b"\r\n",
+        }
+    }
+}
+
+impl<'cs> Phazer<'cs> {
+    /// Returns a synchronous file-like thing that normalizes line endings in what's written to it
+    /// before writing to the working file.
+    ///
+    /// Both `\n` and `\r\n` in the text are written as `line_ending`.  A `\r` that is not
+    /// followed by `\n` is left as-is.  A line ending may be split across writes.
+    ///
+    /// [`NormalizedPhazerWriter::finish`] must be called after the last byte is written.  If
+    /// `ensure_trailing` is `true` and the text does not end with a line ending, one is added.
+    /// An empty file stays empty.  Until [`NormalizedPhazerWriter::finish`] succeeds
+    /// [`Phazer::commit`] fails with an [`InvalidData`][id] error.
+    ///
+    /// This method is available when the `encoding` feature is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `line_ending` - The line ending written to the target.
+    /// * `ensure_trailing` - `true` to ensure the target ends with a line ending.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the working file cannot be opened / created.  Otherwise a
+    /// new [`NormalizedPhazerWriter`] is returned.
+    ///
+    /// [id]: std::io::ErrorKind::InvalidData
+    /// [ioe]: std::io::Error
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "encoding")]
+    /// # {
+    /// use std::io::Write;
+    ///
+    /// use phazer::text_writer::LineEnding;
+    /// use phazer::Phazer;
+    ///
+    /// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let phazer = Phazer::new("settings.conf");
+    ///     let mut writer = phazer.text_writer_normalized(LineEnding::Lf, true)?;
+    ///     write!(writer, "volume = 11\r\nbass = 3")?;
+    ///     // Adds the final newline
+    ///     writer.finish()?;
+    ///     phazer.commit()?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    ///
+    pub fn text_writer_normalized<'a>(
+        &'a self,
+        line_ending: LineEnding,
+        ensure_trailing: bool,
+    ) -> std::io::Result<NormalizedPhazerWriter<'a, 'cs>> {
+        let writer = self.wrapped_simple_writer()?;
+        self.unfinished_writer.store(true, Ordering::Relaxed);
+        Ok(NormalizedPhazerWriter {
+            writer,
+            line_ending,
+            ensure_trailing,
+            pending_cr: false,
+            empty: true,
+            ends_with_line_ending: false,
+            parent: self,
+        })
+    }
+}
+
+/// NormalizedPhazerWriter is a synchronous file-like thing that normalizes the line endings of
+/// what's written to it.
+///
+/// It maintains a reference to the [`Phazer`] used to construct it, ensuring [`Phazer::commit`]
+/// cannot be called if there are any writers.
+///
+/// This struct is available when the `encoding` feature is enabled.
+pub struct NormalizedPhazerWriter<'a, 'cs> {
+    writer: SimplePhazerWriter<'a, 'cs>,
+    line_ending: LineEnding,
+    ensure_trailing: bool,
+    pending_cr: bool,
+    empty: bool,
+    ends_with_line_ending: bool,
+    parent: &'a Phazer<'cs>,
+}
+
+impl<'a, 'cs> NormalizedPhazerWriter<'a, 'cs> {
+    /// Writes a pending `\r`, adds the trailing line ending if requested, and flushes the working
+    /// file.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the working file cannot be written.  In that case
+    /// [`Phazer::commit`] continues to fail.
+    ///
+    /// [ioe]: std::io::Error
+    ///
+    pub fn finish(mut self) -> std::io::Result<()> {
+        if self.pending_cr {
+            self.writer.write_all(b"\r")?;
+            self.ends_with_line_ending = false;
+        }
+        if self.ensure_trailing && !self.empty && !self.ends_with_line_ending {
+            self.writer.write_all(self.line_ending.bytes())?;
+        }
+        self.writer.flush()?;
+        self.parent
+            .unfinished_writer
+            .store(false, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+impl<'a, 'cs> Write for NormalizedPhazerWriter<'a, 'cs> {
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let mut normalized = Vec::with_capacity(buf.len() + buf.len() / 8);
+        let mut pending_cr = self.pending_cr;
+        let mut ends_with_line_ending = self.ends_with_line_ending;
+        for &b in buf {
+            if pending_cr {
+                pending_cr = false;
+                if b == b'\n' {
+                    normalized.extend_from_slice(self.line_ending.bytes());
+                    ends_with_line_ending = true;
+                    continue;
+                }
+                normalized.push(b'\r');
+            }
+            match b {
+                b'\r' => pending_cr = true,
+                b'\n' => {
+                    normalized.extend_from_slice(self.line_ending.bytes());
+                    ends_with_line_ending = true;
+                }
+                _ => {
+                    normalized.push(b);
+                    ends_with_line_ending = false;
+                }
+            }
+        }
+        self.writer.write_all(&normalized)?;
+        self.pending_cr = pending_cr;
+        self.ends_with_line_ending = ends_with_line_ending;
+        self.empty = false;
+        Ok(buf.len())
+    }
+}
@@ -18,13 +18,21 @@
 //!
 //! This module is available when the `tokio` feature is enabled.
 //!
-use crate::Phazer;
+use crate::{Phazer, PhazerError};
 
 use std::marker::PhantomData;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::task::{ready, Poll};
 
+use futures_core::Stream;
+#[cfg(feature = "sha256")]
+use sha2::{Digest, Sha256};
 use tokio::fs::{File, OpenOptions};
-use tokio::io::{AsyncRead, AsyncSeek, AsyncWrite};
+use tokio::io::{AsyncRead, AsyncSeek, AsyncWrite, AsyncWriteExt};
+
+use crate::size_budget::SizeBudget;
+use crate::write_buffering::WriteBuffer;
 
 impl<'cs> Phazer<'cs> {
     /// Returns an asynchronous file-like thing that's used to build the working file.
@@ -80,21 +88,272 @@ impl<'cs> Phazer<'cs> {
     /// # }
     /// ```
     ///
-    pub async fn tokio_writer<'a>(&'a self) -> std::io::Result<TokioPhazerWriter> {
-        let mut options = OpenOptions::new();
-        // Always allow read / write
-        options.read(true).write(true);
-        // Is this the first writer?  Create and truncate.
-        if self.first_writer() {
-            options.truncate(true).create(true);
-        }
-        // Try to open / create the file
+    pub async fn tokio_writer<'a>(&'a self) -> std::io::Result<TokioPhazerWriter<'a, 'a>> {
+        let options = OpenOptions::from(self.working_file_options()?);
         let phase1 = options.open(&self.working_path).await?;
         Ok(TokioPhazerWriter {
             phase1,
+            cancelled: &self.cancelled,
+            size_budget: self.size_budget.as_ref(),
+            buffer: WriteBuffer::new(self.write_buffering),
+            unfinished_writer: &self.unfinished_writer,
+            deferred_error: None,
+            #[cfg(feature = "sha256")]
+            hasher: Sha256::new(),
             _parent: PhantomData::<&'a Self>,
         })
     }
+    /// Drains `stream` into the working file then commits.
+    ///
+    /// This is the streaming analogue of building the working file with a [`TokioPhazerWriter`]
+    /// then calling [`Phazer::commit`].  Each chunk from `stream` is written to the working file.
+    /// When the stream ends the writer is flushed and shut down then the working file is
+    /// committed.  The first error from `stream` stops the transfer; the [`Phazer`] is dropped
+    /// (removing the working file) and the error is returned.
+    ///
+    /// This method is available when the `tokio` feature is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `stream` - Chunks to write; for example the body of a `reqwest` response.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if `stream` yields an error (wrapped as an [`Other`][ok]
+    /// error), if the working file cannot be written, or if the working file cannot be transferred
+    /// to the target file.  Otherwise the number of bytes written is returned.
+    ///
+    /// [ioe]: std::io::Error
+    /// [ok]: std::io::ErrorKind::Other
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "tokio")]
+    /// # {
+    /// use phazer::Phazer;
+    ///
+    /// async fn download(url: &str) -> Result<u64, Box<dyn std::error::Error>> {
+    ///     let response = reqwest::get(url).await?.error_for_status()?;
+    ///     let phazer = Phazer::new("names.zip");
+    ///     Ok(phazer.commit_from_stream(response.bytes_stream()).await?)
+    /// }
+    /// # }
+    /// ```
+    ///
+    pub async fn commit_from_stream<S, B, E>(self, stream: S) -> std::io::Result<u64>
+    where
+        S: Stream<Item = Result<B, E>>,
+        B: AsRef<[u8]>,
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        self.commit_from_stream_with_progress(stream, u64::MAX, |_| {})
+            .await
+    }
+    /// Drains `stream` into the working file, reporting progress, then commits.
+    ///
+    /// This is [`Phazer::commit_from_stream`] with a progress callback for driving a progress bar
+    /// during a large download.  `progress` is called with the total number of bytes written so
+    /// far each time at least `every` bytes have been written since the previous call.  It is
+    /// called once more, with the final total, when the stream ends (unless the final total was
+    /// just reported).
+    ///
+    /// This method is available when the `tokio` feature is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `stream` - Chunks to write; for example the body of a `reqwest` response.
+    /// * `every` - Minimum number of bytes between calls to `progress`.  Zero reports every chunk.
+    /// * `progress` - Called with the number of bytes written so far.
+    ///
+    /// # Return Value
+    ///
+    /// The same as [`Phazer::commit_from_stream`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "tokio")]
+    /// # {
+    /// use phazer::Phazer;
+    ///
+    /// async fn download(url: &str) -> Result<u64, Box<dyn std::error::Error>> {
+    ///     let response = reqwest::get(url).await?.error_for_status()?;
+    ///     let length = response.content_length();
+    ///     let phazer = Phazer::new("names.zip");
+    ///     let total = phazer
+    ///         .commit_from_stream_with_progress(response.bytes_stream(), 1024 * 1024, |so_far| {
+    ///             match length {
+    ///                 Some(length) => println!("{} of {} bytes", so_far, length),
+    ///                 None => println!("{} bytes", so_far),
+    ///             }
+    ///         })
+    ///         .await?;
+    ///     Ok(total)
+    /// }
+    /// # }
+    /// ```
+    ///
+    pub async fn commit_from_stream_with_progress<S, B, E, F>(
+        self,
+        stream: S,
+        every: u64,
+        mut progress: F,
+    ) -> std::io::Result<u64>
+    where
+        S: Stream<Item = Result<B, E>>,
+        B: AsRef<[u8]>,
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+        F: FnMut(u64),
+    {
+        let mut stream = std::pin::pin!(stream);
+        let mut writer = self.tokio_writer().await?;
+        let mut total: u64 = 0;
+        let mut reported: u64 = 0;
+        while let Some(chunk) = std::future::poll_fn(|cx| stream.as_mut().poll_next(cx)).await {
+            let chunk = chunk.map_err(std::io::Error::other)?;
+            let chunk = chunk.as_ref();
+            writer.write_all(chunk).await?;
+            total += chunk.len() as u64;
+            if total - reported >= every {
+                progress(total);
+                reported = total;
+            }
+        }
+        if reported != total {
+            progress(total);
+        }
+        writer.flush().await?;
+        writer.shutdown().await?;
+        drop(writer);
+        self.commit()?;
+        Ok(total)
+    }
+    /// Commits the working file then opens the target for reading with Tokio.
+    ///
+    /// This is the asynchronous analogue of [`Phazer::commit2_and_open`].  If the commit fails, or
+    /// the target cannot be opened, the error and the [`Phazer`] are returned so the application
+    /// can try again or discard the working file.
+    ///
+    /// This method is available when the `tokio` feature is enabled.
+    ///
+    /// # Return Value
+    ///
+    /// A [`PhazerError`] and the [`Phazer`] are returned if the working file cannot be transferred
+    /// to the target file or if the target cannot be opened (a [`Verify`][pev] error).  Otherwise
+    /// the target opened read-only is returned.
+    ///
+    /// [pev]: PhazerError::Verify
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "tokio")]
+    /// # {
+    /// use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    ///
+    /// use phazer::Phazer;
+    ///
+    /// async fn save_and_verify() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let phazer = Phazer::new("config.toml");
+    ///     let mut writer = phazer.tokio_writer().await?;
+    ///     writer.write_all(b"[Serial Port]\nbaud = 250000\n").await?;
+    ///     drop(writer);
+    ///     let mut target = phazer.tokio_commit_and_open().await.map_err(|(e, _)| e)?;
+    ///     let mut verify = String::new();
+    ///     target.read_to_string(&mut verify).await?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    ///
+    // Returning the Phazer on failure is the point so the large error is expected
+    #[allow(clippy::result_large_err)]
+    pub async fn tokio_commit_and_open(self) -> Result<File, (PhazerError, Self)> {
+        if let Err(e) = self.try_commit_classified() {
+            return Err((e, self));
+        }
+        match OpenOptions::new().read(true).open(&self.target_path).await {
+            Ok(file) => Ok(file),
+            Err(e) => Err((PhazerError::Verify(e), self)),
+        }
+    }
+}
+
+#[cfg(feature = "simple")]
+impl Phazer<'static> {
+    /// Builds the working file on a blocking thread then returns the [`Phazer`] so it can be
+    /// committed from async code.
+    ///
+    /// A [`SimplePhazerWriter`][spw] borrows its [`Phazer`] so the writer cannot be moved into
+    /// [`spawn_blocking`][sb] on its own.  The [`Phazer`] itself can cross the boundary.  This
+    /// method moves the [`Phazer`] to a blocking thread, creates a
+    /// [`SimplePhazerWriter`][spw], calls `f` with it, drops the writer, then hands the
+    /// [`Phazer`] back.  The returned [`Phazer`] acts as a commit token; no writers exist so it can
+    /// be committed immediately.
+    ///
+    /// This is a good fit for CPU-bound file generation in a tokio service.
+    ///
+    /// This method is available when both the `tokio` and `simple` features are enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - Called on a blocking thread to build the working file.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the working file cannot be created or opened, if `f`
+    /// returns an error, or if `f` panics.  In each case the [`Phazer`] is dropped (removing the
+    /// working file).  Otherwise the [`Phazer`] and the value returned by `f` are returned.
+    ///
+    /// [spw]: crate::simple_writer::SimplePhazerWriter
+    /// [sb]: tokio::task::spawn_blocking
+    /// [ioe]: std::io::Error
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(all(feature = "tokio", feature = "simple"))]
+    /// # {
+    /// use std::io::Write;
+    ///
+    /// use phazer::Phazer;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let phazer = Phazer::new("report.csv");
+    ///     let (phazer, rows) = phazer
+    ///         .write_blocking(|writer| {
+    ///             // Expensive, synchronous work happens here
+    ///             for row in 0..1000 {
+    ///                 writeln!(writer, "{},{}", row, row * row)?;
+    ///             }
+    ///             Ok(1000)
+    ///         })
+    ///         .await?;
+    ///     phazer.commit()?;
+    ///     println!("{} rows committed", rows);
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    ///
+    pub async fn write_blocking<F, T>(self, f: F) -> std::io::Result<(Self, T)>
+    where
+        F: FnOnce(&mut crate::simple_writer::SimplePhazerWriter) -> std::io::Result<T>
+            + Send
+            + 'static,
+        T: Send + 'static,
+    {
+        tokio::task::spawn_blocking(move || {
+            let mut writer = self.simple_writer()?;
+            let rv = f(&mut writer)?;
+            drop(writer);
+            Ok((self, rv))
+        })
+        .await
+        .map_err(std::io::Error::other)?
+    }
 }
 
 /// TokioPhazerWriter is an asynchronous file-like thing that's used to build the working file.
@@ -105,15 +364,140 @@ impl<'cs> Phazer<'cs> {
 /// This struct is available when the `tokio` feature is enabled.
 pub struct TokioPhazerWriter<'a, 'cs> {
     phase1: File,
+    cancelled: &'a AtomicBool,
+    size_budget: Option<&'a SizeBudget>,
+    buffer: WriteBuffer,
+    unfinished_writer: &'a AtomicBool,
+    deferred_error: Option<std::io::Error>,
+    #[cfg(feature = "sha256")]
+    hasher: Sha256,
     _parent: PhantomData<&'a Phazer<'cs>>,
 }
 
+#[cfg(feature = "sha256")]
+impl<'a, 'cs> TokioPhazerWriter<'a, 'cs> {
+    /// Returns the SHA-256 digest, as lowercase hex, of everything written so far by this writer.
+    ///
+    /// Each chunk is hashed as it is written so the digest is available without reading the
+    /// working file again.  Only bytes written through this writer are included.  The digest does
+    /// not match the working file if the writer seeks and overwrites or if the working file was
+    /// partly built by a different writer.
+    ///
+    /// This method is available when the `sha256` feature is enabled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(all(feature = "tokio", feature = "sha256"))]
+    /// # {
+    /// use tokio::io::AsyncWriteExt;
+    ///
+    /// use phazer::Phazer;
+    ///
+    /// async fn download() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let phazer = Phazer::new("download.bin");
+    ///     let mut writer = phazer.tokio_writer().await?;
+    ///     writer.write_all(b"chunk").await?;
+    ///     writer.flush().await?;
+    ///     println!("sha256 {}", writer.digest());
+    ///     drop(writer);
+    ///     phazer.commit()?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    ///
+    pub fn digest(&self) -> String {
+        crate::checksum::to_hex(&self.hasher.clone().finalize())
+    }
+}
+
+// Write to `file` retrying if a signal interrupts the write.  A single slice is written with
+// poll_write so the vectored path is only used when asked for.
+fn poll_write_retrying(
+    file: &mut File,
+    cx: &mut std::task::Context<'_>,
+    bufs: &[std::io::IoSlice<'_>],
+) -> Poll<std::io::Result<usize>> {
+    loop {
+        let mut pp: Pin<Box<&mut File>> = Pin::from(Box::new(&mut *file));
+        let rv = match bufs {
+            [buf] => pp.as_mut().poll_write(cx, buf),
+            _ => pp.as_mut().poll_write_vectored(cx, bufs),
+        };
+        match rv {
+            Poll::Ready(Err(e)) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            rv => return rv,
+        }
+    }
+}
+
+impl<'a, 'cs> TokioPhazerWriter<'a, 'cs> {
+    // Write the buffered bytes to the working file.
+    fn poll_drain(&mut self, cx: &mut std::task::Context<'_>) -> Poll<std::io::Result<()>> {
+        if let Some(e) = self.deferred_error.take() {
+            return Poll::Ready(Err(e));
+        }
+        let rv = ready!(self.poll_write_buffer(cx));
+        if rv.is_err() {
+            // The file may have taken some of the buffered bytes before it failed so the working
+            // file is incomplete; the commit must fail
+            self.unfinished_writer.store(true, Ordering::Relaxed);
+        }
+        Poll::Ready(rv)
+    }
+    // Write everything in the write buffer to the working file.
+    fn poll_write_buffer(&mut self, cx: &mut std::task::Context<'_>) -> Poll<std::io::Result<()>> {
+        while !self.buffer.is_empty() {
+            let n = ready!(poll_write_retrying(
+                &mut self.phase1,
+                cx,
+                &[std::io::IoSlice::new(self.buffer.pending())]
+            ))?;
+            if n == 0 {
+                return Poll::Ready(Err(std::io::ErrorKind::WriteZero.into()));
+            }
+            self.buffer.consume(n);
+        }
+        Poll::Ready(Ok(()))
+    }
+    // Collect `bufs` in the write buffer.
+    fn poll_buffered(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+        bufs: &[std::io::IoSlice<'_>],
+    ) -> Poll<std::io::Result<usize>> {
+        // Bytes left over from a drain are written before anything new is accepted
+        if self.deferred_error.is_some() || self.buffer.drain_needed() {
+            ready!(self.poll_drain(cx))?;
+        }
+        let total: usize = bufs.iter().map(|b| b.len()).sum();
+        if let Some(size_budget) = self.size_budget {
+            size_budget.reserve(total)?;
+        }
+        for buf in bufs {
+            self.buffer.accept(buf);
+            #[cfg(feature = "sha256")]
+            self.hasher.update(&buf[..]);
+        }
+        // bufs has been accepted so a failure cannot be returned now.  It is returned by the next
+        // write, flush, read, or seek and the commit fails.
+        if self.buffer.drain_needed() {
+            if let Poll::Ready(Err(e)) = self.poll_drain(cx) {
+                self.deferred_error = Some(e);
+            }
+        }
+        Poll::Ready(Ok(total))
+    }
+}
+
 impl<'a, 'cs> AsyncRead for TokioPhazerWriter<'a, 'cs> {
     fn poll_read(
         mut self: std::pin::Pin<&mut Self>,
         cx: &mut std::task::Context<'_>,
         buf: &mut tokio::io::ReadBuf<'_>,
     ) -> std::task::Poll<std::io::Result<()>> {
+        ready!(self.poll_drain(cx))?;
         let mut pp: Pin<Box<&mut File>> = Pin::from(Box::new(&mut self.phase1));
         pp.as_mut().poll_read(cx, buf)
     }
@@ -128,6 +512,11 @@ impl<'a, 'cs> AsyncSeek for TokioPhazerWriter<'a, 'cs> {
         pp.as_mut().poll_complete(cx)
     }
     fn start_seek(mut self: Pin<&mut Self>, position: std::io::SeekFrom) -> std::io::Result<()> {
+        if !self.buffer.is_empty() {
+            return Err(std::io::Error::other(
+                "the writer must be flushed before seeking",
+            ));
+        }
         let mut pp: Pin<Box<&mut File>> = Pin::from(Box::new(&mut self.phase1));
         pp.as_mut().start_seek(position)
     }
@@ -138,6 +527,7 @@ impl<'a, 'cs> AsyncWrite for TokioPhazerWriter<'a, 'cs> {
         mut self: Pin<&mut Self>,
         cx: &mut std::task::Context<'_>,
     ) -> std::task::Poll<Result<(), std::io::Error>> {
+        ready!(self.poll_drain(cx))?;
         let mut pp: Pin<Box<&mut File>> = Pin::from(Box::new(&mut self.phase1));
         pp.as_mut().poll_flush(cx)
     }
@@ -145,6 +535,7 @@ impl<'a, 'cs> AsyncWrite for TokioPhazerWriter<'a, 'cs> {
         mut self: Pin<&mut Self>,
         cx: &mut std::task::Context<'_>,
     ) -> std::task::Poll<Result<(), std::io::Error>> {
+        ready!(self.poll_drain(cx))?;
         let mut pp: Pin<Box<&mut File>> = Pin::from(Box::new(&mut self.phase1));
         pp.as_mut().poll_shutdown(cx)
     }
@@ -153,11 +544,78 @@ impl<'a, 'cs> AsyncWrite for TokioPhazerWriter<'a, 'cs> {
         cx: &mut std::task::Context<'_>,
         buf: &[u8],
     ) -> std::task::Poll<Result<usize, std::io::Error>> {
-        let mut pp: Pin<Box<&mut File>> = Pin::from(Box::new(&mut self.phase1));
-        pp.as_mut().poll_write(cx, buf)
+        if self.cancelled.load(Ordering::Relaxed) {
+            return std::task::Poll::Ready(Err(crate::cancellation::cancelled_error()));
+        }
+        if !self.buffer.is_direct() {
+            return self.poll_buffered(cx, &[std::io::IoSlice::new(buf)]);
+        }
+        if let Some(size_budget) = self.size_budget {
+            size_budget.reserve(buf.len())?;
+        }
+        let rv = poll_write_retrying(&mut self.phase1, cx, &[std::io::IoSlice::new(buf)]);
+        if let Some(size_budget) = self.size_budget {
+            // Return whatever was reserved but not written
+            match rv {
+                std::task::Poll::Ready(Ok(n)) => size_budget.release(buf.len() - n),
+                _ => size_budget.release(buf.len()),
+            }
+        }
+        #[cfg(feature = "sha256")]
+        if let std::task::Poll::Ready(Ok(n)) = rv {
+            self.hasher.update(&buf[..n]);
+        }
+        rv
+    }
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        bufs: &[std::io::IoSlice<'_>],
+    ) -> std::task::Poll<Result<usize, std::io::Error>> {
+        if self.cancelled.load(Ordering::Relaxed) {
+            return std::task::Poll::Ready(Err(crate::cancellation::cancelled_error()));
+        }
+        if !self.buffer.is_direct() {
+            return self.poll_buffered(cx, bufs);
+        }
+        let total: usize = bufs.iter().map(|b| b.len()).sum();
+        if let Some(size_budget) = self.size_budget {
+            size_budget.reserve(total)?;
+        }
+        let rv = poll_write_retrying(&mut self.phase1, cx, bufs);
+        if let Some(size_budget) = self.size_budget {
+            // Return whatever was reserved but not written
+            match rv {
+                std::task::Poll::Ready(Ok(n)) => size_budget.release(total - n),
+                _ => size_budget.release(total),
+            }
+        }
+        #[cfg(feature = "sha256")]
+        if let std::task::Poll::Ready(Ok(n)) = rv {
+            // Only the first n bytes, possibly spanning several slices, were written
+            let mut remaining = n;
+            for buf in bufs {
+                let take = remaining.min(buf.len());
+                self.hasher.update(&buf[..take]);
+                remaining -= take;
+                if remaining == 0 {
+                    break;
+                }
+            }
+        }
+        rv
+    }
+    fn is_write_vectored(&self) -> bool {
+        // Buffering combines the slices
+        !self.buffer.is_direct() || self.phase1.is_write_vectored()
     }
 }
 
 impl<'a, 'cs> Drop for TokioPhazerWriter<'a, 'cs> {
-    fn drop(&mut self) {}
+    fn drop(&mut self) {
+        // Buffered bytes cannot be written here; they must not be lost silently
+        if !self.buffer.is_empty() {
+            self.unfinished_writer.store(true, Ordering::Relaxed);
+        }
+    }
 }
@@ -0,0 +1,238 @@
+#![cfg(all(feature = "io_uring", target_os = "linux"))]
+//
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A file-like thing used to build a working file using io_uring.
+//!
+//! [`TokioPhazerWriter`][tpw] hands each file operation to Tokio's blocking thread pool.  On a
+//! busy Linux service that pool becomes the bottleneck.  [`UringPhazerWriter`] submits the writes
+//! directly to the kernel through io_uring (using [`tokio_uring`]) and
+//! [`Phazer::uring_commit`] does the same for the sync and the final rename.
+//!
+//! Everything here must run on a [`tokio_uring`] runtime; for example, inside
+//! [`tokio_uring::start`].  The regular Tokio backend is unchanged and remains the default.
+//!
+//! This module is available on Linux when the `io_uring` feature is enabled.
+//!
+//! [tpw]: crate::tokio_writer::TokioPhazerWriter
+//!
+use crate::Phazer;
+
+use std::io::ErrorKind;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tokio_uring::buf::IoBuf;
+use tokio_uring::fs::{File, OpenOptions};
+use tokio_uring::BufResult;
+
+use crate::size_budget::SizeBudget;
+use crate::sync_mode::SyncMode;
+
+impl<'cs> Phazer<'cs> {
+    /// Returns an io_uring file-like thing that's used to build the working file.
+    ///
+    /// This is the io_uring analogue of [`Phazer::tokio_writer`][ptw].  If a working file has not
+    /// yet been created this method creates the working file.  If a working file exists this
+    /// method opens the existing file for read / write access and writing starts at the
+    /// beginning.  As with the other writers, a lifetime connecting each [`UringPhazerWriter`] to
+    /// the [`Phazer`] prevents a commit while the writer is alive.
+    ///
+    /// Buffers are handed to the kernel so they are passed by value and returned when the write
+    /// completes.  The [`write_buffering`][pwb] option is not used; each call to
+    /// [`UringPhazerWriter::write_all`] is submitted as it is made.
+    ///
+    /// This method is available on Linux when the `io_uring` feature is enabled.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the working file cannot be created or opened for read
+    /// / write access.  Otherwise a new [`UringPhazerWriter`] is returned that provides access to
+    /// the working file.
+    ///
+    /// [ptw]: crate::Phazer::tokio_writer
+    /// [pwb]: crate::PhazerBuilder::write_buffering
+    /// [ioe]: std::io::Error
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(all(feature = "io_uring", target_os = "linux"))]
+    /// # {
+    /// use phazer::Phazer;
+    ///
+    /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     tokio_uring::start(async {
+    ///         let phazer = Phazer::new("ingest.log");
+    ///         let mut writer = phazer.uring_writer().await?;
+    ///         let (rv, _) = writer.write_all(b"first record\n".to_vec()).await;
+    ///         rv?;
+    ///         writer.close().await?;
+    ///         phazer.uring_commit().await?;
+    ///         Ok(())
+    ///     })
+    /// }
+    /// # }
+    /// ```
+    ///
+    pub async fn uring_writer<'a>(&'a self) -> std::io::Result<UringPhazerWriter<'a, 'cs>> {
+        // The open is a blocking call; the writes are submitted through io_uring
+        let options = self.working_file_options()?;
+        let phase1 = File::from_std(options.open(&self.working_path)?);
+        Ok(UringPhazerWriter {
+            phase1,
+            position: 0,
+            cancelled: &self.cancelled,
+            size_budget: self.size_budget.as_ref(),
+            _parent: PhantomData::<&'a Self>,
+        })
+    }
+    /// Commits the working file using io_uring for the sync and the rename.
+    ///
+    /// This is the io_uring analogue of [`Phazer::commit`][pc].  The same checks are made before
+    /// the commit (cancellation, [`min_commit_bytes`][pmcb], unfinished writers, and so on), the
+    /// working file is synchronized as requested by [`sync_mode`][psm], then the working file is
+    /// renamed over the target.  With [`SyncMode::Full`] the target's directory is also
+    /// synchronized.  The [`after_commit`][pac] hook runs last.
+    ///
+    /// The rename is always a plain rename so the [`commit_strategy`][pcs],
+    /// [`serialize_with_lock`][pswl], [`commit_marker`][pcm], and
+    /// [`verify_boundaries`][pvb] options are not used.  Use [`Phazer::commit`][pc] when any of
+    /// those are needed.
+    ///
+    /// This method is available on Linux when the `io_uring` feature is enabled.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if any of the checks fail, if the working file cannot be
+    /// synchronized, or if the working file cannot be renamed to the target.  In each case the
+    /// [`Phazer`] is dropped which removes the working file.
+    ///
+    /// [pc]: crate::Phazer::commit
+    /// [pmcb]: crate::PhazerBuilder::min_commit_bytes
+    /// [psm]: crate::PhazerBuilder::sync_mode
+    /// [pac]: crate::PhazerBuilder::after_commit
+    /// [pcs]: crate::PhazerBuilder::commit_strategy
+    /// [pswl]: crate::PhazerBuilder::serialize_with_lock
+    /// [pcm]: crate::PhazerBuilder::commit_marker
+    /// [pvb]: crate::PhazerBuilder::verify_boundaries
+    /// [ioe]: std::io::Error
+    ///
+    pub async fn uring_commit(self) -> std::io::Result<()> {
+        if !self.prepare_commit()? {
+            return Ok(());
+        }
+        self.apply_mtime_policy()?;
+        if self.sync_mode != SyncMode::None {
+            let file = OpenOptions::new()
+                .write(true)
+                .open(&self.working_path)
+                .await?;
+            let rv = match self.sync_mode {
+                SyncMode::Data => file.sync_data().await,
+                _ => file.sync_all().await,
+            };
+            file.close().await?;
+            rv?;
+        }
+        tokio_uring::fs::rename(&self.working_path, &self.target_path).await?;
+        // The working file is gone.  Its name could be reused so it must not be removed.
+        self.committed.store(true, Ordering::Relaxed);
+        if self.sync_mode == SyncMode::Full {
+            let dir = File::open(crate::target_parent(&self.target_path)).await?;
+            let rv = dir.sync_all().await;
+            dir.close().await?;
+            rv?;
+        }
+        self.run_after_commit()
+    }
+}
+
+/// UringPhazerWriter is an io_uring file-like thing that's used to build the working file.
+///
+/// It maintains a reference the the [`Phazer`] used to construct it, ensuring [`Phazer::commit`]
+/// cannot be called if there are any potential writers.  Writes are positional; the writer tracks
+/// the position so consecutive calls to [`UringPhazerWriter::write_all`] append.
+///
+/// This struct is available on Linux when the `io_uring` feature is enabled.
+pub struct UringPhazerWriter<'a, 'cs> {
+    phase1: File,
+    position: u64,
+    cancelled: &'a AtomicBool,
+    size_budget: Option<&'a SizeBudget>,
+    _parent: PhantomData<&'a Phazer<'cs>>,
+}
+
+impl<'a, 'cs> UringPhazerWriter<'a, 'cs> {
+    /// Writes all of `buf` to the working file at the current position.
+    ///
+    /// The buffer is owned by the kernel while the write is in flight so it is passed by value
+    /// and returned, along with the result, when the write completes.  Short writes are continued
+    /// and interrupted writes are retried.
+    ///
+    /// # Return Value
+    ///
+    /// The result is an [`Error`][ioe] if the [`Phazer`] was cancelled, if the
+    /// [`size_budget`][psb] is exhausted, or if the write fails.  `buf` is always returned.
+    ///
+    /// [ioe]: std::io::Error
+    /// [psb]: crate::PhazerBuilder::size_budget
+    ///
+    pub async fn write_all<T: IoBuf>(&mut self, buf: T) -> BufResult<(), T> {
+        if self.cancelled.load(Ordering::Relaxed) {
+            return (Err(crate::cancellation::cancelled_error()), buf);
+        }
+        let total = buf.bytes_init();
+        if let Some(size_budget) = self.size_budget {
+            if let Err(e) = size_budget.reserve(total) {
+                return (Err(e), buf);
+            }
+        }
+        let mut buf = buf;
+        let mut written = 0;
+        while written < total {
+            let (rv, slice) = self
+                .phase1
+                .write_at(buf.slice(written..total), self.position)
+                .await;
+            buf = slice.into_inner();
+            let e = match rv {
+                Ok(0) => ErrorKind::WriteZero.into(),
+                Ok(n) => {
+                    written += n;
+                    self.position += n as u64;
+                    continue;
+                }
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => e,
+            };
+            if let Some(size_budget) = self.size_budget {
+                size_budget.release(total - written);
+            }
+            return (Err(e), buf);
+        }
+        (Ok(()), buf)
+    }
+    /// Returns the position of the next write.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+    /// Closes the working file through io_uring.
+    ///
+    /// Dropping the writer also closes the working file but any failure is ignored.
+    pub async fn close(self) -> std::io::Result<()> {
+        self.phase1.close().await
+    }
+}
@@ -0,0 +1,125 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Choose how the writers returned by a [`Phazer`] buffer the working file.
+//!
+//! [`Phazer`]: crate::Phazer
+//!
+
+/// How [`simple_writer`][sw] and [`tokio_writer`][tw] buffer writes to the working file.
+///
+/// Tuning the buffering is done on the builder with [`write_buffering`][wb] so call sites do not
+/// change.  Every mode returns the same writer type.  Buffered bytes are written to the working
+/// file when the writer is flushed, before the writer reads or seeks, and (for
+/// [`simple_writer`][sw]) when the writer is dropped.  If buffered bytes cannot be written the
+/// commit fails so a truncated working file is never committed.  A [`tokio_writer`][tw] cannot
+/// write while being dropped or seeking so it must be flushed (or shut down) first; otherwise the
+/// commit or the seek fails.
+///
+/// [sw]: crate::Phazer::simple_writer
+/// [tw]: crate::Phazer::tokio_writer
+/// [wb]: crate::PhazerBuilderWithTarget::write_buffering
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(feature = "simple")]
+/// # {
+/// use std::io::Write;
+///
+/// use phazer::write_buffering::WriteBuffering;
+/// use phazer::PhazerBuilder;
+///
+/// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let phazer = PhazerBuilder::with_target("many-small-writes.log")
+///         .write_buffering(WriteBuffering::Buffered(64 * 1024))
+///         .build();
+///     let mut writer = phazer.simple_writer()?;
+///     for line in 0..10_000 {
+///         writeln!(writer, "line {}", line)?;
+///     }
+///     drop(writer);
+///     phazer.commit()?;
+///     Ok(())
+/// }
+/// # }
+/// ```
+///
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum WriteBuffering {
+    /// Each write goes straight to the working file.  This is the default.
+    #[default]
+    None,
+    /// Writes are collected until at least this many bytes are waiting.
+    Buffered(usize),
+    /// Writes are collected until a newline is written.
+    Line,
+    /// Writes are collected in memory until the writer is flushed, reads, seeks, or is dropped.
+    InMemory,
+}
+
+// Bytes accepted by a writer but not yet written to the working file.
+#[allow(dead_code)]
+pub(crate) struct WriteBuffer {
+    mode: WriteBuffering,
+    data: Vec<u8>,
+    drain_needed: bool,
+}
+
+#[allow(dead_code)]
+impl WriteBuffer {
+    pub(crate) fn new(mode: WriteBuffering) -> Self {
+        let data = match mode {
+            WriteBuffering::Buffered(capacity) => Vec::with_capacity(capacity),
+            _ => Vec::new(),
+        };
+        Self {
+            mode,
+            data,
+            drain_needed: false,
+        }
+    }
+    // Return true if writes go straight to the working file.
+    pub(crate) fn is_direct(&self) -> bool {
+        self.mode == WriteBuffering::None
+    }
+    // Collect `buf`.  Afterwards `drain_needed` reports whether the mode wants the collected bytes
+    // written to the working file.
+    pub(crate) fn accept(&mut self, buf: &[u8]) {
+        self.data.extend_from_slice(buf);
+        self.drain_needed = match self.mode {
+            WriteBuffering::None => true,
+            WriteBuffering::Buffered(capacity) => self.data.len() >= capacity,
+            WriteBuffering::Line => self.drain_needed || buf.contains(&b'\n'),
+            WriteBuffering::InMemory => false,
+        };
+    }
+    pub(crate) fn drain_needed(&self) -> bool {
+        self.drain_needed
+    }
+    // The bytes waiting to be written.
+    pub(crate) fn pending(&self) -> &[u8] {
+        &self.data
+    }
+    // Forget the first `n` pending bytes because they were written.
+    pub(crate) fn consume(&mut self, n: usize) {
+        self.data.drain(..n);
+        if self.data.is_empty() {
+            self.drain_needed = false;
+        }
+    }
+    pub(crate) fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+}
@@ -0,0 +1,166 @@
+#![cfg(feature = "zip")]
+//
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Atomically replace one member of a zip archive.
+//!
+//! This is the zip counterpart of the [`archive`](crate::archive) module.  The archive is rebuilt
+//! in the working file with the one member replaced then the working file is committed over the
+//! archive.  There is never a window where the archive is corrupt.
+//!
+//! This module is available when the `zip` feature is enabled.
+//!
+use std::fs::OpenOptions;
+use std::io::{ErrorKind, Write};
+use std::sync::atomic::Ordering;
+
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::Phazer;
+
+impl<'cs> Phazer<'cs> {
+    /// Returns a synchronous file-like thing that collects the new content for one member of the
+    /// zip archive that is the target.
+    ///
+    /// [`ZipMemberWriter::finish`] copies every other member of the existing archive (the target)
+    /// to the working file and writes the new content in place of `member_name`.  If the archive
+    /// does not have the member, it is added to the end.  If the archive does not exist, the new
+    /// archive has just the one member.  Until [`ZipMemberWriter::finish`] succeeds
+    /// [`Phazer::commit`] fails with an [`InvalidData`][id] error.
+    ///
+    /// The other members are copied without being decompressed so their data, extra fields, and
+    /// comments are unchanged.  The archive's comment is kept.  The new member uses the replaced
+    /// member's compression method and permissions; a new member is deflated.
+    ///
+    /// The new content is held in memory until [`ZipMemberWriter::finish`] is called.
+    ///
+    /// This method is available when the `zip` feature is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `member_name` - Name of the member within the archive.
+    ///
+    /// [id]: std::io::ErrorKind::InvalidData
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # #[cfg(feature = "zip")]
+    /// # {
+    /// use std::io::Write;
+    ///
+    /// use phazer::Phazer;
+    ///
+    /// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let phazer = Phazer::new("bundle.zip");
+    ///     let mut writer = phazer.zip_member_writer("config/settings.toml");
+    ///     writer.write_all(b"volume = 11\n")?;
+    ///     // Rebuilds the archive in the working file
+    ///     writer.finish()?;
+    ///     phazer.commit()?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    ///
+    pub fn zip_member_writer<'a, S>(&'a self, member_name: S) -> ZipMemberWriter<'a, 'cs>
+    where
+        S: Into<String>,
+    {
+        self.unfinished_writer.store(true, Ordering::Relaxed);
+        ZipMemberWriter {
+            member_name: member_name.into(),
+            content: Vec::new(),
+            parent: self,
+        }
+    }
+}
+
+/// ZipMemberWriter is a synchronous file-like thing that collects the new content for one member
+/// of a zip archive.
+///
+/// It maintains a reference to the [`Phazer`] used to construct it, ensuring [`Phazer::commit`]
+/// cannot be called if there are any writers.
+///
+/// This struct is available when the `zip` feature is enabled.
+pub struct ZipMemberWriter<'a, 'cs> {
+    member_name: String,
+    content: Vec<u8>,
+    parent: &'a Phazer<'cs>,
+}
+
+impl<'a, 'cs> ZipMemberWriter<'a, 'cs> {
+    /// Rebuilds the archive, with the new member content, in the working file.
+    ///
+    /// # Return Value
+    ///
+    /// An [`Error`][ioe] is returned if the existing archive cannot be read or the working file
+    /// cannot be written.  In that case [`Phazer::commit`] continues to fail.
+    ///
+    /// [ioe]: std::io::Error
+    ///
+    pub fn finish(self) -> std::io::Result<()> {
+        let parent = self.parent;
+        let mut writer = ZipWriter::new(parent.simple_writer()?);
+        let mut replaced = false;
+        match parent
+            .file_system
+            .open(&parent.target_path, OpenOptions::new().read(true))
+        {
+            Ok(file) => {
+                let mut archive = ZipArchive::new(file)?;
+                writer.set_raw_comment(archive.comment().into());
+                for i in 0..archive.len() {
+                    let member = archive.by_index_raw(i)?;
+                    if member.name() == self.member_name {
+                        let options = SimpleFileOptions::default()
+                            .compression_method(member.compression())
+                            .unix_permissions(member.unix_mode().unwrap_or(0o644));
+                        drop(member);
+                        writer.start_file(self.member_name.as_str(), options)?;
+                        writer.write_all(&self.content)?;
+                        replaced = true;
+                    } else {
+                        writer.raw_copy_file(member)?;
+                    }
+                }
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+        if !replaced {
+            let options = SimpleFileOptions::default()
+                .compression_method(CompressionMethod::Deflated)
+                .unix_permissions(0o644);
+            writer.start_file(self.member_name.as_str(), options)?;
+            writer.write_all(&self.content)?;
+        }
+        let mut writer = writer.finish()?;
+        writer.flush()?;
+        drop(writer);
+        parent.unfinished_writer.store(false, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+impl<'a, 'cs> Write for ZipMemberWriter<'a, 'cs> {
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.content.write(buf)
+    }
+}
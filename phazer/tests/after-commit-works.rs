@@ -0,0 +1,87 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::io::{ErrorKind, Write};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use phazer::{AfterCommitError, PhazerBuilder};
+
+    use crate::common::{
+        prepare_target_file, AFTER_COMMIT, AFTER_COMMIT_FAILS, AFTER_COMMIT_NO_FILE,
+    };
+
+    #[test]
+    fn after_commit_sees_the_committed_target() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(AFTER_COMMIT)?;
+        let calls = AtomicUsize::new(0);
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .after_commit(|details| {
+                calls.fetch_add(1, Ordering::Relaxed);
+                assert_eq!(
+                    std::fs::read_to_string(details.get_target_path())?,
+                    "hooked"
+                );
+                assert!(!details.get_working_path().exists());
+                Ok(())
+            })
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"hooked")?;
+        drop(w);
+        p.commit()?;
+
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn after_commit_failure_is_reported_after_the_rename() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(AFTER_COMMIT_FAILS)?;
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .after_commit(|_| Err(std::io::Error::from(ErrorKind::Unsupported)))
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"still committed")?;
+        drop(w);
+        let e = p.commit().unwrap_err();
+
+        assert_eq!(e.kind(), ErrorKind::Unsupported);
+        assert!(AfterCommitError::is_after_commit(&e));
+        assert_eq!(std::fs::read_to_string(&target_path)?, "still committed");
+        Ok(())
+    }
+
+    #[test]
+    fn after_commit_is_not_run_without_a_working_file() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(AFTER_COMMIT_NO_FILE)?;
+        let calls = AtomicUsize::new(0);
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .after_commit(|_| {
+                calls.fetch_add(1, Ordering::Relaxed);
+                Ok(())
+            })
+            .build();
+        p.commit()?;
+
+        assert_eq!(calls.load(Ordering::Relaxed), 0);
+        Ok(())
+    }
+}
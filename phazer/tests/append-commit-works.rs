@@ -0,0 +1,33 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+use std::fs::read_to_string;
+
+use phazer::Phazer;
+
+use crate::common::{prepare_target_file, APPEND_COMMIT};
+
+#[test]
+fn append_commit_creates_then_appends() -> Result<(), std::io::Error> {
+    let target_path = prepare_target_file(APPEND_COMMIT)?;
+
+    Phazer::new(&target_path).append_commit(b"first\n")?;
+    assert_eq!(read_to_string(&target_path)?, "first\n");
+
+    Phazer::new(&target_path).append_commit(b"second\n")?;
+    assert_eq!(read_to_string(&target_path)?, "first\nsecond\n");
+    Ok(())
+}
@@ -0,0 +1,121 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "async_std")]
+mod async_std_writer {
+    use std::fs::read_to_string;
+    use std::io::SeekFrom;
+
+    use async_std::io::{ReadExt, SeekExt, WriteExt};
+    use async_std::task::block_on;
+
+    use phazer::write_buffering::WriteBuffering;
+    use phazer::{Phazer, PhazerBuilder};
+
+    use crate::common::{
+        prepare_target_file, ASYNC_STD_WRITER_BUFFERED, ASYNC_STD_WRITER_SEEK,
+        ASYNC_STD_WRITER_UNFLUSHED,
+    };
+
+    #[test]
+    fn async_std_writer_writes_reads_and_seeks() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(ASYNC_STD_WRITER_SEEK)?;
+        block_on(async {
+            let phazer = Phazer::new(&target_path);
+            let mut writer = phazer.async_std_writer().await?;
+            writer.write_all(b"hello world").await?;
+            writer.seek(SeekFrom::Start(6)).await?;
+            let mut s = String::new();
+            writer.read_to_string(&mut s).await?;
+            assert_eq!(s, "world");
+            writer.seek(SeekFrom::Start(0)).await?;
+            writer.write_all(b"HELLO").await?;
+            writer.flush().await?;
+            drop(writer);
+            // A second writer opens the existing working file without truncating
+            let mut writer = phazer.async_std_writer().await?;
+            writer.seek(SeekFrom::End(0)).await?;
+            writer.write_all(b"!").await?;
+            writer.flush().await?;
+            drop(writer);
+            phazer.commit()
+        })?;
+        assert_eq!(read_to_string(&target_path)?, "HELLO world!");
+        Ok(())
+    }
+
+    #[test]
+    fn async_std_writer_buffers() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(ASYNC_STD_WRITER_BUFFERED)?;
+        block_on(async {
+            let phazer = PhazerBuilder::with_target(&target_path)
+                .write_buffering(WriteBuffering::InMemory)
+                .build();
+            let mut writer = phazer.async_std_writer().await?;
+            writer.write_all(b"buffered").await?;
+            assert_eq!(std::fs::metadata(phazer.working_path())?.len(), 0);
+            writer.flush().await?;
+            drop(writer);
+            phazer.commit()
+        })?;
+        assert_eq!(read_to_string(&target_path)?, "buffered");
+        Ok(())
+    }
+
+    #[test]
+    fn async_std_writer_unflushed_fails_the_commit() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(ASYNC_STD_WRITER_UNFLUSHED)?;
+        let rv = block_on(async {
+            let phazer = PhazerBuilder::with_target(&target_path)
+                .write_buffering(WriteBuffering::InMemory)
+                .build();
+            let mut writer = phazer.async_std_writer().await?;
+            writer.write_all(b"lost").await?;
+            drop(writer);
+            phazer.commit()
+        });
+        assert!(rv.is_err());
+        assert!(!target_path.exists());
+        Ok(())
+    }
+
+    // The working file is a link to /dev/full so every write that reaches it fails.  async-std
+    // reports a failed write on the next write which is the one inside the drain.
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn async_std_writer_failed_drain_fails_the_commit() -> Result<(), std::io::Error> {
+        use crate::common::ASYNC_STD_WRITER_DRAIN_FAILS;
+
+        let target_path = prepare_target_file(ASYNC_STD_WRITER_DRAIN_FAILS)?;
+        std::fs::write(&target_path, "old")?;
+        let rv = block_on(async {
+            let phazer = PhazerBuilder::with_target(&target_path)
+                .write_buffering(WriteBuffering::Buffered(4))
+                .build();
+            std::os::unix::fs::symlink("/dev/full", phazer.working_path())?;
+            let mut writer = phazer.async_std_writer().await?;
+            for chunk in [&b"abcd"[..], b"efgh", b"ijkl"] {
+                let _ = writer.write_all(chunk).await;
+            }
+            let _ = writer.flush().await;
+            drop(writer);
+            phazer.commit()
+        });
+        assert!(rv.is_err());
+        assert_eq!(read_to_string(&target_path)?, "old");
+        Ok(())
+    }
+}
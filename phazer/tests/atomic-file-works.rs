@@ -0,0 +1,69 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::fs::{read_to_string, write};
+    use std::io::Write;
+
+    use phazer::atomic_file::AtomicFile;
+    use phazer::write_buffering::WriteBuffering;
+    use phazer::PhazerBuilder;
+
+    use crate::common::{
+        prepare_target_file, ATOMIC_FILE_ABORT, ATOMIC_FILE_COMMIT, ATOMIC_FILE_DROP,
+    };
+
+    #[test]
+    fn atomic_file_commit_writes_the_buffered_bytes() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(ATOMIC_FILE_COMMIT)?;
+        let mut file = PhazerBuilder::with_target(&target_path)
+            .write_buffering(WriteBuffering::InMemory)
+            .build()
+            .into_atomic_file()?;
+        writeln!(file, "one")?;
+        writeln!(file, "two")?;
+        assert!(!target_path.exists());
+        file.commit()?;
+        assert_eq!(read_to_string(&target_path)?, "one\ntwo\n");
+        Ok(())
+    }
+
+    #[test]
+    fn atomic_file_abort_leaves_the_target() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(ATOMIC_FILE_ABORT)?;
+        write(&target_path, "original")?;
+        let mut file = AtomicFile::create(&target_path)?;
+        file.write_all(b"replacement")?;
+        file.abort()?;
+        assert_eq!(read_to_string(&target_path)?, "original");
+        Ok(())
+    }
+
+    #[test]
+    fn atomic_file_drop_removes_the_working_file() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(ATOMIC_FILE_DROP)?;
+        let phazer = PhazerBuilder::with_target(&target_path).build();
+        let working_path = phazer.working_path().to_path_buf();
+        let mut file = phazer.into_atomic_file()?;
+        file.write_all(b"abandoned")?;
+        assert!(working_path.exists());
+        drop(file);
+        assert!(!working_path.exists());
+        assert!(!target_path.exists());
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use phazer::available_strategies;
+
+#[test]
+fn every_strategy_is_listed_once() {
+    let names: Vec<_> = available_strategies().iter().map(|i| i.name()).collect();
+    let mut unique = names.clone();
+    unique.sort();
+    unique.dedup();
+    assert_eq!(names.len(), unique.len());
+    assert!(names.contains(&"SimpleRenameStrategy"));
+    assert!(names.contains(&"RenameWithRetryStrategy"));
+}
+
+#[test]
+fn available_strategies_have_an_instance() {
+    for info in available_strategies() {
+        assert_eq!(
+            info.is_available(),
+            info.strategy().is_some(),
+            "{}",
+            info.name()
+        );
+        assert!(!info.description().is_empty());
+    }
+}
+
+#[test]
+fn exactly_one_strategy_is_recommended() {
+    let recommended: Vec<_> = available_strategies()
+        .iter()
+        .filter(|i| i.is_recommended())
+        .collect();
+    assert_eq!(recommended.len(), 1);
+    assert!(recommended[0].is_available());
+}
+
+#[test]
+fn platform_specific_strategies_follow_the_platform() {
+    let move_file_ex = available_strategies()
+        .iter()
+        .find(|i| i.name() == "MoveFileExStrategy")
+        .unwrap();
+    assert_eq!(move_file_ex.is_available(), cfg!(windows));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn simple_rename_strategy_is_recommended_for_linux() {
+    let recommended = available_strategies()
+        .iter()
+        .find(|i| i.is_recommended())
+        .unwrap();
+    assert_eq!(recommended.name(), "SimpleRenameStrategy");
+}
@@ -0,0 +1,191 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::fs::{read_to_string, remove_file, write, File, Metadata, OpenOptions};
+    use std::io::{ErrorKind, Write};
+    use std::path::{Path, PathBuf};
+
+    use phazer::file_system::{FileSystem, STD_FILE_SYSTEM};
+    use phazer::{PhazerBuilder, LINK_PRESERVING_STRATEGY};
+
+    use crate::common::{
+        prepare_target_file, BACKUP_WITH_EXTENSION_FAILS, BACKUP_WITH_EXTENSION_IN_PLACE,
+        BACKUP_WITH_EXTENSION_NEW, BACKUP_WITH_EXTENSION_NO_LINKS, BACKUP_WITH_EXTENSION_REPLACE,
+    };
+
+    fn backup_for(target_path: &Path) -> PathBuf {
+        let mut name = target_path.as_os_str().to_os_string();
+        name.push(".bak");
+        PathBuf::from(name)
+    }
+
+    #[test]
+    fn backup_with_extension_keeps_the_old_target() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(BACKUP_WITH_EXTENSION_REPLACE)?;
+        let backup_path = backup_for(&target_path);
+        write(&target_path, "old")?;
+        write(&backup_path, "older")?;
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .backup_with_extension("bak")
+            .build();
+        assert_eq!(p.backup_path(), Some(backup_path.clone()));
+        let mut w = p.simple_writer()?;
+        w.write_all(b"new")?;
+        drop(w);
+        assert_eq!(p.commit_with_backup()?, Some(backup_path.clone()));
+
+        assert_eq!(read_to_string(&target_path)?, "new");
+        assert_eq!(read_to_string(&backup_path)?, "old");
+        let _ = remove_file(&backup_path);
+        Ok(())
+    }
+
+    #[test]
+    fn backup_with_extension_with_an_in_place_strategy() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(BACKUP_WITH_EXTENSION_IN_PLACE)?;
+        let backup_path = backup_for(&target_path);
+        let _ = remove_file(&backup_path);
+        write(&target_path, "old")?;
+        // A second link makes the strategy write into the target
+        let link_path = target_path.with_extension("link");
+        let _ = remove_file(&link_path);
+        std::fs::hard_link(&target_path, &link_path)?;
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .commit_strategy(LINK_PRESERVING_STRATEGY)
+            .backup_with_extension("bak")
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"new")?;
+        drop(w);
+        assert_eq!(p.commit_with_backup()?, Some(backup_path.clone()));
+
+        assert_eq!(read_to_string(&target_path)?, "new");
+        assert_eq!(read_to_string(&link_path)?, "new");
+        assert_eq!(read_to_string(&backup_path)?, "old");
+        let _ = remove_file(&backup_path);
+        let _ = remove_file(&link_path);
+        Ok(())
+    }
+
+    #[test]
+    fn backup_with_extension_without_a_target() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(BACKUP_WITH_EXTENSION_NEW)?;
+        let backup_path = backup_for(&target_path);
+        let _ = remove_file(&backup_path);
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .backup_with_extension("bak")
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"first")?;
+        drop(w);
+        assert_eq!(p.commit_with_backup()?, None);
+
+        assert_eq!(read_to_string(&target_path)?, "first");
+        assert!(!backup_path.exists());
+        Ok(())
+    }
+
+    // Refuses to rename the working file so the commit strategy fails.
+    struct RefuseWorking;
+
+    impl FileSystem for RefuseWorking {
+        fn rename(&self, from: &Path, to: &Path) -> std::io::Result<()> {
+            if from.to_string_lossy().contains("phazer-working") {
+                return Err(ErrorKind::PermissionDenied.into());
+            }
+            STD_FILE_SYSTEM.rename(from, to)
+        }
+        fn remove_file(&self, path: &Path) -> std::io::Result<()> {
+            STD_FILE_SYSTEM.remove_file(path)
+        }
+        fn metadata(&self, path: &Path) -> std::io::Result<Metadata> {
+            STD_FILE_SYSTEM.metadata(path)
+        }
+        fn open(&self, path: &Path, options: &OpenOptions) -> std::io::Result<File> {
+            STD_FILE_SYSTEM.open(path, options)
+        }
+    }
+
+    #[test]
+    fn backup_with_extension_restores_after_a_failure() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(BACKUP_WITH_EXTENSION_FAILS)?;
+        let backup_path = backup_for(&target_path);
+        let _ = remove_file(&backup_path);
+        write(&target_path, "old")?;
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .file_system(&RefuseWorking)
+            .backup_with_extension("bak")
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"new")?;
+        drop(w);
+        let e = p.commit_with_backup().unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::PermissionDenied);
+
+        assert_eq!(read_to_string(&target_path)?, "old");
+        assert!(!backup_path.exists());
+        Ok(())
+    }
+
+    // Refuses every hard link so the backup has to be copied.
+    struct RefuseLinks;
+
+    impl FileSystem for RefuseLinks {
+        fn rename(&self, from: &Path, to: &Path) -> std::io::Result<()> {
+            STD_FILE_SYSTEM.rename(from, to)
+        }
+        fn remove_file(&self, path: &Path) -> std::io::Result<()> {
+            STD_FILE_SYSTEM.remove_file(path)
+        }
+        fn metadata(&self, path: &Path) -> std::io::Result<Metadata> {
+            STD_FILE_SYSTEM.metadata(path)
+        }
+        fn open(&self, path: &Path, options: &OpenOptions) -> std::io::Result<File> {
+            STD_FILE_SYSTEM.open(path, options)
+        }
+        fn hard_link(&self, _original: &Path, _link: &Path) -> std::io::Result<()> {
+            Err(ErrorKind::Unsupported.into())
+        }
+    }
+
+    #[test]
+    fn backup_with_extension_copies_when_links_are_refused() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(BACKUP_WITH_EXTENSION_NO_LINKS)?;
+        let backup_path = backup_for(&target_path);
+        let _ = remove_file(&backup_path);
+        write(&target_path, "old")?;
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .file_system(&RefuseLinks)
+            .backup_with_extension("bak")
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"new")?;
+        drop(w);
+        assert_eq!(p.commit_with_backup()?, Some(backup_path.clone()));
+
+        assert_eq!(read_to_string(&target_path)?, "new");
+        assert_eq!(read_to_string(&backup_path)?, "old");
+        let _ = remove_file(&backup_path);
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::io::{ErrorKind, Write};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use phazer::PhazerBuilder;
+
+    use crate::common::{prepare_target_file, BEFORE_COMMIT, BEFORE_COMMIT_FAILS};
+
+    #[test]
+    fn before_commit_sees_the_working_file() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(BEFORE_COMMIT)?;
+        let calls = AtomicUsize::new(0);
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .before_commit(|details| {
+                calls.fetch_add(1, Ordering::Relaxed);
+                assert_eq!(details.get_target_path(), target_path);
+                assert_eq!(
+                    std::fs::read_to_string(details.get_working_path())?,
+                    "hooked"
+                );
+                assert!(!target_path.exists());
+                Ok(())
+            })
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"hooked")?;
+        drop(w);
+        p.commit()?;
+
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+        assert_eq!(std::fs::read_to_string(&target_path)?, "hooked");
+        Ok(())
+    }
+
+    #[test]
+    fn before_commit_failure_abandons_the_commit() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(BEFORE_COMMIT_FAILS)?;
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .before_commit(|_| Err(std::io::Error::from(ErrorKind::Unsupported)))
+            .build();
+        let working_path = p.working_path().to_path_buf();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"never committed")?;
+        drop(w);
+        let e = p.commit().unwrap_err();
+
+        assert_eq!(e.kind(), ErrorKind::Unsupported);
+        assert!(!target_path.exists());
+        assert!(!working_path.exists());
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::io::Write;
+
+    use phazer::Phazer;
+
+    use crate::common::{prepare_target_file, CANCELLATION_HANDLE_SIMPLE};
+
+    #[test]
+    fn cancellation_stops_writes_and_commit() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(CANCELLATION_HANDLE_SIMPLE)?;
+
+        let p = Phazer::new(&target_path);
+        let handle = p.cancellation_handle();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"before")?;
+        assert!(!handle.is_cancelled());
+        // Cancel from another thread like a shutdown handler would
+        let other = handle.clone();
+        std::thread::spawn(move || other.cancel())
+            .join()
+            .expect("the cancelling thread panicked");
+        assert!(handle.is_cancelled());
+        assert!(w.write_all(b"after").is_err());
+        drop(w);
+        assert!(p.commit().is_err());
+        assert!(!target_path.exists());
+        Ok(())
+    }
+}
+
+#[cfg(feature = "tokio")]
+mod tokio {
+    use phazer::Phazer;
+    use tokio::io::AsyncWriteExt;
+
+    use crate::common::{prepare_target_file, CANCELLATION_HANDLE_TOKIO};
+
+    #[tokio::test]
+    async fn cancellation_stops_writes_and_commit() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(CANCELLATION_HANDLE_TOKIO)?;
+
+        let p = Phazer::new(&target_path);
+        let handle = p.cancellation_handle();
+        let mut w = p.tokio_writer().await?;
+        w.write_all(b"before").await?;
+        handle.cancel();
+        assert!(w.write_all(b"after").await.is_err());
+        drop(w);
+        assert!(p.commit().is_err());
+        assert!(!target_path.exists());
+        Ok(())
+    }
+}
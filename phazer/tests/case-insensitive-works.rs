@@ -0,0 +1,60 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::fs::{read_dir, read_to_string, write};
+    use std::io::Write;
+
+    use phazer::Phazer;
+
+    use crate::common::{prepare_target_file, CASE_INSENSITIVE_EXISTING, CASE_INSENSITIVE_TARGET};
+
+    #[test]
+    fn target_differing_only_by_case_is_safe() -> Result<(), std::io::Error> {
+        let existing_path = prepare_target_file(CASE_INSENSITIVE_EXISTING)?;
+        let target_path = prepare_target_file(CASE_INSENSITIVE_TARGET)?;
+        write(&existing_path, "existing")?;
+        // Case-insensitive filesystems treat the two names as one file
+        let case_insensitive = target_path.exists();
+
+        let p = Phazer::new(&target_path);
+        let working_path = p.working_path().to_path_buf();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"new")?;
+        drop(w);
+        p.commit()?;
+
+        assert_eq!(read_to_string(&target_path)?, "new");
+        assert!(!working_path.exists());
+        if case_insensitive {
+            assert_eq!(read_to_string(&existing_path)?, "new");
+        } else {
+            assert_eq!(read_to_string(&existing_path)?, "existing");
+        }
+        // No working file was left behind under either name
+        let parent = target_path.parent().unwrap();
+        let leftovers = read_dir(parent)?
+            .filter_map(|e| e.ok())
+            .filter(|e| {
+                let name = e.file_name().to_string_lossy().to_lowercase();
+                name.starts_with("case-insensitive.txt.phazer-working-")
+            })
+            .count();
+        assert_eq!(leftovers, 0);
+        Ok(())
+    }
+}
@@ -0,0 +1,37 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common::{prepare_target_file, prepare_working_dir, CHECK_SPACE};
+use phazer::Phazer;
+
+mod common;
+
+#[test]
+fn check_space_works() -> Result<(), std::io::Error> {
+    let target_path = prepare_target_file(CHECK_SPACE)?;
+    let p = Phazer::new(target_path);
+    assert!(p.check_space(0)?);
+    assert!(!p.check_space(u64::MAX)?);
+    Ok(())
+}
+
+#[test]
+fn check_space_fails_for_a_missing_directory() -> Result<(), std::io::Error> {
+    let target_path = prepare_working_dir()?
+        .join("check-space-does-not-exist")
+        .join(CHECK_SPACE);
+    let p = Phazer::new(target_path);
+    assert!(p.check_space(0).is_err());
+    Ok(())
+}
@@ -0,0 +1,129 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::cell::RefCell;
+    use std::fs::{create_dir_all, read_dir, remove_dir_all, File, Metadata, OpenOptions};
+    use std::io::{ErrorKind, Write};
+    use std::path::Path;
+
+    use phazer::file_system::{FileSystem, STD_FILE_SYSTEM};
+    use phazer::{Phazer, PhazerBuilder, SIMPLE_RENAME_STRATEGY};
+
+    use crate::common::{
+        prepare_target_file, prepare_working_dir, CHECKPOINTING_WRITER_BYTES,
+        CHECKPOINTING_WRITER_LEFT_BEHIND, CHECKPOINTING_WRITER_MANUAL,
+    };
+
+    #[test]
+    fn checkpoints_are_taken_every_n_bytes() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(CHECKPOINTING_WRITER_BYTES)?;
+        let checkpoints = RefCell::new(Vec::new());
+
+        let p = Phazer::new(&target_path);
+        let mut w = p
+            .checkpointing_writer()?
+            .every_bytes(10)
+            .on_checkpoint(|_, len| checkpoints.borrow_mut().push(len));
+        w.write_all(b"0123456789")?;
+        assert!(!target_path.exists());
+        w.write_all(b"abcde")?;
+        // The first ten bytes were published before the second write
+        assert_eq!(std::fs::read_to_string(&target_path)?, "0123456789");
+        w.write_all(b"fghij")?;
+        w.write_all(b"!")?;
+        assert_eq!(
+            std::fs::read_to_string(&target_path)?,
+            "0123456789abcdefghij"
+        );
+        drop(w);
+        p.commit()?;
+
+        assert_eq!(*checkpoints.borrow(), vec![10, 20]);
+        assert_eq!(
+            std::fs::read_to_string(&target_path)?,
+            "0123456789abcdefghij!"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn manual_checkpoint_publishes_a_snapshot() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(CHECKPOINTING_WRITER_MANUAL)?;
+
+        let p = Phazer::new(&target_path);
+        let mut w = p.checkpointing_writer()?;
+        w.write_all(b"first part")?;
+        w.checkpoint()?;
+        w.write_all(b", second part")?;
+        assert_eq!(std::fs::read_to_string(&target_path)?, "first part");
+        // The working file is dropped without a commit; the checkpoint survives
+        drop(w);
+        drop(p);
+
+        assert_eq!(std::fs::read_to_string(&target_path)?, "first part");
+        Ok(())
+    }
+
+    // Behaves like a process that died mid-checkpoint; renames fail and nothing is removed.
+    struct Crashing;
+
+    impl FileSystem for Crashing {
+        fn rename(&self, _from: &Path, _to: &Path) -> std::io::Result<()> {
+            Err(ErrorKind::Other.into())
+        }
+        fn remove_file(&self, _path: &Path) -> std::io::Result<()> {
+            Ok(())
+        }
+        fn metadata(&self, path: &Path) -> std::io::Result<Metadata> {
+            STD_FILE_SYSTEM.metadata(path)
+        }
+        fn open(&self, path: &Path, options: &OpenOptions) -> std::io::Result<File> {
+            STD_FILE_SYSTEM.open(path, options)
+        }
+    }
+
+    #[test]
+    fn left_behind_checkpoint_is_a_working_file() -> Result<(), std::io::Error> {
+        let dir = prepare_working_dir()?.join(CHECKPOINTING_WRITER_LEFT_BEHIND);
+        let _ = remove_dir_all(&dir);
+        create_dir_all(&dir)?;
+
+        let p = PhazerBuilder::with_target(dir.join("data.txt"))
+            .file_system(&Crashing)
+            .build();
+        let mut w = p.checkpointing_writer()?;
+        w.write_all(b"snapshot")?;
+        assert_eq!(w.checkpoint().unwrap_err().kind(), ErrorKind::Other);
+        drop(w);
+        drop(p);
+
+        // The working file and the copy made through the FileSystem are all that is left
+        let names: Vec<_> = read_dir(&dir)?
+            .map(|e| e.map(|e| e.file_name().to_string_lossy().into_owned()))
+            .collect::<Result<_, _>>()?;
+        assert_eq!(names.len(), 2);
+        assert!(names.iter().any(|n| n.ends_with("-checkpoint")));
+        assert_eq!(
+            Phazer::for_each_in_dir(&dir, SIMPLE_RENAME_STRATEGY)?.count(),
+            0
+        );
+
+        remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,119 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::fs::remove_file;
+    use std::io::{Read, Write};
+
+    use phazer::{Phazer, PhazerBuilder};
+
+    use crate::common::{
+        prepare_target_file, COMMIT2_AND_OPEN_FAILS, COMMIT2_AND_OPEN_SIMPLE,
+        COMMIT_AND_OPEN_SIMPLE,
+    };
+
+    #[test]
+    fn commit_and_open_returns_the_committed_target() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(COMMIT_AND_OPEN_SIMPLE)?;
+
+        let p = Phazer::new(&target_path);
+        let mut w = p.simple_writer()?;
+        w.write_all("committed".as_bytes())?;
+        drop(w);
+        let mut f = p.commit_and_open()?;
+
+        let mut s = String::new();
+        f.read_to_string(&mut s)?;
+        assert_eq!(s, "committed");
+
+        // The handle is read-only
+        assert!(f.write_all("nope".as_bytes()).is_err());
+
+        drop(f);
+        let _ = remove_file(&target_path);
+        Ok(())
+    }
+
+    #[test]
+    fn commit2_and_open_returns_the_committed_target() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(COMMIT2_AND_OPEN_SIMPLE)?;
+
+        let p = Phazer::new(&target_path);
+        let mut w = p.simple_writer()?;
+        w.write_all("committed".as_bytes())?;
+        drop(w);
+        let mut f = p.commit2_and_open().map_err(|(e, _)| e)?;
+
+        let mut s = String::new();
+        f.read_to_string(&mut s)?;
+        assert_eq!(s, "committed");
+        drop(f);
+        let _ = remove_file(&target_path);
+        Ok(())
+    }
+
+    #[test]
+    fn commit2_and_open_returns_the_phazer_on_failure() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(COMMIT2_AND_OPEN_FAILS)?;
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .enforce_extension("toml")
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all("kept".as_bytes())?;
+        drop(w);
+        let Err((_, p)) = p.commit2_and_open() else {
+            panic!("the commit should fail");
+        };
+
+        // The working file survives for another try
+        assert!(p.working_path().exists());
+        assert!(!target_path.exists());
+        p.discard()?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "tokio")]
+mod tokio {
+    use std::fs::remove_file;
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use phazer::Phazer;
+
+    use crate::common::{prepare_target_file, TOKIO_COMMIT_AND_OPEN};
+
+    #[tokio::test]
+    async fn tokio_commit_and_open_returns_the_committed_target() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(TOKIO_COMMIT_AND_OPEN)?;
+
+        let p = Phazer::new(&target_path);
+        let mut w = p.tokio_writer().await?;
+        w.write_all("committed".as_bytes()).await?;
+        w.flush().await?;
+        drop(w);
+        let mut f = p.tokio_commit_and_open().await.map_err(|(e, _)| e)?;
+
+        let mut s = String::new();
+        f.read_to_string(&mut s).await?;
+        assert_eq!(s, "committed");
+        drop(f);
+        let _ = remove_file(&target_path);
+        Ok(())
+    }
+}
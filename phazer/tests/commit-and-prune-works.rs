@@ -0,0 +1,120 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::fs::{
+        create_dir_all, read_to_string, remove_dir_all, write, File, Metadata, OpenOptions,
+    };
+    use std::io::{ErrorKind, Write};
+    use std::path::Path;
+    use std::time::{Duration, SystemTime};
+
+    use phazer::file_system::{FileSystem, STD_FILE_SYSTEM};
+    use phazer::{Phazer, PhazerBuilder, PruneError};
+
+    use crate::common::{prepare_working_dir, COMMIT_AND_PRUNE, COMMIT_AND_PRUNE_FAILS};
+
+    #[test]
+    fn commit_and_prune_keeps_the_newest() -> Result<(), std::io::Error> {
+        let dir = prepare_working_dir()?.join(COMMIT_AND_PRUNE);
+        let _ = remove_dir_all(&dir);
+        create_dir_all(&dir)?;
+        // Three older generations, oldest first, plus a file that does not match
+        let now = SystemTime::now();
+        for (i, age) in [300, 200, 100].iter().enumerate() {
+            let path = dir.join(format!("backup-{}.txt", i + 1));
+            write(&path, "old")?;
+            File::options()
+                .write(true)
+                .open(&path)?
+                .set_modified(now - Duration::from_secs(*age))?;
+        }
+        write(dir.join("other.txt"), "other")?;
+        File::options()
+            .write(true)
+            .open(dir.join("other.txt"))?
+            .set_modified(now - Duration::from_secs(1000))?;
+
+        let p = Phazer::new(dir.join("backup-4.txt"));
+        let mut w = p.simple_writer()?;
+        w.write_all(b"new")?;
+        drop(w);
+        let mut removed = p.commit_and_prune(2, |path| {
+            path.file_name()
+                .is_some_and(|n| n.to_string_lossy().starts_with("backup-"))
+        })?;
+        removed.sort();
+
+        assert_eq!(
+            removed,
+            vec![dir.join("backup-1.txt"), dir.join("backup-2.txt")]
+        );
+        assert!(!dir.join("backup-1.txt").exists());
+        assert!(!dir.join("backup-2.txt").exists());
+        assert!(dir.join("backup-3.txt").exists());
+        assert!(dir.join("backup-4.txt").exists());
+        assert!(dir.join("other.txt").exists());
+
+        let _ = remove_dir_all(&dir);
+        Ok(())
+    }
+
+    // Refuses to remove anything but a working file.
+    struct KeepEverything;
+
+    impl FileSystem for KeepEverything {
+        fn rename(&self, from: &Path, to: &Path) -> std::io::Result<()> {
+            STD_FILE_SYSTEM.rename(from, to)
+        }
+        fn remove_file(&self, path: &Path) -> std::io::Result<()> {
+            if path.to_string_lossy().contains("phazer-working") {
+                return STD_FILE_SYSTEM.remove_file(path);
+            }
+            Err(ErrorKind::PermissionDenied.into())
+        }
+        fn metadata(&self, path: &Path) -> std::io::Result<Metadata> {
+            STD_FILE_SYSTEM.metadata(path)
+        }
+        fn open(&self, path: &Path, options: &OpenOptions) -> std::io::Result<File> {
+            STD_FILE_SYSTEM.open(path, options)
+        }
+    }
+
+    #[test]
+    fn failed_prune_is_a_prune_error() -> Result<(), std::io::Error> {
+        let dir = prepare_working_dir()?.join(COMMIT_AND_PRUNE_FAILS);
+        let _ = remove_dir_all(&dir);
+        create_dir_all(&dir)?;
+        write(dir.join("backup-1.txt"), "old")?;
+
+        let p = PhazerBuilder::with_target(dir.join("backup-2.txt"))
+            .file_system(&KeepEverything)
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"new")?;
+        drop(w);
+        let e = p.commit_and_prune(0, |_| true).unwrap_err();
+
+        // The commit happened; only the pruning failed
+        assert!(PruneError::is_prune_error(&e));
+        assert_eq!(e.kind(), ErrorKind::PermissionDenied);
+        assert_eq!(read_to_string(dir.join("backup-2.txt"))?, "new");
+
+        let _ = remove_dir_all(&dir);
+        Ok(())
+    }
+}
@@ -0,0 +1,119 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::io::Write;
+    use std::path::{Path, PathBuf};
+
+    use phazer::Phazer;
+
+    use crate::common::{prepare_working_dir, COMMIT_AND_UPDATE_POINTER_DIR};
+
+    fn prepare_dir(name: &str) -> Result<PathBuf, std::io::Error> {
+        let dir = prepare_working_dir()?
+            .join(COMMIT_AND_UPDATE_POINTER_DIR)
+            .join(name);
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir)?;
+        Ok(dir)
+    }
+
+    fn write_version(target_path: &Path, pointer_path: &Path, n: usize) -> std::io::Result<()> {
+        let p = Phazer::new(target_path);
+        let mut w = p.simple_writer()?;
+        write!(w, "version {}", n)?;
+        drop(w);
+        p.commit_and_update_pointer(pointer_path)
+    }
+
+    #[test]
+    fn pointer_file_refers_to_the_newest_version() -> Result<(), std::io::Error> {
+        let dir = prepare_dir("text")?;
+        let pointer_path = dir.join("latest");
+
+        write_version(&dir.join("data-1.bin"), &pointer_path, 1)?;
+        assert_eq!(std::fs::read_to_string(&pointer_path)?, "data-1.bin\n");
+        write_version(&dir.join("data-2.bin"), &pointer_path, 2)?;
+        assert_eq!(std::fs::read_to_string(&pointer_path)?, "data-2.bin\n");
+
+        assert_eq!(
+            std::fs::read_to_string(dir.join("data-1.bin"))?,
+            "version 1"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join("data-2.bin"))?,
+            "version 2"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn pointer_in_another_directory_resolves_to_the_target() -> Result<(), std::io::Error> {
+        let dir = prepare_dir("other-dir")?;
+        let target_path = dir.join("data-1.bin");
+        std::fs::create_dir(dir.join("pointers"))?;
+        let pointer_path = dir.join("pointers").join("latest");
+
+        write_version(&target_path, &pointer_path, 1)?;
+
+        let reference = std::fs::read_to_string(&pointer_path)?;
+        assert_eq!(
+            reference,
+            format!("{}\n", Path::new("..").join("data-1.bin").display())
+        );
+        let resolved = dir.join("pointers").join(reference.trim_end());
+        assert_eq!(std::fs::read_to_string(resolved)?, "version 1");
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symbolic_link_pointer_in_another_directory_resolves() -> Result<(), std::io::Error> {
+        let dir = prepare_dir("other-dir-symlink")?;
+        let target_path = dir.join("data").join("data-1.bin");
+        std::fs::create_dir(dir.join("data"))?;
+        std::fs::create_dir(dir.join("pointers"))?;
+        let pointer_path = dir.join("pointers").join("latest");
+        std::os::unix::fs::symlink("data-0.bin", &pointer_path)?;
+
+        write_version(&target_path, &pointer_path, 1)?;
+
+        assert_eq!(
+            std::fs::read_link(&pointer_path)?,
+            Path::new("../data/data-1.bin")
+        );
+        assert_eq!(std::fs::read_to_string(&pointer_path)?, "version 1");
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symbolic_link_pointer_stays_a_symbolic_link() -> Result<(), std::io::Error> {
+        let dir = prepare_dir("symlink")?;
+        let pointer_path = dir.join("latest");
+        std::os::unix::fs::symlink("data-0.bin", &pointer_path)?;
+
+        write_version(&dir.join("data-1.bin"), &pointer_path, 1)?;
+
+        assert!(std::fs::symlink_metadata(&pointer_path)?
+            .file_type()
+            .is_symlink());
+        assert_eq!(std::fs::read_link(&pointer_path)?, Path::new("data-1.bin"));
+        assert_eq!(std::fs::read_to_string(&pointer_path)?, "version 1");
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs::{read_to_string, remove_file, write};
+
+use common::{prepare_target_file, COMMIT_COPY_OF_SOURCE, COMMIT_COPY_OF_TARGET};
+use phazer::Phazer;
+
+mod common;
+
+#[test]
+fn commit_copy_of_works() -> Result<(), std::io::Error> {
+    let source_path = prepare_target_file(COMMIT_COPY_OF_SOURCE)?;
+    let target_path = prepare_target_file(COMMIT_COPY_OF_TARGET)?;
+    write(&source_path, "copied")?;
+    write(&target_path, "original")?;
+
+    let copied = Phazer::new(&target_path).commit_copy_of(&source_path)?;
+    assert_eq!(copied, 6);
+    assert_eq!(read_to_string(&target_path)?, "copied");
+    assert_eq!(read_to_string(&source_path)?, "copied");
+
+    let _ = remove_file(&source_path);
+    let _ = remove_file(&target_path);
+    Ok(())
+}
+
+#[test]
+fn commit_copy_of_missing_source_leaves_target_alone() -> Result<(), std::io::Error> {
+    let target_path = prepare_target_file(COMMIT_COPY_OF_TARGET)?.with_extension("missing.txt");
+    write(&target_path, "original")?;
+
+    let rv = Phazer::new(&target_path).commit_copy_of(&target_path.with_extension("nope"));
+    assert!(rv.is_err());
+    assert_eq!(read_to_string(&target_path)?, "original");
+
+    let _ = remove_file(&target_path);
+    Ok(())
+}
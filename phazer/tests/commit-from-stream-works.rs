@@ -0,0 +1,82 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "tokio")]
+mod tokio {
+    use std::io::ErrorKind;
+
+    use futures::stream;
+    use phazer::Phazer;
+    use tokio::fs::read_to_string;
+
+    use crate::common::{
+        prepare_target_file, COMMIT_FROM_STREAM, COMMIT_FROM_STREAM_ERROR,
+        COMMIT_FROM_STREAM_PROGRESS,
+    };
+
+    #[tokio::test]
+    async fn commit_from_stream_works() -> Result<(), Box<dyn std::error::Error>> {
+        let target_path = prepare_target_file(COMMIT_FROM_STREAM)?;
+
+        let chunks: Vec<Result<&[u8], std::io::Error>> = vec![Ok(b"first "), Ok(b"second")];
+        let p = Phazer::new(&target_path);
+        let total = p.commit_from_stream(stream::iter(chunks)).await?;
+
+        assert_eq!(total, 12);
+        assert_eq!(read_to_string(&target_path).await?, "first second");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn commit_from_stream_cleans_up_on_error() -> Result<(), Box<dyn std::error::Error>> {
+        let target_path = prepare_target_file(COMMIT_FROM_STREAM_ERROR)?;
+
+        let chunks: Vec<Result<&[u8], std::io::Error>> = vec![
+            Ok(b"partial"),
+            Err(std::io::Error::new(ErrorKind::ConnectionReset, "gone")),
+            Ok(b"never"),
+        ];
+        let p = Phazer::new(&target_path);
+        let working_path = p.working_path().to_path_buf();
+        let rv = p.commit_from_stream(stream::iter(chunks)).await;
+
+        assert!(rv.is_err());
+        assert!(!target_path.exists());
+        assert!(!working_path.exists());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn commit_from_stream_reports_progress() -> Result<(), Box<dyn std::error::Error>> {
+        let target_path = prepare_target_file(COMMIT_FROM_STREAM_PROGRESS)?;
+
+        let chunks: Vec<Result<&[u8], std::io::Error>> =
+            vec![Ok(b"1234"), Ok(b"5678"), Ok(b"9"), Ok(b"0123"), Ok(b"45")];
+        let mut reports = Vec::new();
+        let p = Phazer::new(&target_path);
+        let total = p
+            .commit_from_stream_with_progress(stream::iter(chunks), 6, |so_far| {
+                reports.push(so_far)
+            })
+            .await?;
+
+        assert_eq!(total, 15);
+        // Every six bytes or more then the final total
+        assert_eq!(reports, vec![8, 15]);
+        assert_eq!(read_to_string(&target_path).await?, "123456789012345");
+        Ok(())
+    }
+}
@@ -0,0 +1,76 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::io::Write;
+    use std::path::Path;
+    use std::time::{Duration, SystemTime};
+
+    use phazer::Phazer;
+
+    use crate::common::{
+        prepare_target_file, COMMIT_IF_OLDER_THAN_MISSING, COMMIT_IF_OLDER_THAN_NEW,
+        COMMIT_IF_OLDER_THAN_OLD,
+    };
+
+    const MINUTE: Duration = Duration::from_secs(60);
+
+    fn write_target(target_path: &Path, age: Duration) -> Result<(), std::io::Error> {
+        std::fs::write(target_path, "old")?;
+        std::fs::File::options()
+            .write(true)
+            .open(target_path)?
+            .set_modified(SystemTime::now() - age)
+    }
+
+    fn try_commit(target_path: &Path) -> Result<bool, std::io::Error> {
+        let p = Phazer::new(target_path);
+        let mut w = p.simple_writer()?;
+        w.write_all(b"new")?;
+        drop(w);
+        p.commit_if_older_than(MINUTE)
+    }
+
+    #[test]
+    fn an_old_target_is_replaced() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(COMMIT_IF_OLDER_THAN_OLD)?;
+        write_target(&target_path, MINUTE * 2)?;
+
+        assert!(try_commit(&target_path)?);
+        assert_eq!(std::fs::read_to_string(&target_path)?, "new");
+        Ok(())
+    }
+
+    #[test]
+    fn a_new_target_is_left_alone() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(COMMIT_IF_OLDER_THAN_NEW)?;
+        write_target(&target_path, Duration::ZERO)?;
+
+        assert!(!try_commit(&target_path)?);
+        assert_eq!(std::fs::read_to_string(&target_path)?, "old");
+        Ok(())
+    }
+
+    #[test]
+    fn a_missing_target_is_created() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(COMMIT_IF_OLDER_THAN_MISSING)?;
+
+        assert!(try_commit(&target_path)?);
+        assert_eq!(std::fs::read_to_string(&target_path)?, "new");
+        Ok(())
+    }
+}
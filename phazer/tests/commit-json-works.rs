@@ -0,0 +1,59 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "serde")]
+mod serde {
+    use std::collections::BTreeMap;
+
+    use phazer::Phazer;
+
+    use crate::common::{prepare_target_file, COMMIT_JSON, COMMIT_JSON_FAILS};
+
+    #[test]
+    fn commit_json_writes_the_value() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(COMMIT_JSON)?;
+        let mut limits = BTreeMap::new();
+        limits.insert("connections", 100);
+        limits.insert("requests", 250);
+
+        Phazer::new(&target_path).commit_json(&limits)?;
+
+        let value: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&target_path)?)?;
+        assert_eq!(
+            value,
+            serde_json::json!({"connections": 100, "requests": 250})
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn commit_json_serialization_failure_commits_nothing() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(COMMIT_JSON_FAILS)?;
+        std::fs::write(&target_path, "{}\n")?;
+        // JSON object keys must be strings
+        let mut bad = BTreeMap::new();
+        bad.insert(vec![1u8], 1);
+
+        let p = Phazer::new(&target_path);
+        let working_path = p.working_path().to_path_buf();
+        assert!(p.commit_json(&bad).is_err());
+
+        assert_eq!(std::fs::read_to_string(&target_path)?, "{}\n");
+        assert!(!working_path.exists());
+        Ok(())
+    }
+}
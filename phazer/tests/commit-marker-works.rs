@@ -0,0 +1,83 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::fs::{create_dir_all, read_to_string, remove_dir_all, write};
+    use std::io::Write;
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use phazer::{CommitDetails, CommitStrategy, Phazer, PhazerBuilder};
+
+    use crate::common::{prepare_working_dir, COMMIT_MARKER};
+
+    // Renames after noting whether the commit marker exists.
+    struct MarkerCheckingStrategy {
+        marker_path: PathBuf,
+        marker_seen: AtomicBool,
+    }
+
+    impl CommitStrategy for MarkerCheckingStrategy {
+        fn commit(&self, phazer: &dyn CommitDetails) -> std::io::Result<()> {
+            self.marker_seen
+                .store(self.marker_path.exists(), Ordering::Relaxed);
+            std::fs::rename(phazer.get_working_path(), phazer.get_target_path())
+        }
+    }
+
+    #[test]
+    fn commit_marker_works() -> Result<(), std::io::Error> {
+        let dir = prepare_working_dir()?.join(COMMIT_MARKER);
+        let _ = remove_dir_all(&dir);
+        create_dir_all(&dir)?;
+        let target_path = dir.join("data.txt");
+        let marker_path = dir.join("data.txt.phazer-committing");
+
+        let strategy = MarkerCheckingStrategy {
+            marker_path: marker_path.clone(),
+            marker_seen: AtomicBool::new(false),
+        };
+        let p = PhazerBuilder::with_target(&target_path)
+            .commit_strategy(&strategy)
+            .commit_marker(true)
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"marked")?;
+        drop(w);
+        p.commit()?;
+
+        assert!(strategy.marker_seen.load(Ordering::Relaxed));
+        assert!(!marker_path.exists());
+        assert_eq!(read_to_string(&target_path)?, "marked");
+
+        // A commit in progress (this process is running) is left alone
+        write(&marker_path, format!("{}\n", std::process::id()))?;
+        assert!(Phazer::recover_markers(&dir)?.is_empty());
+        assert!(marker_path.exists());
+
+        // Simulate an interrupted commit; no process has this id
+        write(&marker_path, format!("{}\n", i32::MAX))?;
+        let interrupted = Phazer::recover_markers(&dir)?;
+        assert_eq!(interrupted, vec![target_path.clone()]);
+        assert!(!marker_path.exists());
+        assert!(target_path.exists());
+        assert!(Phazer::recover_markers(&dir)?.is_empty());
+
+        let _ = remove_dir_all(&dir);
+        Ok(())
+    }
+}
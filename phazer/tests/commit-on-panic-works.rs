@@ -0,0 +1,59 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::io::Write;
+    use std::path::PathBuf;
+
+    use phazer::PhazerBuilder;
+
+    use crate::common::{prepare_target_file, COMMIT_ON_PANIC, COMMIT_ON_PANIC_OFF};
+
+    fn write_then_panic(target_path: PathBuf, commit_on_panic: bool) {
+        let result = std::thread::spawn(move || {
+            let p = PhazerBuilder::with_target(&target_path)
+                .commit_on_panic(commit_on_panic)
+                .build();
+            let mut w = p.simple_writer().unwrap();
+            w.write_all(b"partial crash log").unwrap();
+            drop(w);
+            panic!("deliberate panic to test commit_on_panic");
+        })
+        .join();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn commit_on_panic_preserves_the_working_file() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(COMMIT_ON_PANIC)?;
+
+        write_then_panic(target_path.clone(), true);
+
+        assert_eq!(std::fs::read_to_string(&target_path)?, "partial crash log");
+        Ok(())
+    }
+
+    #[test]
+    fn without_commit_on_panic_the_working_file_is_abandoned() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(COMMIT_ON_PANIC_OFF)?;
+
+        write_then_panic(target_path.clone(), false);
+
+        assert!(!target_path.exists());
+        Ok(())
+    }
+}
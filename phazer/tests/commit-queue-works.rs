@@ -0,0 +1,80 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::fs::{read_to_string, remove_file};
+    use std::io::Write;
+    use std::time::Duration;
+
+    use phazer::commit_queue::CommitQueue;
+    use phazer::Phazer;
+
+    use crate::common::{prepare_target_file, COMMIT_QUEUE_FIRST, COMMIT_QUEUE_SECOND};
+
+    fn write_one(target: &std::path::Path, text: &str) -> Result<Phazer<'static>, std::io::Error> {
+        let p = Phazer::new(target);
+        let mut w = p.simple_writer()?;
+        w.write_all(text.as_bytes())?;
+        drop(w);
+        Ok(p)
+    }
+
+    #[test]
+    fn commit_queue_coalesces_updates() -> Result<(), std::io::Error> {
+        let first = prepare_target_file(COMMIT_QUEUE_FIRST)?;
+        let second = prepare_target_file(COMMIT_QUEUE_SECOND)?;
+
+        let mut queue = CommitQueue::new(Duration::from_secs(3600));
+        queue.enqueue(write_one(&first, "1")?);
+        queue.enqueue(write_one(&first, "2")?);
+        queue.enqueue(write_one(&second, "a")?);
+        queue.enqueue(write_one(&first, "3")?);
+        assert_eq!(queue.len(), 2);
+
+        // Nothing is due so nothing is committed
+        assert!(queue.flush_if_due().is_empty());
+        assert!(!first.exists());
+
+        let results = queue.flush();
+        assert_eq!(results.len(), 2);
+        for (_, result) in results {
+            result?;
+        }
+        assert!(queue.is_empty());
+        assert_eq!(read_to_string(&first)?, "3");
+        assert_eq!(read_to_string(&second)?, "a");
+
+        let _ = remove_file(&first);
+        let _ = remove_file(&second);
+        Ok(())
+    }
+
+    #[test]
+    fn commit_queue_flushes_when_due() -> Result<(), std::io::Error> {
+        let first = prepare_target_file(COMMIT_QUEUE_FIRST)?.with_extension("due.txt");
+        let _ = remove_file(&first);
+
+        let mut queue = CommitQueue::new(Duration::ZERO);
+        queue.enqueue(write_one(&first, "due")?);
+        assert!(queue.is_due());
+        assert_eq!(queue.flush_if_due().len(), 1);
+        assert_eq!(read_to_string(&first)?, "due");
+
+        let _ = remove_file(&first);
+        Ok(())
+    }
+}
@@ -0,0 +1,136 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::io::{ErrorKind, Write};
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use phazer::{CommitDetails, CommitStrategy, Phazer, PhazerBuilder};
+
+    #[cfg(feature = "test_helpers")]
+    use crate::common::COMMIT_REF_REUSED_NAME;
+    use crate::common::{prepare_target_file, COMMIT_REF, COMMIT_REF_CYCLES, COMMIT_REF_RETRY};
+
+    // Fails the first commit then renames
+    struct FailOnceStrategy {
+        failed: AtomicBool,
+    }
+
+    impl CommitStrategy for FailOnceStrategy {
+        fn commit(&self, phazer: &dyn CommitDetails) -> std::io::Result<()> {
+            if !self.failed.swap(true, Ordering::Relaxed) {
+                return Err(std::io::Error::from(ErrorKind::PermissionDenied));
+            }
+            std::fs::rename(phazer.get_working_path(), phazer.get_target_path())
+        }
+    }
+
+    struct Holder<'cs> {
+        phazer: Phazer<'cs>,
+    }
+
+    #[test]
+    fn commit_ref_commits_once() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(COMMIT_REF)?;
+
+        let mut holder = Holder {
+            phazer: Phazer::new(&target_path),
+        };
+        let mut w = holder.phazer.simple_writer()?;
+        w.write_all(b"held in a struct")?;
+        drop(w);
+        holder.phazer.commit_ref()?;
+        assert_eq!(std::fs::read_to_string(&target_path)?, "held in a struct");
+
+        // A second commit does nothing
+        std::fs::write(&target_path, "changed by someone else")?;
+        holder.phazer.commit_ref()?;
+        drop(holder);
+        assert_eq!(
+            std::fs::read_to_string(&target_path)?,
+            "changed by someone else"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn commit_ref_can_commit_the_same_target_again() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(COMMIT_REF_CYCLES)?;
+
+        let mut p = Phazer::new(&target_path);
+        for cycle in 1..=3 {
+            let mut w = p.simple_writer()?;
+            write!(w, "status {}", cycle)?;
+            drop(w);
+            p.commit_ref()?;
+            assert_eq!(
+                std::fs::read_to_string(&target_path)?,
+                format!("status {}", cycle)
+            );
+            assert!(!p.working_path().exists());
+        }
+        // A cycle that writes nothing does not change the target
+        let working_path = p.working_path().to_path_buf();
+        drop(p);
+        assert!(!working_path.exists());
+        assert_eq!(std::fs::read_to_string(&target_path)?, "status 3");
+        Ok(())
+    }
+
+    #[test]
+    fn commit_ref_keeps_the_working_file_after_a_failure() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(COMMIT_REF_RETRY)?;
+        let strategy = FailOnceStrategy {
+            failed: AtomicBool::new(false),
+        };
+
+        let mut p = PhazerBuilder::with_target(&target_path)
+            .commit_strategy(&strategy)
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"second try")?;
+        drop(w);
+        let e = p.commit_ref().unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::PermissionDenied);
+        assert!(!target_path.exists());
+        p.commit_ref()?;
+        drop(p);
+
+        assert_eq!(std::fs::read_to_string(&target_path)?, "second try");
+        Ok(())
+    }
+
+    #[cfg(feature = "test_helpers")]
+    #[test]
+    fn drop_after_commit_ref_leaves_a_reused_working_name_alone() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(COMMIT_REF_REUSED_NAME)?;
+
+        let mut p = Phazer::new(&target_path);
+        let mut w = p.simple_writer()?;
+        w.write_all(b"committed")?;
+        drop(w);
+        p.commit_ref()?;
+        // Something else happens to create a file with the same name as the working file
+        let reused = p.working_path().to_path_buf();
+        std::fs::write(&reused, "unrelated")?;
+        drop(p);
+
+        assert_eq!(std::fs::read_to_string(&reused)?, "unrelated");
+        std::fs::remove_file(&reused)?;
+        Ok(())
+    }
+}
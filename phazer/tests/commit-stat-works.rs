@@ -0,0 +1,51 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::io::{ErrorKind, Write};
+
+    use phazer::Phazer;
+
+    use crate::common::{prepare_target_file, COMMIT_STAT, COMMIT_STAT_NO_TARGET};
+
+    #[test]
+    fn commit_stat_returns_the_committed_metadata() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(COMMIT_STAT)?;
+
+        let p = Phazer::new(&target_path);
+        let mut w = p.simple_writer()?;
+        w.write_all(b"twelve bytes")?;
+        drop(w);
+        let m = p.commit_stat()?;
+
+        assert!(m.is_file());
+        assert_eq!(m.len(), 12);
+        Ok(())
+    }
+
+    #[test]
+    fn commit_stat_without_a_target_fails() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(COMMIT_STAT_NO_TARGET)?;
+        match Phazer::new(&target_path).commit_stat() {
+            Ok(_) => Err(std::io::Error::other("there is no target to stat")),
+            Err(e) => {
+                assert_eq!(e.kind(), ErrorKind::NotFound);
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,89 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::fs::{read_to_string, File, Metadata, OpenOptions};
+    use std::io::{ErrorKind, Write};
+    use std::path::Path;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use phazer::file_system::FileSystem;
+    use phazer::{Phazer, PhazerBuilder, RENAME_WITH_RETRY_STRATEGY};
+
+    use crate::common::{prepare_target_file, COMMIT_VERBOSE, COMMIT_VERBOSE_RETRY};
+
+    // Fails the first `failures` renames with permission denied then renames for real.
+    struct BusyFileSystem {
+        failures: usize,
+        renames: AtomicUsize,
+    }
+
+    impl FileSystem for BusyFileSystem {
+        fn rename(&self, from: &Path, to: &Path) -> std::io::Result<()> {
+            if self.renames.fetch_add(1, Ordering::Relaxed) < self.failures {
+                return Err(std::io::Error::from(ErrorKind::PermissionDenied));
+            }
+            std::fs::rename(from, to)
+        }
+        fn remove_file(&self, path: &Path) -> std::io::Result<()> {
+            std::fs::remove_file(path)
+        }
+        fn metadata(&self, path: &Path) -> std::io::Result<Metadata> {
+            std::fs::metadata(path)
+        }
+        fn open(&self, path: &Path, options: &OpenOptions) -> std::io::Result<File> {
+            options.open(path)
+        }
+    }
+
+    #[test]
+    fn commit_verbose_reports_one_attempt() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(COMMIT_VERBOSE)?;
+
+        let p = Phazer::new(&target_path);
+        let mut w = p.simple_writer()?;
+        w.write_all(b"content")?;
+        drop(w);
+        assert_eq!(p.commit_verbose()?, 1);
+        assert_eq!(read_to_string(&target_path)?, "content");
+
+        // Nothing written so nothing attempted
+        let p = Phazer::new(&target_path);
+        assert_eq!(p.commit_verbose()?, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn commit_verbose_reports_retries() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(COMMIT_VERBOSE_RETRY)?;
+        let file_system = BusyFileSystem {
+            failures: 2,
+            renames: AtomicUsize::new(0),
+        };
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .commit_strategy(RENAME_WITH_RETRY_STRATEGY)
+            .file_system(&file_system)
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"contended")?;
+        drop(w);
+        assert_eq!(p.commit_verbose()?, 3);
+        assert_eq!(read_to_string(&target_path)?, "contended");
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(all(unix, feature = "simple"))]
+mod simple {
+    use std::io::Write;
+    use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+    use phazer::Phazer;
+
+    use crate::common::{prepare_target_file, COMMIT_WITH_OWNERSHIP};
+
+    #[test]
+    fn commit_with_ownership_sets_owner_group_and_mode() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(COMMIT_WITH_OWNERSHIP)?;
+        // Without privileges the owner and group can only be "changed" to the current ones
+        std::fs::write(&target_path, "")?;
+        let current = std::fs::metadata(&target_path)?;
+
+        let p = Phazer::new(&target_path);
+        let mut w = p.simple_writer()?;
+        w.write_all(b"listen = 8080\n")?;
+        drop(w);
+        p.commit_with_ownership(current.uid(), current.gid(), 0o640)?;
+
+        let m = std::fs::metadata(&target_path)?;
+        assert_eq!(m.uid(), current.uid());
+        assert_eq!(m.gid(), current.gid());
+        assert_eq!(m.permissions().mode() & 0o7777, 0o640);
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(all(feature = "sha256", feature = "simple"))]
+mod sha256 {
+    use std::fs::{read_to_string, remove_file};
+    use std::io::Write;
+
+    use phazer::Phazer;
+
+    use crate::common::{
+        prepare_target_file, COMMIT_WITH_SIDECAR_CHECKSUM, COMMIT_WITH_SIDECAR_CHECKSUM_NOTHING,
+    };
+
+    #[test]
+    fn commit_with_sidecar_checksum_works() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(COMMIT_WITH_SIDECAR_CHECKSUM)?;
+        let sidecar_path = target_path.with_extension("txt.sha256");
+        let _ = remove_file(&sidecar_path);
+
+        let p = Phazer::new(&target_path);
+        let mut w = p.simple_writer()?;
+        w.write_all(b"abc")?;
+        drop(w);
+        p.commit_with_sidecar_checksum()?;
+
+        assert_eq!(read_to_string(&target_path)?, "abc");
+        assert_eq!(
+            read_to_string(&sidecar_path)?,
+            format!(
+                "{}  {}\n",
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+                COMMIT_WITH_SIDECAR_CHECKSUM
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn nothing_written_commits_nothing() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(COMMIT_WITH_SIDECAR_CHECKSUM_NOTHING)?;
+        let sidecar_path = target_path.with_extension("txt.sha256");
+        let _ = remove_file(&sidecar_path);
+        Phazer::new(&target_path).commit_with_sidecar_checksum()?;
+        assert!(!target_path.exists());
+        assert!(!sidecar_path.exists());
+        Ok(())
+    }
+}
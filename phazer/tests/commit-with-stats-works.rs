@@ -0,0 +1,48 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::fs::read_to_string;
+    use std::io::Write;
+
+    use phazer::{CommitStats, Phazer};
+
+    use crate::common::{prepare_target_file, COMMIT_WITH_STATS, COMMIT_WITH_STATS_NO_FILE};
+
+    #[test]
+    fn commit_with_stats_reports_the_size() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(COMMIT_WITH_STATS)?;
+        let p = Phazer::new(&target_path);
+        let mut w = p.simple_writer()?;
+        w.write_all(b"twelve bytes")?;
+        drop(w);
+        let stats = p.commit_with_stats().map_err(|(e, _)| e)?;
+        assert_eq!(stats, CommitStats { bytes: 12 });
+        assert_eq!(read_to_string(&target_path)?, "twelve bytes");
+        Ok(())
+    }
+
+    #[test]
+    fn commit_with_stats_without_a_working_file_is_zero() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(COMMIT_WITH_STATS_NO_FILE)?;
+        let p = Phazer::new(&target_path);
+        let stats = p.commit_with_stats().map_err(|(e, _)| e)?;
+        assert_eq!(stats.bytes, 0);
+        assert!(!target_path.exists());
+        Ok(())
+    }
+}
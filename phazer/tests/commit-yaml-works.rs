@@ -0,0 +1,63 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "yaml")]
+mod yaml {
+    use std::collections::BTreeMap;
+
+    use phazer::Phazer;
+
+    use crate::common::{prepare_target_file, COMMIT_YAML, COMMIT_YAML_FAILS};
+
+    // A value that always fails to serialize
+    struct Unserializable;
+
+    impl serde::Serialize for Unserializable {
+        fn serialize<S: serde::Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+            Err(serde::ser::Error::custom("not today"))
+        }
+    }
+
+    #[test]
+    fn commit_yaml_writes_the_value() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(COMMIT_YAML)?;
+        let mut limits = BTreeMap::new();
+        limits.insert("connections", 100);
+        limits.insert("requests", 250);
+
+        Phazer::new(&target_path).commit_yaml(&limits)?;
+
+        assert_eq!(
+            std::fs::read_to_string(&target_path)?,
+            "connections: 100\nrequests: 250\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn commit_yaml_serialization_failure_commits_nothing() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(COMMIT_YAML_FAILS)?;
+        std::fs::write(&target_path, "volume: 11\n")?;
+
+        let p = Phazer::new(&target_path);
+        let working_path = p.working_path().to_path_buf();
+        assert!(p.commit_yaml(&Unserializable).is_err());
+
+        assert_eq!(std::fs::read_to_string(&target_path)?, "volume: 11\n");
+        assert!(!working_path.exists());
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::{Path, PathBuf};
+
+use phazer::file_system::FileSystem;
+use phazer::CommitDetails;
+
+// Commits `working_path` to `target_path` through any FileSystem so a commit strategy can be
+// called directly.
+pub struct MockCommitDetails<'a> {
+    file_system: &'a dyn FileSystem,
+    working_path: PathBuf,
+    target_path: PathBuf,
+}
+
+impl<'a> MockCommitDetails<'a> {
+    pub fn new<W, T>(file_system: &'a dyn FileSystem, working_path: W, target_path: T) -> Self
+    where
+        W: Into<PathBuf>,
+        T: Into<PathBuf>,
+    {
+        Self {
+            file_system,
+            working_path: working_path.into(),
+            target_path: target_path.into(),
+        }
+    }
+}
+
+impl CommitDetails for MockCommitDetails<'_> {
+    fn get_working_path(&self) -> &Path {
+        &self.working_path
+    }
+    fn get_target_path(&self) -> &Path {
+        &self.target_path
+    }
+    fn get_jitter(&self) -> usize {
+        0
+    }
+    fn get_file_system(&self) -> &dyn FileSystem {
+        self.file_system
+    }
+}
@@ -14,11 +14,227 @@
 
 #![allow(dead_code)]
 
+mod commit_details;
 mod simple_fs;
 
+#[allow(unused_imports)]
+pub use commit_details::MockCommitDetails;
 #[allow(unused_imports)]
 pub use simple_fs::{prepare_target_file, prepare_working_dir};
 
+// Used in after-commit-works
+pub const AFTER_COMMIT: &str = "after-commit.txt";
+pub const AFTER_COMMIT_FAILS: &str = "after-commit-fails.txt";
+pub const AFTER_COMMIT_NO_FILE: &str = "after-commit-no-file.txt";
+
+// Used in append-commit-works
+pub const APPEND_COMMIT: &str = "append-commit.txt";
+
+// Used in async-std-writer-works
+pub const ASYNC_STD_WRITER_SEEK: &str = "async-std-writer-seek.txt";
+pub const ASYNC_STD_WRITER_BUFFERED: &str = "async-std-writer-buffered.txt";
+pub const ASYNC_STD_WRITER_UNFLUSHED: &str = "async-std-writer-unflushed.txt";
+pub const ASYNC_STD_WRITER_DRAIN_FAILS: &str = "async-std-writer-drain-fails.txt";
+
+// Used in atomic-file-works
+pub const ATOMIC_FILE_COMMIT: &str = "atomic-file-commit.txt";
+pub const ATOMIC_FILE_ABORT: &str = "atomic-file-abort.txt";
+pub const ATOMIC_FILE_DROP: &str = "atomic-file-drop.txt";
+
+// Used in backup-with-extension-works
+pub const BACKUP_WITH_EXTENSION_REPLACE: &str = "backup-with-extension-replace.txt";
+pub const BACKUP_WITH_EXTENSION_NEW: &str = "backup-with-extension-new.txt";
+pub const BACKUP_WITH_EXTENSION_FAILS: &str = "backup-with-extension-fails.txt";
+pub const BACKUP_WITH_EXTENSION_IN_PLACE: &str = "backup-with-extension-in-place.txt";
+pub const BACKUP_WITH_EXTENSION_NO_LINKS: &str = "backup-with-extension-no-links.txt";
+
+// Used in before-commit-works
+pub const BEFORE_COMMIT: &str = "before-commit.txt";
+pub const BEFORE_COMMIT_FAILS: &str = "before-commit-fails.txt";
+
+// Used in cancellation-handle-works
+pub const CANCELLATION_HANDLE_SIMPLE: &str = "cancellation-handle-simple.txt";
+pub const CANCELLATION_HANDLE_TOKIO: &str = "cancellation-handle-tokio.txt";
+
+// Used in case-insensitive-works
+pub const CASE_INSENSITIVE_EXISTING: &str = "case-insensitive.txt";
+pub const CASE_INSENSITIVE_TARGET: &str = "Case-Insensitive.txt";
+
+// Used in check-space-works
+pub const CHECK_SPACE: &str = "check-space.txt";
+
+// Used in checkpointing-writer-works
+pub const CHECKPOINTING_WRITER_BYTES: &str = "checkpointing-writer-bytes.txt";
+pub const CHECKPOINTING_WRITER_MANUAL: &str = "checkpointing-writer-manual.txt";
+pub const CHECKPOINTING_WRITER_LEFT_BEHIND: &str = "checkpointing-writer-left-behind";
+
+// Used in commit-and-prune-works
+pub const COMMIT_AND_PRUNE: &str = "commit-and-prune";
+pub const COMMIT_AND_PRUNE_FAILS: &str = "commit-and-prune-fails";
+
+// Used in commit-and-update-pointer-works
+pub const COMMIT_AND_UPDATE_POINTER_DIR: &str = "commit-and-update-pointer";
+
+// Used in commit-from-stream-works
+pub const COMMIT_FROM_STREAM: &str = "commit-from-stream.txt";
+pub const COMMIT_FROM_STREAM_ERROR: &str = "commit-from-stream-error.txt";
+pub const COMMIT_FROM_STREAM_PROGRESS: &str = "commit-from-stream-progress.txt";
+
+// Used in commit-if-older-than-works
+pub const COMMIT_IF_OLDER_THAN_MISSING: &str = "commit-if-older-than-missing.txt";
+pub const COMMIT_IF_OLDER_THAN_NEW: &str = "commit-if-older-than-new.txt";
+pub const COMMIT_IF_OLDER_THAN_OLD: &str = "commit-if-older-than-old.txt";
+
+// Used in commit-json-works
+pub const COMMIT_JSON: &str = "commit-json.json";
+pub const COMMIT_JSON_FAILS: &str = "commit-json-fails.json";
+
+// Used in commit-marker-works
+pub const COMMIT_MARKER: &str = "commit-marker";
+
+// Used in commit-on-panic-works
+pub const COMMIT_ON_PANIC: &str = "commit-on-panic.txt";
+pub const COMMIT_ON_PANIC_OFF: &str = "commit-on-panic-off.txt";
+
+// Used in commit-ref-works
+pub const COMMIT_REF: &str = "commit-ref.txt";
+pub const COMMIT_REF_RETRY: &str = "commit-ref-retry.txt";
+pub const COMMIT_REF_REUSED_NAME: &str = "commit-ref-reused-name.txt";
+pub const COMMIT_REF_CYCLES: &str = "commit-ref-cycles.txt";
+
+// Used in commit-toml-works
+pub const COMMIT_TOML: &str = "commit-toml.toml";
+pub const COMMIT_TOML_FAILS: &str = "commit-toml-fails.toml";
+
+// Used in commit-verbose-works
+pub const COMMIT_VERBOSE: &str = "commit-verbose.txt";
+pub const COMMIT_VERBOSE_RETRY: &str = "commit-verbose-retry.txt";
+
+// Used in commit-with-ownership-works
+pub const COMMIT_WITH_OWNERSHIP: &str = "commit-with-ownership.txt";
+
+// Used in commit-with-sidecar-checksum-works
+pub const COMMIT_WITH_SIDECAR_CHECKSUM: &str = "commit-with-sidecar-checksum.txt";
+pub const COMMIT_WITH_SIDECAR_CHECKSUM_NOTHING: &str = "commit-with-sidecar-checksum-nothing.txt";
+
+// Used in commit-and-open-works
+pub const COMMIT_AND_OPEN_SIMPLE: &str = "commit-and-open-simple.txt";
+pub const COMMIT2_AND_OPEN_SIMPLE: &str = "commit2-and-open-simple.txt";
+pub const COMMIT2_AND_OPEN_FAILS: &str = "commit2-and-open-fails.txt";
+pub const TOKIO_COMMIT_AND_OPEN: &str = "tokio-commit-and-open.txt";
+
+// Used in commit-stat-works
+pub const COMMIT_STAT: &str = "commit-stat.txt";
+pub const COMMIT_STAT_NO_TARGET: &str = "commit-stat-no-target.txt";
+
+// Used in commit-queue-works
+pub const COMMIT_QUEUE_FIRST: &str = "commit-queue-first.txt";
+pub const COMMIT_QUEUE_SECOND: &str = "commit-queue-second.txt";
+
+// Used in commit-copy-of-works
+pub const COMMIT_COPY_OF_SOURCE: &str = "commit-copy-of-source.txt";
+pub const COMMIT_COPY_OF_TARGET: &str = "commit-copy-of-target.txt";
+
+// Used in commit-with-stats-works
+pub const COMMIT_WITH_STATS: &str = "commit-with-stats.txt";
+pub const COMMIT_WITH_STATS_NO_FILE: &str = "commit-with-stats-no-file.txt";
+
+// Used in commit-yaml-works
+pub const COMMIT_YAML: &str = "commit-yaml.yaml";
+pub const COMMIT_YAML_FAILS: &str = "commit-yaml-fails.yaml";
+
+// Used in configurable-retry-strategy-works
+pub const CONFIGURABLE_RETRY_STRATEGY_SUCCEEDS: &str = "configurable-retry-strategy-succeeds.txt";
+pub const CONFIGURABLE_RETRY_STRATEGY_EXHAUSTED: &str = "configurable-retry-strategy-exhausted.txt";
+
+// Used in create-new-strategy-works
+pub const CREATE_NEW_STRATEGY_RACE: &str = "create-new-strategy-race.txt";
+pub const CREATE_NEW_STRATEGY_EXISTS: &str = "create-new-strategy-exists.txt";
+
+// Used in cross-device-safe-strategy-works
+pub const CROSS_DEVICE_SAFE_STRATEGY_RENAMES: &str = "cross-device-safe-strategy-renames.txt";
+pub const CROSS_DEVICE_SAFE_STRATEGY_COPIES: &str = "cross-device-safe-strategy-copies.txt";
+pub const CROSS_DEVICE_SAFE_STRATEGY_FAILS: &str = "cross-device-safe-strategy-fails.txt";
+
+// Used in dense-writer-works
+pub const DENSE_WRITER: &str = "dense-writer.bin";
+pub const DENSE_WRITER_INSIDE: &str = "dense-writer-inside.bin";
+
+// Used in deterministic-names-works
+pub const DETERMINISTIC_NAMES: &str = "deterministic-names.txt";
+
+// Used in directory-phazer-works
+pub const DIRECTORY_PHAZER_NEW: &str = "directory-phazer-new";
+pub const DIRECTORY_PHAZER_REPLACE: &str = "directory-phazer-replace";
+pub const DIRECTORY_PHAZER_WORKING_PATH: &str = "directory-phazer-working-path";
+pub const DIRECTORY_PHAZER_DROP: &str = "directory-phazer-drop";
+
+// Used in discard-works
+pub const DISCARD_AFTER_WRITE: &str = "discard-after-write.txt";
+pub const DISCARD_FAILS: &str = "discard-fails.txt";
+pub const DISCARD_NOTHING_WRITTEN: &str = "discard-nothing-written.txt";
+
+// Used in dry-run-strategy-works
+pub const DRY_RUN_STRATEGY: &str = "dry-run-strategy.txt";
+pub const DRY_RUN_STRATEGY_NOTHING: &str = "dry-run-strategy-nothing.txt";
+pub const DRY_RUN_STRATEGY_DROP: &str = "dry-run-strategy-drop.txt";
+
+// Used in durable-rename-strategy-works
+pub const DURABLE_RENAME_STRATEGY_NEW: &str = "durable-rename-strategy-new.txt";
+pub const DURABLE_RENAME_STRATEGY_REPLACE: &str = "durable-rename-strategy-replace.txt";
+
+// Used in enforce-extension-works
+pub const ENFORCE_EXTENSION_MATCH: &str = "enforce-extension-match.json";
+pub const ENFORCE_EXTENSION_MISMATCH: &str = "enforce-extension-mismatch.toml";
+
+// Used in fallback-strategy-works
+pub const FALLBACK_STRATEGY_PRIMARY: &str = "fallback-strategy-primary.txt";
+pub const FALLBACK_STRATEGY_SECONDARY: &str = "fallback-strategy-secondary.txt";
+pub const FALLBACK_STRATEGY_BOTH_FAIL: &str = "fallback-strategy-both-fail.txt";
+
+// Used in follow-symlinks-works
+pub const FOLLOW_SYMLINKS: &str = "follow-symlinks";
+pub const FOLLOW_SYMLINKS_DEFAULT: &str = "follow-symlinks-default";
+
+// Used in for-each-in-dir-works
+pub const FOR_EACH_IN_DIR: &str = "for-each-in-dir";
+
+// Used in gunzip-writer-works
+pub const GUNZIP_WRITER_COMPLETE: &str = "gunzip-writer-complete.txt";
+pub const GUNZIP_WRITER_TRUNCATED: &str = "gunzip-writer-truncated.txt";
+
+// Used in gzip-writer-works
+pub const GZIP_WRITER_DROP: &str = "gzip-writer-drop.txt.gz";
+pub const GZIP_WRITER_FINISH: &str = "gzip-writer-finish.txt.gz";
+
+// Used in id-works
+pub const ID_FIRST: &str = "id-first.txt";
+pub const ID_SECOND: &str = "id-second.txt";
+
+// Used in interrupted-write-works
+pub const INTERRUPTED_WRITE: &str = "interrupted-write.txt";
+
+// Used in length-delimited-works
+pub const LENGTH_DELIMITED: &str = "length-delimited.bin";
+
+// Used in link-preserving-strategy-works
+pub const LINK_PRESERVING_LINKED: &str = "link-preserving-linked.txt";
+pub const LINK_PRESERVING_LINKED_OTHER: &str = "link-preserving-linked-other.txt";
+pub const LINK_PRESERVING_SINGLE: &str = "link-preserving-single.txt";
+
+// Used in min-commit-bytes-works
+pub const MIN_COMMIT_BYTES_TOO_SMALL: &str = "min-commit-bytes-too-small.txt";
+pub const MIN_COMMIT_BYTES_BIG_ENOUGH: &str = "min-commit-bytes-big-enough.txt";
+
+// Used in move-file-ex-strategy-works
+pub const MOVE_FILE_EX_STRATEGY_DEFAULT: &str = "move-file-ex-strategy-default.txt";
+pub const MOVE_FILE_EX_STRATEGY_WRITE_THROUGH: &str = "move-file-ex-strategy-write-through.txt";
+
+// Used in new-checked-works
+pub const NEW_CHECKED_DIRECTORY_TARGET: &str = "new-checked-directory-target";
+pub const NEW_CHECKED_FILE_PARENT: &str = "new-checked-file-parent.txt";
+pub const NEW_CHECKED_GOOD: &str = "new-checked-good.txt";
+
 // Used in no-writer-commit-works
 pub const NO_WRITER_COMMIT_DEFAULT: &str = "no-writer-commit-default.txt";
 pub const NO_WRITER_COMMIT_SIMPLE_RENAME: &str = "no-writer-commit-simple-rename.txt";
@@ -30,9 +246,185 @@ pub const ONE_WINS_IN_RACE_SIMPLE_WITH_RETRY: &str = "one-wins-in-race-simple-wi
 pub const ONE_WINS_IN_RACE_TOKIO_RENAME: &str = "one-wins-in-race-tokio-rename.txt";
 pub const ONE_WINS_IN_RACE_TOKIO_WITH_RETRY: &str = "one-wins-in-race-tokio-with-retry.txt";
 
+// Used in path-template-works
+pub const PATH_TEMPLATE_DIR: &str = "path-template";
+pub const PATH_TEMPLATE_UNKNOWN: &str = "path-template-unknown";
+
+// Used in phazer-error-works
+pub const PHAZER_ERROR_PREPARE: &str = "phazer-error-prepare.txt";
+pub const PHAZER_ERROR_RENAME: &str = "phazer-error-rename";
+pub const PHAZER_ERROR_AFTER_COMMIT: &str = "phazer-error-after-commit.txt";
+pub const PHAZER_ERROR_LOCK: &str = "phazer-error-lock.txt";
+pub const PHAZER_ERROR_MARKER: &str = "phazer-error-marker.txt";
+
 // Used in posix-read-only-works
 pub const POSIX_READ_ONLY_DEFAULT: &str = "posix-read-only-default.txt";
 
+// Used in preflight-target-works
+pub const PREFLIGHT_TARGET_DIRECTORY: &str = "preflight-target-directory";
+pub const PREFLIGHT_TARGET_GOOD: &str = "preflight-target-good.txt";
+pub const PREFLIGHT_TARGET_READ_ONLY: &str = "preflight-target-read-only.txt";
+
+// Used in release-barrier-works
+pub const RELEASE_BARRIER: &str = "release-barrier";
+
+// Used in remove-then-rename-strategy-works
+pub const REMOVE_THEN_RENAME_STRATEGY_OPEN: &str = "remove-then-rename-strategy-open.txt";
+
+// Used in replace-contents-strategy-works
+pub const REPLACE_CONTENTS_EXISTING: &str = "replace-contents-existing.txt";
+pub const REPLACE_CONTENTS_INODE: &str = "replace-contents-inode.txt";
+pub const REPLACE_CONTENTS_LINKED: &str = "replace-contents-linked.txt";
+pub const REPLACE_CONTENTS_MISSING: &str = "replace-contents-missing.txt";
+
+// Used in replace-file-strategy-works
+pub const REPLACE_FILE_STRATEGY_ATTRIBUTES: &str = "replace-file-strategy-attributes.txt";
+pub const REPLACE_FILE_STRATEGY_MISSING: &str = "replace-file-strategy-missing.txt";
+
+// Used in reset-id-counter-works
+pub const RESET_ID_COUNTER: &str = "reset-id-counter.txt";
+
+// Used in restore-selinux-context-works
+pub const RESTORE_SELINUX_CONTEXT: &str = "restore-selinux-context.txt";
+
+// Used in seed-from-target-works
+pub const SEED_FROM_TARGET: &str = "seed-from-target.txt";
+pub const SEED_FROM_TARGET_MISSING: &str = "seed-from-target-missing.txt";
+
+// Used in serialize-with-lock-works
+pub const SERIALIZE_WITH_LOCK: &str = "serialize-with-lock";
+
+// Used in set-mtime-works
+pub const SET_MTIME_NOW: &str = "set-mtime-now.txt";
+pub const SET_MTIME_EXACT: &str = "set-mtime-exact.txt";
+pub const SET_MTIME_FROM_TARGET: &str = "set-mtime-from-target.txt";
+pub const SET_MTIME_IN_PLACE: &str = "set-mtime-in-place.txt";
+pub const SET_MTIME_READ_ONLY: &str = "set-mtime-read-only.txt";
+
+// Used in simple-buffered-writer-works
+pub const SIMPLE_BUFFERED_WRITER_BYTES: &str = "simple-buffered-writer-bytes.txt";
+pub const SIMPLE_BUFFERED_WRITER_SEEK: &str = "simple-buffered-writer-seek.txt";
+
+// Used in simple-rename-watcher-events-works
+pub const SIMPLE_RENAME_WATCHER_EVENTS: &str = "simple-rename-watcher-events.txt";
+
+// Used in simple-tee-writer-works
+pub const SIMPLE_TEE_WRITER: &str = "simple-tee-writer.txt";
+pub const SIMPLE_TEE_WRITER_FAILS: &str = "simple-tee-writer-fails.txt";
+pub const SIMPLE_TEE_WRITER_PARTIAL: &str = "simple-tee-writer-partial.txt";
+
+// Used in size-budget-works
+pub const SIZE_BUDGET_FIRST: &str = "size-budget-first.txt";
+pub const SIZE_BUDGET_SECOND: &str = "size-budget-second.txt";
+pub const SIZE_BUDGET_TOKIO: &str = "size-budget-tokio.txt";
+
+// Used in strict-phazer-works
+pub const STRICT_PHAZER_COMMIT: &str = "strict-phazer-commit.txt";
+pub const STRICT_PHAZER_ABORT: &str = "strict-phazer-abort.txt";
+pub const STRICT_PHAZER_DROP: &str = "strict-phazer-drop.txt";
+
+// Used in swap-works
+pub const SWAP_A: &str = "swap-a.txt";
+pub const SWAP_B: &str = "swap-b.txt";
+pub const SWAP_PRESENT: &str = "swap-present.txt";
+pub const SWAP_MISSING: &str = "swap-missing.txt";
+
+// Used in sweep-stale-working-files-works
+pub const SWEEP_STALE_WORKING_FILES: &str = "sweep-stale-working-files";
+
+// Used in sync-mode-works
+pub const SYNC_MODE_DATA: &str = "sync-mode-data.txt";
+pub const SYNC_MODE_FULL: &str = "sync-mode-full.txt";
+pub const SYNC_MODE_FULL_WRITE_THROUGH: &str = "sync-mode-full-write-through.txt";
+
+// Used in tar-member-writer-works
+pub const TAR_MEMBER_WRITER_REPLACE: &str = "tar-member-writer-replace.tar";
+pub const TAR_MEMBER_WRITER_NEW: &str = "tar-member-writer-new.tar";
+pub const TAR_MEMBER_WRITER_UNFINISHED: &str = "tar-member-writer-unfinished.tar";
+pub const TAR_MEMBER_WRITER_EXTENSIONS: &str = "tar-member-writer-extensions.tar";
+
+// Used in text-writer-normalized-works
+pub const TEXT_WRITER_NORMALIZED_CRLF: &str = "text-writer-normalized-crlf.txt";
+pub const TEXT_WRITER_NORMALIZED_EMPTY: &str = "text-writer-normalized-empty.txt";
+pub const TEXT_WRITER_NORMALIZED_LF: &str = "text-writer-normalized-lf.txt";
+
+// Used in text-writer-works
+pub const TEXT_WRITER_UTF8_BOM: &str = "text-writer-utf8-bom.txt";
+pub const TEXT_WRITER_UTF16LE: &str = "text-writer-utf16le.txt";
+pub const TEXT_WRITER_INCOMPLETE: &str = "text-writer-incomplete.txt";
+pub const TEXT_WRITER_SECOND_WRITER: &str = "text-writer-second-writer.txt";
+
+// Used in tokio-write-vectored-works
+pub const TOKIO_WRITE_VECTORED: &str = "tokio-write-vectored.txt";
+
+// Used in tokio-writer-digest-works
+pub const TOKIO_WRITER_DIGEST: &str = "tokio-writer-digest.txt";
+
+// Used in touch-commit-works
+pub const TOUCH_COMMIT_EXISTING: &str = "touch-commit-existing.txt";
+pub const TOUCH_COMMIT_MISSING: &str = "touch-commit-missing.txt";
+
+// Used in tracing-works
+pub const TRACING_COMMIT: &str = "tracing-commit.txt";
+pub const TRACING_RETRY: &str = "tracing-retry.txt";
+
+// Used in trashing-strategy-works
+pub const TRASHING_STRATEGY_NO_TARGET: &str = "trashing-strategy-no-target.txt";
+
+// Used in update-json-works
+pub const UPDATE_JSON_EXISTING: &str = "update-json-existing.json";
+pub const UPDATE_JSON_MISSING: &str = "update-json-missing.json";
+pub const UPDATE_JSON_INVALID: &str = "update-json-invalid.json";
+
+// Used in uring-writer-works
+pub const URING_WRITER_WORKS: &str = "uring-writer-works.txt";
+pub const URING_WRITER_FULL: &str = "uring-writer-full.txt";
+pub const URING_WRITER_CANCELLED: &str = "uring-writer-cancelled.txt";
+
+// Used in verify-boundaries-works
+pub const VERIFY_BOUNDARIES_GOOD: &str = "verify-boundaries-good.txt";
+pub const VERIFY_BOUNDARIES_BAD: &str = "verify-boundaries-bad.txt";
+pub const VERIFY_BOUNDARIES_MISMATCH: &str = "verify-boundaries-mismatch.txt";
+
+// Used in was-written-works
+pub const WAS_WRITTEN_DEFAULT: &str = "was-written-default.txt";
+
+// Used in windows-best-effort-strategy-works
+pub const WINDOWS_BEST_EFFORT_STRATEGY_NEW: &str = "windows-best-effort-strategy-new.txt";
+pub const WINDOWS_BEST_EFFORT_STRATEGY_REPLACE: &str = "windows-best-effort-strategy-replace.txt";
+
+// Used in working-dir-works
+pub const WORKING_DIR: &str = "working-dir.txt";
+pub const WORKING_DIR_SUBDIR: &str = "working-dir-subdir";
+
+// Used in working-dir-works
+pub const WORKING_DIR_STAGING: &str = "working-dir-staging.txt";
+pub const WORKING_DIR_CROSS_DEVICE: &str = "working-dir-cross-device.txt";
+
+// Used in working-mode-works
+pub const WORKING_MODE_COPY_OF: &str = "working-mode-copy-of.txt";
+pub const WORKING_MODE_CREATE: &str = "working-mode-create.txt";
+pub const WORKING_MODE_EXISTING: &str = "working-mode-existing.txt";
+
+// Used in working-namer-works
+pub const WORKING_NAMER: &str = "working-namer.txt";
+pub const WORKING_NAMER_DROP: &str = "working-namer-drop.txt";
+pub const WORKING_NAMER_ALIAS: &str = "working-namer-alias.txt";
+
+// Used in working-subdir-works
+pub const WORKING_SUBDIR_CREATED: &str = "working-subdir-created";
+pub const WORKING_SUBDIR_EXISTING: &str = "working-subdir-existing";
+
+// Used in write-blocking-works
+pub const WRITE_BLOCKING_COMMIT: &str = "write-blocking-commit.txt";
+pub const WRITE_BLOCKING_ERROR: &str = "write-blocking-error.txt";
+
+// Used in write-buffering-works
+pub const WRITE_BUFFERING_SIMPLE: &str = "write-buffering-simple.txt";
+pub const WRITE_BUFFERING_TOKIO: &str = "write-buffering-tokio.txt";
+pub const WRITE_BUFFERING_TOKIO_UNFLUSHED: &str = "write-buffering-tokio-unflushed.txt";
+pub const WRITE_BUFFERING_TOKIO_DRAIN_FAILS: &str = "write-buffering-tokio-drain-fails.txt";
+
 // Used in write-commit-works
 pub const WRITE_COMMIT_SIMPLE_DEFAULT: &str = "write-commit-simple-default.txt";
 pub const WRITE_COMMIT_SIMPLE_RENAME: &str = "write-commit-simple-rename.txt";
@@ -66,3 +458,8 @@ pub const WRITE_NO_COMMIT_HAVE_TARGET_TOKIO_RENAME: &str =
     "write-no-commit-have-target-tokio-rename.txt";
 pub const WRITE_NO_COMMIT_HAVE_TARGET_TOKIO_WITH_RETRY: &str =
     "write-no-commit-have-target-tokio-with-retry.txt";
+
+// Used in zip-member-writer-works
+pub const ZIP_MEMBER_WRITER_REPLACE: &str = "zip-member-writer-replace.zip";
+pub const ZIP_MEMBER_WRITER_NEW: &str = "zip-member-writer-new.zip";
+pub const ZIP_MEMBER_WRITER_UNFINISHED: &str = "zip-member-writer-unfinished.zip";
@@ -0,0 +1,107 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::fs::{read_to_string, File, Metadata, OpenOptions};
+    use std::io::{ErrorKind, Write};
+    use std::path::Path;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use phazer::file_system::FileSystem;
+    use phazer::{Backoff, ConfigurableRetryStrategy, PhazerBuilder};
+
+    use crate::common::{
+        prepare_target_file, CONFIGURABLE_RETRY_STRATEGY_EXHAUSTED,
+        CONFIGURABLE_RETRY_STRATEGY_SUCCEEDS,
+    };
+
+    // Fails the first `failures` renames with permission denied then renames for real.
+    struct BusyFileSystem {
+        failures: usize,
+        renames: AtomicUsize,
+    }
+
+    impl FileSystem for BusyFileSystem {
+        fn rename(&self, from: &Path, to: &Path) -> std::io::Result<()> {
+            if self.renames.fetch_add(1, Ordering::Relaxed) < self.failures {
+                return Err(std::io::Error::from(ErrorKind::PermissionDenied));
+            }
+            std::fs::rename(from, to)
+        }
+        fn remove_file(&self, path: &Path) -> std::io::Result<()> {
+            std::fs::remove_file(path)
+        }
+        fn metadata(&self, path: &Path) -> std::io::Result<Metadata> {
+            std::fs::metadata(path)
+        }
+        fn open(&self, path: &Path, options: &OpenOptions) -> std::io::Result<File> {
+            options.open(path)
+        }
+    }
+
+    static QUICK: ConfigurableRetryStrategy = ConfigurableRetryStrategy::new()
+        .max_tries(9)
+        .base_millis(1)
+        .jitter_mask(0)
+        .backoff(Backoff::Exponential);
+
+    #[test]
+    fn configurable_retry_strategy_retries_past_seven() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(CONFIGURABLE_RETRY_STRATEGY_SUCCEEDS)?;
+        let file_system = BusyFileSystem {
+            failures: 8,
+            renames: AtomicUsize::new(0),
+        };
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .commit_strategy(&QUICK)
+            .file_system(&file_system)
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"eventually")?;
+        drop(w);
+        assert_eq!(p.commit_verbose()?, 9);
+        assert_eq!(read_to_string(&target_path)?, "eventually");
+        Ok(())
+    }
+
+    #[test]
+    fn configurable_retry_strategy_gives_up() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(CONFIGURABLE_RETRY_STRATEGY_EXHAUSTED)?;
+        let file_system = BusyFileSystem {
+            failures: usize::MAX,
+            renames: AtomicUsize::new(0),
+        };
+        let strategy = ConfigurableRetryStrategy::new()
+            .max_tries(3)
+            .base_millis(1)
+            .jitter_mask(0);
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .commit_strategy(&strategy)
+            .file_system(&file_system)
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"never")?;
+        drop(w);
+        let e = p.commit().unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::PermissionDenied);
+        assert_eq!(file_system.renames.load(Ordering::Relaxed), 3);
+        assert!(!target_path.exists());
+        Ok(())
+    }
+}
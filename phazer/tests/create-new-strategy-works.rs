@@ -0,0 +1,89 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::fs::{read_to_string, remove_file, write};
+    use std::io::{ErrorKind, Write};
+    use std::thread::scope;
+
+    use phazer::{PhazerBuilder, CREATE_NEW_STRATEGY};
+
+    use crate::common::{
+        prepare_target_file, CREATE_NEW_STRATEGY_EXISTS, CREATE_NEW_STRATEGY_RACE,
+    };
+
+    const CONTENTS: [&str; 10] = [
+        "first", "second", "third", "fourth", "fifth", "sixth", "seventh", "eighth", "ninth",
+        "tenth",
+    ];
+
+    #[test]
+    fn create_new_strategy_exactly_one_wins() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(CREATE_NEW_STRATEGY_RACE)?;
+
+        let results: Vec<std::io::Result<()>> = scope(|s| {
+            let handles: Vec<_> = CONTENTS
+                .iter()
+                .map(|content| {
+                    let tpc = target_path.clone();
+                    s.spawn(move || {
+                        let p = PhazerBuilder::with_target(tpc)
+                            .commit_strategy(CREATE_NEW_STRATEGY)
+                            .build();
+                        let mut w = p.simple_writer()?;
+                        w.write_all(content.as_bytes())?;
+                        drop(w);
+                        p.commit()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|h| h.join().expect("a thread failed to start"))
+                .collect()
+        });
+
+        assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 1);
+        for e in results.iter().filter_map(|r| r.as_ref().err()) {
+            assert_eq!(e.kind(), ErrorKind::AlreadyExists);
+        }
+        let s = read_to_string(&target_path)?;
+        assert!(CONTENTS.contains(&s.as_str()));
+        let _ = remove_file(&target_path);
+        Ok(())
+    }
+
+    #[test]
+    fn create_new_strategy_leaves_an_existing_target() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(CREATE_NEW_STRATEGY_EXISTS)?;
+        write(&target_path, "original")?;
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .commit_strategy(CREATE_NEW_STRATEGY)
+            .build();
+        let working_path = p.working_path().to_path_buf();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"replacement")?;
+        drop(w);
+        let e = p.commit().unwrap_err();
+
+        assert_eq!(e.kind(), ErrorKind::AlreadyExists);
+        assert_eq!(read_to_string(&target_path)?, "original");
+        assert!(!working_path.exists());
+        Ok(())
+    }
+}
@@ -0,0 +1,133 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::fs::{read_dir, read_to_string, write, File, Metadata, OpenOptions};
+    use std::io::{ErrorKind, Write};
+    use std::path::Path;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use phazer::file_system::{FileSystem, STD_FILE_SYSTEM};
+    use phazer::{PhazerBuilder, CROSS_DEVICE_SAFE_STRATEGY};
+
+    use crate::common::{
+        prepare_target_file, prepare_working_dir, CROSS_DEVICE_SAFE_STRATEGY_COPIES,
+        CROSS_DEVICE_SAFE_STRATEGY_FAILS, CROSS_DEVICE_SAFE_STRATEGY_RENAMES,
+    };
+
+    // Pretends the working file is on another filesystem; renaming a working file whose name
+    // does not end with "-copy" fails with CrossesDevices.  Renames are counted.
+    #[derive(Default)]
+    struct OtherDevice {
+        renames: AtomicUsize,
+        fail_copy: bool,
+    }
+
+    impl FileSystem for OtherDevice {
+        fn rename(&self, from: &Path, to: &Path) -> std::io::Result<()> {
+            self.renames.fetch_add(1, Ordering::Relaxed);
+            let copy = from.to_string_lossy().ends_with("-copy");
+            if !copy || self.fail_copy {
+                return Err(ErrorKind::CrossesDevices.into());
+            }
+            STD_FILE_SYSTEM.rename(from, to)
+        }
+        fn remove_file(&self, path: &Path) -> std::io::Result<()> {
+            STD_FILE_SYSTEM.remove_file(path)
+        }
+        fn metadata(&self, path: &Path) -> std::io::Result<Metadata> {
+            STD_FILE_SYSTEM.metadata(path)
+        }
+        fn open(&self, path: &Path, options: &OpenOptions) -> std::io::Result<File> {
+            STD_FILE_SYSTEM.open(path, options)
+        }
+    }
+
+    fn working_files_for(target: &str) -> Result<usize, std::io::Error> {
+        let mut count = 0;
+        for entry in read_dir(prepare_working_dir()?)? {
+            let name = entry?.file_name();
+            let name = name.to_string_lossy();
+            if name.starts_with(target) && name.contains("phazer-working") {
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    #[test]
+    fn cross_device_safe_strategy_renames() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(CROSS_DEVICE_SAFE_STRATEGY_RENAMES)?;
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .commit_strategy(CROSS_DEVICE_SAFE_STRATEGY)
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"same device")?;
+        drop(w);
+        p.commit()?;
+
+        assert_eq!(read_to_string(&target_path)?, "same device");
+        assert_eq!(working_files_for(CROSS_DEVICE_SAFE_STRATEGY_RENAMES)?, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn cross_device_safe_strategy_copies_across_devices() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(CROSS_DEVICE_SAFE_STRATEGY_COPIES)?;
+        write(&target_path, "original")?;
+        let fs = OtherDevice::default();
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .file_system(&fs)
+            .commit_strategy(CROSS_DEVICE_SAFE_STRATEGY)
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"other device")?;
+        drop(w);
+        p.commit()?;
+
+        assert_eq!(fs.renames.load(Ordering::Relaxed), 2);
+        assert_eq!(read_to_string(&target_path)?, "other device");
+        assert_eq!(working_files_for(CROSS_DEVICE_SAFE_STRATEGY_COPIES)?, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn cross_device_safe_strategy_removes_a_failed_copy() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(CROSS_DEVICE_SAFE_STRATEGY_FAILS)?;
+        write(&target_path, "original")?;
+        let fs = OtherDevice {
+            fail_copy: true,
+            ..Default::default()
+        };
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .file_system(&fs)
+            .commit_strategy(CROSS_DEVICE_SAFE_STRATEGY)
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"other device")?;
+        drop(w);
+        let e = p.commit().unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::CrossesDevices);
+
+        assert_eq!(read_to_string(&target_path)?, "original");
+        assert_eq!(working_files_for(CROSS_DEVICE_SAFE_STRATEGY_FAILS)?, 0);
+        Ok(())
+    }
+}
@@ -0,0 +1,60 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::io::{Seek, SeekFrom, Write};
+
+    use phazer::Phazer;
+
+    use crate::common::{prepare_target_file, DENSE_WRITER, DENSE_WRITER_INSIDE};
+
+    #[test]
+    fn dense_writer_zero_fills_the_gap() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(DENSE_WRITER)?;
+
+        let p = Phazer::new(&target_path);
+        let mut w = p.simple_writer()?.dense(true);
+        w.write_all(b"head")?;
+        assert_eq!(w.seek(SeekFrom::Start(8192))?, 8192);
+        w.write_all(b"tail")?;
+        drop(w);
+        p.commit()?;
+
+        let content = std::fs::read(&target_path)?;
+        assert_eq!(content.len(), 8196);
+        assert_eq!(&content[..4], b"head");
+        assert!(content[4..8192].iter().all(|b| *b == 0));
+        assert_eq!(&content[8192..], b"tail");
+        Ok(())
+    }
+
+    #[test]
+    fn dense_writer_seeking_inside_the_file_does_not_fill() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(DENSE_WRITER_INSIDE)?;
+
+        let p = Phazer::new(&target_path);
+        let mut w = p.simple_writer()?.dense(true);
+        w.write_all(b"0123456789")?;
+        w.seek(SeekFrom::Start(2))?;
+        w.write_all(b"ab")?;
+        drop(w);
+        p.commit()?;
+
+        assert_eq!(std::fs::read(&target_path)?, b"01ab456789");
+        Ok(())
+    }
+}
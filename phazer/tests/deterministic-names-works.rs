@@ -0,0 +1,45 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(all(feature = "deterministic_names", feature = "simple"))]
+mod simple {
+    use std::fs::read_to_string;
+    use std::io::Write;
+
+    use phazer::PhazerBuilder;
+
+    use crate::common::{prepare_target_file, DETERMINISTIC_NAMES};
+
+    #[test]
+    fn working_name_tag_is_used() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(DETERMINISTIC_NAMES)?;
+        let working_path =
+            target_path.with_extension("txt.phazer-working-working_name_tag_is_used");
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .working_name_tag("working_name_tag_is_used")
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"predictable")?;
+        drop(w);
+        assert_eq!(read_to_string(&working_path)?, "predictable");
+        p.commit()?;
+
+        assert!(!working_path.exists());
+        assert_eq!(read_to_string(&target_path)?, "predictable");
+        Ok(())
+    }
+}
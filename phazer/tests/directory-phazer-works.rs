@@ -0,0 +1,117 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+use std::fs::{create_dir_all, read_dir, read_to_string, remove_dir_all, write};
+use std::path::Path;
+
+use phazer::directory::DirectoryPhazer;
+
+use crate::common::{
+    prepare_working_dir, DIRECTORY_PHAZER_DROP, DIRECTORY_PHAZER_NEW, DIRECTORY_PHAZER_REPLACE,
+    DIRECTORY_PHAZER_WORKING_PATH,
+};
+
+// Return the number of entries in `dir`.
+fn entries(dir: &Path) -> usize {
+    read_dir(dir).unwrap().count()
+}
+
+#[test]
+fn directory_phazer_creates_a_new_tree() -> Result<(), std::io::Error> {
+    let parent = prepare_working_dir()?.join(DIRECTORY_PHAZER_NEW);
+    let _ = remove_dir_all(&parent);
+    create_dir_all(&parent)?;
+    let target_dir = parent.join("site");
+
+    let mut p = DirectoryPhazer::new(&target_dir);
+    let staging = p.staging_dir()?.to_path_buf();
+    create_dir_all(staging.join("css"))?;
+    write(staging.join("index.html"), "index")?;
+    write(staging.join("css").join("site.css"), "css")?;
+    assert!(!target_dir.exists());
+    p.commit()?;
+
+    assert_eq!(read_to_string(target_dir.join("index.html"))?, "index");
+    assert_eq!(
+        read_to_string(target_dir.join("css").join("site.css"))?,
+        "css"
+    );
+    // Only the target remains
+    assert_eq!(entries(&parent), 1);
+    let _ = remove_dir_all(&parent);
+    Ok(())
+}
+
+#[test]
+fn directory_phazer_replaces_an_existing_tree() -> Result<(), std::io::Error> {
+    let parent = prepare_working_dir()?.join(DIRECTORY_PHAZER_REPLACE);
+    let _ = remove_dir_all(&parent);
+    let target_dir = parent.join("site");
+    create_dir_all(&target_dir)?;
+    write(target_dir.join("old.html"), "old")?;
+
+    let mut p = DirectoryPhazer::new(&target_dir);
+    write(p.staging_dir()?.join("new.html"), "new")?;
+    p.commit()?;
+
+    assert!(!target_dir.join("old.html").exists());
+    assert_eq!(read_to_string(target_dir.join("new.html"))?, "new");
+    // The old tree was removed
+    assert_eq!(entries(&parent), 1);
+    let _ = remove_dir_all(&parent);
+    Ok(())
+}
+
+#[test]
+fn directory_phazer_drop_removes_the_staging_dir() -> Result<(), std::io::Error> {
+    let parent = prepare_working_dir()?.join(DIRECTORY_PHAZER_DROP);
+    let _ = remove_dir_all(&parent);
+    let target_dir = parent.join("site");
+    create_dir_all(&target_dir)?;
+    write(target_dir.join("old.html"), "old")?;
+
+    let mut p = DirectoryPhazer::new(&target_dir);
+    let staging = p.staging_dir()?.to_path_buf();
+    write(staging.join("partial.html"), "partial")?;
+    drop(p);
+
+    assert!(!staging.exists());
+    assert_eq!(read_to_string(target_dir.join("old.html"))?, "old");
+    assert_eq!(entries(&parent), 1);
+    let _ = remove_dir_all(&parent);
+    Ok(())
+}
+
+#[test]
+fn directory_phazer_working_path_is_the_staging_dir() -> Result<(), std::io::Error> {
+    let parent = prepare_working_dir()?.join(DIRECTORY_PHAZER_WORKING_PATH);
+    let _ = remove_dir_all(&parent);
+    create_dir_all(&parent)?;
+    let target_dir = parent.join("site");
+
+    let mut p = DirectoryPhazer::new(&target_dir);
+    let working_path = p.working_path().to_path_buf();
+    assert!(!working_path.exists());
+    assert_eq!(p.staging_dir()?, working_path);
+    assert!(working_path.is_dir());
+    write(working_path.join("index.html"), "index")?;
+    p.commit()?;
+
+    assert!(!working_path.exists());
+    assert_eq!(read_to_string(target_dir.join("index.html"))?, "index");
+    let _ = remove_dir_all(&parent);
+    Ok(())
+}
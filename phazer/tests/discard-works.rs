@@ -0,0 +1,104 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::fs::{read_dir, File, Metadata, OpenOptions};
+    use std::io::Write;
+    use std::path::Path;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use phazer::file_system::{FileSystem, STD_FILE_SYSTEM};
+    use phazer::{Phazer, PhazerBuilder};
+
+    use crate::common::{
+        prepare_target_file, prepare_working_dir, DISCARD_AFTER_WRITE, DISCARD_FAILS,
+        DISCARD_NOTHING_WRITTEN,
+    };
+
+    // Fails the first `remove_file` then behaves like the Standard Library.
+    #[derive(Default)]
+    struct FailFirstRemove {
+        failed: AtomicBool,
+    }
+
+    impl FileSystem for FailFirstRemove {
+        fn rename(&self, from: &Path, to: &Path) -> std::io::Result<()> {
+            STD_FILE_SYSTEM.rename(from, to)
+        }
+        fn remove_file(&self, path: &Path) -> std::io::Result<()> {
+            if self.failed.swap(true, Ordering::Relaxed) {
+                STD_FILE_SYSTEM.remove_file(path)
+            } else {
+                Err(std::io::Error::other("remove_file failed"))
+            }
+        }
+        fn metadata(&self, path: &Path) -> std::io::Result<Metadata> {
+            STD_FILE_SYSTEM.metadata(path)
+        }
+        fn open(&self, path: &Path, options: &OpenOptions) -> std::io::Result<File> {
+            STD_FILE_SYSTEM.open(path, options)
+        }
+    }
+
+    fn working_files_for(target: &str) -> Result<usize, std::io::Error> {
+        let mut count = 0;
+        for entry in read_dir(prepare_working_dir()?)? {
+            let name = entry?.file_name();
+            let name = name.to_string_lossy();
+            if name.starts_with(target) && name.contains("phazer-working") {
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    #[test]
+    fn discard_removes_the_working_file() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(DISCARD_AFTER_WRITE)?;
+        let p = Phazer::new(&target_path);
+        let mut w = p.simple_writer()?;
+        w.write_all(b"never committed")?;
+        drop(w);
+        assert_eq!(working_files_for(DISCARD_AFTER_WRITE)?, 1);
+        p.discard()?;
+        assert_eq!(working_files_for(DISCARD_AFTER_WRITE)?, 0);
+        assert!(!target_path.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn discard_without_a_working_file_works() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(DISCARD_NOTHING_WRITTEN)?;
+        Phazer::new(target_path).discard()
+    }
+
+    #[test]
+    fn discard_reports_a_failure() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(DISCARD_FAILS)?;
+        let fs = FailFirstRemove::default();
+        let p = PhazerBuilder::with_target(&target_path)
+            .file_system(&fs)
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"never committed")?;
+        drop(w);
+        assert!(p.discard().is_err());
+        // The implicit drop cleans up after the failed discard
+        assert_eq!(working_files_for(DISCARD_FAILS)?, 0);
+        Ok(())
+    }
+}
@@ -0,0 +1,90 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::fs::{read_to_string, remove_file, write};
+    use std::io::Write;
+
+    use phazer::file_system::STD_FILE_SYSTEM;
+    use phazer::{CommitStrategy, DryRunStrategy, PhazerBuilder};
+
+    use crate::common::{
+        prepare_target_file, MockCommitDetails, DRY_RUN_STRATEGY, DRY_RUN_STRATEGY_DROP,
+        DRY_RUN_STRATEGY_NOTHING,
+    };
+
+    #[test]
+    fn dry_run_strategy_leaves_the_target_alone() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(DRY_RUN_STRATEGY)?;
+        write(&target_path, "real")?;
+        let dry_run = DryRunStrategy::new();
+
+        for content in ["first", "second"] {
+            let p = PhazerBuilder::with_target(&target_path)
+                .commit_strategy(&dry_run)
+                .build();
+            let working_path = p.working_path().to_path_buf();
+            let mut w = p.simple_writer()?;
+            w.write_all(content.as_bytes())?;
+            drop(w);
+            p.commit()?;
+            assert!(!working_path.exists());
+        }
+
+        assert!(dry_run.was_committed());
+        assert_eq!(dry_run.commit_count(), 2);
+        assert_eq!(dry_run.last_content().as_deref(), Some(&b"second"[..]));
+        assert_eq!(read_to_string(&target_path)?, "real");
+        Ok(())
+    }
+
+    #[test]
+    fn dry_run_strategy_leaves_the_working_file_for_drop() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(DRY_RUN_STRATEGY_DROP)?;
+        let working_path = target_path.with_extension("phazer-working-dry-run");
+        write(&working_path, "kept")?;
+        let dry_run = DryRunStrategy::new();
+
+        dry_run.commit(&MockCommitDetails::new(
+            STD_FILE_SYSTEM,
+            &working_path,
+            &target_path,
+        ))?;
+
+        // Removing the working file is left to the Phazer's drop
+        assert_eq!(read_to_string(&working_path)?, "kept");
+        assert!(!target_path.exists());
+        remove_file(&working_path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn dry_run_strategy_without_a_working_file() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(DRY_RUN_STRATEGY_NOTHING)?;
+        let dry_run = DryRunStrategy::new();
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .commit_strategy(&dry_run)
+            .build();
+        p.commit()?;
+
+        assert!(!dry_run.was_committed());
+        assert_eq!(dry_run.last_content(), None);
+        assert!(!target_path.exists());
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::fs::{read_to_string, write};
+    use std::io::Write;
+
+    use phazer::{PhazerBuilder, DURABLE_RENAME_STRATEGY};
+
+    use crate::common::{
+        prepare_target_file, DURABLE_RENAME_STRATEGY_NEW, DURABLE_RENAME_STRATEGY_REPLACE,
+    };
+
+    #[test]
+    fn durable_rename_strategy_creates_the_target() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(DURABLE_RENAME_STRATEGY_NEW)?;
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .commit_strategy(DURABLE_RENAME_STRATEGY)
+            .build();
+        let working_path = p.working_path().to_path_buf();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"survives a power loss")?;
+        drop(w);
+        p.commit()?;
+
+        assert!(!working_path.exists());
+        assert_eq!(read_to_string(&target_path)?, "survives a power loss");
+        Ok(())
+    }
+
+    #[test]
+    fn durable_rename_strategy_replaces_the_target() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(DURABLE_RENAME_STRATEGY_REPLACE)?;
+        write(&target_path, "original")?;
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .commit_strategy(DURABLE_RENAME_STRATEGY)
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"replacement")?;
+        drop(w);
+        p.commit()?;
+
+        assert_eq!(read_to_string(&target_path)?, "replacement");
+        Ok(())
+    }
+}
@@ -0,0 +1,53 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs::{read_to_string, write};
+use std::io::ErrorKind;
+
+use common::{prepare_target_file, ENFORCE_EXTENSION_MATCH, ENFORCE_EXTENSION_MISMATCH};
+use phazer::PhazerBuilder;
+
+mod common;
+
+#[test]
+fn enforce_extension_allows_a_match() -> Result<(), std::io::Error> {
+    let target_path = prepare_target_file(ENFORCE_EXTENSION_MATCH)?;
+    let source_path = target_path.with_extension("source");
+    write(&source_path, "{}")?;
+    let p = PhazerBuilder::with_target(&target_path)
+        .enforce_extension(".json")
+        .build();
+    p.commit_copy_of(&source_path)?;
+    assert_eq!(read_to_string(&target_path)?, "{}");
+    Ok(())
+}
+
+#[test]
+fn enforce_extension_rejects_a_mismatch() -> Result<(), std::io::Error> {
+    let target_path = prepare_target_file(ENFORCE_EXTENSION_MISMATCH)?;
+    let p = PhazerBuilder::new()
+        .enforce_extension("json")
+        .target(&target_path)
+        .build();
+    match p.commit() {
+        Ok(()) => Err(std::io::Error::other(
+            "a mismatched extension must be rejected",
+        )),
+        Err(e) => {
+            assert_eq!(e.kind(), ErrorKind::InvalidInput);
+            assert!(!target_path.exists());
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,105 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::io::{ErrorKind, Write};
+
+    use phazer::{
+        CommitDetails, CommitStrategy, FallbackStrategy, PhazerBuilder, SIMPLE_RENAME_STRATEGY,
+    };
+
+    use crate::common::{
+        prepare_target_file, FALLBACK_STRATEGY_BOTH_FAIL, FALLBACK_STRATEGY_PRIMARY,
+        FALLBACK_STRATEGY_SECONDARY,
+    };
+
+    // Always fails with the specified error kind
+    struct FailingStrategy {
+        kind: ErrorKind,
+    }
+
+    impl CommitStrategy for FailingStrategy {
+        fn commit(&self, _phazer: &dyn CommitDetails) -> std::io::Result<()> {
+            Err(std::io::Error::from(self.kind))
+        }
+    }
+
+    fn write_and_commit(
+        target_path: &std::path::Path,
+        strategy: &dyn CommitStrategy,
+    ) -> std::io::Result<()> {
+        let p = PhazerBuilder::with_target(target_path)
+            .commit_strategy(strategy)
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"fallback")?;
+        drop(w);
+        p.commit()
+    }
+
+    #[test]
+    fn fallback_strategy_uses_the_primary() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(FALLBACK_STRATEGY_PRIMARY)?;
+        let secondary = FailingStrategy {
+            kind: ErrorKind::Unsupported,
+        };
+
+        write_and_commit(
+            &target_path,
+            &FallbackStrategy::new(SIMPLE_RENAME_STRATEGY, &secondary),
+        )?;
+
+        assert_eq!(std::fs::read_to_string(&target_path)?, "fallback");
+        Ok(())
+    }
+
+    #[test]
+    fn fallback_strategy_falls_back_to_the_secondary() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(FALLBACK_STRATEGY_SECONDARY)?;
+        let primary = FailingStrategy {
+            kind: ErrorKind::Unsupported,
+        };
+
+        write_and_commit(
+            &target_path,
+            &FallbackStrategy::new(&primary, SIMPLE_RENAME_STRATEGY),
+        )?;
+
+        assert_eq!(std::fs::read_to_string(&target_path)?, "fallback");
+        Ok(())
+    }
+
+    #[test]
+    fn fallback_strategy_returns_the_primary_error() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(FALLBACK_STRATEGY_BOTH_FAIL)?;
+        let primary = FailingStrategy {
+            kind: ErrorKind::Unsupported,
+        };
+        let secondary = FailingStrategy {
+            kind: ErrorKind::PermissionDenied,
+        };
+
+        match write_and_commit(&target_path, &FallbackStrategy::new(&primary, &secondary)) {
+            Ok(()) => Err(std::io::Error::other("both strategies should fail")),
+            Err(e) => {
+                assert_eq!(e.kind(), ErrorKind::Unsupported);
+                assert!(!target_path.exists());
+                Ok(())
+            }
+        }
+    }
+}
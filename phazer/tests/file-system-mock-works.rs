@@ -0,0 +1,152 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+use std::fs::{File, Metadata, OpenOptions};
+use std::io::ErrorKind;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use phazer::file_system::FileSystem;
+use phazer::{CommitStrategy, RENAME_WITH_RETRY_STRATEGY, SIMPLE_RENAME_STRATEGY};
+
+use crate::common::MockCommitDetails;
+
+// Fails the first `failures` renames with `kind` (or `raw` if set) then succeeds.
+struct MockFileSystem {
+    failures: usize,
+    kind: ErrorKind,
+    raw: Option<i32>,
+    renames: AtomicUsize,
+}
+
+impl MockFileSystem {
+    fn new(failures: usize, kind: ErrorKind) -> Self {
+        Self {
+            failures,
+            kind,
+            raw: None,
+            renames: AtomicUsize::new(0),
+        }
+    }
+    #[allow(dead_code)]
+    fn with_raw_os_error(failures: usize, raw: i32) -> Self {
+        Self {
+            failures,
+            kind: ErrorKind::Other,
+            raw: Some(raw),
+            renames: AtomicUsize::new(0),
+        }
+    }
+    fn renames(&self) -> usize {
+        self.renames.load(Ordering::Relaxed)
+    }
+}
+
+impl FileSystem for MockFileSystem {
+    fn rename(&self, _from: &Path, _to: &Path) -> std::io::Result<()> {
+        let previous = self.renames.fetch_add(1, Ordering::Relaxed);
+        if previous < self.failures {
+            match self.raw {
+                Some(raw) => Err(std::io::Error::from_raw_os_error(raw)),
+                None => Err(std::io::Error::from(self.kind)),
+            }
+        } else {
+            Ok(())
+        }
+    }
+    fn remove_file(&self, _path: &Path) -> std::io::Result<()> {
+        Ok(())
+    }
+    fn metadata(&self, path: &Path) -> std::io::Result<Metadata> {
+        std::fs::metadata(path)
+    }
+    fn open(&self, _path: &Path, _options: &OpenOptions) -> std::io::Result<File> {
+        Err(std::io::Error::from(ErrorKind::Unsupported))
+    }
+}
+
+// The paths are never touched; MockFileSystem ignores them.
+const WORKING: &str = "mock.phazer-working";
+const TARGET: &str = "mock";
+
+fn commit_using(
+    strategy: &dyn CommitStrategy,
+    failures: usize,
+    kind: ErrorKind,
+) -> (std::io::Result<()>, usize) {
+    let fs = MockFileSystem::new(failures, kind);
+    let rv = strategy.commit(&MockCommitDetails::new(&fs, WORKING, TARGET));
+    (rv, fs.renames())
+}
+
+#[test]
+fn simple_rename_tries_once() {
+    let (rv, renames) = commit_using(SIMPLE_RENAME_STRATEGY, 1, ErrorKind::PermissionDenied);
+    assert_eq!(rv.unwrap_err().kind(), ErrorKind::PermissionDenied);
+    assert_eq!(renames, 1);
+}
+
+#[test]
+fn rename_with_retry_recovers_from_permission_denied() {
+    let (rv, renames) = commit_using(RENAME_WITH_RETRY_STRATEGY, 3, ErrorKind::PermissionDenied);
+    assert!(rv.is_ok());
+    assert_eq!(renames, 4);
+}
+
+#[test]
+fn rename_with_retry_gives_up_after_seven_tries() {
+    let (rv, renames) = commit_using(RENAME_WITH_RETRY_STRATEGY, 100, ErrorKind::PermissionDenied);
+    assert_eq!(rv.unwrap_err().kind(), ErrorKind::PermissionDenied);
+    assert_eq!(renames, 7);
+}
+
+#[test]
+fn rename_with_retry_does_not_retry_other_errors() {
+    let (rv, renames) = commit_using(RENAME_WITH_RETRY_STRATEGY, 1, ErrorKind::NotFound);
+    assert_eq!(rv.unwrap_err().kind(), ErrorKind::NotFound);
+    assert_eq!(renames, 1);
+}
+
+// ERROR_SHARING_VIOLATION is transient; keep trying
+#[cfg(windows)]
+#[test]
+fn rename_with_retry_retries_sharing_violation() {
+    let fs = MockFileSystem::with_raw_os_error(2, 32);
+    let rv = RENAME_WITH_RETRY_STRATEGY.commit(&MockCommitDetails::new(&fs, WORKING, TARGET));
+    assert!(rv.is_ok());
+    assert_eq!(fs.renames(), 3);
+}
+
+// ERROR_ACCESS_DENIED from a directory in the way of the target is not transient; fail fast
+#[cfg(windows)]
+#[test]
+fn rename_with_retry_does_not_retry_access_denied_for_a_directory() {
+    let fs = MockFileSystem::with_raw_os_error(2, 5);
+    let details = MockCommitDetails::new(&fs, WORKING, std::env::temp_dir());
+    let rv = RENAME_WITH_RETRY_STRATEGY.commit(&details);
+    assert_eq!(rv.unwrap_err().raw_os_error(), Some(5));
+    assert_eq!(fs.renames(), 1);
+}
+
+// ERROR_ACCESS_DENIED for a file target is usually contention; keep trying
+#[cfg(windows)]
+#[test]
+fn rename_with_retry_retries_access_denied_for_a_missing_target() {
+    let fs = MockFileSystem::with_raw_os_error(2, 5);
+    let rv = RENAME_WITH_RETRY_STRATEGY.commit(&MockCommitDetails::new(&fs, WORKING, TARGET));
+    assert!(rv.is_ok());
+    assert_eq!(fs.renames(), 3);
+}
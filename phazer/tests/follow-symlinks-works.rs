@@ -0,0 +1,88 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(all(unix, feature = "simple"))]
+mod simple {
+    use std::fs::{create_dir_all, read_link, read_to_string, remove_dir_all, write};
+    use std::io::Write;
+    use std::os::unix::fs::symlink;
+
+    use phazer::PhazerBuilder;
+
+    use crate::common::{prepare_working_dir, FOLLOW_SYMLINKS, FOLLOW_SYMLINKS_DEFAULT};
+
+    #[test]
+    fn follow_symlinks_updates_the_linked_file() -> Result<(), std::io::Error> {
+        let dir = prepare_working_dir()?.join(FOLLOW_SYMLINKS);
+        let _ = remove_dir_all(&dir);
+        create_dir_all(dir.join("v2"))?;
+        write(dir.join("v2/app.conf"), "old")?;
+        // app.conf -> current.conf -> v2/app.conf
+        symlink("v2/app.conf", dir.join("current.conf"))?;
+        symlink("current.conf", dir.join("app.conf"))?;
+        // A dangling link is followed too
+        symlink("v2/new.conf", dir.join("new.conf"))?;
+
+        let p = PhazerBuilder::with_target(dir.join("app.conf"))
+            .follow_symlinks(true)
+            .build();
+        assert_eq!(p.target_path(), dir.join("v2/app.conf"));
+        assert!(p.working_path().starts_with(dir.join("v2")));
+        let mut w = p.simple_writer()?;
+        w.write_all(b"new")?;
+        drop(w);
+        p.commit()?;
+        assert_eq!(read_to_string(dir.join("v2/app.conf"))?, "new");
+        assert_eq!(
+            read_link(dir.join("app.conf"))?,
+            std::path::Path::new("current.conf")
+        );
+
+        let p = PhazerBuilder::with_target(dir.join("new.conf"))
+            .follow_symlinks(true)
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"created")?;
+        drop(w);
+        p.commit()?;
+        assert_eq!(read_to_string(dir.join("v2/new.conf"))?, "created");
+        assert!(read_link(dir.join("new.conf")).is_ok());
+
+        let _ = remove_dir_all(&dir);
+        Ok(())
+    }
+
+    #[test]
+    fn by_default_the_link_is_replaced() -> Result<(), std::io::Error> {
+        let dir = prepare_working_dir()?.join(FOLLOW_SYMLINKS_DEFAULT);
+        let _ = remove_dir_all(&dir);
+        create_dir_all(&dir)?;
+        write(dir.join("real.conf"), "old")?;
+        symlink("real.conf", dir.join("app.conf"))?;
+
+        let p = PhazerBuilder::with_target(dir.join("app.conf")).build();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"new")?;
+        drop(w);
+        p.commit()?;
+        assert!(read_link(dir.join("app.conf")).is_err());
+        assert_eq!(read_to_string(dir.join("app.conf"))?, "new");
+        assert_eq!(read_to_string(dir.join("real.conf"))?, "old");
+
+        let _ = remove_dir_all(&dir);
+        Ok(())
+    }
+}
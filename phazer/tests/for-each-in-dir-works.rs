@@ -0,0 +1,59 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::fs::{create_dir_all, read_to_string, remove_dir_all, write};
+    use std::io::Write;
+
+    use phazer::{Phazer, SIMPLE_RENAME_STRATEGY};
+
+    use crate::common::{prepare_working_dir, FOR_EACH_IN_DIR};
+
+    #[test]
+    fn for_each_in_dir_rewrites_every_file() -> Result<(), std::io::Error> {
+        let dir = prepare_working_dir()?.join(FOR_EACH_IN_DIR);
+        let _ = remove_dir_all(&dir);
+        create_dir_all(dir.join("not-a-file"))?;
+        write(dir.join("one.txt"), "one")?;
+        write(dir.join("two.txt"), "two")?;
+        write(dir.join("three"), "three")?;
+        // Looks like a working file left behind by a crash
+        write(dir.join("four.txt.phazer-working-1-2"), "four")?;
+
+        let mut count = 0;
+        for phazer in Phazer::for_each_in_dir(&dir, SIMPLE_RENAME_STRATEGY)? {
+            let phazer = phazer?;
+            let text = read_to_string(phazer.target_path())?;
+            let mut w = phazer.simple_writer()?;
+            w.write_all(text.to_uppercase().as_bytes())?;
+            drop(w);
+            phazer.commit()?;
+            count += 1;
+        }
+        assert_eq!(count, 3);
+        assert_eq!(read_to_string(dir.join("one.txt"))?, "ONE");
+        assert_eq!(read_to_string(dir.join("two.txt"))?, "TWO");
+        assert_eq!(read_to_string(dir.join("three"))?, "THREE");
+        assert_eq!(
+            read_to_string(dir.join("four.txt.phazer-working-1-2"))?,
+            "four"
+        );
+
+        let _ = remove_dir_all(&dir);
+        Ok(())
+    }
+}
@@ -0,0 +1,66 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "gzip")]
+mod gzip {
+    use std::io::{ErrorKind, Write};
+
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use phazer::Phazer;
+
+    use crate::common::{prepare_target_file, GUNZIP_WRITER_COMPLETE, GUNZIP_WRITER_TRUNCATED};
+
+    fn compress(data: &[u8]) -> Result<Vec<u8>, std::io::Error> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data)?;
+        encoder.finish()
+    }
+
+    #[test]
+    fn gunzip_writer_works() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(GUNZIP_WRITER_COMPLETE)?;
+        let compressed = compress(b"stored decompressed")?;
+
+        let p = Phazer::new(&target_path);
+        let mut w = p.gunzip_writer()?;
+        w.write_all(&compressed)?;
+        w.finish()?;
+        p.commit()?;
+
+        assert_eq!(std::fs::read(&target_path)?, b"stored decompressed");
+        Ok(())
+    }
+
+    #[test]
+    fn gunzip_writer_rejects_a_truncated_stream() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(GUNZIP_WRITER_TRUNCATED)?;
+        let compressed = compress(b"this stream will be cut short")?;
+
+        let p = Phazer::new(&target_path);
+        let mut w = p.gunzip_writer()?;
+        w.write_all(&compressed[..compressed.len() - 4])?;
+        assert!(w.finish().is_err());
+        match p.commit() {
+            Ok(()) => Err(std::io::Error::other("a truncated stream must not commit")),
+            Err(e) => {
+                assert_eq!(e.kind(), ErrorKind::InvalidData);
+                assert!(!target_path.exists());
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,63 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "gzip")]
+mod gzip {
+    use std::fs::File;
+    use std::io::{Read, Write};
+    use std::path::Path;
+
+    use flate2::read::GzDecoder;
+    use phazer::Phazer;
+
+    use crate::common::{prepare_target_file, GZIP_WRITER_DROP, GZIP_WRITER_FINISH};
+
+    fn decompress(path: &Path) -> Result<String, std::io::Error> {
+        let mut s = String::new();
+        GzDecoder::new(File::open(path)?).read_to_string(&mut s)?;
+        Ok(s)
+    }
+
+    #[test]
+    fn gzip_writer_finishes_on_drop() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(GZIP_WRITER_DROP)?;
+        let data = "stored compressed ".repeat(100);
+
+        let p = Phazer::new(&target_path);
+        let mut w = p.gzip_writer(9)?;
+        w.write_all(data.as_bytes())?;
+        drop(w);
+        p.commit()?;
+
+        assert!(std::fs::metadata(&target_path)?.len() < data.len() as u64);
+        assert_eq!(decompress(&target_path)?, data);
+        Ok(())
+    }
+
+    #[test]
+    fn gzip_writer_finish_works() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(GZIP_WRITER_FINISH)?;
+
+        let p = Phazer::new(&target_path);
+        let mut w = p.gzip_writer(0)?;
+        w.write_all(b"not really compressed")?;
+        w.finish()?;
+        p.commit()?;
+
+        assert_eq!(decompress(&target_path)?, "not really compressed");
+        Ok(())
+    }
+}
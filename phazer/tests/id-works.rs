@@ -0,0 +1,37 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+use phazer::{Phazer, PhazerBuilder};
+
+use crate::common::{prepare_target_file, ID_FIRST, ID_SECOND};
+
+#[test]
+fn id_is_unique_and_stable() -> Result<(), std::io::Error> {
+    let first_path = prepare_target_file(ID_FIRST)?;
+    let second_path = prepare_target_file(ID_SECOND)?;
+
+    let first = Phazer::new(&first_path);
+    let second = PhazerBuilder::with_target(&second_path).build();
+    assert_ne!(first.id(), second.id());
+    assert_eq!(first.id(), first.id());
+    assert_eq!(first.target_path(), first_path);
+    assert_eq!(second.target_path(), second_path);
+
+    // Without a name tag the id is part of the working file's name
+    let name = first.working_path().to_string_lossy().into_owned();
+    assert!(name.ends_with(&format!("-{}", first.id())));
+    Ok(())
+}
@@ -0,0 +1,133 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(all(feature = "simple", target_os = "linux"))]
+mod simple {
+    use std::ffi::CString;
+    use std::fs::{File, Metadata, OpenOptions};
+    use std::io::{ErrorKind, Read, Write};
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::fs::OpenOptionsExt;
+    use std::os::unix::thread::JoinHandleExt;
+    use std::path::{Path, PathBuf};
+    use std::time::Duration;
+
+    use phazer::file_system::{FileSystem, STD_FILE_SYSTEM};
+    use phazer::PhazerBuilder;
+
+    use crate::common::{prepare_target_file, INTERRUPTED_WRITE};
+
+    // Opens a FIFO in place of the working file so a write blocks until the test reads
+    struct FifoFileSystem {
+        fifo_path: PathBuf,
+    }
+
+    impl FileSystem for FifoFileSystem {
+        fn rename(&self, from: &Path, to: &Path) -> std::io::Result<()> {
+            STD_FILE_SYSTEM.rename(from, to)
+        }
+        fn remove_file(&self, _path: &Path) -> std::io::Result<()> {
+            Ok(())
+        }
+        fn metadata(&self, path: &Path) -> std::io::Result<Metadata> {
+            STD_FILE_SYSTEM.metadata(path)
+        }
+        fn open(&self, _path: &Path, _options: &OpenOptions) -> std::io::Result<File> {
+            // Linux allows a FIFO to be opened for read / write without a reader
+            OpenOptions::new()
+                .read(true)
+                .write(true)
+                .open(&self.fifo_path)
+        }
+    }
+
+    extern "C" fn ignore_signal(_signal: libc::c_int) {}
+
+    // Install a SIGUSR1 handler without SA_RESTART so a blocked write fails with EINTR
+    fn interrupt_writes() {
+        unsafe {
+            let mut action: libc::sigaction = std::mem::zeroed();
+            action.sa_sigaction = ignore_signal as extern "C" fn(libc::c_int) as usize;
+            libc::sigemptyset(&mut action.sa_mask);
+            assert_eq!(
+                libc::sigaction(libc::SIGUSR1, &action, std::ptr::null_mut()),
+                0
+            );
+        }
+    }
+
+    #[test]
+    fn interrupted_write_is_retried() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(INTERRUPTED_WRITE)?;
+        let fifo_path = target_path.with_extension("fifo");
+        let _ = std::fs::remove_file(&fifo_path);
+        let c_path = CString::new(fifo_path.as_os_str().as_bytes())?;
+        if unsafe { libc::mkfifo(c_path.as_ptr(), 0o600) } != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        interrupt_writes();
+        // Open the reading end first (without blocking) so the test ends if the writer fails
+        let mut reader = OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(&fifo_path)?;
+
+        // Much more than a pipe holds so the writer blocks
+        let content = vec![b'x'; 1024 * 1024];
+        let expected = content.len();
+        let fs = FifoFileSystem {
+            fifo_path: fifo_path.clone(),
+        };
+        let writer = std::thread::spawn(move || -> std::io::Result<()> {
+            let p = PhazerBuilder::with_target(&target_path)
+                .file_system(&fs)
+                .build();
+            let mut w = p.simple_writer()?;
+            let mut rest = &content[..];
+            while !rest.is_empty() {
+                // Not write_all; it hides Interrupted
+                let n = w.write(rest)?;
+                rest = &rest[n..];
+            }
+            Ok(())
+        });
+
+        // Interrupt the blocked writer a few times
+        for _ in 0..5 {
+            std::thread::sleep(Duration::from_millis(20));
+            unsafe { libc::pthread_kill(writer.as_pthread_t(), libc::SIGUSR1) };
+        }
+        let mut buf = vec![0u8; 65536];
+        let mut total = 0;
+        while total < expected && !writer.is_finished() {
+            match reader.read(&mut buf) {
+                Ok(n) => total += n,
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                    std::thread::sleep(Duration::from_millis(1))
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        while let Ok(n @ 1..) = reader.read(&mut buf) {
+            total += n;
+        }
+
+        writer.join().unwrap()?;
+        assert_eq!(total, expected);
+        let _ = std::fs::remove_file(&fifo_path);
+        Ok(())
+    }
+}
@@ -0,0 +1,37 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "length_delimited")]
+mod length_delimited {
+    use std::fs::read;
+
+    use phazer::Phazer;
+
+    use crate::common::{prepare_target_file, LENGTH_DELIMITED};
+
+    #[test]
+    fn length_delimited_works() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(LENGTH_DELIMITED)?;
+        let long = vec![7u8; 300];
+        let msgs = vec![b"abc".to_vec(), Vec::new(), long.clone()];
+        Phazer::new(&target_path).commit_length_delimited(msgs)?;
+
+        let mut expected = vec![3u8, b'a', b'b', b'c', 0, 0xac, 0x02];
+        expected.extend_from_slice(&long);
+        assert_eq!(read(&target_path)?, expected);
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs::{hard_link, read_to_string, write};
+
+use common::{
+    prepare_target_file, LINK_PRESERVING_LINKED, LINK_PRESERVING_LINKED_OTHER,
+    LINK_PRESERVING_SINGLE,
+};
+use phazer::{PhazerBuilder, LINK_PRESERVING_STRATEGY};
+
+mod common;
+
+#[test]
+fn link_preserving_strategy_updates_every_link() -> Result<(), std::io::Error> {
+    let target_path = prepare_target_file(LINK_PRESERVING_LINKED)?;
+    let other_path = prepare_target_file(LINK_PRESERVING_LINKED_OTHER)?;
+    let source_path = target_path.with_extension("source");
+    write(&target_path, "old")?;
+    hard_link(&target_path, &other_path)?;
+    write(&source_path, "new")?;
+
+    let p = PhazerBuilder::with_target(&target_path)
+        .commit_strategy(LINK_PRESERVING_STRATEGY)
+        .build();
+    p.commit_copy_of(&source_path)?;
+
+    assert_eq!(read_to_string(&target_path)?, "new");
+    assert_eq!(read_to_string(&other_path)?, "new");
+    Ok(())
+}
+
+#[test]
+fn link_preserving_strategy_renames_a_single_link() -> Result<(), std::io::Error> {
+    let target_path = prepare_target_file(LINK_PRESERVING_SINGLE)?;
+    let source_path = target_path.with_extension("source");
+    write(&target_path, "old")?;
+    write(&source_path, "new")?;
+
+    let p = PhazerBuilder::with_target(&target_path)
+        .commit_strategy(LINK_PRESERVING_STRATEGY)
+        .build();
+    p.commit_copy_of(&source_path)?;
+
+    assert_eq!(read_to_string(&target_path)?, "new");
+    Ok(())
+}
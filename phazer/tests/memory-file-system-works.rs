@@ -0,0 +1,154 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+use std::io::ErrorKind;
+use std::path::Path;
+
+use phazer::file_system::{FileSystem, MemoryFileSystem};
+use phazer::{
+    Backoff, CommitStrategy, ConfigurableRetryStrategy, FallbackStrategy, CREATE_NEW_STRATEGY,
+    RENAME_WITH_RETRY_STRATEGY, SIMPLE_RENAME_STRATEGY,
+};
+
+use crate::common::MockCommitDetails;
+
+const WORKING: &str = "memory.phazer-working";
+const TARGET: &str = "memory";
+
+fn details(fs: &MemoryFileSystem) -> MockCommitDetails<'_> {
+    MockCommitDetails::new(fs, WORKING, TARGET)
+}
+
+fn prepare_memory_file_system() -> MemoryFileSystem {
+    let fs = MemoryFileSystem::new();
+    fs.insert(TARGET, b"old".to_vec());
+    fs.insert(WORKING, b"new".to_vec());
+    fs
+}
+
+#[test]
+fn simple_rename_replaces_the_target() -> Result<(), std::io::Error> {
+    let fs = prepare_memory_file_system();
+    SIMPLE_RENAME_STRATEGY.commit(&details(&fs))?;
+    assert_eq!(fs.contents(TARGET), Some(b"new".to_vec()));
+    assert!(!fs.contains(WORKING));
+    Ok(())
+}
+
+#[test]
+fn missing_working_file_is_not_found() {
+    let fs = MemoryFileSystem::new();
+    let rv = SIMPLE_RENAME_STRATEGY.commit(&details(&fs));
+    assert_eq!(rv.unwrap_err().kind(), ErrorKind::NotFound);
+    assert!(!fs.contains(TARGET));
+}
+
+#[test]
+fn failed_rename_leaves_the_files_alone() {
+    let fs = prepare_memory_file_system();
+    fs.fail_renames(1, ErrorKind::PermissionDenied);
+    let rv = SIMPLE_RENAME_STRATEGY.commit(&details(&fs));
+    assert_eq!(rv.unwrap_err().kind(), ErrorKind::PermissionDenied);
+    assert_eq!(fs.contents(TARGET), Some(b"old".to_vec()));
+    assert_eq!(fs.contents(WORKING), Some(b"new".to_vec()));
+}
+
+#[test]
+fn rename_with_retry_recovers_deterministically() -> Result<(), std::io::Error> {
+    let fs = prepare_memory_file_system();
+    fs.fail_renames(3, ErrorKind::PermissionDenied);
+    RENAME_WITH_RETRY_STRATEGY.commit(&details(&fs))?;
+    assert_eq!(fs.contents(TARGET), Some(b"new".to_vec()));
+    assert!(!fs.contains(WORKING));
+    Ok(())
+}
+
+#[test]
+fn rename_with_retry_gives_up_and_leaves_the_files_alone() {
+    let fs = prepare_memory_file_system();
+    fs.fail_renames(100, ErrorKind::PermissionDenied);
+    let rv = RENAME_WITH_RETRY_STRATEGY.commit(&details(&fs));
+    assert_eq!(rv.unwrap_err().kind(), ErrorKind::PermissionDenied);
+    assert_eq!(fs.contents(TARGET), Some(b"old".to_vec()));
+    assert_eq!(fs.contents(WORKING), Some(b"new".to_vec()));
+}
+
+#[test]
+fn configurable_retry_stops_at_max_tries() {
+    const STRATEGY: ConfigurableRetryStrategy = ConfigurableRetryStrategy::new()
+        .max_tries(3)
+        .base_millis(1)
+        .backoff(Backoff::Exponential);
+    let fs = prepare_memory_file_system();
+    fs.fail_renames(3, ErrorKind::PermissionDenied);
+    let rv = STRATEGY.commit(&details(&fs));
+    assert_eq!(rv.unwrap_err().kind(), ErrorKind::PermissionDenied);
+    // The fourth rename is the first to succeed
+    STRATEGY.commit(&details(&fs)).unwrap();
+    assert_eq!(fs.contents(TARGET), Some(b"new".to_vec()));
+}
+
+#[test]
+fn fallback_tries_the_secondary_strategy() -> Result<(), std::io::Error> {
+    const STRATEGY: FallbackStrategy =
+        FallbackStrategy::new(SIMPLE_RENAME_STRATEGY, RENAME_WITH_RETRY_STRATEGY);
+    let fs = prepare_memory_file_system();
+    fs.fail_renames(2, ErrorKind::PermissionDenied);
+    STRATEGY.commit(&details(&fs))?;
+    assert_eq!(fs.contents(TARGET), Some(b"new".to_vec()));
+    assert!(!fs.contains(WORKING));
+    Ok(())
+}
+
+#[test]
+fn remove_file_and_unsupported_operations() {
+    let fs = prepare_memory_file_system();
+    assert!(fs.remove_file(Path::new(WORKING)).is_ok());
+    assert_eq!(
+        fs.remove_file(Path::new(WORKING)).unwrap_err().kind(),
+        ErrorKind::NotFound
+    );
+    assert_eq!(
+        fs.metadata(Path::new(TARGET)).unwrap_err().kind(),
+        ErrorKind::Unsupported
+    );
+}
+
+#[test]
+fn create_new_links_only_a_missing_target() -> Result<(), std::io::Error> {
+    let fs = prepare_memory_file_system();
+    let rv = CREATE_NEW_STRATEGY.commit(&details(&fs));
+    assert_eq!(rv.unwrap_err().kind(), ErrorKind::AlreadyExists);
+    assert_eq!(fs.contents(TARGET), Some(b"old".to_vec()));
+    fs.remove_file(Path::new(TARGET))?;
+    CREATE_NEW_STRATEGY.commit(&details(&fs))?;
+    assert_eq!(fs.contents(TARGET), Some(b"new".to_vec()));
+    assert!(!fs.contains(WORKING));
+    Ok(())
+}
+
+#[test]
+fn read_dir_lists_the_files_in_a_directory() -> Result<(), std::io::Error> {
+    let fs = MemoryFileSystem::new();
+    fs.insert("logs/b.txt", Vec::new());
+    fs.insert("logs/a.txt", Vec::new());
+    fs.insert("other/c.txt", Vec::new());
+    assert_eq!(
+        fs.read_dir(Path::new("logs"))?,
+        vec![Path::new("logs/a.txt"), Path::new("logs/b.txt")]
+    );
+    Ok(())
+}
@@ -0,0 +1,67 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::fs::{read_to_string, write};
+    use std::io::{ErrorKind, Write};
+
+    use phazer::{PhazerBuilder, TooSmallError};
+
+    use crate::common::{
+        prepare_target_file, MIN_COMMIT_BYTES_BIG_ENOUGH, MIN_COMMIT_BYTES_TOO_SMALL,
+    };
+
+    #[test]
+    fn min_commit_bytes_refuses_a_small_file() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(MIN_COMMIT_BYTES_TOO_SMALL)?;
+        write(&target_path, "the real content")?;
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .min_commit_bytes(100)
+            .build();
+        let working_path = p.working_path().to_path_buf();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"error page")?;
+        drop(w);
+        let e = p.commit().unwrap_err();
+
+        assert_eq!(e.kind(), ErrorKind::InvalidData);
+        assert!(TooSmallError::is_too_small(&e));
+        let inner = e.into_inner().unwrap().downcast::<TooSmallError>().unwrap();
+        assert_eq!(inner.size(), 10);
+        assert_eq!(inner.minimum(), 100);
+        assert_eq!(read_to_string(&target_path)?, "the real content");
+        assert!(!working_path.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn min_commit_bytes_allows_a_big_enough_file() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(MIN_COMMIT_BYTES_BIG_ENOUGH)?;
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .min_commit_bytes(10)
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"0123456789")?;
+        drop(w);
+        p.commit()?;
+
+        assert_eq!(read_to_string(&target_path)?, "0123456789");
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(windows)]
+mod windows {
+    use std::fs::{read_to_string, write};
+
+    use phazer::{PhazerBuilder, MOVE_FILE_EX_STRATEGY, MOVE_FILE_EX_WRITE_THROUGH_STRATEGY};
+
+    use crate::common::{
+        prepare_target_file, MOVE_FILE_EX_STRATEGY_DEFAULT, MOVE_FILE_EX_STRATEGY_WRITE_THROUGH,
+    };
+
+    fn commit_replaces_target(
+        filename: &str,
+        strategy: &'static dyn phazer::CommitStrategy,
+    ) -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(filename)?;
+        let source_path = target_path.with_extension("source");
+        write(&target_path, "old")?;
+        write(&source_path, "new")?;
+        let p = PhazerBuilder::with_target(&target_path)
+            .commit_strategy(strategy)
+            .build();
+        p.commit_copy_of(&source_path)?;
+        assert_eq!(read_to_string(&target_path)?, "new");
+        Ok(())
+    }
+
+    #[test]
+    fn move_file_ex_strategy_works() -> Result<(), std::io::Error> {
+        commit_replaces_target(MOVE_FILE_EX_STRATEGY_DEFAULT, MOVE_FILE_EX_STRATEGY)
+    }
+
+    #[test]
+    fn move_file_ex_write_through_strategy_works() -> Result<(), std::io::Error> {
+        commit_replaces_target(
+            MOVE_FILE_EX_STRATEGY_WRITE_THROUGH,
+            MOVE_FILE_EX_WRITE_THROUGH_STRATEGY,
+        )
+    }
+}
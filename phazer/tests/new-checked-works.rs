@@ -0,0 +1,75 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs::{create_dir_all, remove_file, write};
+use std::io::ErrorKind;
+
+use common::{
+    prepare_target_file, prepare_working_dir, NEW_CHECKED_DIRECTORY_TARGET,
+    NEW_CHECKED_FILE_PARENT, NEW_CHECKED_GOOD,
+};
+use phazer::Phazer;
+
+mod common;
+
+#[test]
+fn new_checked_accepts_a_good_target() -> Result<(), std::io::Error> {
+    let target_path = prepare_target_file(NEW_CHECKED_GOOD)?;
+    let p = Phazer::new_checked(&target_path)?;
+    p.commit()
+}
+
+#[test]
+fn new_checked_rejects_a_missing_parent() -> Result<(), std::io::Error> {
+    let target_path = prepare_working_dir()?
+        .join("new-checked-does-not-exist")
+        .join("target.txt");
+    match Phazer::new_checked(target_path) {
+        Ok(_) => Err(std::io::Error::other("a missing parent must be rejected")),
+        Err(e) => {
+            assert_eq!(e.kind(), ErrorKind::NotFound);
+            Ok(())
+        }
+    }
+}
+
+#[test]
+fn new_checked_rejects_a_parent_that_is_a_file() -> Result<(), std::io::Error> {
+    let parent = prepare_target_file(NEW_CHECKED_FILE_PARENT)?;
+    write(&parent, "not a directory")?;
+    let rv = Phazer::new_checked(parent.join("target.txt"));
+    let _ = remove_file(&parent);
+    match rv {
+        Ok(_) => Err(std::io::Error::other(
+            "a parent that is a file must be rejected",
+        )),
+        Err(e) => {
+            assert_eq!(e.kind(), ErrorKind::NotADirectory);
+            Ok(())
+        }
+    }
+}
+
+#[test]
+fn new_checked_rejects_a_target_that_is_a_directory() -> Result<(), std::io::Error> {
+    let target_path = prepare_working_dir()?.join(NEW_CHECKED_DIRECTORY_TARGET);
+    create_dir_all(&target_path)?;
+    match Phazer::new_checked(target_path) {
+        Ok(_) => Err(std::io::Error::other("a directory target must be rejected")),
+        Err(e) => {
+            assert_eq!(e.kind(), ErrorKind::InvalidInput);
+            Ok(())
+        }
+    }
+}
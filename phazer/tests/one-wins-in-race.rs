@@ -12,6 +12,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+// These tests predate the lints below; keep them as written.
+#![allow(
+    clippy::len_zero,
+    clippy::manual_map,
+    clippy::manual_ok_err,
+    clippy::redundant_static_lifetimes
+)]
+
 mod common;
 
 #[allow(dead_code)]
@@ -0,0 +1,65 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::io::{ErrorKind, Write};
+
+    use phazer::PhazerBuilder;
+
+    use crate::common::{prepare_working_dir, PATH_TEMPLATE_DIR, PATH_TEMPLATE_UNKNOWN};
+
+    #[test]
+    fn path_template_fills_placeholders_and_creates_parents() -> Result<(), std::io::Error> {
+        let working_dir = prepare_working_dir()?.join(PATH_TEMPLATE_DIR);
+        let _ = std::fs::remove_dir_all(&working_dir);
+        let template = format!("{}/{{year}}/{{month}}/{{name}}.log", working_dir.display());
+
+        let p = PhazerBuilder::new()
+            .target_template(&template, &[("year", "2024"), ("name", "app")])?
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"started\n")?;
+        drop(w);
+        p.commit()?;
+
+        let mut year_dir = working_dir.join("2024");
+        let months: Vec<_> = std::fs::read_dir(&year_dir)?.collect::<Result<_, _>>()?;
+        assert_eq!(months.len(), 1);
+        let month = months[0].file_name().into_string().unwrap();
+        let month: u32 = month.parse().unwrap();
+        assert!((1..=12).contains(&month));
+        year_dir.push(format!("{:02}", month));
+        year_dir.push("app.log");
+        assert_eq!(std::fs::read_to_string(&year_dir)?, "started\n");
+        Ok(())
+    }
+
+    #[test]
+    fn path_template_rejects_a_placeholder_without_a_value() -> Result<(), std::io::Error> {
+        let working_dir = prepare_working_dir()?.join(PATH_TEMPLATE_UNKNOWN);
+        let template = format!("{}/{{nope}}.log", working_dir.display());
+
+        match PhazerBuilder::new().target_template(&template, &[]) {
+            Ok(_) => Err(std::io::Error::other("the placeholder has no value")),
+            Err(e) => {
+                assert_eq!(e.kind(), ErrorKind::InvalidInput);
+                assert!(!working_dir.exists());
+                Ok(())
+            }
+        }
+    }
+}
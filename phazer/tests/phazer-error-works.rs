@@ -0,0 +1,135 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::fs::{create_dir, create_dir_all, read_to_string, remove_dir, remove_dir_all, write};
+    use std::io::{ErrorKind, Write};
+
+    use phazer::{AfterCommitError, Phazer, PhazerBuilder, PhazerError};
+
+    use crate::common::{
+        prepare_target_file, prepare_working_dir, PHAZER_ERROR_AFTER_COMMIT, PHAZER_ERROR_LOCK,
+        PHAZER_ERROR_MARKER, PHAZER_ERROR_PREPARE, PHAZER_ERROR_RENAME,
+    };
+
+    #[test]
+    fn failed_check_is_a_prepare_error() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(PHAZER_ERROR_PREPARE)?;
+
+        let phazer = PhazerBuilder::with_target(&target_path)
+            .min_commit_bytes(100)
+            .build();
+        let mut writer = phazer.simple_writer()?;
+        writer.write_all(b"too small")?;
+        drop(writer);
+        let (e, phazer) = phazer.commit2().unwrap_err();
+        assert!(matches!(e, PhazerError::Prepare(_)));
+        assert_eq!(e.kind(), ErrorKind::InvalidData);
+        phazer.discard()?;
+        assert!(!target_path.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn failed_transfer_is_a_rename_error() -> Result<(), std::io::Error> {
+        let target_path = prepare_working_dir()?.join(PHAZER_ERROR_RENAME);
+        let _ = remove_dir_all(&target_path);
+        // A directory that is not empty cannot be replaced by a file
+        create_dir_all(&target_path)?;
+        write(target_path.join("occupied.txt"), "occupied")?;
+
+        let phazer = Phazer::new(&target_path);
+        let mut writer = phazer.simple_writer()?;
+        writer.write_all(b"never committed")?;
+        drop(writer);
+        let (e, phazer) = phazer.commit2().unwrap_err();
+        assert!(matches!(e, PhazerError::Rename(_)));
+        phazer.discard()?;
+        assert_eq!(
+            read_to_string(target_path.join("occupied.txt"))?,
+            "occupied"
+        );
+
+        remove_dir_all(&target_path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn failed_lock_is_a_lock_error() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(PHAZER_ERROR_LOCK)?;
+        // A directory where the lock file belongs cannot be opened for writing
+        let lock_path = target_path.with_extension("txt.phazer-lock");
+        let _ = remove_dir(&lock_path);
+        create_dir(&lock_path)?;
+
+        let phazer = PhazerBuilder::with_target(&target_path)
+            .serialize_with_lock(true)
+            .build();
+        let mut writer = phazer.simple_writer()?;
+        writer.write_all(b"never committed")?;
+        drop(writer);
+        let (e, phazer) = phazer.commit2().unwrap_err();
+        assert!(matches!(e, PhazerError::Lock(_)));
+        phazer.discard()?;
+        assert!(!target_path.exists());
+
+        remove_dir(&lock_path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn failed_marker_is_a_prepare_error() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(PHAZER_ERROR_MARKER)?;
+        // A directory where the commit marker belongs cannot be opened for writing
+        let marker_path = target_path.with_extension("txt.phazer-committing");
+        let _ = remove_dir(&marker_path);
+        create_dir(&marker_path)?;
+
+        let phazer = PhazerBuilder::with_target(&target_path)
+            .commit_marker(true)
+            .build();
+        let mut writer = phazer.simple_writer()?;
+        writer.write_all(b"never committed")?;
+        drop(writer);
+        let (e, phazer) = phazer.commit2().unwrap_err();
+        assert!(matches!(e, PhazerError::Prepare(_)));
+        phazer.discard()?;
+        assert!(!target_path.exists());
+
+        remove_dir(&marker_path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn failed_hook_is_an_after_commit_error() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(PHAZER_ERROR_AFTER_COMMIT)?;
+
+        let phazer = PhazerBuilder::with_target(&target_path)
+            .after_commit(|_| Err(ErrorKind::Other.into()))
+            .build();
+        let mut writer = phazer.simple_writer()?;
+        writer.write_all(b"committed anyway")?;
+        drop(writer);
+        let (e, _) = phazer.commit2().unwrap_err();
+        assert!(matches!(e, PhazerError::AfterCommit(_)));
+        assert_eq!(read_to_string(&target_path)?, "committed anyway");
+        // The conversion gives the error commit would have returned
+        let e: std::io::Error = e.into();
+        assert!(AfterCommitError::is_after_commit(&e));
+        Ok(())
+    }
+}
@@ -12,6 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+// These tests predate the lints below; keep them as written.
+#![allow(clippy::io_other_error, clippy::single_match)]
+
 mod common;
 
 #[cfg(feature = "simple")]
@@ -0,0 +1,94 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::fs::create_dir_all;
+    use std::io::{ErrorKind, Write};
+
+    use phazer::PhazerBuilder;
+
+    use crate::common::{
+        prepare_target_file, prepare_working_dir, PREFLIGHT_TARGET_DIRECTORY, PREFLIGHT_TARGET_GOOD,
+    };
+
+    #[test]
+    fn preflight_accepts_a_good_target() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(PREFLIGHT_TARGET_GOOD)?;
+        let p = PhazerBuilder::with_target(&target_path)
+            .preflight_target(true)
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"good")?;
+        drop(w);
+        p.commit()
+    }
+
+    #[test]
+    fn preflight_rejects_a_directory_target() -> Result<(), std::io::Error> {
+        let target_path = prepare_working_dir()?.join(PREFLIGHT_TARGET_DIRECTORY);
+        create_dir_all(&target_path)?;
+
+        // Without preflight the problem is not discovered until commit
+        let p = PhazerBuilder::with_target(&target_path).build();
+        drop(p.simple_writer()?);
+        assert!(p.commit().is_err());
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .preflight_target(true)
+            .build();
+        match p.simple_writer().map(drop) {
+            Ok(()) => Err(std::io::Error::other("a directory target must be rejected")),
+            Err(e) => {
+                assert_eq!(e.kind(), ErrorKind::InvalidInput);
+                Ok(())
+            }
+        }
+    }
+
+    #[cfg(windows)]
+    #[test]
+    // The lint is about POSIX permissions; this test is Windows only
+    #[allow(clippy::permissions_set_readonly_false)]
+    fn preflight_rejects_a_read_only_target() -> Result<(), std::io::Error> {
+        use std::fs::{metadata, remove_file, set_permissions, write};
+
+        use crate::common::PREFLIGHT_TARGET_READ_ONLY;
+
+        let target_path = prepare_target_file(PREFLIGHT_TARGET_READ_ONLY)?;
+        write(&target_path, "read-only")?;
+        let mut permissions = metadata(&target_path)?.permissions();
+        permissions.set_readonly(true);
+        set_permissions(&target_path, permissions.clone())?;
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .preflight_target(true)
+            .build();
+        let rv = p.simple_writer().map(drop);
+
+        permissions.set_readonly(false);
+        set_permissions(&target_path, permissions)?;
+        let _ = remove_file(&target_path);
+
+        match rv {
+            Ok(()) => Err(std::io::Error::other("a read-only target must be rejected")),
+            Err(e) => {
+                assert_eq!(e.kind(), ErrorKind::PermissionDenied);
+                Ok(())
+            }
+        }
+    }
+}
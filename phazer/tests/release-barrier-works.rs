@@ -0,0 +1,79 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::fs::{create_dir_all, read_dir, read_to_string, remove_dir_all};
+    use std::io::Write;
+
+    use phazer::release_barrier::ReleaseBarrier;
+    use phazer::{Phazer, PhazerBuilder};
+
+    use crate::common::{prepare_working_dir, RELEASE_BARRIER};
+
+    #[test]
+    fn release_barrier_publishes_everything() -> Result<(), std::io::Error> {
+        let dir = prepare_working_dir()?.join(RELEASE_BARRIER);
+        let _ = remove_dir_all(&dir);
+        create_dir_all(&dir)?;
+
+        let mut barrier = ReleaseBarrier::new();
+        assert!(barrier.is_empty());
+        for name in ["one.txt", "two.txt", "three.txt"] {
+            let p = Phazer::new(dir.join(name));
+            let mut w = p.simple_writer()?;
+            w.write_all(name.as_bytes())?;
+            drop(w);
+            barrier.add(p);
+        }
+        // This one fails so the one after it proves failures do not stop the release
+        let p = PhazerBuilder::with_target(dir.join("wrong.txt"))
+            .enforce_extension("json")
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"wrong")?;
+        drop(w);
+        barrier.add(p);
+        let p = Phazer::new(dir.join("four.txt"));
+        let mut w = p.simple_writer()?;
+        w.write_all(b"four.txt")?;
+        drop(w);
+        barrier.add(p);
+        assert_eq!(barrier.len(), 5);
+
+        let results = barrier.publish_all();
+        let names: Vec<_> = results
+            .iter()
+            .map(|(target, _)| target.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(
+            names,
+            ["one.txt", "two.txt", "three.txt", "wrong.txt", "four.txt"]
+        );
+        for (target, result) in results {
+            if target.ends_with("wrong.txt") {
+                assert!(result.is_err());
+            } else {
+                result?;
+                let name = target.file_name().unwrap().to_string_lossy().into_owned();
+                assert_eq!(read_to_string(&target)?, name);
+            }
+        }
+        // Only the four targets remain; the working files are cleaned up
+        assert_eq!(read_dir(&dir)?.count(), 4);
+        Ok(())
+    }
+}
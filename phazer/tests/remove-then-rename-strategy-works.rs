@@ -0,0 +1,52 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(windows)]
+mod windows {
+    use std::fs::{read_to_string, write, File};
+    use std::io::Read;
+    use std::os::windows::fs::OpenOptionsExt;
+
+    use phazer::{PhazerBuilder, REMOVE_THEN_RENAME_STRATEGY};
+    use windows_sys::Win32::Storage::FileSystem::{FILE_SHARE_DELETE, FILE_SHARE_READ};
+
+    use crate::common::{prepare_target_file, REMOVE_THEN_RENAME_STRATEGY_OPEN};
+
+    #[test]
+    fn remove_then_rename_replaces_an_open_target() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(REMOVE_THEN_RENAME_STRATEGY_OPEN)?;
+        let source_path = target_path.with_extension("source");
+        write(&target_path, "old")?;
+        write(&source_path, "new")?;
+        let mut reader = File::options()
+            .read(true)
+            .share_mode(FILE_SHARE_READ | FILE_SHARE_DELETE)
+            .open(&target_path)?;
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .commit_strategy(REMOVE_THEN_RENAME_STRATEGY)
+            .build();
+        p.commit_copy_of(&source_path)?;
+
+        // The reader still sees the old content
+        let mut old = String::new();
+        reader.read_to_string(&mut old)?;
+        assert_eq!(old, "old");
+        drop(reader);
+        assert_eq!(read_to_string(&target_path)?, "new");
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs::{hard_link, read_to_string, write};
+
+use common::{
+    prepare_target_file, REPLACE_CONTENTS_EXISTING, REPLACE_CONTENTS_LINKED,
+    REPLACE_CONTENTS_MISSING,
+};
+use phazer::{PhazerBuilder, REPLACE_CONTENTS_STRATEGY};
+
+mod common;
+
+#[test]
+fn replace_contents_strategy_updates_every_link() -> Result<(), std::io::Error> {
+    let target_path = prepare_target_file(REPLACE_CONTENTS_EXISTING)?;
+    let other_path = prepare_target_file(REPLACE_CONTENTS_LINKED)?;
+    let source_path = target_path.with_extension("source");
+    write(&target_path, "old content")?;
+    hard_link(&target_path, &other_path)?;
+    write(&source_path, "new")?;
+
+    let p = PhazerBuilder::with_target(&target_path)
+        .commit_strategy(REPLACE_CONTENTS_STRATEGY)
+        .build();
+    let working_path = p.working_path().to_path_buf();
+    p.commit_copy_of(&source_path)?;
+
+    assert_eq!(read_to_string(&target_path)?, "new");
+    assert_eq!(read_to_string(&other_path)?, "new");
+    assert!(!working_path.exists());
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn replace_contents_strategy_keeps_the_inode() -> Result<(), std::io::Error> {
+    use std::os::unix::fs::MetadataExt;
+
+    use common::REPLACE_CONTENTS_INODE;
+
+    let target_path = prepare_target_file(REPLACE_CONTENTS_INODE)?;
+    write(&target_path, "old")?;
+    let before = std::fs::metadata(&target_path)?.ino();
+
+    let p = PhazerBuilder::with_target(&target_path)
+        .commit_strategy(REPLACE_CONTENTS_STRATEGY)
+        .build();
+    let source_path = target_path.with_extension("inode");
+    write(&source_path, "new")?;
+    p.commit_copy_of(&source_path)?;
+
+    assert_eq!(read_to_string(&target_path)?, "new");
+    assert_eq!(std::fs::metadata(&target_path)?.ino(), before);
+    Ok(())
+}
+
+#[test]
+fn replace_contents_strategy_renames_when_missing() -> Result<(), std::io::Error> {
+    let target_path = prepare_target_file(REPLACE_CONTENTS_MISSING)?;
+    let source_path = target_path.with_extension("source");
+    write(&source_path, "new")?;
+
+    let p = PhazerBuilder::with_target(&target_path)
+        .commit_strategy(REPLACE_CONTENTS_STRATEGY)
+        .build();
+    p.commit_copy_of(&source_path)?;
+
+    assert_eq!(read_to_string(&target_path)?, "new");
+    Ok(())
+}
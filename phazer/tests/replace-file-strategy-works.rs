@@ -0,0 +1,67 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(windows)]
+mod windows {
+    use std::fs::{read_to_string, write, File};
+    use std::io::Write;
+    use std::os::windows::fs::{MetadataExt, OpenOptionsExt};
+
+    use phazer::{PhazerBuilder, REPLACE_FILE_STRATEGY};
+    use windows_sys::Win32::Storage::FileSystem::FILE_ATTRIBUTE_HIDDEN;
+
+    use crate::common::{
+        prepare_target_file, REPLACE_FILE_STRATEGY_ATTRIBUTES, REPLACE_FILE_STRATEGY_MISSING,
+    };
+
+    #[test]
+    fn replace_file_keeps_the_target_attributes() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(REPLACE_FILE_STRATEGY_ATTRIBUTES)?;
+        let source_path = target_path.with_extension("source");
+        File::options()
+            .write(true)
+            .create_new(true)
+            .attributes(FILE_ATTRIBUTE_HIDDEN)
+            .open(&target_path)?
+            .write_all(b"old")?;
+        write(&source_path, "new")?;
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .commit_strategy(REPLACE_FILE_STRATEGY)
+            .build();
+        p.commit_copy_of(&source_path)?;
+
+        assert_eq!(read_to_string(&target_path)?, "new");
+        let attributes = target_path.metadata()?.file_attributes();
+        assert_ne!(attributes & FILE_ATTRIBUTE_HIDDEN, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn replace_file_renames_when_the_target_is_missing() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(REPLACE_FILE_STRATEGY_MISSING)?;
+        let source_path = target_path.with_extension("source");
+        write(&source_path, "new")?;
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .commit_strategy(REPLACE_FILE_STRATEGY)
+            .build();
+        p.commit_copy_of(&source_path)?;
+
+        assert_eq!(read_to_string(&target_path)?, "new");
+        Ok(())
+    }
+}
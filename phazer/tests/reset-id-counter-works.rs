@@ -0,0 +1,43 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "test_helpers")]
+mod test_helpers {
+    use phazer::{reset_id_counter, Phazer};
+
+    use crate::common::{prepare_target_file, RESET_ID_COUNTER};
+
+    #[test]
+    fn reset_id_counter_makes_working_names_reproducible() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(RESET_ID_COUNTER)?;
+        let suffix = |id: usize| format!("-{}-{}", std::process::id(), id);
+
+        reset_id_counter(1000);
+        let first = Phazer::new(&target_path);
+        let second = Phazer::new(&target_path);
+        let first_name = first.working_path().to_string_lossy().into_owned();
+        assert!(first_name.ends_with(&suffix(1000)), "{}", first_name);
+        let second_name = second.working_path().to_string_lossy().into_owned();
+        assert!(second_name.ends_with(&suffix(1001)), "{}", second_name);
+
+        reset_id_counter(1000);
+        assert_eq!(
+            Phazer::new(&target_path).working_path(),
+            first.working_path()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::fs::read_to_string;
+    use std::io::Write;
+    use std::path::Path;
+
+    use phazer::PhazerBuilder;
+
+    use crate::common::{prepare_target_file, RESTORE_SELINUX_CONTEXT};
+
+    fn commit_with_context(target_path: &Path, content: &str) -> Result<(), std::io::Error> {
+        let p = PhazerBuilder::with_target(target_path)
+            .restore_selinux_context(true)
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(content.as_bytes())?;
+        drop(w);
+        p.commit()
+    }
+
+    #[test]
+    fn restore_selinux_context_commits() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(RESTORE_SELINUX_CONTEXT)?;
+
+        // No target so there is no context to copy
+        commit_with_context(&target_path, "first")?;
+        assert_eq!(read_to_string(&target_path)?, "first");
+
+        // Copying the context (or doing nothing without SELinux) must not interfere
+        commit_with_context(&target_path, "second")?;
+        assert_eq!(read_to_string(&target_path)?, "second");
+        Ok(())
+    }
+}
@@ -0,0 +1,75 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::fs::{read_to_string, write};
+    use std::io::{Read, Seek, SeekFrom, Write};
+
+    use phazer::PhazerBuilder;
+
+    use crate::common::{prepare_target_file, SEED_FROM_TARGET, SEED_FROM_TARGET_MISSING};
+
+    #[test]
+    fn seed_from_target_copies_the_target_into_the_working_file() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(SEED_FROM_TARGET)?;
+        write(&target_path, "volume = 5\n")?;
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .seed_from_target(true)
+            .build();
+        let mut w = p.simple_writer()?;
+        let mut text = String::new();
+        w.read_to_string(&mut text)?;
+        assert_eq!(text, "volume = 5\n");
+        w.seek(SeekFrom::Start(0))?;
+        w.write_all(b"volume = 7\n")?;
+        w.seek(SeekFrom::End(0))?;
+        w.write_all(b"muted = false\n")?;
+        drop(w);
+        // A later writer does not copy the target again
+        let mut w = p.simple_writer()?;
+        w.seek(SeekFrom::End(0))?;
+        w.write_all(b"bass = 3\n")?;
+        drop(w);
+        p.commit()?;
+
+        assert_eq!(
+            read_to_string(&target_path)?,
+            "volume = 7\nmuted = false\nbass = 3\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn seed_from_target_without_a_target_starts_empty() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(SEED_FROM_TARGET_MISSING)?;
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .seed_from_target(true)
+            .build();
+        let mut w = p.simple_writer()?;
+        let mut text = String::new();
+        w.read_to_string(&mut text)?;
+        assert_eq!(text, "");
+        w.write_all(b"fresh")?;
+        drop(w);
+        p.commit()?;
+
+        assert_eq!(read_to_string(&target_path)?, "fresh");
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::fs::{create_dir_all, read_to_string, remove_dir_all};
+    use std::io::Write;
+    use std::thread::scope;
+
+    use phazer::{Phazer, PhazerBuilder, SIMPLE_RENAME_STRATEGY};
+
+    use crate::common::{prepare_working_dir, SERIALIZE_WITH_LOCK};
+
+    const CONTENTS: [&str; 5] = ["first", "second", "third", "fourth", "fifth"];
+
+    #[test]
+    fn serialize_with_lock_works() -> Result<(), std::io::Error> {
+        let dir = prepare_working_dir()?.join(SERIALIZE_WITH_LOCK);
+        let _ = remove_dir_all(&dir);
+        create_dir_all(&dir)?;
+        let target_path = dir.join("target.txt");
+
+        let results: Vec<Result<(), std::io::Error>> = scope(|s| {
+            let handles: Vec<_> = CONTENTS
+                .iter()
+                .map(|content| {
+                    let target_path = &target_path;
+                    s.spawn(move || {
+                        let p = PhazerBuilder::with_target(target_path)
+                            .serialize_with_lock(true)
+                            .build();
+                        let mut w = p.simple_writer()?;
+                        w.write_all(content.as_bytes())?;
+                        drop(w);
+                        p.commit()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|h| h.join().expect("a thread panicked"))
+                .collect()
+        });
+        for result in results {
+            result?;
+        }
+
+        let s = read_to_string(&target_path)?;
+        assert!(CONTENTS.contains(&s.as_str()));
+        assert!(dir.join("target.txt.phazer-lock").exists());
+
+        // The lock file is not mistaken for a target
+        let mut count = 0;
+        for p in Phazer::for_each_in_dir(&dir, SIMPLE_RENAME_STRATEGY)? {
+            assert_eq!(p?.target_path(), target_path);
+            count += 1;
+        }
+        assert_eq!(count, 1);
+        Ok(())
+    }
+}
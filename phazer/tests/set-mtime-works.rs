@@ -0,0 +1,126 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::fs::{metadata, read_to_string, write, File};
+    use std::io::Write;
+    use std::time::{Duration, SystemTime};
+
+    use phazer::mtime_policy::MtimePolicy;
+    use phazer::{PhazerBuilder, REPLACE_CONTENTS_STRATEGY};
+
+    use crate::common::{
+        prepare_target_file, SET_MTIME_EXACT, SET_MTIME_FROM_TARGET, SET_MTIME_IN_PLACE,
+        SET_MTIME_NOW,
+    };
+
+    fn past() -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(1_500_000_000)
+    }
+
+    fn commit_with(target: &std::path::Path, policy: MtimePolicy) -> Result<(), std::io::Error> {
+        let phazer = PhazerBuilder::with_target(target).set_mtime(policy).build();
+        let mut writer = phazer.simple_writer()?;
+        writer.write_all(b"new")?;
+        drop(writer);
+        phazer.commit()
+    }
+
+    #[test]
+    fn exact_sets_the_given_time() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(SET_MTIME_EXACT)?;
+        commit_with(&target_path, MtimePolicy::Exact(past()))?;
+        assert_eq!(read_to_string(&target_path)?, "new");
+        assert_eq!(metadata(&target_path)?.modified()?, past());
+        Ok(())
+    }
+
+    #[test]
+    fn from_target_keeps_the_replaced_time() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(SET_MTIME_FROM_TARGET)?;
+        // Nothing to keep; the working file's time is used
+        commit_with(&target_path, MtimePolicy::FromTarget)?;
+        assert_ne!(metadata(&target_path)?.modified()?, past());
+
+        File::options()
+            .write(true)
+            .open(&target_path)?
+            .set_modified(past())?;
+        commit_with(&target_path, MtimePolicy::FromTarget)?;
+        assert_eq!(read_to_string(&target_path)?, "new");
+        assert_eq!(metadata(&target_path)?.modified()?, past());
+        Ok(())
+    }
+
+    #[test]
+    fn exact_applies_to_an_in_place_strategy() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(SET_MTIME_IN_PLACE)?;
+        write(&target_path, "old")?;
+
+        let phazer = PhazerBuilder::with_target(&target_path)
+            .commit_strategy(REPLACE_CONTENTS_STRATEGY)
+            .set_mtime(MtimePolicy::Exact(past()))
+            .build();
+        let mut writer = phazer.simple_writer()?;
+        writer.write_all(b"new")?;
+        drop(writer);
+        phazer.commit()?;
+        assert_eq!(read_to_string(&target_path)?, "new");
+        assert_eq!(metadata(&target_path)?.modified()?, past());
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn exact_applies_to_a_read_only_working_file() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(crate::common::SET_MTIME_READ_ONLY)?;
+
+        let phazer = PhazerBuilder::with_target(&target_path)
+            .working_mode(0o440)
+            .set_mtime(MtimePolicy::Exact(past()))
+            .build();
+        let mut writer = phazer.simple_writer()?;
+        writer.write_all(b"new")?;
+        drop(writer);
+        phazer.commit()?;
+        assert_eq!(read_to_string(&target_path)?, "new");
+        assert_eq!(metadata(&target_path)?.modified()?, past());
+        Ok(())
+    }
+
+    #[test]
+    fn now_sets_the_commit_time() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(SET_MTIME_NOW)?;
+        write(&target_path, "old")?;
+
+        let phazer = PhazerBuilder::with_target(&target_path)
+            .set_mtime(MtimePolicy::Now)
+            .build();
+        let mut writer = phazer.simple_writer()?;
+        writer.write_all(b"new")?;
+        drop(writer);
+        // Make the working file look old so the commit must change it
+        File::options()
+            .write(true)
+            .open(phazer.working_path())?
+            .set_modified(past())?;
+        let before = SystemTime::now() - Duration::from_secs(1);
+        phazer.commit()?;
+        assert!(metadata(&target_path)?.modified()? >= before);
+        Ok(())
+    }
+}
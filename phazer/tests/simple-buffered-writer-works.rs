@@ -0,0 +1,66 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::fs::{metadata, read_to_string};
+    use std::io::{Read, Seek, SeekFrom, Write};
+
+    use phazer::Phazer;
+
+    use crate::common::{
+        prepare_target_file, SIMPLE_BUFFERED_WRITER_BYTES, SIMPLE_BUFFERED_WRITER_SEEK,
+    };
+
+    #[test]
+    fn simple_buffered_writer_collects_small_writes() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(SIMPLE_BUFFERED_WRITER_BYTES)?;
+
+        let p = Phazer::new(&target_path);
+        let mut w = p.simple_buffered_writer()?;
+        for b in b"one byte at a time".iter() {
+            w.write_all(&[*b])?;
+        }
+        // Nothing has reached the working file yet
+        assert_eq!(metadata(p.working_path())?.len(), 0);
+        drop(w);
+        assert_eq!(metadata(p.working_path())?.len(), 18);
+        p.commit()?;
+
+        assert_eq!(read_to_string(&target_path)?, "one byte at a time");
+        Ok(())
+    }
+
+    #[test]
+    fn simple_buffered_writer_reads_and_seeks() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(SIMPLE_BUFFERED_WRITER_SEEK)?;
+
+        let p = Phazer::new(&target_path);
+        let mut w = p.simple_buffered_writer()?;
+        w.write_all(b"hello world")?;
+        w.seek(SeekFrom::Start(6))?;
+        let mut s = String::new();
+        w.read_to_string(&mut s)?;
+        assert_eq!(s, "world");
+        w.seek(SeekFrom::Start(0))?;
+        w.write_all(b"HELLO")?;
+        drop(w);
+        p.commit()?;
+
+        assert_eq!(read_to_string(&target_path)?, "HELLO world");
+        Ok(())
+    }
+}
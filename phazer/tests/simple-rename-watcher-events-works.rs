@@ -0,0 +1,93 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(all(feature = "simple", target_os = "linux"))]
+mod simple {
+    use std::ffi::CString;
+    use std::fs::{read_to_string, write};
+    use std::io::Write;
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::Path;
+
+    use phazer::{PhazerBuilder, SIMPLE_RENAME_STRATEGY};
+
+    use crate::common::{prepare_target_file, SIMPLE_RENAME_WATCHER_EVENTS};
+
+    // Collect the (mask, name) of each pending inotify event
+    fn read_events(fd: i32) -> Vec<(u32, Vec<u8>)> {
+        let mut events = Vec::new();
+        let mut buf = [0u8; 16384];
+        loop {
+            let n = unsafe { libc::read(fd, buf.as_mut_ptr().cast(), buf.len()) };
+            if n <= 0 {
+                break;
+            }
+            let mut i = 0;
+            while i < n as usize {
+                let mask = u32::from_ne_bytes(buf[i + 4..i + 8].try_into().unwrap());
+                let len = u32::from_ne_bytes(buf[i + 12..i + 16].try_into().unwrap()) as usize;
+                let name = &buf[i + 16..i + 16 + len];
+                let end = name.iter().position(|b| *b == 0).unwrap_or(len);
+                events.push((mask, name[..end].to_vec()));
+                i += 16 + len;
+            }
+        }
+        events
+    }
+
+    fn watch(dir: &Path) -> std::io::Result<i32> {
+        let fd = unsafe { libc::inotify_init1(libc::IN_NONBLOCK | libc::IN_CLOEXEC) };
+        if fd < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        let cdir = CString::new(dir.as_os_str().as_bytes())?;
+        let mask = libc::IN_MOVED_TO | libc::IN_CREATE | libc::IN_MODIFY;
+        if unsafe { libc::inotify_add_watch(fd, cdir.as_ptr(), mask) } < 0 {
+            let e = std::io::Error::last_os_error();
+            unsafe { libc::close(fd) };
+            return Err(e);
+        }
+        Ok(fd)
+    }
+
+    #[test]
+    fn simple_rename_strategy_generates_moved_to() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(SIMPLE_RENAME_WATCHER_EVENTS)?;
+        write(&target_path, "old")?;
+        let fd = watch(target_path.parent().unwrap())?;
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .commit_strategy(SIMPLE_RENAME_STRATEGY)
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"new")?;
+        drop(w);
+        p.commit()?;
+
+        let events = read_events(fd);
+        unsafe { libc::close(fd) };
+        let name = target_path.file_name().unwrap().as_bytes();
+        let for_target: Vec<u32> = events
+            .iter()
+            .filter(|(_, n)| n == name)
+            .map(|(m, _)| *m)
+            .collect();
+        assert!(for_target.iter().any(|m| m & libc::IN_MOVED_TO != 0));
+        assert!(for_target.iter().all(|m| m & libc::IN_MODIFY == 0));
+        assert_eq!(read_to_string(&target_path)?, "new");
+        Ok(())
+    }
+}
@@ -0,0 +1,100 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::io::{ErrorKind, Write};
+
+    use phazer::Phazer;
+
+    use crate::common::{
+        prepare_target_file, SIMPLE_TEE_WRITER, SIMPLE_TEE_WRITER_FAILS, SIMPLE_TEE_WRITER_PARTIAL,
+    };
+
+    // A sink that always fails
+    struct Broken {}
+
+    impl Write for Broken {
+        fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::from(ErrorKind::BrokenPipe))
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    // A sink that accepts `left` bytes then fails
+    struct Stingy {
+        left: usize,
+    }
+
+    impl Write for Stingy {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            if self.left == 0 {
+                return Err(std::io::Error::from(ErrorKind::BrokenPipe));
+            }
+            let n = buf.len().min(self.left);
+            self.left -= n;
+            Ok(n)
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn simple_tee_writer_echoes_to_the_other_sink() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(SIMPLE_TEE_WRITER)?;
+
+        let p = Phazer::new(&target_path);
+        let mut w = p.simple_tee_writer(Vec::new())?;
+        w.write_all(b"seen twice")?;
+        w.flush()?;
+        let echoed = w.into_other();
+        p.commit()?;
+
+        assert_eq!(echoed, b"seen twice");
+        assert_eq!(std::fs::read_to_string(&target_path)?, "seen twice");
+        Ok(())
+    }
+
+    #[test]
+    fn simple_tee_writer_fails_if_the_other_sink_fails() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(SIMPLE_TEE_WRITER_FAILS)?;
+
+        let p = Phazer::new(&target_path);
+        let mut w = p.simple_tee_writer(Broken {})?;
+        let e = w.write_all(b"lost").unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::BrokenPipe);
+        Ok(())
+    }
+
+    #[test]
+    fn simple_tee_writer_keeps_only_what_the_other_sink_accepted() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(SIMPLE_TEE_WRITER_PARTIAL)?;
+
+        let p = Phazer::new(&target_path);
+        let mut w = p.simple_tee_writer(Stingy { left: 2 })?;
+        let e = w.write_all(b"lost").unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::BrokenPipe);
+        drop(w);
+        p.commit()?;
+
+        // A retry of the rest will not repeat anything in the working file
+        assert_eq!(std::fs::read_to_string(&target_path)?, "lo");
+        Ok(())
+    }
+}
@@ -0,0 +1,88 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::io::{ErrorKind, Write};
+
+    use phazer::size_budget::SizeBudget;
+    use phazer::PhazerBuilder;
+
+    use crate::common::{prepare_target_file, SIZE_BUDGET_FIRST, SIZE_BUDGET_SECOND};
+
+    #[test]
+    fn size_budget_is_shared_across_phazers() -> Result<(), std::io::Error> {
+        let first_path = prepare_target_file(SIZE_BUDGET_FIRST)?;
+        let second_path = prepare_target_file(SIZE_BUDGET_SECOND)?;
+        let budget = SizeBudget::new(16);
+
+        let p = PhazerBuilder::with_target(&first_path)
+            .size_budget(budget.clone())
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"twelve bytes")?;
+        drop(w);
+        p.commit()?;
+        assert_eq!(budget.used(), 12);
+        assert_eq!(budget.remaining(), 4);
+
+        let p = PhazerBuilder::with_target(&second_path)
+            .size_budget(budget.clone())
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"four")?;
+        let e = w.write_all(b"!").unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::QuotaExceeded);
+        drop(w);
+        p.commit()?;
+
+        assert_eq!(budget.used(), 16);
+        assert_eq!(std::fs::read_to_string(&second_path)?, "four");
+        Ok(())
+    }
+}
+
+#[cfg(feature = "tokio")]
+mod tokio {
+    use std::io::ErrorKind;
+
+    use phazer::size_budget::SizeBudget;
+    use phazer::PhazerBuilder;
+    use tokio::io::AsyncWriteExt;
+
+    use crate::common::{prepare_target_file, SIZE_BUDGET_TOKIO};
+
+    #[tokio::test]
+    async fn size_budget_stops_tokio_writes() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(SIZE_BUDGET_TOKIO)?;
+        let budget = SizeBudget::new(8);
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .size_budget(budget.clone())
+            .build();
+        let mut w = p.tokio_writer().await?;
+        w.write_all(b"eight by").await?;
+        let e = w.write_all(b"tes").await.unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::QuotaExceeded);
+        w.flush().await?;
+        drop(w);
+        p.commit()?;
+
+        assert_eq!(budget.used(), 8);
+        assert_eq!(std::fs::read_to_string(&target_path)?, "eight by");
+        Ok(())
+    }
+}
@@ -0,0 +1,76 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::fs::read_to_string;
+    use std::io::Write;
+    use std::panic::AssertUnwindSafe;
+
+    use phazer::Phazer;
+
+    use crate::common::{
+        prepare_target_file, STRICT_PHAZER_ABORT, STRICT_PHAZER_COMMIT, STRICT_PHAZER_DROP,
+    };
+
+    #[test]
+    fn strict_phazer_commit_works() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(STRICT_PHAZER_COMMIT)?;
+
+        let p = Phazer::new(&target_path).strict();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"committed")?;
+        drop(w);
+        p.commit()?;
+
+        assert_eq!(read_to_string(&target_path)?, "committed");
+        Ok(())
+    }
+
+    #[test]
+    fn strict_phazer_abort_works() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(STRICT_PHAZER_ABORT)?;
+
+        let p = Phazer::new(&target_path).strict();
+        let working_path = p.working_path().to_path_buf();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"aborted")?;
+        drop(w);
+        p.abort()?;
+
+        assert!(!working_path.exists());
+        assert!(!target_path.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn strict_phazer_drop_is_reported() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(STRICT_PHAZER_DROP)?;
+
+        let p = Phazer::new(&target_path).strict();
+        let working_path = p.working_path().to_path_buf();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"forgotten")?;
+        drop(w);
+        let rv = std::panic::catch_unwind(AssertUnwindSafe(move || drop(p)));
+
+        // A debug build panics; a release build only warns
+        assert_eq!(rv.is_err(), cfg!(debug_assertions));
+        assert!(!working_path.exists());
+        assert!(!target_path.exists());
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+use std::fs::{read_to_string, write};
+
+use crate::common::{prepare_target_file, SWAP_A, SWAP_B, SWAP_MISSING, SWAP_PRESENT};
+
+#[test]
+fn swap_exchanges_two_files() -> Result<(), std::io::Error> {
+    let a = prepare_target_file(SWAP_A)?;
+    let b = prepare_target_file(SWAP_B)?;
+    write(&a, "blue")?;
+    write(&b, "green")?;
+
+    phazer::swap(&a, &b)?;
+    assert_eq!(read_to_string(&a)?, "green");
+    assert_eq!(read_to_string(&b)?, "blue");
+
+    phazer::swap(&a, &b)?;
+    assert_eq!(read_to_string(&a)?, "blue");
+    assert_eq!(read_to_string(&b)?, "green");
+    Ok(())
+}
+
+#[test]
+fn swap_with_a_missing_file_fails() -> Result<(), std::io::Error> {
+    let a = prepare_target_file(SWAP_PRESENT)?;
+    let missing = prepare_target_file(SWAP_MISSING)?;
+    write(&a, "present")?;
+
+    assert!(phazer::swap(&a, &missing).is_err());
+    assert_eq!(read_to_string(&a)?, "present");
+    assert!(!missing.exists());
+    Ok(())
+}
@@ -0,0 +1,61 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+use std::fs::{create_dir_all, remove_dir_all, write};
+use std::process::Command;
+
+use phazer::Phazer;
+
+use crate::common::{prepare_working_dir, SWEEP_STALE_WORKING_FILES};
+
+#[test]
+fn sweep_removes_only_working_files_of_ended_processes() -> Result<(), std::io::Error> {
+    let dir = prepare_working_dir()?.join(SWEEP_STALE_WORKING_FILES);
+    let _ = remove_dir_all(&dir);
+    create_dir_all(&dir)?;
+    // A process that has ended; running this test binary with --list exits promptly
+    let mut child = Command::new(std::env::current_exe()?)
+        .arg("--list")
+        .stdout(std::process::Stdio::null())
+        .spawn()?;
+    let ended = child.id();
+    child.wait()?;
+    let running = std::process::id();
+
+    let stale = dir.join(format!("one.txt.phazer-working-{}-0", ended));
+    let copy = dir.join(format!("two.txt.phazer-working-{}-1-copy", ended));
+    let live = dir.join(format!("three.txt.phazer-working-{}-2", running));
+    let tagged = dir.join("four.txt.phazer-working-tagged");
+    let target = dir.join("five.txt");
+    for path in [&stale, &copy, &live, &tagged, &target] {
+        write(path, "content")?;
+    }
+    create_dir_all(dir.join(format!("six.phazer-working-{}-3", ended)))?;
+
+    assert_eq!(Phazer::sweep_stale_working_files(&dir)?, 2);
+    assert!(!stale.exists());
+    assert!(!copy.exists());
+    assert!(live.exists());
+    assert!(tagged.exists());
+    assert!(target.exists());
+    assert!(dir.join(format!("six.phazer-working-{}-3", ended)).exists());
+
+    // Nothing is left to remove
+    assert_eq!(Phazer::sweep_stale_working_files(&dir)?, 0);
+
+    let _ = remove_dir_all(&dir);
+    Ok(())
+}
@@ -0,0 +1,109 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::fs::read_to_string;
+    use std::io::Write;
+    use std::path::Path;
+
+    use phazer::sync_mode::SyncMode;
+    use phazer::PhazerBuilder;
+
+    use crate::common::{prepare_target_file, SYNC_MODE_DATA, SYNC_MODE_FULL};
+
+    fn commit_with(target_path: &Path, sync_mode: SyncMode) -> Result<(), std::io::Error> {
+        let p = PhazerBuilder::with_target(target_path)
+            .sync_mode(sync_mode)
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"durable")?;
+        drop(w);
+        p.commit()
+    }
+
+    #[test]
+    fn sync_mode_data_works() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(SYNC_MODE_DATA)?;
+        commit_with(&target_path, SyncMode::Data)?;
+        assert_eq!(read_to_string(&target_path)?, "durable");
+        Ok(())
+    }
+
+    #[test]
+    fn sync_mode_full_works() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(SYNC_MODE_FULL)?;
+        commit_with(&target_path, SyncMode::Full)?;
+        assert_eq!(read_to_string(&target_path)?, "durable");
+        Ok(())
+    }
+
+    #[cfg(windows)]
+    mod windows {
+        use std::fs::{read_to_string, File, Metadata, OpenOptions};
+        use std::io::Write;
+        use std::path::Path;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        use phazer::file_system::{FileSystem, STD_FILE_SYSTEM};
+        use phazer::sync_mode::SyncMode;
+        use phazer::PhazerBuilder;
+
+        use crate::common::{prepare_target_file, SYNC_MODE_FULL_WRITE_THROUGH};
+
+        // Counts the write-through renames
+        #[derive(Default)]
+        struct Counting {
+            write_throughs: AtomicUsize,
+        }
+
+        impl FileSystem for Counting {
+            fn rename(&self, from: &Path, to: &Path) -> std::io::Result<()> {
+                STD_FILE_SYSTEM.rename(from, to)
+            }
+            fn remove_file(&self, path: &Path) -> std::io::Result<()> {
+                STD_FILE_SYSTEM.remove_file(path)
+            }
+            fn metadata(&self, path: &Path) -> std::io::Result<Metadata> {
+                STD_FILE_SYSTEM.metadata(path)
+            }
+            fn open(&self, path: &Path, options: &OpenOptions) -> std::io::Result<File> {
+                STD_FILE_SYSTEM.open(path, options)
+            }
+            fn rename_write_through(&self, from: &Path, to: &Path) -> std::io::Result<()> {
+                self.write_throughs.fetch_add(1, Ordering::Relaxed);
+                STD_FILE_SYSTEM.rename_write_through(from, to)
+            }
+        }
+
+        #[test]
+        fn sync_mode_full_writes_the_rename_through() -> Result<(), std::io::Error> {
+            let target_path = prepare_target_file(SYNC_MODE_FULL_WRITE_THROUGH)?;
+            let fs = Counting::default();
+            let p = PhazerBuilder::with_target(&target_path)
+                .file_system(&fs)
+                .sync_mode(SyncMode::Full)
+                .build();
+            let mut w = p.simple_writer()?;
+            w.write_all(b"durable")?;
+            drop(w);
+            p.commit()?;
+            assert_eq!(fs.write_throughs.load(Ordering::Relaxed), 1);
+            assert_eq!(read_to_string(&target_path)?, "durable");
+            Ok(())
+        }
+    }
+}
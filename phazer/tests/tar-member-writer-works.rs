@@ -0,0 +1,195 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "tar")]
+mod tar {
+    use std::collections::BTreeMap;
+    use std::io::{ErrorKind, Read, Write};
+    use std::path::Path;
+
+    use phazer::Phazer;
+
+    use crate::common::{
+        prepare_target_file, TAR_MEMBER_WRITER_EXTENSIONS, TAR_MEMBER_WRITER_NEW,
+        TAR_MEMBER_WRITER_REPLACE, TAR_MEMBER_WRITER_UNFINISHED,
+    };
+
+    fn create_archive(path: &Path, members: &[(&str, &str)]) -> Result<(), std::io::Error> {
+        let mut builder = ::tar::Builder::new(std::fs::File::create(path)?);
+        for (name, content) in members {
+            let mut header = ::tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o600);
+            builder.append_data(&mut header, name, content.as_bytes())?;
+        }
+        builder.into_inner()?.sync_all()
+    }
+
+    fn read_archive(path: &Path) -> Result<BTreeMap<String, String>, std::io::Error> {
+        let mut members = BTreeMap::new();
+        let mut archive = ::tar::Archive::new(std::fs::File::open(path)?);
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let name = entry.path()?.to_string_lossy().into_owned();
+            let mut content = String::new();
+            entry.read_to_string(&mut content)?;
+            members.insert(name, content);
+        }
+        Ok(members)
+    }
+
+    #[test]
+    fn tar_member_writer_replaces_one_member() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(TAR_MEMBER_WRITER_REPLACE)?;
+        create_archive(
+            &target_path,
+            &[("a.txt", "first"), ("b.txt", "second"), ("c.txt", "third")],
+        )?;
+
+        let p = Phazer::new(&target_path);
+        let mut w = p.tar_member_writer("b.txt");
+        w.write_all(b"replaced")?;
+        w.finish()?;
+        p.commit()?;
+
+        let members = read_archive(&target_path)?;
+        assert_eq!(members.len(), 3);
+        assert_eq!(members["a.txt"], "first");
+        assert_eq!(members["b.txt"], "replaced");
+        assert_eq!(members["c.txt"], "third");
+        Ok(())
+    }
+
+    #[test]
+    fn tar_member_writer_creates_a_new_archive() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(TAR_MEMBER_WRITER_NEW)?;
+
+        let p = Phazer::new(&target_path);
+        let mut w = p.tar_member_writer("dir/new.txt");
+        w.write_all(b"new member")?;
+        w.finish()?;
+        p.commit()?;
+
+        let members = read_archive(&target_path)?;
+        assert_eq!(members.len(), 1);
+        assert_eq!(members["dir/new.txt"], "new member");
+        Ok(())
+    }
+
+    #[test]
+    fn tar_member_writer_keeps_extension_headers() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(TAR_MEMBER_WRITER_EXTENSIONS)?;
+        let long_name = format!("{}/long.txt", "d".repeat(120));
+        let long_link = format!("{}/target.txt", "t".repeat(120));
+        let pax = b"23 mtime=1500000000.25\n16 comment=kept\n";
+
+        let mut builder = ::tar::Builder::new(std::fs::File::create(&target_path)?);
+        // A PAX extended header then the member it describes
+        let mut header = ::tar::Header::new_ustar();
+        header.set_path("././@PaxHeader")?;
+        header.set_entry_type(::tar::EntryType::XHeader);
+        header.set_size(pax.len() as u64);
+        header.set_cksum();
+        builder.append(&header, &pax[..])?;
+        let mut header = ::tar::Header::new_ustar();
+        header.set_size(3);
+        header.set_mode(0o600);
+        builder.append_data(&mut header, "pax.txt", &b"pax"[..])?;
+        // GNU long name and long link entries
+        let mut header = ::tar::Header::new_gnu();
+        header.set_size(4);
+        header.set_mode(0o600);
+        builder.append_data(&mut header, &long_name, &b"long"[..])?;
+        let mut header = ::tar::Header::new_gnu();
+        header.set_entry_type(::tar::EntryType::Symlink);
+        header.set_size(0);
+        header.set_mode(0o777);
+        builder.append_link(&mut header, "link", &long_link)?;
+        let mut header = ::tar::Header::new_gnu();
+        header.set_size(3);
+        header.set_mode(0o600);
+        builder.append_data(&mut header, "b.txt", &b"old"[..])?;
+        builder.into_inner()?.sync_all()?;
+
+        let p = Phazer::new(&target_path);
+        let mut w = p.tar_member_writer("b.txt");
+        w.write_all(b"replaced")?;
+        w.finish()?;
+        p.commit()?;
+
+        let mut archive = ::tar::Archive::new(std::fs::File::open(&target_path)?);
+        let mut seen = Vec::new();
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let name = entry.path()?.to_string_lossy().into_owned();
+            if name == "pax.txt" {
+                let mut extensions = Vec::new();
+                for extension in entry.pax_extensions()?.into_iter().flatten() {
+                    let extension = extension?;
+                    extensions.push((
+                        String::from_utf8_lossy(extension.key_bytes()).into_owned(),
+                        String::from_utf8_lossy(extension.value_bytes()).into_owned(),
+                    ));
+                }
+                assert_eq!(
+                    extensions,
+                    [
+                        ("mtime".to_owned(), "1500000000.25".to_owned()),
+                        ("comment".to_owned(), "kept".to_owned())
+                    ]
+                );
+            }
+            if name == "link" {
+                let link = entry.link_name()?.map(|l| l.to_string_lossy().into_owned());
+                assert_eq!(link, Some(long_link.clone()));
+            }
+            let mut content = String::new();
+            entry.read_to_string(&mut content)?;
+            seen.push((name, content));
+        }
+        assert_eq!(
+            seen,
+            [
+                ("pax.txt".to_owned(), "pax".to_owned()),
+                (long_name, "long".to_owned()),
+                ("link".to_owned(), String::new()),
+                ("b.txt".to_owned(), "replaced".to_owned()),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn tar_member_writer_must_be_finished() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(TAR_MEMBER_WRITER_UNFINISHED)?;
+        create_archive(&target_path, &[("a.txt", "first")])?;
+
+        let p = Phazer::new(&target_path);
+        let mut w = p.tar_member_writer("a.txt");
+        w.write_all(b"never finished")?;
+        drop(w);
+        match p.commit() {
+            Ok(()) => Err(std::io::Error::other(
+                "an unfinished writer should not commit",
+            )),
+            Err(e) => {
+                assert_eq!(e.kind(), ErrorKind::InvalidData);
+                assert_eq!(read_archive(&target_path)?["a.txt"], "first");
+                Ok(())
+            }
+        }
+    }
+}
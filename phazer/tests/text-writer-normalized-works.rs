@@ -0,0 +1,75 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "encoding")]
+mod encoding {
+    use std::io::{ErrorKind, Write};
+
+    use phazer::text_writer::LineEnding;
+    use phazer::Phazer;
+
+    use crate::common::{
+        prepare_target_file, TEXT_WRITER_NORMALIZED_CRLF, TEXT_WRITER_NORMALIZED_EMPTY,
+        TEXT_WRITER_NORMALIZED_LF,
+    };
+
+    #[test]
+    fn mixed_line_endings_become_lf() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(TEXT_WRITER_NORMALIZED_LF)?;
+
+        let p = Phazer::new(&target_path);
+        let mut w = p.text_writer_normalized(LineEnding::Lf, true)?;
+        // The CR LF pair is split across writes
+        w.write_all(b"one\r")?;
+        w.write_all(b"\ntwo\nthree\rfour")?;
+        w.finish()?;
+        p.commit()?;
+
+        assert_eq!(std::fs::read(&target_path)?, b"one\ntwo\nthree\rfour\n");
+        Ok(())
+    }
+
+    #[test]
+    fn crlf_without_a_trailing_line_ending() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(TEXT_WRITER_NORMALIZED_CRLF)?;
+
+        let p = Phazer::new(&target_path);
+        let mut w = p.text_writer_normalized(LineEnding::CrLf, false)?;
+        w.write_all(b"one\ntwo\r\nthree")?;
+        w.finish()?;
+        p.commit()?;
+
+        assert_eq!(std::fs::read(&target_path)?, b"one\r\ntwo\r\nthree");
+        Ok(())
+    }
+
+    #[test]
+    fn empty_stays_empty_and_finish_is_required() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(TEXT_WRITER_NORMALIZED_EMPTY)?;
+
+        let p = Phazer::new(&target_path);
+        let w = p.text_writer_normalized(LineEnding::Lf, true)?;
+        drop(w);
+        let (e, p) = p.commit2().unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::InvalidData);
+
+        let w = p.text_writer_normalized(LineEnding::Lf, true)?;
+        w.finish()?;
+        p.commit()?;
+        assert_eq!(std::fs::read(&target_path)?, b"");
+        Ok(())
+    }
+}
@@ -0,0 +1,92 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "encoding")]
+mod encoding {
+    use std::fs::read;
+    use std::io::{ErrorKind, Write};
+
+    use phazer::text_writer::Encoding;
+    use phazer::Phazer;
+
+    use crate::common::{
+        prepare_target_file, TEXT_WRITER_INCOMPLETE, TEXT_WRITER_SECOND_WRITER,
+        TEXT_WRITER_UTF16LE, TEXT_WRITER_UTF8_BOM,
+    };
+
+    #[test]
+    fn utf8_bom_works() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(TEXT_WRITER_UTF8_BOM)?;
+        let p = Phazer::new(&target_path);
+        let mut w = p.text_writer(Encoding::Utf8Bom)?;
+        w.write_all("hé".as_bytes())?;
+        w.finish()?;
+        p.commit()?;
+        assert_eq!(read(&target_path)?, [0xEF, 0xBB, 0xBF, b'h', 0xC3, 0xA9]);
+        Ok(())
+    }
+
+    #[test]
+    fn utf16le_works_with_split_characters() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(TEXT_WRITER_UTF16LE)?;
+        let p = Phazer::new(&target_path);
+        let mut w = p.text_writer(Encoding::Utf16Le)?;
+        // "é" then "😀" (a surrogate pair) each split across writes
+        let text = "aé😀".as_bytes();
+        w.write_all(&text[..2])?;
+        w.write_all(&text[2..5])?;
+        w.write_all(&text[5..])?;
+        w.finish()?;
+        p.commit()?;
+        assert_eq!(
+            read(&target_path)?,
+            [0xFF, 0xFE, b'a', 0x00, 0xE9, 0x00, 0x3D, 0xD8, 0x00, 0xDE]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn second_writer_does_not_repeat_the_bom() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(TEXT_WRITER_SECOND_WRITER)?;
+        let p = Phazer::new(&target_path);
+        let mut w = p.text_writer(Encoding::Utf16Le)?;
+        w.write_all(b"a")?;
+        w.finish()?;
+        let mut w = p.text_writer(Encoding::Utf16Le)?;
+        w.write_all(b"b")?;
+        w.finish()?;
+        p.commit()?;
+        assert_eq!(read(&target_path)?, [0xFF, 0xFE, b'a', 0x00, b'b', 0x00]);
+        Ok(())
+    }
+
+    #[test]
+    fn incomplete_text_is_not_committed() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(TEXT_WRITER_INCOMPLETE)?;
+        let p = Phazer::new(&target_path);
+        let mut w = p.text_writer(Encoding::Utf16LeNoBom)?;
+        w.write_all(&"é".as_bytes()[..1])?;
+        assert!(w.finish().is_err());
+        match p.commit() {
+            Ok(()) => Err(std::io::Error::other("incomplete text must not commit")),
+            Err(e) => {
+                assert_eq!(e.kind(), ErrorKind::InvalidData);
+                assert!(!target_path.exists());
+                Ok(())
+            }
+        }
+    }
+}
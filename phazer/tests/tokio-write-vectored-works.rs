@@ -0,0 +1,50 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "tokio")]
+mod tokio {
+    use std::io::IoSlice;
+
+    use phazer::Phazer;
+    use tokio::fs::read_to_string;
+    use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+    use crate::common::{prepare_target_file, TOKIO_WRITE_VECTORED};
+
+    #[tokio::test]
+    async fn tokio_write_vectored_works() -> Result<(), Box<dyn std::error::Error>> {
+        let target_path = prepare_target_file(TOKIO_WRITE_VECTORED)?;
+
+        let p = Phazer::new(&target_path);
+        let mut w = p.tokio_writer().await?;
+        assert!(w.is_write_vectored());
+        let header = b"header\n";
+        let body = b"body\n";
+        let n = w
+            .write_vectored(&[IoSlice::new(header), IoSlice::new(body)])
+            .await?;
+        // Finish whatever a short write left behind
+        let mut all = header.to_vec();
+        all.extend_from_slice(body);
+        w.write_all(&all[n..]).await?;
+        w.flush().await?;
+        drop(w);
+        p.commit()?;
+
+        assert_eq!(read_to_string(&target_path).await?, "header\nbody\n");
+        Ok(())
+    }
+}
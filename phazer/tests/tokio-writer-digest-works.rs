@@ -0,0 +1,50 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(all(feature = "tokio", feature = "sha256"))]
+mod tokio {
+    use phazer::Phazer;
+    use tokio::fs::read_to_string;
+    use tokio::io::AsyncWriteExt;
+
+    use crate::common::{prepare_target_file, TOKIO_WRITER_DIGEST};
+
+    #[tokio::test]
+    async fn tokio_writer_digest_works() -> Result<(), Box<dyn std::error::Error>> {
+        let target_path = prepare_target_file(TOKIO_WRITER_DIGEST)?;
+
+        let p = Phazer::new(&target_path);
+        let mut w = p.tokio_writer().await?;
+        // SHA-256 of nothing
+        assert_eq!(
+            w.digest(),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        w.write_all(b"a").await?;
+        w.write_all(b"bc").await?;
+        w.flush().await?;
+        // SHA-256 of "abc"
+        assert_eq!(
+            w.digest(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        drop(w);
+        p.commit()?;
+
+        assert_eq!(read_to_string(&target_path).await?, "abc");
+        Ok(())
+    }
+}
@@ -0,0 +1,51 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+use std::time::{Duration, SystemTime};
+
+use phazer::Phazer;
+
+use crate::common::{prepare_target_file, TOUCH_COMMIT_EXISTING, TOUCH_COMMIT_MISSING};
+
+#[test]
+fn touch_commit_preserves_content_and_bumps_mtime() -> Result<(), std::io::Error> {
+    let target_path = prepare_target_file(TOUCH_COMMIT_EXISTING)?;
+    std::fs::write(&target_path, "lease held by worker 7")?;
+    let old = SystemTime::now() - Duration::from_secs(3600);
+    std::fs::File::options()
+        .write(true)
+        .open(&target_path)?
+        .set_modified(old)?;
+
+    Phazer::new(&target_path).touch_commit()?;
+
+    assert_eq!(
+        std::fs::read_to_string(&target_path)?,
+        "lease held by worker 7"
+    );
+    assert!(std::fs::metadata(&target_path)?.modified()? > old + Duration::from_secs(60));
+    Ok(())
+}
+
+#[test]
+fn touch_commit_creates_an_empty_target() -> Result<(), std::io::Error> {
+    let target_path = prepare_target_file(TOUCH_COMMIT_MISSING)?;
+
+    Phazer::new(&target_path).touch_commit()?;
+
+    assert_eq!(std::fs::metadata(&target_path)?.len(), 0);
+    Ok(())
+}
@@ -0,0 +1,147 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(all(feature = "tracing", feature = "simple"))]
+mod tracing_simple {
+    use std::fs::{File, Metadata, OpenOptions};
+    use std::io::{ErrorKind, Write};
+    use std::path::Path;
+    use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    use phazer::file_system::{FileSystem, STD_FILE_SYSTEM};
+    use phazer::{PhazerBuilder, RENAME_WITH_RETRY_STRATEGY};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata as TracingMetadata, Subscriber};
+
+    use crate::common::{prepare_target_file, TRACING_COMMIT, TRACING_RETRY};
+
+    // Records the names of spans and the message and fields of events.
+    #[derive(Clone, Default)]
+    struct Recorder {
+        next_id: Arc<AtomicU64>,
+        lines: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Recorder {
+        fn lines(&self) -> Vec<String> {
+            self.lines.lock().unwrap().clone()
+        }
+    }
+
+    struct Line<'a>(&'a mut String);
+
+    impl Visit for Line<'_> {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.0.push_str(&format!(" {}={:?}", field.name(), value));
+        }
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, _metadata: &TracingMetadata<'_>) -> bool {
+            true
+        }
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let mut line = format!("span {}", span.metadata().name());
+            span.record(&mut Line(&mut line));
+            self.lines.lock().unwrap().push(line);
+            Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+        }
+        fn record(&self, _span: &Id, _values: &Record<'_>) {}
+        fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+        fn event(&self, event: &Event<'_>) {
+            let mut line = String::from("event");
+            event.record(&mut Line(&mut line));
+            self.lines.lock().unwrap().push(line);
+        }
+        fn enter(&self, _span: &Id) {}
+        fn exit(&self, _span: &Id) {}
+    }
+
+    // Fails the first rename with PermissionDenied.
+    struct BusyOnce {
+        renames: AtomicUsize,
+    }
+
+    impl FileSystem for BusyOnce {
+        fn rename(&self, from: &Path, to: &Path) -> std::io::Result<()> {
+            if self.renames.fetch_add(1, Ordering::Relaxed) == 0 {
+                return Err(ErrorKind::PermissionDenied.into());
+            }
+            STD_FILE_SYSTEM.rename(from, to)
+        }
+        fn remove_file(&self, path: &Path) -> std::io::Result<()> {
+            STD_FILE_SYSTEM.remove_file(path)
+        }
+        fn metadata(&self, path: &Path) -> std::io::Result<Metadata> {
+            STD_FILE_SYSTEM.metadata(path)
+        }
+        fn open(&self, path: &Path, options: &OpenOptions) -> std::io::Result<File> {
+            STD_FILE_SYSTEM.open(path, options)
+        }
+    }
+
+    #[test]
+    fn commit_is_traced() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(TRACING_COMMIT)?;
+        let recorder = Recorder::default();
+        tracing::subscriber::with_default(recorder.clone(), || {
+            let p = PhazerBuilder::with_target(&target_path).build();
+            let mut w = p.simple_writer()?;
+            w.write_all(b"traced")?;
+            drop(w);
+            p.commit()
+        })?;
+        let lines = recorder.lines();
+        let target = format!("target={}", target_path.display());
+        assert!(lines
+            .iter()
+            .any(|l| l.starts_with("span phazer.writer") && l.contains(&target)));
+        assert!(lines
+            .iter()
+            .any(|l| l.starts_with("span phazer.commit") && l.contains(&target)));
+        assert!(lines
+            .iter()
+            .any(|l| l.contains("transferring the working file")));
+        assert!(lines.iter().any(|l| l.contains("commit succeeded")));
+        Ok(())
+    }
+
+    #[test]
+    fn retry_sleep_is_traced() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(TRACING_RETRY)?;
+        let file_system = BusyOnce {
+            renames: AtomicUsize::new(0),
+        };
+        let recorder = Recorder::default();
+        tracing::subscriber::with_default(recorder.clone(), || {
+            let p = PhazerBuilder::with_target(&target_path)
+                .commit_strategy(RENAME_WITH_RETRY_STRATEGY)
+                .file_system(&file_system)
+                .build();
+            let mut w = p.simple_writer()?;
+            w.write_all(b"traced")?;
+            drop(w);
+            p.commit()
+        })?;
+        let lines = recorder.lines();
+        assert!(lines
+            .iter()
+            .any(|l| l.contains("rename failed; trying again") && l.contains("try_number=1")));
+        Ok(())
+    }
+}
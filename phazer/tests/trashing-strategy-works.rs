@@ -0,0 +1,40 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "trash")]
+mod trash {
+    use std::fs::{read_to_string, write};
+
+    use phazer::{PhazerBuilder, TRASHING_STRATEGY};
+
+    use crate::common::{prepare_target_file, TRASHING_STRATEGY_NO_TARGET};
+
+    // Only the missing target case is tested so the test does not fill the user's trash
+    #[test]
+    fn trashing_strategy_without_a_target_renames() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(TRASHING_STRATEGY_NO_TARGET)?;
+        let source_path = target_path.with_extension("source");
+        write(&source_path, "new")?;
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .commit_strategy(TRASHING_STRATEGY)
+            .build();
+        p.commit_copy_of(&source_path)?;
+
+        assert_eq!(read_to_string(&target_path)?, "new");
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "serde")]
+mod serde {
+    use std::io::ErrorKind;
+
+    use phazer::Phazer;
+
+    use crate::common::{
+        prepare_target_file, UPDATE_JSON_EXISTING, UPDATE_JSON_INVALID, UPDATE_JSON_MISSING,
+    };
+
+    #[test]
+    fn update_json_modifies_an_existing_target() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(UPDATE_JSON_EXISTING)?;
+        std::fs::write(&target_path, r#"{"name": "phazer", "volume": 10}"#)?;
+
+        Phazer::new(&target_path).update_json(|value| {
+            value["volume"] = 11.into();
+        })?;
+
+        let value: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&target_path)?)?;
+        assert_eq!(value, serde_json::json!({"name": "phazer", "volume": 11}));
+        Ok(())
+    }
+
+    #[test]
+    fn update_json_starts_from_an_empty_object() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(UPDATE_JSON_MISSING)?;
+
+        Phazer::new(&target_path).update_json(|value| {
+            assert_eq!(*value, serde_json::json!({}));
+            value["created"] = true.into();
+        })?;
+
+        let value: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&target_path)?)?;
+        assert_eq!(value, serde_json::json!({"created": true}));
+        Ok(())
+    }
+
+    #[test]
+    fn update_json_rejects_an_invalid_target() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(UPDATE_JSON_INVALID)?;
+        std::fs::write(&target_path, "not json")?;
+
+        match Phazer::new(&target_path).update_json(|_| {}) {
+            Ok(()) => Err(std::io::Error::other(
+                "the invalid target should be rejected",
+            )),
+            Err(e) => {
+                assert_eq!(e.kind(), ErrorKind::InvalidData);
+                assert_eq!(std::fs::read_to_string(&target_path)?, "not json");
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,82 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(all(feature = "io_uring", target_os = "linux"))]
+mod uring {
+    use std::fs::read_to_string;
+
+    use phazer::sync_mode::SyncMode;
+    use phazer::{Phazer, PhazerBuilder};
+
+    use crate::common::{
+        prepare_target_file, URING_WRITER_CANCELLED, URING_WRITER_FULL, URING_WRITER_WORKS,
+    };
+
+    #[test]
+    fn uring_writer_works() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(URING_WRITER_WORKS)?;
+        tokio_uring::start(async {
+            let phazer = Phazer::new(&target_path);
+            let mut writer = phazer.uring_writer().await?;
+            let (rv, _) = writer.write_all(b"first ".to_vec()).await;
+            rv?;
+            let (rv, _) = writer.write_all("second").await;
+            rv?;
+            assert_eq!(writer.position(), 12);
+            writer.close().await?;
+            let working_path = phazer.working_path().to_path_buf();
+            phazer.uring_commit().await?;
+            assert!(!working_path.exists());
+            Ok::<(), std::io::Error>(())
+        })?;
+        assert_eq!(read_to_string(&target_path)?, "first second");
+        Ok(())
+    }
+
+    #[test]
+    fn uring_commit_full_sync_works() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(URING_WRITER_FULL)?;
+        tokio_uring::start(async {
+            let phazer = PhazerBuilder::with_target(&target_path)
+                .sync_mode(SyncMode::Full)
+                .build();
+            let mut writer = phazer.uring_writer().await?;
+            let (rv, _) = writer.write_all("durable").await;
+            rv?;
+            drop(writer);
+            phazer.uring_commit().await
+        })?;
+        assert_eq!(read_to_string(&target_path)?, "durable");
+        Ok(())
+    }
+
+    #[test]
+    fn uring_writer_cancelled_works() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(URING_WRITER_CANCELLED)?;
+        tokio_uring::start(async {
+            let phazer = Phazer::new(&target_path);
+            let mut writer = phazer.uring_writer().await?;
+            phazer.cancellation_handle().cancel();
+            let (rv, _) = writer.write_all("never").await;
+            assert!(rv.is_err());
+            drop(writer);
+            assert!(phazer.uring_commit().await.is_err());
+            Ok::<(), std::io::Error>(())
+        })?;
+        assert!(!target_path.exists());
+        Ok(())
+    }
+}
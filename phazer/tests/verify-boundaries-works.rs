@@ -0,0 +1,108 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::fs::{File, Metadata, OpenOptions};
+    use std::io::{ErrorKind, Write};
+    use std::path::Path;
+
+    use phazer::file_system::FileSystem;
+    use phazer::{PhazerBuilder, PhazerError};
+
+    use crate::common::{
+        prepare_target_file, VERIFY_BOUNDARIES_BAD, VERIFY_BOUNDARIES_GOOD,
+        VERIFY_BOUNDARIES_MISMATCH,
+    };
+
+    // Renames then lops the last byte off the target.
+    struct TruncatingFileSystem {}
+
+    impl FileSystem for TruncatingFileSystem {
+        fn rename(&self, from: &Path, to: &Path) -> std::io::Result<()> {
+            std::fs::rename(from, to)?;
+            let file = OpenOptions::new().write(true).open(to)?;
+            let len = file.metadata()?.len();
+            file.set_len(len - 1)
+        }
+        fn remove_file(&self, path: &Path) -> std::io::Result<()> {
+            std::fs::remove_file(path)
+        }
+        fn metadata(&self, path: &Path) -> std::io::Result<Metadata> {
+            std::fs::metadata(path)
+        }
+        fn open(&self, path: &Path, options: &OpenOptions) -> std::io::Result<File> {
+            options.open(path)
+        }
+    }
+
+    #[test]
+    fn verify_boundaries_passes_a_good_commit() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(VERIFY_BOUNDARIES_GOOD)?;
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .verify_boundaries(4)
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"head middle tail")?;
+        drop(w);
+        p.commit()?;
+
+        assert_eq!(std::fs::read_to_string(&target_path)?, "head middle tail");
+        Ok(())
+    }
+
+    #[test]
+    fn verify_boundaries_catches_a_truncated_target() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(VERIFY_BOUNDARIES_BAD)?;
+
+        let fs = TruncatingFileSystem {};
+        let p = PhazerBuilder::with_target(&target_path)
+            .file_system(&fs)
+            .verify_boundaries(4)
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"head middle tail")?;
+        drop(w);
+        match p.commit() {
+            Ok(()) => Err(std::io::Error::other(
+                "the truncation should have been caught",
+            )),
+            Err(e) => {
+                assert_eq!(e.kind(), ErrorKind::InvalidData);
+                Ok(())
+            }
+        }
+    }
+
+    #[test]
+    fn verify_boundaries_mismatch_is_a_checksum_mismatch() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(VERIFY_BOUNDARIES_MISMATCH)?;
+
+        let fs = TruncatingFileSystem {};
+        let p = PhazerBuilder::with_target(&target_path)
+            .file_system(&fs)
+            .verify_boundaries(4)
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"head middle tail")?;
+        drop(w);
+        let (e, _) = p.commit2().unwrap_err();
+        assert!(matches!(e, PhazerError::ChecksumMismatch(_)));
+        assert_eq!(e.kind(), ErrorKind::InvalidData);
+        Ok(())
+    }
+}
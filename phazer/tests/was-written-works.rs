@@ -0,0 +1,46 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::fs::read_to_string;
+    use std::io::Write;
+
+    use phazer::Phazer;
+
+    use crate::common::{prepare_target_file, WAS_WRITTEN_DEFAULT};
+
+    #[test]
+    fn was_written_tracks_the_working_file() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(WAS_WRITTEN_DEFAULT)?;
+
+        let mut phazer = Phazer::new(&target_path);
+        assert!(!phazer.was_written());
+        // Nothing was written so there is nothing to commit
+        phazer.commit_ref()?;
+        assert!(!target_path.exists());
+
+        let mut writer = phazer.simple_writer()?;
+        assert!(phazer.was_written());
+        writer.write_all(b"new content")?;
+        drop(writer);
+        phazer.commit_ref()?;
+        assert_eq!(read_to_string(&target_path)?, "new content");
+        // Ready for reuse; nothing written yet
+        assert!(!phazer.was_written());
+        Ok(())
+    }
+}
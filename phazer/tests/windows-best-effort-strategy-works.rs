@@ -0,0 +1,68 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(windows)]
+mod windows {
+    use std::fs::{read_to_string, write, File};
+    use std::os::windows::fs::OpenOptionsExt;
+
+    use phazer::{PhazerBuilder, WINDOWS_BEST_EFFORT_STRATEGY};
+    use windows_sys::Win32::Storage::FileSystem::{
+        FILE_SHARE_DELETE, FILE_SHARE_READ, FILE_SHARE_WRITE,
+    };
+
+    use crate::common::{
+        prepare_target_file, WINDOWS_BEST_EFFORT_STRATEGY_NEW, WINDOWS_BEST_EFFORT_STRATEGY_REPLACE,
+    };
+
+    #[test]
+    fn windows_best_effort_strategy_creates_a_target() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(WINDOWS_BEST_EFFORT_STRATEGY_NEW)?;
+        let source_path = target_path.with_extension("source");
+        write(&source_path, "new")?;
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .commit_strategy(WINDOWS_BEST_EFFORT_STRATEGY)
+            .build();
+        p.commit_copy_of(&source_path)?;
+
+        assert_eq!(read_to_string(&target_path)?, "new");
+        Ok(())
+    }
+
+    #[test]
+    fn windows_best_effort_strategy_replaces_a_target_with_a_reader() -> Result<(), std::io::Error>
+    {
+        let target_path = prepare_target_file(WINDOWS_BEST_EFFORT_STRATEGY_REPLACE)?;
+        let source_path = target_path.with_extension("source");
+        write(&target_path, "old")?;
+        write(&source_path, "new")?;
+        // A well-behaved reader allows the file to be replaced
+        let reader = File::options()
+            .read(true)
+            .share_mode(FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE)
+            .open(&target_path)?;
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .commit_strategy(WINDOWS_BEST_EFFORT_STRATEGY)
+            .build();
+        p.commit_copy_of(&source_path)?;
+        drop(reader);
+
+        assert_eq!(read_to_string(&target_path)?, "new");
+        Ok(())
+    }
+}
@@ -0,0 +1,138 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+use std::path::Path;
+
+use phazer::{Phazer, PhazerBuilder};
+
+use crate::common::{prepare_target_file, WORKING_DIR, WORKING_DIR_SUBDIR};
+
+#[test]
+fn working_dir_is_the_targets_directory() -> Result<(), std::io::Error> {
+    let target_path = prepare_target_file(WORKING_DIR)?;
+
+    let p = Phazer::new(&target_path);
+
+    assert_eq!(p.working_dir(), target_path.parent().unwrap());
+    assert_eq!(p.working_path().parent(), Some(p.working_dir()));
+    Ok(())
+}
+
+#[test]
+fn working_dir_of_a_bare_file_name_is_the_current_directory() {
+    let p = Phazer::new(WORKING_DIR);
+
+    assert_eq!(p.working_dir(), Path::new("."));
+}
+
+#[test]
+fn working_dir_includes_the_working_subdir() -> Result<(), std::io::Error> {
+    let target_path = prepare_target_file(WORKING_DIR_SUBDIR)?;
+
+    let p = PhazerBuilder::with_target(&target_path)
+        .working_subdir(".phazer")
+        .build();
+
+    assert_eq!(
+        p.working_dir(),
+        target_path.parent().unwrap().join(".phazer")
+    );
+    Ok(())
+}
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::fs::{create_dir_all, read_to_string};
+    use std::io::Write;
+
+    use phazer::PhazerBuilder;
+
+    use crate::common::{prepare_target_file, prepare_working_dir, WORKING_DIR_STAGING};
+
+    #[test]
+    fn working_dir_holds_the_working_file() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(WORKING_DIR_STAGING)?;
+        let staging = prepare_working_dir()?.join("working-dir-staging");
+        create_dir_all(&staging)?;
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .working_subdir(".ignored")
+            .working_dir(&staging)
+            .build();
+        assert_eq!(p.working_dir(), staging);
+        let working_path = p.working_path().to_path_buf();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"staged")?;
+        drop(w);
+        assert!(working_path.exists());
+        p.commit()?;
+
+        assert!(!working_path.exists());
+        assert_eq!(read_to_string(&target_path)?, "staged");
+        Ok(())
+    }
+}
+
+#[cfg(all(feature = "simple", target_os = "linux"))]
+mod cross_device {
+    use std::fs::{metadata, read_to_string};
+    use std::io::{ErrorKind, Write};
+    use std::os::unix::fs::MetadataExt;
+    use std::path::Path;
+
+    use phazer::{PhazerBuilder, CROSS_DEVICE_SAFE_STRATEGY};
+
+    use crate::common::{prepare_target_file, WORKING_DIR_CROSS_DEVICE};
+
+    // /dev/shm is normally a tmpfs; a different filesystem than the test directory.
+    const OTHER_DEVICE: &str = "/dev/shm";
+
+    #[test]
+    fn working_dir_on_another_device_works() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(WORKING_DIR_CROSS_DEVICE)?;
+        let other = Path::new(OTHER_DEVICE);
+        let Ok(other_meta) = metadata(other) else {
+            return Ok(());
+        };
+        if other_meta.dev() == metadata(target_path.parent().unwrap())?.dev() {
+            return Ok(());
+        }
+
+        // The default strategy cannot rename across filesystems
+        let p = PhazerBuilder::with_target(&target_path)
+            .working_dir(other)
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"doomed")?;
+        drop(w);
+        assert_eq!(p.commit().unwrap_err().kind(), ErrorKind::CrossesDevices);
+        assert!(!target_path.exists());
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .working_dir(other)
+            .commit_strategy(CROSS_DEVICE_SAFE_STRATEGY)
+            .build();
+        let working_path = p.working_path().to_path_buf();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"copied")?;
+        drop(w);
+        p.commit()?;
+
+        assert!(!working_path.exists());
+        assert_eq!(read_to_string(&target_path)?, "copied");
+        Ok(())
+    }
+}
@@ -0,0 +1,79 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(all(unix, feature = "simple"))]
+mod simple {
+    use std::fs::{metadata, read_to_string, set_permissions, write, Permissions};
+    use std::io::Write;
+    use std::os::unix::fs::PermissionsExt;
+
+    use phazer::PhazerBuilder;
+
+    use crate::common::{
+        prepare_target_file, WORKING_MODE_COPY_OF, WORKING_MODE_CREATE, WORKING_MODE_EXISTING,
+    };
+
+    #[test]
+    fn working_mode_applies_when_the_working_file_is_created() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(WORKING_MODE_CREATE)?;
+        let p = PhazerBuilder::with_target(&target_path)
+            .working_mode(0o600)
+            .build();
+        let mut writer = p.simple_writer()?;
+        writer.write_all(b"secret")?;
+        drop(writer);
+        assert_eq!(
+            metadata(p.working_path())?.permissions().mode() & 0o777,
+            0o600
+        );
+        p.commit()?;
+        assert_eq!(metadata(&target_path)?.permissions().mode() & 0o777, 0o600);
+        Ok(())
+    }
+
+    #[test]
+    fn working_mode_does_not_change_an_existing_working_file() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(WORKING_MODE_EXISTING)?;
+        let p = PhazerBuilder::with_target(&target_path)
+            .working_mode(0o600)
+            .build();
+        let mut writer = p.simple_writer()?;
+        writer.write_all(b"first")?;
+        drop(writer);
+        set_permissions(p.working_path(), Permissions::from_mode(0o640))?;
+        let mut writer = p.simple_writer()?;
+        writer.write_all(b"second")?;
+        drop(writer);
+        p.commit()?;
+        assert_eq!(metadata(&target_path)?.permissions().mode() & 0o777, 0o640);
+        Ok(())
+    }
+
+    #[test]
+    fn working_mode_applies_to_commit_copy_of() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(WORKING_MODE_COPY_OF)?;
+        let source_path = target_path.with_extension("source");
+        write(&source_path, "secret")?;
+        set_permissions(&source_path, Permissions::from_mode(0o644))?;
+        let p = PhazerBuilder::with_target(&target_path)
+            .working_mode(0o600)
+            .build();
+        p.commit_copy_of(&source_path)?;
+        assert_eq!(read_to_string(&target_path)?, "secret");
+        assert_eq!(metadata(&target_path)?.permissions().mode() & 0o777, 0o600);
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::fs::{read_to_string, write};
+    use std::io::{ErrorKind, Write};
+
+    use phazer::PhazerBuilder;
+
+    use crate::common::{
+        prepare_target_file, WORKING_NAMER, WORKING_NAMER_ALIAS, WORKING_NAMER_DROP,
+    };
+
+    #[test]
+    fn working_namer_names_the_working_file() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(WORKING_NAMER)?;
+        let p = PhazerBuilder::with_target(&target_path)
+            .working_namer(|target, id| target.with_file_name(format!(".working-namer.{}.tmp", id)))
+            .build();
+        let working_path = target_path.with_file_name(format!(".working-namer.{}.tmp", p.id()));
+        assert_eq!(p.working_path(), working_path);
+        let mut w = p.simple_writer()?;
+        w.write_all(b"named")?;
+        drop(w);
+        assert_eq!(read_to_string(&working_path)?, "named");
+        p.commit()?;
+        assert_eq!(read_to_string(&target_path)?, "named");
+        assert!(!working_path.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn working_namer_file_is_removed_on_drop() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(WORKING_NAMER_DROP)?;
+        let p = PhazerBuilder::with_target(&target_path)
+            .working_namer(|target, id| target.with_extension(format!("{}.tmp", id)))
+            .build();
+        let working_path = p.working_path().to_path_buf();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"abandoned")?;
+        drop(w);
+        assert!(working_path.exists());
+        drop(p);
+        assert!(!working_path.exists());
+        assert!(!target_path.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn working_namer_alias_of_the_target_is_refused() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(WORKING_NAMER_ALIAS)?;
+        write(&target_path, "precious")?;
+        let p = PhazerBuilder::with_target(&target_path)
+            .working_namer(|target, _| {
+                target
+                    .parent()
+                    .unwrap()
+                    .join(".")
+                    .join(target.file_name().unwrap())
+            })
+            .build();
+        let Err(e) = p.simple_writer() else {
+            panic!("the alias was accepted");
+        };
+        assert_eq!(e.kind(), ErrorKind::InvalidInput);
+        drop(p);
+        assert_eq!(read_to_string(&target_path)?, "precious");
+        Ok(())
+    }
+}
@@ -0,0 +1,78 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::fs::{create_dir_all, read_dir, read_to_string, remove_dir_all};
+    use std::io::Write;
+    use std::path::Path;
+
+    use phazer::PhazerBuilder;
+
+    use crate::common::{prepare_working_dir, WORKING_SUBDIR_CREATED, WORKING_SUBDIR_EXISTING};
+
+    fn count_entries(dir: &Path) -> Result<usize, std::io::Error> {
+        Ok(read_dir(dir)?.count())
+    }
+
+    #[test]
+    fn working_subdir_is_created_and_removed() -> Result<(), std::io::Error> {
+        let dir = prepare_working_dir()?.join(WORKING_SUBDIR_CREATED);
+        let _ = remove_dir_all(&dir);
+        create_dir_all(&dir)?;
+        let target_path = dir.join("target.txt");
+        let subdir = dir.join(".phazer");
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .working_subdir(".phazer")
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"hidden")?;
+        drop(w);
+        // Only the subdirectory is visible in the target's directory
+        assert_eq!(count_entries(&dir)?, 1);
+        assert_eq!(count_entries(&subdir)?, 1);
+        p.commit()?;
+
+        assert_eq!(read_to_string(&target_path)?, "hidden");
+        assert!(!subdir.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn existing_working_subdir_is_kept() -> Result<(), std::io::Error> {
+        let dir = prepare_working_dir()?.join(WORKING_SUBDIR_EXISTING);
+        let _ = remove_dir_all(&dir);
+        let subdir = dir.join(".phazer");
+        create_dir_all(&subdir)?;
+        let target_path = dir.join("target.txt");
+
+        let p = PhazerBuilder::new()
+            .working_subdir(".phazer")
+            .target(&target_path)
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"kept")?;
+        drop(w);
+        drop(p);
+
+        // The Phazer did not create the subdirectory so it is left alone
+        assert!(subdir.exists());
+        assert_eq!(count_entries(&subdir)?, 0);
+        assert!(!target_path.exists());
+        Ok(())
+    }
+}
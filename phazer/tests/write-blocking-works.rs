@@ -0,0 +1,64 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(all(feature = "simple", feature = "tokio"))]
+mod tokio {
+    use std::io::Write;
+
+    use phazer::Phazer;
+    use tokio::fs::{read_to_string, remove_file};
+
+    use crate::common::{prepare_target_file, WRITE_BLOCKING_COMMIT, WRITE_BLOCKING_ERROR};
+
+    #[tokio::test]
+    async fn write_blocking_then_commit_works() -> Result<(), Box<dyn std::error::Error>> {
+        let target_path = prepare_target_file(WRITE_BLOCKING_COMMIT)?;
+
+        let p = Phazer::new(&target_path);
+        let (p, written) = p
+            .write_blocking(|w| {
+                w.write_all("built on a blocking thread".as_bytes())?;
+                Ok(26)
+            })
+            .await?;
+        assert_eq!(written, 26);
+        assert!(!target_path.exists());
+        p.commit()?;
+        assert_eq!(
+            read_to_string(&target_path).await?,
+            "built on a blocking thread"
+        );
+
+        let _ = remove_file(&target_path).await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn write_blocking_error_discards() -> Result<(), Box<dyn std::error::Error>> {
+        let target_path = prepare_target_file(WRITE_BLOCKING_ERROR)?;
+
+        let p = Phazer::new(&target_path);
+        let rv = p
+            .write_blocking(|w| -> std::io::Result<()> {
+                w.write_all("never committed".as_bytes())?;
+                Err(std::io::Error::other("stop"))
+            })
+            .await;
+        assert!(rv.is_err());
+        assert!(!target_path.exists());
+        Ok(())
+    }
+}
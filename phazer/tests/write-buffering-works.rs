@@ -0,0 +1,154 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "simple")]
+mod simple {
+    use std::fs::{metadata, read_to_string};
+    use std::io::Write;
+    use std::path::Path;
+
+    use phazer::write_buffering::WriteBuffering;
+    use phazer::PhazerBuilder;
+
+    use crate::common::{prepare_target_file, WRITE_BUFFERING_SIMPLE};
+
+    fn len(path: &Path) -> u64 {
+        metadata(path).unwrap().len()
+    }
+
+    #[test]
+    fn write_buffering_modes_work() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(WRITE_BUFFERING_SIMPLE)?;
+
+        // Buffered until the threshold is reached
+        let p = PhazerBuilder::with_target(&target_path)
+            .write_buffering(WriteBuffering::Buffered(8))
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"1234")?;
+        assert_eq!(len(p.working_path()), 0);
+        w.write_all(b"5678")?;
+        assert_eq!(len(p.working_path()), 8);
+        w.write_all(b"9")?;
+        drop(w);
+        p.commit()?;
+        assert_eq!(read_to_string(&target_path)?, "123456789");
+
+        // Buffered until a newline
+        let p = PhazerBuilder::with_target(&target_path)
+            .write_buffering(WriteBuffering::Line)
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(b"first")?;
+        assert_eq!(len(p.working_path()), 0);
+        w.write_all(b" line\nsecond")?;
+        assert_eq!(len(p.working_path()), 17);
+        drop(w);
+        p.commit()?;
+        assert_eq!(read_to_string(&target_path)?, "first line\nsecond");
+
+        // Buffered until flushed
+        let p = PhazerBuilder::with_target(&target_path)
+            .write_buffering(WriteBuffering::InMemory)
+            .build();
+        let mut w = p.simple_writer()?;
+        w.write_all(&[b'x'; 10000])?;
+        assert_eq!(len(p.working_path()), 0);
+        w.flush()?;
+        assert_eq!(len(p.working_path()), 10000);
+        w.write_all(b"y")?;
+        drop(w);
+        p.commit()?;
+        assert_eq!(len(&target_path), 10001);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "tokio")]
+mod tokio {
+    use std::io::ErrorKind;
+
+    use phazer::write_buffering::WriteBuffering;
+    use phazer::PhazerBuilder;
+    use tokio::fs::read_to_string;
+    use tokio::io::AsyncWriteExt;
+
+    use crate::common::{
+        prepare_target_file, WRITE_BUFFERING_TOKIO, WRITE_BUFFERING_TOKIO_UNFLUSHED,
+    };
+
+    #[tokio::test]
+    async fn write_buffering_tokio_works() -> Result<(), Box<dyn std::error::Error>> {
+        let target_path = prepare_target_file(WRITE_BUFFERING_TOKIO)?;
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .write_buffering(WriteBuffering::Buffered(4))
+            .build();
+        let mut w = p.tokio_writer().await?;
+        for chunk in [&b"ab"[..], b"cd", b"ef", b"g"] {
+            w.write_all(chunk).await?;
+        }
+        w.flush().await?;
+        drop(w);
+        p.commit()?;
+
+        assert_eq!(read_to_string(&target_path).await?, "abcdefg");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn write_buffering_tokio_unflushed_fails() -> Result<(), Box<dyn std::error::Error>> {
+        let target_path = prepare_target_file(WRITE_BUFFERING_TOKIO_UNFLUSHED)?;
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .write_buffering(WriteBuffering::InMemory)
+            .build();
+        let mut w = p.tokio_writer().await?;
+        w.write_all(b"never flushed").await?;
+        drop(w);
+        let e = p.commit().unwrap_err();
+
+        assert_eq!(e.kind(), ErrorKind::InvalidData);
+        assert!(!target_path.exists());
+        Ok(())
+    }
+
+    // The working file is a link to /dev/full so every write that reaches it fails.  tokio reports
+    // a failed write on the next write which is the one inside the drain.
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn write_buffering_tokio_failed_drain_fails() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::common::WRITE_BUFFERING_TOKIO_DRAIN_FAILS;
+
+        let target_path = prepare_target_file(WRITE_BUFFERING_TOKIO_DRAIN_FAILS)?;
+        tokio::fs::write(&target_path, "old").await?;
+
+        let p = PhazerBuilder::with_target(&target_path)
+            .write_buffering(WriteBuffering::Buffered(4))
+            .build();
+        tokio::fs::symlink("/dev/full", p.working_path()).await?;
+        let mut w = p.tokio_writer().await?;
+        for chunk in [&b"abcd"[..], b"efgh", b"ijkl"] {
+            let _ = w.write_all(chunk).await;
+        }
+        let _ = w.flush().await;
+        drop(w);
+
+        assert!(p.commit().is_err());
+        assert_eq!(read_to_string(&target_path).await?, "old");
+        Ok(())
+    }
+}
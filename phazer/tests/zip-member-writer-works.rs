@@ -0,0 +1,121 @@
+// Copyright 2024 Brian Cook (a.k.a. Coding-Badly)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+#[cfg(feature = "zip")]
+mod zip {
+    use std::collections::BTreeMap;
+    use std::io::{ErrorKind, Read, Write};
+    use std::path::Path;
+
+    use ::zip::write::SimpleFileOptions;
+    use ::zip::{CompressionMethod, ZipArchive, ZipWriter};
+    use phazer::Phazer;
+
+    use crate::common::{
+        prepare_target_file, ZIP_MEMBER_WRITER_NEW, ZIP_MEMBER_WRITER_REPLACE,
+        ZIP_MEMBER_WRITER_UNFINISHED,
+    };
+
+    fn create_archive(path: &Path, members: &[(&str, &str)]) -> Result<(), std::io::Error> {
+        let mut writer = ZipWriter::new(std::fs::File::create(path)?);
+        writer.set_comment("kept");
+        for (name, content) in members {
+            let options = SimpleFileOptions::default()
+                .compression_method(CompressionMethod::Stored)
+                .unix_permissions(0o600);
+            writer.start_file(*name, options)?;
+            writer.write_all(content.as_bytes())?;
+        }
+        writer.finish()?.sync_all()
+    }
+
+    fn read_archive(path: &Path) -> Result<BTreeMap<String, String>, std::io::Error> {
+        let mut members = BTreeMap::new();
+        let mut archive = ZipArchive::new(std::fs::File::open(path)?)?;
+        for i in 0..archive.len() {
+            let mut member = archive.by_index(i)?;
+            let mut content = String::new();
+            member.read_to_string(&mut content)?;
+            members.insert(member.name().to_owned(), content);
+        }
+        Ok(members)
+    }
+
+    #[test]
+    fn zip_member_writer_replaces_one_member() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(ZIP_MEMBER_WRITER_REPLACE)?;
+        create_archive(
+            &target_path,
+            &[("a.txt", "first"), ("b.txt", "second"), ("c.txt", "third")],
+        )?;
+
+        let p = Phazer::new(&target_path);
+        let mut w = p.zip_member_writer("b.txt");
+        w.write_all(b"replaced")?;
+        w.finish()?;
+        p.commit()?;
+
+        let members = read_archive(&target_path)?;
+        assert_eq!(members.len(), 3);
+        assert_eq!(members["a.txt"], "first");
+        assert_eq!(members["b.txt"], "replaced");
+        assert_eq!(members["c.txt"], "third");
+
+        let mut archive = ZipArchive::new(std::fs::File::open(&target_path)?)?;
+        assert_eq!(archive.comment(), b"kept");
+        let replaced = archive.by_name("b.txt")?;
+        assert_eq!(replaced.compression(), CompressionMethod::Stored);
+        assert_eq!(replaced.unix_mode().map(|m| m & 0o777), Some(0o600));
+        Ok(())
+    }
+
+    #[test]
+    fn zip_member_writer_creates_a_new_archive() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(ZIP_MEMBER_WRITER_NEW)?;
+
+        let p = Phazer::new(&target_path);
+        let mut w = p.zip_member_writer("dir/new.txt");
+        w.write_all(b"new member")?;
+        w.finish()?;
+        p.commit()?;
+
+        let members = read_archive(&target_path)?;
+        assert_eq!(members.len(), 1);
+        assert_eq!(members["dir/new.txt"], "new member");
+        Ok(())
+    }
+
+    #[test]
+    fn zip_member_writer_must_be_finished() -> Result<(), std::io::Error> {
+        let target_path = prepare_target_file(ZIP_MEMBER_WRITER_UNFINISHED)?;
+        create_archive(&target_path, &[("a.txt", "first")])?;
+
+        let p = Phazer::new(&target_path);
+        let mut w = p.zip_member_writer("a.txt");
+        w.write_all(b"never finished")?;
+        drop(w);
+        match p.commit() {
+            Ok(()) => Err(std::io::Error::other(
+                "an unfinished writer should not commit",
+            )),
+            Err(e) => {
+                assert_eq!(e.kind(), ErrorKind::InvalidData);
+                assert_eq!(read_archive(&target_path)?["a.txt"], "first");
+                Ok(())
+            }
+        }
+    }
+}